@@ -0,0 +1,156 @@
+# English, which every other language is checked against and falls back to
+
+game-title = The Caverns
+language-name = English
+fps = FPS: { $fps }
+
+## Start screen
+
+menu-continue = Continue
+menu-start = Start
+menu-load = Load
+menu-settings = Settings
+menu-quit = Quit
+menu-back = Back
+
+## Pause menu
+
+pause-title = Paused
+pause-resume = Resume
+pause-save = Save Game
+pause-load = Load Game
+pause-title-screen = Return to Title
+confirm-quit-title = Quit the game?
+
+## Game over and victory
+
+game-over-title = Game Over
+victory-title = Victory
+end-retry = Retry
+end-play-again = Play Again
+end-title-screen = Title
+stats-time = Time: { $time }
+stats-enemies = Enemies defeated: { $count }
+stats-deaths = Deaths: { $count }
+stats-items = Items collected: { $count }
+stats-quests = Quests completed: { $count }
+stats-deepest = Deepest level: { $depth }
+
+## Save slots
+
+slots-save-title = Save Game
+slots-load-title = Load Game
+slot-empty = Slot { $slot }: Empty
+slot-saved = Slot { $slot }: { $map }, { $time }
+game-saved = Game saved
+game-save-failed = Couldn't save the game
+
+## Settings
+
+settings-title = Settings
+settings-decrease = <
+settings-increase = >
+settings-key = { $key }
+settings-press-key = Press a key
+setting-volume = Volume
+setting-music = Music
+setting-sounds = Sounds
+setting-window = Window
+setting-resolution = Resolution
+setting-vsync = VSync
+setting-show-fps = Show FPS
+setting-screen-shake = Screen Shake
+setting-language = Language
+setting-percent = { $value }%
+setting-on = On
+setting-off = Off
+setting-resolution-value = { $width }x{ $height }
+window-windowed = Windowed
+window-borderless = Borderless
+window-fullscreen = Fullscreen
+action-up = Up
+action-down = Down
+action-left = Left
+action-right = Right
+action-interact = Interact
+action-attack = Attack
+action-throw = Throw
+action-dash = Dash
+action-inventory = Inventory
+
+## Gameplay
+
+interact-prompt = { $key } — { $action }
+prompt-open = Open
+prompt-close = Close
+prompt-pull = Pull
+prompt-talk = Talk
+
+## Inventory
+
+inventory-details = { $name } — { $category }
+inventory-hint = Arrows: choose — Enter: move — { $throw }: throw this
+item-category-weapon = Weapon
+item-category-ammo = Ammo
+item-category-consumable = Consumable
+item-category-material = Material
+item-category-key = Key
+item-category-currency = Currency
+
+## Story intro
+
+story-intro-1 = A darkness has fallen upon this once beautiful land.
+story-intro-2 = What was once full of life is now permeated with the stench of death and decay
+story-intro-3 = Lake crystal clear and sky pure blue turned red by the smoke that chokes the air.
+story-intro-4 = Where there were once animals and people living in harmony,
+story-intro-5 = There are now only creatures of darkness plotting their evil machinations.
+story-intro-6 = Where there was once a great kingdon of dwarves, their halls filled with splendor,
+story-intro-7 = There is now only the remains of their dark and dusty halls...
+
+## Items
+
+item-dagger = Throwing Dagger
+item-sling-stone = Sling Stone
+item-health-potion = Health Potion
+item-rusty-key = Rusty Key
+item-gold-coin = Gold Coin
+item-cave-gem = Cave Gem
+item-bone = Bone
+item-glow-mushroom = Glow Mushroom
+
+## Quests
+
+objective-progress = { $objective } ({ $count }/{ $target })
+quest-the-way-down = The Way Down
+quest-the-way-down-description = Find a way deeper into the caverns.
+objective-find-survivor = Find a survivor
+objective-find-way-down = Find the way down
+objective-slay-troll = Slay the cave troll
+quest-pest-control = Pest Control
+quest-pest-control-description = Borin wants the vermin near his camp dealt with.
+objective-slay-bats = Slay cave bats
+objective-slay-rat = Slay the cave rat
+
+## Dialogue
+
+speaker-borin = Borin
+dialogue-borin-greeting = Another living soul! I had given up hope of ever seeing one down here.
+dialogue-borin-introduction = Name's Borin. These were my people's halls, before the darkness took them.
+dialogue-borin-ask-history = What happened here?
+dialogue-borin-ask-help = Can you help me?
+dialogue-borin-leave = I should go.
+dialogue-borin-history = We dug too deep. Something woke below, and the creatures came pouring out of the rock.
+dialogue-borin-help = Take this, it'll patch you up. Clear the bats and that rat away from my camp, and if you find cave gems, bring me a few.
+dialogue-borin-welcome-back = Back again? Found any of those gems?
+dialogue-borin-give-gems = Here, take three.
+dialogue-borin-not-yet = Not yet.
+dialogue-borin-pests-cleared = Quiet at last! I can finally hear myself think. Here, you've earned these.
+dialogue-borin-thanks = Bless you, friend. With these I might yet light the old forges again.
+dialogue-borin-farewell = Watch your step out there.
+dialogue-the-way-down-opened = With the troll slain, the rubble blocking the way down finally gives. The deep halls await.
+
+## Map text
+
+text-skittering = Something skitters in the dark...
+text-heavy-seal = A heavy seal blocks the way down. Borin might know more.
+text-cold-air = The air down here is cold and damp.
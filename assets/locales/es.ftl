@@ -0,0 +1,156 @@
+# Spanish
+
+game-title = Las Cavernas
+language-name = Español
+fps = FPS: { $fps }
+
+## Start screen
+
+menu-continue = Continuar
+menu-start = Empezar
+menu-load = Cargar
+menu-settings = Opciones
+menu-quit = Salir
+menu-back = Volver
+
+## Pause menu
+
+pause-title = Pausa
+pause-resume = Reanudar
+pause-save = Guardar partida
+pause-load = Cargar partida
+pause-title-screen = Volver al título
+confirm-quit-title = ¿Salir del juego?
+
+## Game over and victory
+
+game-over-title = Fin del juego
+victory-title = Victoria
+end-retry = Reintentar
+end-play-again = Jugar de nuevo
+end-title-screen = Título
+stats-time = Tiempo: { $time }
+stats-enemies = Enemigos derrotados: { $count }
+stats-deaths = Muertes: { $count }
+stats-items = Objetos recogidos: { $count }
+stats-quests = Misiones completadas: { $count }
+stats-deepest = Nivel más profundo: { $depth }
+
+## Save slots
+
+slots-save-title = Guardar partida
+slots-load-title = Cargar partida
+slot-empty = Ranura { $slot }: Vacía
+slot-saved = Ranura { $slot }: { $map }, { $time }
+game-saved = Partida guardada
+game-save-failed = No se pudo guardar la partida
+
+## Settings
+
+settings-title = Opciones
+settings-decrease = <
+settings-increase = >
+settings-key = { $key }
+settings-press-key = Pulsa una tecla
+setting-volume = Volumen
+setting-music = Música
+setting-sounds = Sonidos
+setting-window = Ventana
+setting-resolution = Resolución
+setting-vsync = VSync
+setting-show-fps = Mostrar FPS
+setting-screen-shake = Temblor
+setting-language = Idioma
+setting-percent = { $value }%
+setting-on = Sí
+setting-off = No
+setting-resolution-value = { $width }x{ $height }
+window-windowed = Ventana
+window-borderless = Sin bordes
+window-fullscreen = Completa
+action-up = Arriba
+action-down = Abajo
+action-left = Izquierda
+action-right = Derecha
+action-interact = Interactuar
+action-attack = Atacar
+action-throw = Lanzar
+action-dash = Impulso
+action-inventory = Inventario
+
+## Gameplay
+
+interact-prompt = { $key } — { $action }
+prompt-open = Abrir
+prompt-close = Cerrar
+prompt-pull = Tirar
+prompt-talk = Hablar
+
+## Inventory
+
+inventory-details = { $name } — { $category }
+inventory-hint = Flechas: elegir — Enter: mover — { $throw }: lanzar esto
+item-category-weapon = Arma
+item-category-ammo = Munición
+item-category-consumable = Consumible
+item-category-material = Material
+item-category-key = Llave
+item-category-currency = Moneda
+
+## Story intro
+
+story-intro-1 = Una oscuridad ha caído sobre esta tierra, antes tan hermosa.
+story-intro-2 = Lo que antes rebosaba vida ahora apesta a muerte y podredumbre
+story-intro-3 = El lago cristalino y el cielo azul se tiñeron de rojo por el humo que ahoga el aire.
+story-intro-4 = Donde antes animales y gentes vivían en armonía,
+story-intro-5 = Ahora solo quedan criaturas de la oscuridad tramando sus malvadas maquinaciones.
+story-intro-6 = Donde antes se alzaba un gran reino de enanos, con salones llenos de esplendor,
+story-intro-7 = Ahora solo quedan los restos de sus salones oscuros y polvorientos...
+
+## Items
+
+item-dagger = Daga arrojadiza
+item-sling-stone = Piedra de honda
+item-health-potion = Poción de salud
+item-rusty-key = Llave oxidada
+item-gold-coin = Moneda de oro
+item-cave-gem = Gema de la cueva
+item-bone = Hueso
+item-glow-mushroom = Seta luminosa
+
+## Quests
+
+objective-progress = { $objective } ({ $count }/{ $target })
+quest-the-way-down = El descenso
+quest-the-way-down-description = Encuentra un camino hacia lo más hondo de las cavernas.
+objective-find-survivor = Encuentra a un superviviente
+objective-find-way-down = Encuentra el camino hacia abajo
+objective-slay-troll = Acaba con el trol de las cavernas
+quest-pest-control = Control de plagas
+quest-pest-control-description = Borin quiere que alguien se ocupe de las alimañas cerca de su campamento.
+objective-slay-bats = Acaba con los murciélagos
+objective-slay-rat = Acaba con la rata de las cavernas
+
+## Dialogue
+
+speaker-borin = Borin
+dialogue-borin-greeting = ¡Otra alma viva! Ya había perdido la esperanza de ver una aquí abajo.
+dialogue-borin-introduction = Me llamo Borin. Estas eran las salas de mi pueblo, antes de que la oscuridad se las llevara.
+dialogue-borin-ask-history = ¿Qué pasó aquí?
+dialogue-borin-ask-help = ¿Puedes ayudarme?
+dialogue-borin-leave = Debería irme.
+dialogue-borin-history = Cavamos demasiado hondo. Algo despertó ahí abajo, y las criaturas brotaron de la roca.
+dialogue-borin-help = Toma esto, te curará. Echa a los murciélagos y a esa rata de mi campamento, y si encuentras gemas de la cueva, tráeme unas cuantas.
+dialogue-borin-welcome-back = ¿Otra vez por aquí? ¿Has encontrado alguna de esas gemas?
+dialogue-borin-give-gems = Toma, aquí tienes tres.
+dialogue-borin-not-yet = Todavía no.
+dialogue-borin-pests-cleared = ¡Por fin hay silencio! Al fin puedo oír mis propios pensamientos. Toma, te las has ganado.
+dialogue-borin-thanks = Bendito seas, amigo. Con esto quizá vuelva a encender las viejas forjas.
+dialogue-borin-farewell = Ten cuidado ahí fuera.
+dialogue-the-way-down-opened = Con el trol muerto, los escombros que bloqueaban el descenso por fin ceden. Las salas profundas aguardan.
+
+## Map text
+
+text-skittering = Algo se escabulle en la oscuridad...
+text-heavy-seal = Un pesado sello bloquea el descenso. Quizá Borin sepa algo más.
+text-cold-air = El aire aquí abajo es frío y húmedo.
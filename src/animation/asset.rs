@@ -0,0 +1,154 @@
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The different states an animated sprite can be in
+///
+/// Each state maps to a set of clips in an [AnimationSet].
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Deserialize)]
+pub enum AnimationState {
+    #[default]
+    Idle,
+    Walk,
+    Dash,
+    Hurt,
+    Death,
+}
+
+/// The direction a sprite is facing
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Deserialize)]
+pub enum Facing {
+    Up,
+    #[default]
+    Down,
+    Left,
+    Right,
+}
+
+impl Facing {
+    /// Picks the facing that best matches the provided direction.
+    ///
+    /// The dominant axis wins, so moving diagonally will face left or right
+    /// when moving at least as fast horizontally as vertically.
+    pub fn from_direction(direction: Vec2) -> Option<Self> {
+        if direction == Vec2::ZERO {
+            return None;
+        }
+
+        if direction.x.abs() >= direction.y.abs() {
+            if direction.x > 0.0 {
+                Some(Facing::Right)
+            } else {
+                Some(Facing::Left)
+            }
+        } else if direction.y > 0.0 {
+            Some(Facing::Up)
+        } else {
+            Some(Facing::Down)
+        }
+    }
+
+    /// Returns a unit vector pointing in this direction
+    pub fn as_vec2(&self) -> Vec2 {
+        match self {
+            Facing::Up => Vec2::Y,
+            Facing::Down => Vec2::NEG_Y,
+            Facing::Left => Vec2::NEG_X,
+            Facing::Right => Vec2::X,
+        }
+    }
+}
+
+/// A single animation made of frames from a sprite sheet
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnimationClip {
+    /// Indices of the frames within the sprite sheet, in playback order
+    pub frames: Vec<usize>,
+    /// How long each frame is shown for, in seconds
+    pub frame_time: f32,
+    /// Whether the clip starts over once the last frame is reached
+    #[serde(default = "default_looping")]
+    pub looping: bool,
+}
+
+fn default_looping() -> bool {
+    true
+}
+
+/// Asset describing a sprite sheet and every animation clip within it
+///
+/// Clips are grouped by [AnimationState] and then by [Facing]. A state that
+/// doesn't care about direction (like death) only needs a clip for
+/// [Down](Facing::Down), which is used as the fallback for every direction.
+#[derive(TypeUuid, Debug, Deserialize)]
+#[uuid = "5b0b6b0e-6f1c-4d38-9d5e-51ab61bb7c2e"]
+pub struct AnimationSet {
+    /// Path to the sprite sheet image, relative to the assets folder
+    pub texture: String,
+    /// The size of a single frame in pixels
+    pub tile_size: (f32, f32),
+    /// The number of columns in the sprite sheet
+    pub columns: usize,
+    /// The number of rows in the sprite sheet
+    pub rows: usize,
+    /// The clips for every state and facing
+    pub clips: HashMap<AnimationState, HashMap<Facing, AnimationClip>>,
+    /// The texture atlas built from the sprite sheet
+    #[serde(skip)]
+    pub atlas: Handle<TextureAtlas>,
+}
+
+impl AnimationSet {
+    /// Looks up the clip for the given state and facing.
+    ///
+    /// Falls back to the [Down](Facing::Down) clip of the state when there is
+    /// no clip for the requested facing.
+    pub fn clip(&self, state: AnimationState, facing: Facing) -> Option<&AnimationClip> {
+        let clips = self.clips.get(&state)?;
+        clips.get(&facing).or_else(|| clips.get(&Facing::Down))
+    }
+}
+
+#[derive(Default)]
+pub struct AnimationSetLoader;
+
+impl AssetLoader for AnimationSetLoader {
+    /// Load an animation set along with its sprite sheet
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext<'_>,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let mut set: AnimationSet = ron::de::from_bytes(bytes)?;
+            debug!("Loaded animation set {:?}", load_context.path());
+
+            // Build the texture atlas as a sub asset so the set owns it
+            let texture_path = AssetPath::from(set.texture.as_str()).to_owned();
+            let texture: Handle<Image> = load_context.get_handle(texture_path.clone());
+            let atlas = TextureAtlas::from_grid(
+                texture,
+                Vec2::new(set.tile_size.0, set.tile_size.1),
+                set.columns,
+                set.rows,
+                None,
+                None,
+            );
+            set.atlas = load_context.set_labeled_asset("atlas", LoadedAsset::new(atlas));
+
+            load_context.set_default_asset(LoadedAsset::new(set).with_dependency(texture_path));
+
+            Ok(())
+        })
+    }
+
+    /// Returns a list of supported extensions
+    fn extensions(&self) -> &[&str] {
+        &["anim.ron"]
+    }
+}
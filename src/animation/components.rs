@@ -0,0 +1,65 @@
+use super::asset::{AnimationSet, AnimationState, Facing};
+use bevy::prelude::*;
+
+/// Drives the animation of a sprite sheet based entity
+///
+/// The base state (idle or walking) and the facing are derived from the
+/// entity's velocity every frame. Gameplay code can override the base state
+/// with an action, such as a dash or getting hurt, through
+/// [play_action](SpriteAnimator::play_action).
+#[derive(Debug, Component)]
+pub struct SpriteAnimator {
+    /// The set of clips used by this sprite
+    pub set: Handle<AnimationSet>,
+    /// The direction the sprite is currently facing
+    pub facing: Facing,
+    /// An action that overrides the velocity driven state
+    ///
+    /// Non-looping actions are cleared once their clip has finished, except
+    /// for [Death](AnimationState::Death) which holds its last frame.
+    pub action: Option<AnimationState>,
+    /// The state that is currently being played
+    pub(super) state: AnimationState,
+    /// The index into the current clip's frames
+    pub(super) frame: usize,
+    /// Time spent on the current frame
+    pub(super) elapsed: f32,
+    /// Whether the current clip has reached its last frame
+    pub(super) finished: bool,
+}
+
+impl SpriteAnimator {
+    pub fn new(set: Handle<AnimationSet>) -> Self {
+        Self {
+            set,
+            facing: Facing::default(),
+            action: None,
+            state: AnimationState::default(),
+            frame: 0,
+            elapsed: 0.0,
+            finished: false,
+        }
+    }
+
+    /// Plays an action, restarting it if it was already playing
+    pub fn play_action(&mut self, action: AnimationState) {
+        self.action = Some(action);
+        self.restart();
+    }
+
+    pub(super) fn restart(&mut self) {
+        self.frame = 0;
+        self.elapsed = 0.0;
+        self.finished = false;
+    }
+}
+
+/// The speed under which an animated entity is considered to be standing still
+#[derive(Debug, Component)]
+pub struct WalkThreshold(pub f32);
+
+impl Default for WalkThreshold {
+    fn default() -> Self {
+        Self(5.0)
+    }
+}
@@ -0,0 +1,15 @@
+//! Module for animated sprites
+//!
+//! This module includes:
+//! - An asset describing every animation clip found in a sprite sheet
+//! - An asset loader to load those descriptions from RON files
+//! - Components to drive an animation from an entity's velocity and actions
+//! - A plugin to setup the required systems and loaders
+//!
+//! Nothing in here is specific to the player, so enemies and NPCs can reuse
+//! it by adding a [SpriteAnimator](components::SpriteAnimator) of their own.
+
+pub mod asset;
+pub mod components;
+pub mod plugin;
+pub mod systems;
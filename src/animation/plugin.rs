@@ -0,0 +1,19 @@
+use super::{
+    asset::{AnimationSet, AnimationSetLoader},
+    systems::{advance_animations, drive_animation_from_velocity},
+};
+use bevy::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct SpriteAnimationPlugin;
+
+impl Plugin for SpriteAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<AnimationSet>();
+        app.init_asset_loader::<AnimationSetLoader>();
+
+        app.add_system(drive_animation_from_velocity);
+        app.add_system(advance_animations.after(drive_animation_from_velocity));
+        debug!("SpriteAnimationPlugin loaded");
+    }
+}
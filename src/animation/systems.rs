@@ -0,0 +1,94 @@
+//! Defines all of the systems used by the animation plugin
+
+use super::{
+    asset::{AnimationSet, AnimationState, Facing},
+    components::{SpriteAnimator, WalkThreshold},
+};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+/// Picks the state and facing of every animated entity that has a velocity
+pub fn drive_animation_from_velocity(
+    mut animated_query: Query<(&Velocity, &mut SpriteAnimator, Option<&WalkThreshold>)>,
+) {
+    for (velocity, mut animator, threshold) in &mut animated_query {
+        let threshold = threshold.map_or(WalkThreshold::default().0, |t| t.0);
+        let moving = velocity.linvel.length() > threshold;
+
+        // Don't turn around while dying
+        if moving && animator.action != Some(AnimationState::Death) {
+            if let Some(facing) = Facing::from_direction(velocity.linvel) {
+                if animator.facing != facing {
+                    animator.facing = facing;
+                }
+            }
+        }
+
+        let state = animator.action.unwrap_or(if moving {
+            AnimationState::Walk
+        } else {
+            AnimationState::Idle
+        });
+
+        if animator.state != state {
+            animator.state = state;
+            animator.restart();
+        }
+    }
+}
+
+/// Advances every animation and updates the sprite to the current frame
+pub fn advance_animations(
+    time: Res<Time>,
+    animation_sets: Res<Assets<AnimationSet>>,
+    mut animated_query: Query<(
+        &mut SpriteAnimator,
+        &mut TextureAtlasSprite,
+        &mut Handle<TextureAtlas>,
+    )>,
+) {
+    for (mut animator, mut sprite, mut atlas) in &mut animated_query {
+        // The set might still be loading
+        let Some(set) = animation_sets.get(&animator.set) else {
+            continue;
+        };
+
+        if *atlas != set.atlas {
+            *atlas = set.atlas.clone();
+        }
+
+        let Some(clip) = set.clip(animator.state, animator.facing) else {
+            continue;
+        };
+
+        if clip.frames.is_empty() {
+            continue;
+        }
+
+        if !animator.finished {
+            animator.elapsed += time.delta_seconds();
+
+            while animator.elapsed >= clip.frame_time && !animator.finished {
+                animator.elapsed -= clip.frame_time;
+
+                if animator.frame + 1 < clip.frames.len() {
+                    animator.frame += 1;
+                } else if clip.looping {
+                    animator.frame = 0;
+                } else {
+                    animator.finished = true;
+                }
+            }
+        }
+
+        // Hand control back to the velocity once an action is over
+        if animator.finished && !clip.looping && animator.action != Some(AnimationState::Death) {
+            animator.action = None;
+        }
+
+        let index = clip.frames[animator.frame.min(clip.frames.len() - 1)];
+        if sprite.index != index {
+            sprite.index = index;
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use super::resources::STEP_DISTANCE;
+use bevy::prelude::*;
+
+/// Makes footstep sounds as its entity walks around
+#[derive(Debug, Default, Clone, Component)]
+pub struct Footsteps {
+    /// How far the entity walked since its last step, in pixels
+    pub traveled: f32,
+    /// Where the entity was last frame
+    pub last_position: Option<Vec2>,
+}
+
+impl Footsteps {
+    /// Follows the entity to its new position, returning whether that took
+    /// another step
+    ///
+    /// Jumps much longer than a step, like being placed on a new map, don't
+    /// count as walking.
+    pub fn advance(&mut self, position: Vec2) -> bool {
+        let last = self.last_position.replace(position);
+        let Some(distance) = last.map(|last| last.distance(position)) else {
+            return false;
+        };
+        if distance > STEP_DISTANCE * 4.0 {
+            return false;
+        }
+
+        self.traveled += distance;
+        if self.traveled < STEP_DISTANCE {
+            return false;
+        }
+        self.traveled %= STEP_DISTANCE;
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn steps_follow_the_distance_walked() {
+        let mut footsteps = Footsteps::default();
+        assert!(!footsteps.advance(Vec2::ZERO));
+
+        let stride = Vec2::X * STEP_DISTANCE * 0.4;
+        assert!(!footsteps.advance(stride));
+        assert!(!footsteps.advance(stride * 2.0));
+        assert!(footsteps.advance(stride * 3.0));
+
+        // Standing still or being moved far away isn't walking
+        assert!(!footsteps.advance(stride * 3.0));
+        assert!(!footsteps.advance(Vec2::splat(10_000.0)));
+    }
+}
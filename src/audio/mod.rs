@@ -0,0 +1,23 @@
+//! Module for music and sound effects
+//!
+//! This module includes:
+//! - Volume buses for everything, the music and the sound effects, tied to
+//!   the settings
+//! - Music picked by the map's `music` property, crossfading when the map
+//!   changes
+//! - A pool of sound effects, cutting off the oldest when too many play at
+//!   once
+//! - Sounds for footsteps, hits and pickups, quieter the further they are
+//!   from the player
+//! - A plugin to setup the required systems
+//!
+//! What should play is decided by [MusicPlayer](resources::MusicPlayer),
+//! [SfxPool](resources::SfxPool) and the functions in [util], none of which
+//! need an audio device. The systems only pass those decisions on to Bevy's
+//! audio.
+
+pub mod components;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
+pub mod util;
@@ -0,0 +1,34 @@
+use super::{
+    resources::{MusicPlayer, MusicSinks, SfxPool, SoundEffects},
+    systems::{
+        add_player_footsteps, choose_music, play_footsteps, play_hit_sounds, play_music,
+        play_pickup_sounds, play_sound_effects,
+    },
+};
+use crate::menu::state::GameMenuState;
+use bevy::prelude::*;
+
+/// Plays the music of the current map and the sound effects of the game, as
+/// loud as the settings say
+#[derive(Debug, Default)]
+pub struct GameAudioPlugin;
+
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MusicPlayer>();
+        app.init_resource::<MusicSinks>();
+        app.init_resource::<SfxPool>();
+        app.init_resource::<SoundEffects>();
+
+        app.add_systems((choose_music, play_music).chain());
+
+        app.add_system(add_player_footsteps);
+        app.add_systems(
+            (play_footsteps, play_hit_sounds, play_pickup_sounds)
+                .before(play_sound_effects)
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_system(play_sound_effects);
+        debug!("GameAudioPlugin loaded");
+    }
+}
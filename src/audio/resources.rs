@@ -0,0 +1,318 @@
+use crate::settings::resources::Settings;
+use bevy::{prelude::*, utils::HashMap};
+use std::collections::VecDeque;
+
+/// How long the music takes to fade from one track to the next, in seconds
+pub const CROSSFADE_TIME: f32 = 2.0;
+/// How many sound effects can play at once before the oldest is cut off
+pub const SFX_VOICES: usize = 16;
+/// How soon the same sound effect can play again, in seconds, which keeps a
+/// swarm of enemies getting hit at once from being deafening
+pub const SFX_REPEAT_TIME: f32 = 0.05;
+/// How long a sound effect is held on to, in seconds. Sounds still playing
+/// after that play out on their own, and no longer count against the pool.
+pub const SFX_HOLD_TIME: f32 = 2.0;
+/// How far an entity walks between footsteps, in pixels
+pub const STEP_DISTANCE: f32 = 40.0;
+/// Sounds this close to the player play at full volume, in pixels
+pub const FULL_VOLUME_DISTANCE: f32 = 64.0;
+/// Sounds further than this from the player can't be heard, in pixels
+pub const HEARING_DISTANCE: f32 = 640.0;
+
+pub const FOOTSTEP_SOUND: &str = "audio/footstep.ogg";
+pub const HIT_SOUND: &str = "audio/hit.ogg";
+pub const PICKUP_SOUND: &str = "audio/pickup.ogg";
+
+/// How loud everything, the music and the sound effects play, from the
+/// settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolumeBuses {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl VolumeBuses {
+    /// The volume music plays at
+    pub fn music(&self) -> f32 {
+        self.master * self.music
+    }
+
+    /// The volume sound effects play at, before they're made quieter by
+    /// their distance from the player
+    pub fn sfx(&self) -> f32 {
+        self.master * self.sfx
+    }
+
+    /// Whether any music can be heard, which keeps it from playing while
+    /// it's muted
+    pub fn music_active(&self) -> bool {
+        self.music() > 0.0
+    }
+
+    /// Whether any sound effects can be heard, which keeps them from
+    /// starting while they're muted
+    pub fn sfx_active(&self) -> bool {
+        self.sfx() > 0.0
+    }
+}
+
+impl From<&Settings> for VolumeBuses {
+    fn from(settings: &Settings) -> Self {
+        Self {
+            master: settings.master_volume,
+            music: settings.music_volume,
+            sfx: settings.sfx_volume,
+        }
+    }
+}
+
+/// A track of music along with how far it has faded in
+#[derive(Debug, Clone, PartialEq)]
+pub struct MusicTrack {
+    /// Path to the music
+    pub path: String,
+    /// From silent at zero to full volume at one
+    pub fade: f32,
+    pub fading_out: bool,
+}
+
+/// Decides which music plays and how loud, crossfading from one track to
+/// the next
+///
+/// Every track that can be heard is listed, the one fading in last.
+#[derive(Debug, Default, Clone, Resource)]
+pub struct MusicPlayer {
+    tracks: Vec<MusicTrack>,
+}
+
+impl MusicPlayer {
+    /// Fades over to a track, or out to silence with `None`
+    ///
+    /// Asking for the track already playing changes nothing, and asking for
+    /// one that's fading out fades it back in from where it is.
+    pub fn play(&mut self, path: Option<&str>) {
+        for track in &mut self.tracks {
+            track.fading_out = Some(track.path.as_str()) != path;
+        }
+
+        if let Some(path) = path {
+            if !self.tracks.iter().any(|track| track.path == path) {
+                self.tracks.push(MusicTrack {
+                    path: path.to_string(),
+                    fade: 0.0,
+                    fading_out: false,
+                });
+            }
+        }
+    }
+
+    /// The track playing or fading in, if there is one
+    pub fn current(&self) -> Option<&str> {
+        self.tracks
+            .iter()
+            .find(|track| !track.fading_out)
+            .map(|track| track.path.as_str())
+    }
+
+    pub fn tracks(&self) -> &[MusicTrack] {
+        &self.tracks
+    }
+
+    /// Moves the fades along, returning the paths of the tracks that faded
+    /// out completely
+    pub fn tick(&mut self, delta: f32) -> Vec<String> {
+        let step = delta / CROSSFADE_TIME;
+        for track in &mut self.tracks {
+            let step = if track.fading_out { -step } else { step };
+            track.fade = (track.fade + step).clamp(0.0, 1.0);
+        }
+
+        let (silent, audible) = std::mem::take(&mut self.tracks)
+            .into_iter()
+            .partition(|track| track.fading_out && track.fade <= 0.0);
+        self.tracks = audible;
+        silent.into_iter().map(|track| track.path).collect()
+    }
+}
+
+/// The sinks the tracks of the [MusicPlayer] play through, by path
+#[derive(Debug, Default, Resource)]
+pub struct MusicSinks(pub HashMap<String, Handle<AudioSink>>);
+
+/// The sound effects asked for so far, by path
+///
+/// Holding on to them keeps them loaded between plays, and lets sounds that
+/// failed to load be skipped instead of being read again every time.
+#[derive(Debug, Default, Resource)]
+pub struct SoundEffects(pub HashMap<String, Handle<AudioSource>>);
+
+/// A sound effect playing
+#[derive(Debug, Clone)]
+struct Voice<T> {
+    path: String,
+    /// When the sound started, in seconds
+    started: f32,
+    sink: T,
+}
+
+/// Keeps track of the sound effects playing, so only so many play at once
+///
+/// The sinks of the sounds are held on to while they play, which lets the
+/// oldest be stopped to make room for new ones.
+#[derive(Debug, Resource)]
+pub struct SfxPool<T: Send + Sync + 'static = Handle<AudioSink>> {
+    capacity: usize,
+    /// Oldest first
+    voices: VecDeque<Voice<T>>,
+}
+
+impl<T: Send + Sync + 'static> Default for SfxPool<T> {
+    fn default() -> Self {
+        Self::new(SFX_VOICES)
+    }
+}
+
+impl<T: Send + Sync + 'static> SfxPool<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            voices: VecDeque::with_capacity(capacity + 1),
+        }
+    }
+
+    /// Whether a sound can start at the given time, which it can't when it
+    /// only just started
+    pub fn can_play(&self, path: &str, now: f32) -> bool {
+        !self
+            .voices
+            .iter()
+            .any(|voice| voice.path == path && now - voice.started < SFX_REPEAT_TIME)
+    }
+
+    /// Adds a sound that just started, returning the sink of the oldest
+    /// sound when it has to be cut off to make room
+    pub fn push(&mut self, path: impl Into<String>, now: f32, sink: T) -> Option<T> {
+        self.voices.push_back(Voice {
+            path: path.into(),
+            started: now,
+            sink,
+        });
+
+        if self.voices.len() > self.capacity {
+            self.voices.pop_front().map(|voice| voice.sink)
+        } else {
+            None
+        }
+    }
+
+    /// Lets go of the sounds held on to for long enough, which play out on
+    /// their own
+    pub fn release_finished(&mut self, now: f32) {
+        while self
+            .voices
+            .front()
+            .is_some_and(|voice| now - voice.started >= SFX_HOLD_TIME)
+        {
+            self.voices.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fades(player: &MusicPlayer) -> Vec<(&str, f32)> {
+        player
+            .tracks()
+            .iter()
+            .map(|track| (track.path.as_str(), track.fade))
+            .collect()
+    }
+
+    #[test]
+    fn music_crossfades_between_tracks() {
+        let mut player = MusicPlayer::default();
+        player.play(Some("upper.ogg"));
+        player.tick(CROSSFADE_TIME);
+        assert_eq!(fades(&player), vec![("upper.ogg", 1.0)]);
+
+        // The same track keeps playing
+        player.play(Some("upper.ogg"));
+        assert!(player.tick(CROSSFADE_TIME).is_empty());
+
+        player.play(Some("deep.ogg"));
+        assert_eq!(player.current(), Some("deep.ogg"));
+        assert!(player.tick(CROSSFADE_TIME / 4.0).is_empty());
+        assert_eq!(
+            fades(&player),
+            vec![("upper.ogg", 0.75), ("deep.ogg", 0.25)]
+        );
+
+        assert_eq!(player.tick(CROSSFADE_TIME), vec!["upper.ogg".to_string()]);
+        assert_eq!(fades(&player), vec![("deep.ogg", 1.0)]);
+
+        player.play(None);
+        assert_eq!(player.current(), None);
+        assert_eq!(player.tick(CROSSFADE_TIME), vec!["deep.ogg".to_string()]);
+        assert!(player.tracks().is_empty());
+    }
+
+    #[test]
+    fn music_fading_out_can_fade_back_in() {
+        let mut player = MusicPlayer::default();
+        player.play(Some("upper.ogg"));
+        player.tick(CROSSFADE_TIME);
+        player.play(Some("deep.ogg"));
+        player.tick(CROSSFADE_TIME / 2.0);
+
+        player.play(Some("upper.ogg"));
+        player.tick(CROSSFADE_TIME / 4.0);
+        assert_eq!(
+            fades(&player),
+            vec![("upper.ogg", 0.75), ("deep.ogg", 0.25)]
+        );
+    }
+
+    #[test]
+    fn buses_follow_the_settings() {
+        let mut settings = Settings {
+            master_volume: 0.5,
+            music_volume: 0.8,
+            sfx_volume: 0.0,
+            ..default()
+        };
+        let buses = VolumeBuses::from(&settings);
+        assert_eq!(buses.music(), 0.4);
+        assert!(buses.music_active());
+        assert!(!buses.sfx_active());
+
+        settings.sfx_volume = 1.0;
+        settings.master_volume = 0.0;
+        let buses = VolumeBuses::from(&settings);
+        assert!(!buses.music_active());
+        assert!(!buses.sfx_active());
+    }
+
+    #[test]
+    fn the_pool_cuts_off_the_oldest_sounds() {
+        let mut pool = SfxPool::<u32>::new(2);
+        assert!(pool.can_play("hit.ogg", 0.0));
+        assert_eq!(pool.push("hit.ogg", 0.0, 1), None);
+
+        // The same sound can't pile up on itself
+        assert!(!pool.can_play("hit.ogg", SFX_REPEAT_TIME / 2.0));
+        assert!(pool.can_play("pickup.ogg", SFX_REPEAT_TIME / 2.0));
+        assert!(pool.can_play("hit.ogg", SFX_REPEAT_TIME));
+
+        assert_eq!(pool.push("pickup.ogg", 0.25, 2), None);
+        assert_eq!(pool.push("hit.ogg", 0.5, 3), Some(1));
+        assert_eq!(pool.voices.len(), 2);
+
+        pool.release_finished(0.25 + SFX_HOLD_TIME);
+        assert_eq!(pool.voices.len(), 1);
+        pool.release_finished(0.5 + SFX_HOLD_TIME);
+        assert!(pool.voices.is_empty());
+    }
+}
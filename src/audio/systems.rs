@@ -0,0 +1,204 @@
+//! Defines all of the systems used by the audio plugin
+
+use super::{
+    components::Footsteps,
+    resources::{
+        MusicPlayer, MusicSinks, SfxPool, SoundEffects, VolumeBuses, FOOTSTEP_SOUND, HIT_SOUND,
+        PICKUP_SOUND,
+    },
+    util::{attenuation, music_cue, MusicCue},
+};
+use crate::{
+    health::events::DamageEvent,
+    inventory::events::ItemPickedUpEvent,
+    map::state::{MapReadinessState, MapState},
+    menu::state::GameMenuState,
+    player::Player,
+    settings::resources::Settings,
+    trigger::events::PlaySoundEvent,
+};
+use bevy::{asset::LoadState, prelude::*};
+
+/// Gives the player footsteps
+pub fn add_player_footsteps(mut commands: Commands, player_query: Query<Entity, Added<Player>>) {
+    for player in &player_query {
+        commands.entity(player).insert(Footsteps::default());
+    }
+}
+
+/// Switches the music to the track for what's going on
+pub fn choose_music(
+    menu_state: Res<State<GameMenuState>>,
+    map_readiness: Res<State<MapReadinessState>>,
+    map_state: Res<MapState>,
+    mut music: ResMut<MusicPlayer>,
+) {
+    let track = match music_cue(menu_state.0, map_readiness.0, map_state.music.as_deref()) {
+        MusicCue::Keep => return,
+        MusicCue::Play(path) => Some(path),
+        MusicCue::Silence => None,
+    };
+
+    if music.current() != track {
+        debug!("Switching the music to {:?}", track);
+        music.play(track);
+    }
+}
+
+/// Plays the tracks of the [MusicPlayer] as loud as they've faded in,
+/// starting and stopping them as they come and go
+///
+/// The music pauses while its volume is muted.
+pub fn play_music(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    audio: Res<Audio>,
+    asset_server: Res<AssetServer>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    mut music: ResMut<MusicPlayer>,
+    mut sinks: ResMut<MusicSinks>,
+) {
+    // The music keeps fading while the game is paused
+    for path in music.tick(time.raw_delta_seconds()) {
+        if let Some(sink) = sinks.0.remove(&path) {
+            if let Some(sink) = audio_sinks.get(&sink) {
+                sink.stop();
+            }
+        }
+    }
+
+    let buses = VolumeBuses::from(settings.as_ref());
+    for track in music.tracks() {
+        let volume = track.fade * buses.music();
+        let Some(sink) = sinks.0.get(&track.path) else {
+            let sink = audio.play_with_settings(
+                asset_server.load(track.path.as_str()),
+                PlaybackSettings::LOOP.with_volume(volume),
+            );
+            sinks
+                .0
+                .insert(track.path.clone(), audio_sinks.get_handle(sink));
+            continue;
+        };
+
+        // The sink shows up once the track has loaded
+        let Some(sink) = audio_sinks.get(sink) else {
+            continue;
+        };
+        sink.set_volume(volume);
+        if buses.music_active() && sink.is_paused() {
+            sink.play();
+        } else if !buses.music_active() && !sink.is_paused() {
+            sink.pause();
+        }
+    }
+}
+
+/// Plays a footstep every so often as entities with [Footsteps] walk
+pub fn play_footsteps(
+    mut sound_events: EventWriter<PlaySoundEvent>,
+    mut walker_query: Query<(&GlobalTransform, &mut Footsteps)>,
+) {
+    for (transform, mut footsteps) in &mut walker_query {
+        let position = transform.translation().truncate();
+        if footsteps.advance(position) {
+            sound_events.send(PlaySoundEvent {
+                path: FOOTSTEP_SOUND.into(),
+                position: Some(position),
+            });
+        }
+    }
+}
+
+/// Plays a sound where anything takes a hit
+pub fn play_hit_sounds(
+    mut damage_events: EventReader<DamageEvent>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+    transform_query: Query<&GlobalTransform>,
+) {
+    for event in damage_events.iter() {
+        sound_events.send(PlaySoundEvent {
+            path: HIT_SOUND.into(),
+            position: transform_query
+                .get(event.target)
+                .ok()
+                .map(|transform| transform.translation().truncate()),
+        });
+    }
+}
+
+/// Plays a sound where anything picks up an item
+pub fn play_pickup_sounds(
+    mut pickup_events: EventReader<ItemPickedUpEvent>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+    transform_query: Query<&GlobalTransform>,
+) {
+    for event in pickup_events.iter() {
+        sound_events.send(PlaySoundEvent {
+            path: PICKUP_SOUND.into(),
+            position: transform_query
+                .get(event.entity)
+                .ok()
+                .map(|transform| transform.translation().truncate()),
+        });
+    }
+}
+
+/// Plays the sound effects asked for, quieter the further they are from the
+/// player
+///
+/// Sounds are skipped while their volume is muted, when they can't be heard
+/// from where the player is, when the same sound only just started, or when
+/// they failed to load.
+#[allow(clippy::too_many_arguments)]
+pub fn play_sound_effects(
+    mut sound_events: EventReader<PlaySoundEvent>,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    audio: Res<Audio>,
+    asset_server: Res<AssetServer>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    mut pool: ResMut<SfxPool>,
+    mut sounds: ResMut<SoundEffects>,
+    player_query: Query<&GlobalTransform, With<Player>>,
+) {
+    let now = time.raw_elapsed_seconds();
+    pool.release_finished(now);
+
+    let buses = VolumeBuses::from(settings.as_ref());
+    let listener = player_query
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation().truncate());
+
+    for event in sound_events.iter() {
+        if !buses.sfx_active() || !pool.can_play(&event.path, now) {
+            continue;
+        }
+
+        let distance = match (event.position, listener) {
+            (Some(position), Some(listener)) => position.distance(listener),
+            _ => 0.0,
+        };
+        let volume = buses.sfx() * attenuation(distance);
+        if volume <= 0.0 {
+            continue;
+        }
+
+        let sound = sounds
+            .0
+            .entry(event.path.clone())
+            .or_insert_with(|| asset_server.load(event.path.as_str()));
+        if asset_server.get_load_state(&*sound) == LoadState::Failed {
+            continue;
+        }
+
+        let sink =
+            audio.play_with_settings(sound.clone(), PlaybackSettings::ONCE.with_volume(volume));
+        if let Some(oldest) = pool.push(event.path.clone(), now, audio_sinks.get_handle(sink)) {
+            if let Some(oldest) = audio_sinks.get(&oldest) {
+                oldest.stop();
+            }
+        }
+    }
+}
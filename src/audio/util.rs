@@ -0,0 +1,84 @@
+//! Utilities for the audio module
+
+use super::resources::{FULL_VOLUME_DISTANCE, HEARING_DISTANCE};
+use crate::{map::state::MapReadinessState, menu::state::GameMenuState};
+
+/// What the music should do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MusicCue<'a> {
+    /// Keep playing whatever is playing
+    Keep,
+    Play(&'a str),
+    Silence,
+}
+
+/// Picks the music for what's going on
+///
+/// Only gameplay has music, which is the track of the map being played.
+/// While a map is changing the old track keeps playing, so it crossfades
+/// into the new one once that's loaded.
+pub fn music_cue<'a>(
+    menu: GameMenuState,
+    map_readiness: MapReadinessState,
+    map_music: Option<&'a str>,
+) -> MusicCue<'a> {
+    match (menu, map_readiness) {
+        (GameMenuState::InGame, MapReadinessState::Loaded | MapReadinessState::Ready) => {
+            map_music.map_or(MusicCue::Silence, MusicCue::Play)
+        }
+        (GameMenuState::InGame, _) => MusicCue::Keep,
+        _ => MusicCue::Silence,
+    }
+}
+
+/// How loud a sound is at some distance from the player, from one up close
+/// down to zero at the [HEARING_DISTANCE]
+pub fn attenuation(distance: f32) -> f32 {
+    let t = (distance - FULL_VOLUME_DISTANCE) / (HEARING_DISTANCE - FULL_VOLUME_DISTANCE);
+    // Falling off quickly at first sounds closer to real life than a straight line
+    (1.0 - t.clamp(0.0, 1.0)).powi(2)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn the_map_picks_the_music() {
+        let loaded = MapReadinessState::Loaded;
+        assert_eq!(
+            music_cue(GameMenuState::InGame, loaded, Some("deep.ogg")),
+            MusicCue::Play("deep.ogg")
+        );
+        assert_eq!(
+            music_cue(GameMenuState::InGame, loaded, None),
+            MusicCue::Silence
+        );
+        assert_eq!(
+            music_cue(
+                GameMenuState::InGame,
+                MapReadinessState::Unloading,
+                Some("deep.ogg")
+            ),
+            MusicCue::Keep
+        );
+        assert_eq!(
+            music_cue(GameMenuState::GameOver, loaded, Some("deep.ogg")),
+            MusicCue::Silence
+        );
+        assert_eq!(
+            music_cue(GameMenuState::Intro, MapReadinessState::Loading, None),
+            MusicCue::Silence
+        );
+    }
+
+    #[test]
+    fn sounds_fade_with_distance() {
+        assert_eq!(attenuation(0.0), 1.0);
+        assert_eq!(attenuation(FULL_VOLUME_DISTANCE), 1.0);
+        let halfway = attenuation((FULL_VOLUME_DISTANCE + HEARING_DISTANCE) / 2.0);
+        assert_eq!(halfway, 0.25);
+        assert_eq!(attenuation(HEARING_DISTANCE), 0.0);
+        assert_eq!(attenuation(HEARING_DISTANCE * 2.0), 0.0);
+    }
+}
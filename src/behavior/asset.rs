@@ -0,0 +1,222 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+/// The most children a [Parallel](BehaviorNode::Parallel) node can have
+pub const MAX_PARALLEL_CHILDREN: usize = 64;
+
+/// When a [Parallel](BehaviorNode::Parallel) node is done
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+pub enum ParallelPolicy {
+    /// Succeeds once every child succeeds and fails as soon as one fails
+    RequireAll,
+    /// Succeeds as soon as one child succeeds and fails once every child fails
+    RequireOne,
+}
+
+/// A node of a behavior tree, as written in the tree's file
+#[derive(Debug, Clone, Deserialize)]
+pub enum BehaviorNode {
+    /// Runs its children in order until one of them doesn't succeed. A
+    /// running child is picked back up on the next tick.
+    Sequence(Vec<BehaviorNode>),
+    /// Runs its children in order until one of them doesn't fail. Earlier
+    /// children are checked again every tick and interrupt later ones.
+    Selector(Vec<BehaviorNode>),
+    /// Runs all of its children every tick
+    Parallel {
+        policy: ParallelPolicy,
+        children: Vec<BehaviorNode>,
+    },
+    /// Turns success into failure and the other way around
+    Invert(Box<BehaviorNode>),
+    /// Always succeeds once its child is done
+    Succeed(Box<BehaviorNode>),
+    /// Runs its child again every time it succeeds, up to `times` times or
+    /// forever. The child is run at most once per tick.
+    Repeat {
+        times: Option<u32>,
+        child: Box<BehaviorNode>,
+    },
+    /// Fails for `seconds` after its child succeeds
+    Cooldown {
+        seconds: f32,
+        child: Box<BehaviorNode>,
+    },
+    /// Keeps running for a number of seconds, then succeeds
+    Wait(f32),
+    /// Succeeds or fails based on a registered condition function
+    Condition(String),
+    /// Runs a registered action function
+    Action(String),
+}
+
+/// What a [CompiledNode] does
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeKind {
+    Sequence,
+    Selector,
+    Parallel(ParallelPolicy),
+    Invert,
+    Succeed,
+    Repeat(Option<u32>),
+    Cooldown(f32),
+    Wait(f32),
+    Condition(String),
+    Action(String),
+}
+
+/// A node of a [BehaviorTree], flattened so every node has an index its
+/// runtime state can be stored at
+#[derive(Debug, Clone)]
+pub struct CompiledNode {
+    pub kind: NodeKind,
+    pub children: Vec<usize>,
+    /// One past the index of this node's last descendant
+    pub end: usize,
+    /// How deep in the tree the node is, with the root at zero
+    pub depth: usize,
+}
+
+impl CompiledNode {
+    /// A short description of the node, used by the debug overlay
+    pub fn label(&self) -> String {
+        match &self.kind {
+            NodeKind::Sequence => "Sequence".into(),
+            NodeKind::Selector => "Selector".into(),
+            NodeKind::Parallel(policy) => format!("Parallel({:?})", policy),
+            NodeKind::Invert => "Invert".into(),
+            NodeKind::Succeed => "Succeed".into(),
+            NodeKind::Repeat(Some(times)) => format!("Repeat({})", times),
+            NodeKind::Repeat(None) => "Repeat".into(),
+            NodeKind::Cooldown(seconds) => format!("Cooldown({}s)", seconds),
+            NodeKind::Wait(seconds) => format!("Wait({}s)", seconds),
+            NodeKind::Condition(name) => format!("?{}", name),
+            NodeKind::Action(name) => format!("!{}", name),
+        }
+    }
+}
+
+/// Asset holding a behavior tree
+///
+/// The nodes are stored in depth first order, so the root is always the
+/// first node and every node's descendants directly follow it.
+#[derive(TypeUuid, Debug, Clone)]
+#[uuid = "b14e2f07-6c3d-4a58-9e1b-7d20c8f5a6e4"]
+pub struct BehaviorTree {
+    pub nodes: Vec<CompiledNode>,
+}
+
+impl BehaviorTree {
+    /// Flattens a tree
+    ///
+    /// Fails if the tree can't be run, like when a parallel node has too
+    /// many children.
+    pub fn compile(root: &BehaviorNode) -> Result<Self, String> {
+        let mut nodes = vec![];
+        compile_node(root, 0, &mut nodes)?;
+        Ok(Self { nodes })
+    }
+
+    /// Iterates over the names of every condition the tree uses
+    pub fn conditions(&self) -> impl Iterator<Item = &str> {
+        self.nodes.iter().filter_map(|node| match &node.kind {
+            NodeKind::Condition(name) => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Iterates over the names of every action the tree uses
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.nodes.iter().filter_map(|node| match &node.kind {
+            NodeKind::Action(name) => Some(name.as_str()),
+            _ => None,
+        })
+    }
+}
+
+/// Adds a node and its descendants to the list, returning the node's index
+fn compile_node(
+    node: &BehaviorNode,
+    depth: usize,
+    nodes: &mut Vec<CompiledNode>,
+) -> Result<usize, String> {
+    let index = nodes.len();
+    let (kind, children): (NodeKind, Vec<&BehaviorNode>) = match node {
+        BehaviorNode::Sequence(children) => (NodeKind::Sequence, children.iter().collect()),
+        BehaviorNode::Selector(children) => (NodeKind::Selector, children.iter().collect()),
+        BehaviorNode::Parallel { policy, children } => {
+            if children.len() > MAX_PARALLEL_CHILDREN {
+                return Err(format!(
+                    "parallel nodes can have at most {} children",
+                    MAX_PARALLEL_CHILDREN
+                ));
+            }
+            (NodeKind::Parallel(*policy), children.iter().collect())
+        }
+        BehaviorNode::Invert(child) => (NodeKind::Invert, vec![child]),
+        BehaviorNode::Succeed(child) => (NodeKind::Succeed, vec![child]),
+        BehaviorNode::Repeat { times, child } => (NodeKind::Repeat(*times), vec![child]),
+        BehaviorNode::Cooldown { seconds, child } => (NodeKind::Cooldown(*seconds), vec![child]),
+        BehaviorNode::Wait(seconds) => (NodeKind::Wait(*seconds), vec![]),
+        BehaviorNode::Condition(name) => (NodeKind::Condition(name.clone()), vec![]),
+        BehaviorNode::Action(name) => (NodeKind::Action(name.clone()), vec![]),
+    };
+
+    nodes.push(CompiledNode {
+        kind,
+        children: vec![],
+        end: index + 1,
+        depth,
+    });
+
+    let mut child_indices = vec![];
+    for child in children {
+        child_indices.push(compile_node(child, depth + 1, nodes)?);
+    }
+
+    nodes[index].children = child_indices;
+    nodes[index].end = nodes.len();
+    Ok(index)
+}
+
+#[derive(Default)]
+pub struct BehaviorTreeLoader;
+
+impl AssetLoader for BehaviorTreeLoader {
+    /// Load a behavior tree
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext<'_>,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let root: BehaviorNode = ron::de::from_bytes(bytes)?;
+            let tree = BehaviorTree::compile(&root).map_err(|error| {
+                bevy::asset::Error::msg(format!(
+                    "Invalid behavior tree {:?}: {}",
+                    load_context.path(),
+                    error
+                ))
+            })?;
+
+            debug!(
+                "Loaded behavior tree {:?} with {} nodes",
+                load_context.path(),
+                tree.nodes.len()
+            );
+            load_context.set_default_asset(LoadedAsset::new(tree));
+
+            Ok(())
+        })
+    }
+
+    /// Returns a list of supported extensions
+    fn extensions(&self) -> &[&str] {
+        &["bt.ron"]
+    }
+}
@@ -0,0 +1,32 @@
+use super::{
+    asset::BehaviorTree,
+    runtime::{BehaviorMemory, BehaviorStatus},
+};
+use bevy::prelude::*;
+
+/// Drives an entity with a behavior tree
+#[derive(Debug, Component)]
+pub struct Behavior {
+    pub tree: Handle<BehaviorTree>,
+    /// The runtime state of the tree, which is only for this entity
+    pub memory: BehaviorMemory,
+    /// What the tree returned on its last tick
+    pub status: Option<BehaviorStatus>,
+}
+
+impl Behavior {
+    pub fn new(tree: Handle<BehaviorTree>) -> Self {
+        Self {
+            tree,
+            memory: BehaviorMemory::default(),
+            status: None,
+        }
+    }
+}
+
+/// Text showing the state of an entity's behavior tree for debugging
+#[derive(Debug, Component)]
+pub struct BehaviorDebugText {
+    /// The entity whose tree is shown
+    pub owner: Entity,
+}
@@ -0,0 +1,17 @@
+//! Module for behavior trees
+//!
+//! This module includes:
+//! - An asset describing a behavior tree, loaded from a RON file and
+//!   reloaded whenever the file changes
+//! - A runtime ticking trees made of sequence, selector, parallel, decorator,
+//!   condition and action nodes
+//! - A registry of the Rust functions the condition and action leaves call
+//! - A debug overlay showing what every tree did on its last tick
+//! - A plugin to setup the required systems, loaders and resources
+
+pub mod asset;
+pub mod components;
+pub mod plugin;
+pub mod resources;
+pub mod runtime;
+pub mod systems;
@@ -0,0 +1,55 @@
+use super::{
+    asset::{BehaviorTree, BehaviorTreeLoader},
+    resources::{ActionFn, BehaviorDebug, BehaviorRegistry, ConditionFn},
+    systems::{
+        handle_behavior_tree_changes, tick_behavior_trees, toggle_behavior_debug,
+        update_behavior_debug,
+    },
+};
+use crate::{dialogue::systems::dialogue_closed, menu::state::GameMenuState};
+use bevy::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct BehaviorPlugin;
+
+impl Plugin for BehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<BehaviorTree>();
+        app.init_asset_loader::<BehaviorTreeLoader>();
+
+        app.init_resource::<BehaviorRegistry>();
+        app.init_resource::<BehaviorDebug>();
+
+        app.add_system(handle_behavior_tree_changes);
+        app.add_system(
+            tick_behavior_trees
+                .run_if(dialogue_closed)
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_system(toggle_behavior_debug);
+        app.add_system(update_behavior_debug.after(toggle_behavior_debug));
+        debug!("BehaviorPlugin loaded");
+    }
+}
+
+/// Lets plugins register the leaves their behavior trees use
+pub trait BehaviorAppExt {
+    fn register_behavior_condition(&mut self, name: &str, condition: ConditionFn) -> &mut Self;
+    fn register_behavior_action(&mut self, name: &str, action: ActionFn) -> &mut Self;
+}
+
+impl BehaviorAppExt for App {
+    fn register_behavior_condition(&mut self, name: &str, condition: ConditionFn) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(BehaviorRegistry::default)
+            .add_condition(name, condition);
+        self
+    }
+
+    fn register_behavior_action(&mut self, name: &str, action: ActionFn) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(BehaviorRegistry::default)
+            .add_action(name, action);
+        self
+    }
+}
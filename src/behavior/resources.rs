@@ -0,0 +1,80 @@
+use super::runtime::{BehaviorStatus, LeafRunner};
+use bevy::{prelude::*, utils::HashMap};
+
+/// A function behind a condition leaf
+pub type ConditionFn = fn(Entity, &World) -> bool;
+
+/// A function behind an action leaf
+pub type ActionFn = fn(&mut ActionContext) -> BehaviorStatus;
+
+/// Everything an action gets to work with
+pub struct ActionContext<'w> {
+    /// The entity the tree belongs to
+    pub entity: Entity,
+    pub world: &'w mut World,
+    /// How long the action has been running for. This is zero the first
+    /// time the action is ticked.
+    pub running_for: f32,
+}
+
+/// The functions behind the leaves of behavior trees, keyed by the names
+/// trees refer to them with
+#[derive(Debug, Default, Clone, Resource)]
+pub struct BehaviorRegistry {
+    conditions: HashMap<String, ConditionFn>,
+    actions: HashMap<String, ActionFn>,
+}
+
+impl BehaviorRegistry {
+    pub fn add_condition(&mut self, name: impl Into<String>, condition: ConditionFn) {
+        self.conditions.insert(name.into(), condition);
+    }
+
+    pub fn add_action(&mut self, name: impl Into<String>, action: ActionFn) {
+        self.actions.insert(name.into(), action);
+    }
+
+    pub fn has_condition(&self, name: &str) -> bool {
+        self.conditions.contains_key(name)
+    }
+
+    pub fn has_action(&self, name: &str) -> bool {
+        self.actions.contains_key(name)
+    }
+}
+
+/// Runs leaves through the registry for a single entity
+pub struct WorldLeaves<'a> {
+    pub entity: Entity,
+    pub world: &'a mut World,
+    pub registry: &'a BehaviorRegistry,
+}
+
+impl LeafRunner for WorldLeaves<'_> {
+    /// Unknown conditions always fail
+    fn condition(&mut self, name: &str) -> bool {
+        self.registry
+            .conditions
+            .get(name)
+            .is_some_and(|condition| condition(self.entity, self.world))
+    }
+
+    /// Unknown actions always fail
+    fn action(&mut self, name: &str, running_for: f32) -> BehaviorStatus {
+        let Some(action) = self.registry.actions.get(name) else {
+            return BehaviorStatus::Failure;
+        };
+
+        action(&mut ActionContext {
+            entity: self.entity,
+            world: self.world,
+            running_for,
+        })
+    }
+}
+
+/// Whether the behavior tree debug overlay is shown
+#[derive(Debug, Default, Resource)]
+pub struct BehaviorDebug {
+    pub enabled: bool,
+}
@@ -0,0 +1,542 @@
+//! Ticks behavior trees
+//!
+//! The runtime state of a tree lives in a [BehaviorMemory], kept apart from
+//! the [BehaviorTree] asset so many entities can share one tree. Leaves are
+//! run through a [LeafRunner], which lets the runtime be tested without a
+//! [World].
+//!
+//! A tick visits every node at most once, so the time a tick takes is bounded
+//! by the size of the tree and the leaves it calls. A [Repeat](NodeKind::Repeat)
+//! node for example only runs its child once per tick rather than looping
+//! until it's done.
+
+use super::asset::{BehaviorTree, NodeKind, ParallelPolicy};
+
+/// The result of ticking a node
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BehaviorStatus {
+    Success,
+    Failure,
+    Running,
+}
+
+impl BehaviorStatus {
+    /// Whether the node is done, one way or another
+    pub fn is_done(self) -> bool {
+        self != Self::Running
+    }
+}
+
+/// Runs the leaves of a tree
+pub trait LeafRunner {
+    /// Checks a condition
+    fn condition(&mut self, name: &str) -> bool;
+
+    /// Runs an action. `running_for` is how long the action has been
+    /// running for, and is zero the first time it's ticked.
+    fn action(&mut self, name: &str, running_for: f32) -> BehaviorStatus;
+}
+
+/// The runtime state of a single node
+#[derive(Debug, Default, Clone)]
+pub struct NodeMemory {
+    /// The child a sequence or selector is running
+    cursor: usize,
+    /// How many times a repeat node's child has succeeded
+    count: u32,
+    /// How long a leaf has been running for
+    elapsed: f32,
+    /// Which children of a parallel node are done
+    succeeded: u64,
+    failed: u64,
+    /// The tree time a cooldown node is ready again at
+    ready_at: f32,
+    /// The status the node returned the last time it was ticked
+    pub last_status: Option<BehaviorStatus>,
+    /// The tick the node was last ticked on
+    pub last_tick: u64,
+}
+
+impl NodeMemory {
+    /// Forgets about the node's progress, keeping the cooldown and debug
+    /// information
+    fn reset(&mut self) {
+        *self = Self {
+            ready_at: self.ready_at,
+            last_status: self.last_status,
+            last_tick: self.last_tick,
+            ..Default::default()
+        };
+    }
+}
+
+/// The runtime state of a behavior tree
+#[derive(Debug, Default, Clone)]
+pub struct BehaviorMemory {
+    nodes: Vec<NodeMemory>,
+    /// How long the tree has been running for, in seconds
+    time: f32,
+    /// How many times the tree has been ticked
+    ticks: u64,
+}
+
+impl BehaviorMemory {
+    pub fn new(tree: &BehaviorTree) -> Self {
+        Self {
+            nodes: vec![NodeMemory::default(); tree.nodes.len()],
+            ..Default::default()
+        }
+    }
+
+    /// Whether the memory was made for a tree shaped like this one
+    pub fn fits(&self, tree: &BehaviorTree) -> bool {
+        self.nodes.len() == tree.nodes.len()
+    }
+
+    /// The runtime state of every node, in the same order as the tree's nodes
+    pub fn nodes(&self) -> &[NodeMemory] {
+        &self.nodes
+    }
+
+    /// Whether a node was visited on the last tick
+    pub fn visited_last_tick(&self, index: usize) -> bool {
+        self.nodes
+            .get(index)
+            .is_some_and(|node| node.last_tick == self.ticks && self.ticks > 0)
+    }
+
+    /// Ticks the tree once, `delta` seconds after the last tick
+    pub fn tick(
+        &mut self,
+        tree: &BehaviorTree,
+        delta: f32,
+        leaves: &mut impl LeafRunner,
+    ) -> BehaviorStatus {
+        if !self.fits(tree) {
+            *self = Self::new(tree);
+        }
+        if tree.nodes.is_empty() {
+            return BehaviorStatus::Failure;
+        }
+
+        self.time += delta;
+        self.ticks += 1;
+        self.tick_node(tree, 0, delta, leaves)
+    }
+
+    fn tick_node(
+        &mut self,
+        tree: &BehaviorTree,
+        index: usize,
+        delta: f32,
+        leaves: &mut impl LeafRunner,
+    ) -> BehaviorStatus {
+        let node = &tree.nodes[index];
+
+        let status = match &node.kind {
+            NodeKind::Sequence => {
+                let mut status = BehaviorStatus::Success;
+                for position in self.nodes[index].cursor..node.children.len() {
+                    match self.tick_node(tree, node.children[position], delta, leaves) {
+                        BehaviorStatus::Success => continue,
+                        BehaviorStatus::Running => {
+                            self.nodes[index].cursor = position;
+                            status = BehaviorStatus::Running;
+                            break;
+                        }
+                        BehaviorStatus::Failure => {
+                            status = BehaviorStatus::Failure;
+                            break;
+                        }
+                    }
+                }
+                status
+            }
+            NodeKind::Selector => {
+                let running = self.nodes[index].cursor;
+                let mut status = BehaviorStatus::Failure;
+                let mut position = 0;
+                while position < node.children.len() {
+                    status = self.tick_node(tree, node.children[position], delta, leaves);
+                    if status != BehaviorStatus::Failure {
+                        break;
+                    }
+                    position += 1;
+                }
+
+                // A higher priority child took over, so stop the one that
+                // was running
+                if position < running && running < node.children.len() {
+                    self.reset_subtree(tree, node.children[running]);
+                }
+                self.nodes[index].cursor = position;
+                status
+            }
+            NodeKind::Parallel(policy) => {
+                for (position, &child) in node.children.iter().enumerate() {
+                    let bit = 1 << position;
+                    let memory = &self.nodes[index];
+                    if (memory.succeeded | memory.failed) & bit != 0 {
+                        continue;
+                    }
+
+                    match self.tick_node(tree, child, delta, leaves) {
+                        BehaviorStatus::Success => self.nodes[index].succeeded |= bit,
+                        BehaviorStatus::Failure => self.nodes[index].failed |= bit,
+                        BehaviorStatus::Running => {}
+                    }
+                }
+
+                let memory = &self.nodes[index];
+                let children = node.children.len() as u32;
+                let succeeded = memory.succeeded.count_ones();
+                let failed = memory.failed.count_ones();
+                match policy {
+                    ParallelPolicy::RequireAll if failed > 0 => BehaviorStatus::Failure,
+                    ParallelPolicy::RequireAll if succeeded == children => BehaviorStatus::Success,
+                    ParallelPolicy::RequireOne if succeeded > 0 => BehaviorStatus::Success,
+                    ParallelPolicy::RequireOne if failed == children => BehaviorStatus::Failure,
+                    _ => BehaviorStatus::Running,
+                }
+            }
+            NodeKind::Invert => match self.tick_node(tree, node.children[0], delta, leaves) {
+                BehaviorStatus::Success => BehaviorStatus::Failure,
+                BehaviorStatus::Failure => BehaviorStatus::Success,
+                BehaviorStatus::Running => BehaviorStatus::Running,
+            },
+            NodeKind::Succeed => match self.tick_node(tree, node.children[0], delta, leaves) {
+                BehaviorStatus::Running => BehaviorStatus::Running,
+                _ => BehaviorStatus::Success,
+            },
+            NodeKind::Repeat(times) => {
+                match self.tick_node(tree, node.children[0], delta, leaves) {
+                    BehaviorStatus::Success => {
+                        self.nodes[index].count += 1;
+                        if times.is_some_and(|times| self.nodes[index].count >= times) {
+                            BehaviorStatus::Success
+                        } else {
+                            // Run the child again on the next tick
+                            BehaviorStatus::Running
+                        }
+                    }
+                    status => status,
+                }
+            }
+            NodeKind::Cooldown(seconds) => {
+                if self.time < self.nodes[index].ready_at {
+                    BehaviorStatus::Failure
+                } else {
+                    let status = self.tick_node(tree, node.children[0], delta, leaves);
+                    if status == BehaviorStatus::Success {
+                        self.nodes[index].ready_at = self.time + seconds;
+                    }
+                    status
+                }
+            }
+            NodeKind::Wait(seconds) => {
+                self.nodes[index].elapsed += delta;
+                if self.nodes[index].elapsed >= *seconds {
+                    BehaviorStatus::Success
+                } else {
+                    BehaviorStatus::Running
+                }
+            }
+            NodeKind::Condition(name) => {
+                if leaves.condition(name) {
+                    BehaviorStatus::Success
+                } else {
+                    BehaviorStatus::Failure
+                }
+            }
+            NodeKind::Action(name) => {
+                let status = leaves.action(name, self.nodes[index].elapsed);
+                self.nodes[index].elapsed += delta;
+                status
+            }
+        };
+
+        // Finished nodes start over the next time they're ticked
+        if status.is_done() {
+            self.reset_subtree(tree, index);
+        }
+
+        let memory = &mut self.nodes[index];
+        memory.last_status = Some(status);
+        memory.last_tick = self.ticks;
+        status
+    }
+
+    /// Forgets about the progress of a node and all of its descendants
+    fn reset_subtree(&mut self, tree: &BehaviorTree, index: usize) {
+        for memory in &mut self.nodes[index..tree.nodes[index].end] {
+            memory.reset();
+        }
+    }
+}
+
+/// Writes out every node of a tree along with the status it returned on the
+/// last tick, one node per line
+///
+/// Nodes that weren't visited on the last tick have no status.
+pub fn describe_tree(tree: &BehaviorTree, memory: &BehaviorMemory) -> String {
+    let mut description = String::new();
+
+    for (index, node) in tree.nodes.iter().enumerate() {
+        let status = match memory.nodes().get(index) {
+            Some(node_memory) if memory.visited_last_tick(index) => match node_memory.last_status {
+                Some(BehaviorStatus::Success) => " [S]",
+                Some(BehaviorStatus::Failure) => " [F]",
+                Some(BehaviorStatus::Running) => " [R]",
+                None => "",
+            },
+            _ => "",
+        };
+
+        if index > 0 {
+            description.push('\n');
+        }
+        description.push_str(&"  ".repeat(node.depth));
+        description.push_str(&node.label());
+        description.push_str(status);
+    }
+
+    description
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::behavior::asset::BehaviorNode;
+    use std::collections::HashMap;
+
+    /// Leaves whose results are set by the test, counting how often each
+    /// one runs
+    #[derive(Default)]
+    struct TestLeaves {
+        conditions: HashMap<&'static str, bool>,
+        actions: HashMap<&'static str, BehaviorStatus>,
+        calls: HashMap<String, u32>,
+    }
+
+    impl TestLeaves {
+        fn calls(&self, name: &str) -> u32 {
+            self.calls.get(name).copied().unwrap_or_default()
+        }
+    }
+
+    impl LeafRunner for TestLeaves {
+        fn condition(&mut self, name: &str) -> bool {
+            *self.calls.entry(name.into()).or_default() += 1;
+            self.conditions.get(name).copied().unwrap_or_default()
+        }
+
+        fn action(&mut self, name: &str, _running_for: f32) -> BehaviorStatus {
+            *self.calls.entry(name.into()).or_default() += 1;
+            self.actions
+                .get(name)
+                .copied()
+                .unwrap_or(BehaviorStatus::Failure)
+        }
+    }
+
+    fn tree(source: &str) -> BehaviorTree {
+        let root: BehaviorNode = ron::from_str(source).unwrap();
+        BehaviorTree::compile(&root).unwrap()
+    }
+
+    #[test]
+    fn compiles_depth_first() {
+        let tree = tree(r#"Selector([Sequence([Condition("a"), Action("b")]), Action("c")])"#);
+
+        assert_eq!(tree.nodes.len(), 5);
+        assert_eq!(tree.nodes[0].children, vec![1, 4]);
+        assert_eq!(tree.nodes[1].children, vec![2, 3]);
+        assert_eq!(tree.nodes[1].end, 4);
+        assert_eq!(tree.nodes[3].depth, 2);
+        assert_eq!(tree.actions().collect::<Vec<_>>(), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn sequence_resumes_running_child() {
+        let tree = tree(r#"Sequence([Action("a"), Action("b")])"#);
+        let mut leaves = TestLeaves::default();
+        leaves.actions.insert("a", BehaviorStatus::Success);
+        leaves.actions.insert("b", BehaviorStatus::Running);
+        let mut memory = BehaviorMemory::new(&tree);
+
+        assert_eq!(
+            memory.tick(&tree, 0.1, &mut leaves),
+            BehaviorStatus::Running
+        );
+        assert_eq!(
+            memory.tick(&tree, 0.1, &mut leaves),
+            BehaviorStatus::Running
+        );
+        assert_eq!(leaves.calls("a"), 1);
+        assert_eq!(leaves.calls("b"), 2);
+
+        leaves.actions.insert("b", BehaviorStatus::Success);
+        assert_eq!(
+            memory.tick(&tree, 0.1, &mut leaves),
+            BehaviorStatus::Success
+        );
+
+        // Finished sequences start over
+        memory.tick(&tree, 0.1, &mut leaves);
+        assert_eq!(leaves.calls("a"), 2);
+    }
+
+    #[test]
+    fn selector_lets_higher_priority_children_interrupt() {
+        let tree = tree(r#"Selector([Condition("alert"), Sequence([Action("a"), Action("b")])])"#);
+        let mut leaves = TestLeaves::default();
+        leaves.actions.insert("a", BehaviorStatus::Success);
+        leaves.actions.insert("b", BehaviorStatus::Running);
+        let mut memory = BehaviorMemory::new(&tree);
+
+        assert_eq!(
+            memory.tick(&tree, 0.1, &mut leaves),
+            BehaviorStatus::Running
+        );
+        assert_eq!(leaves.calls("alert"), 1);
+
+        leaves.conditions.insert("alert", true);
+        assert_eq!(
+            memory.tick(&tree, 0.1, &mut leaves),
+            BehaviorStatus::Success
+        );
+        assert!(!memory.visited_last_tick(2));
+
+        // The interrupted sequence starts over from its first child
+        leaves.conditions.insert("alert", false);
+        memory.tick(&tree, 0.1, &mut leaves);
+        assert_eq!(leaves.calls("a"), 2);
+    }
+
+    #[test]
+    fn parallel_policies() {
+        let source = |policy| {
+            format!(
+                r#"Parallel(policy: {}, children: [Action("a"), Action("b")])"#,
+                policy
+            )
+        };
+        let mut leaves = TestLeaves::default();
+        leaves.actions.insert("a", BehaviorStatus::Success);
+        leaves.actions.insert("b", BehaviorStatus::Running);
+
+        let all = tree(&source("RequireAll"));
+        let mut memory = BehaviorMemory::new(&all);
+        assert_eq!(memory.tick(&all, 0.1, &mut leaves), BehaviorStatus::Running);
+        assert_eq!(memory.tick(&all, 0.1, &mut leaves), BehaviorStatus::Running);
+        // Finished children aren't run again
+        assert_eq!(leaves.calls("a"), 1);
+
+        let one = tree(&source("RequireOne"));
+        let mut memory = BehaviorMemory::new(&one);
+        assert_eq!(memory.tick(&one, 0.1, &mut leaves), BehaviorStatus::Success);
+    }
+
+    #[test]
+    fn decorators() {
+        let mut leaves = TestLeaves::default();
+        leaves.actions.insert("a", BehaviorStatus::Success);
+
+        let inverted = tree(r#"Invert(Action("a"))"#);
+        let mut memory = BehaviorMemory::new(&inverted);
+        assert_eq!(
+            memory.tick(&inverted, 0.1, &mut leaves),
+            BehaviorStatus::Failure
+        );
+
+        let succeed = tree(r#"Succeed(Condition("missing"))"#);
+        let mut memory = BehaviorMemory::new(&succeed);
+        assert_eq!(
+            memory.tick(&succeed, 0.1, &mut leaves),
+            BehaviorStatus::Success
+        );
+    }
+
+    #[test]
+    fn repeat_runs_child_once_per_tick() {
+        let three_times = tree(r#"Repeat(times: Some(3), child: Action("a"))"#);
+        let mut leaves = TestLeaves::default();
+        leaves.actions.insert("a", BehaviorStatus::Success);
+        let mut memory = BehaviorMemory::new(&three_times);
+
+        assert_eq!(
+            memory.tick(&three_times, 0.1, &mut leaves),
+            BehaviorStatus::Running
+        );
+        assert_eq!(
+            memory.tick(&three_times, 0.1, &mut leaves),
+            BehaviorStatus::Running
+        );
+        assert_eq!(
+            memory.tick(&three_times, 0.1, &mut leaves),
+            BehaviorStatus::Success
+        );
+        assert_eq!(leaves.calls("a"), 3);
+
+        // Repeating forever still only runs the child once per tick
+        let forever = tree(r#"Repeat(times: None, child: Action("a"))"#);
+        let mut memory = BehaviorMemory::new(&forever);
+        assert_eq!(
+            memory.tick(&forever, 0.1, &mut leaves),
+            BehaviorStatus::Running
+        );
+        assert_eq!(leaves.calls("a"), 4);
+    }
+
+    #[test]
+    fn cooldown_and_wait_use_tree_time() {
+        let tree = tree(r#"Selector([Cooldown(seconds: 1.0, child: Action("a")), Wait(0.25)])"#);
+        let mut leaves = TestLeaves::default();
+        leaves.actions.insert("a", BehaviorStatus::Success);
+        let mut memory = BehaviorMemory::new(&tree);
+
+        assert_eq!(
+            memory.tick(&tree, 0.1, &mut leaves),
+            BehaviorStatus::Success
+        );
+        // The cooldown falls through to the wait
+        assert_eq!(
+            memory.tick(&tree, 0.1, &mut leaves),
+            BehaviorStatus::Running
+        );
+        assert_eq!(
+            memory.tick(&tree, 0.2, &mut leaves),
+            BehaviorStatus::Success
+        );
+        assert_eq!(leaves.calls("a"), 1);
+
+        memory.tick(&tree, 1.0, &mut leaves);
+        assert_eq!(leaves.calls("a"), 2);
+    }
+
+    #[test]
+    fn describes_last_tick() {
+        let tree = tree(r#"Selector([Condition("a"), Action("b")])"#);
+        let mut leaves = TestLeaves::default();
+        leaves.conditions.insert("a", true);
+        let mut memory = BehaviorMemory::new(&tree);
+        memory.tick(&tree, 0.1, &mut leaves);
+
+        assert_eq!(
+            describe_tree(&tree, &memory),
+            "Selector [S]\n  ?a [S]\n  !b"
+        );
+    }
+
+    #[test]
+    fn memory_resets_when_the_tree_changes() {
+        let small = tree(r#"Action("a")"#);
+        let large = tree(r#"Sequence([Action("a"), Action("b")])"#);
+        let mut leaves = TestLeaves::default();
+        let mut memory = BehaviorMemory::new(&small);
+
+        assert!(!memory.fits(&large));
+        memory.tick(&large, 0.1, &mut leaves);
+        assert!(memory.fits(&large));
+    }
+}
@@ -0,0 +1,160 @@
+//! Defines all of the systems used by the behavior plugin
+
+use super::{
+    asset::BehaviorTree,
+    components::{Behavior, BehaviorDebugText},
+    resources::{BehaviorDebug, BehaviorRegistry, WorldLeaves},
+    runtime::{describe_tree, BehaviorMemory},
+};
+use crate::{
+    constants::{DRAW_LAYER, KEYMAP},
+    theme::resources::CurrentTheme,
+};
+use bevy::{prelude::*, sprite::Anchor, utils::HashSet};
+
+/// How far above its owner the debug overlay is drawn, in pixels
+const DEBUG_OFFSET: f32 = 24.0;
+
+/// Ticks the behavior tree of every entity with a [Behavior]
+///
+/// Actions get full access to the world, so this is an exclusive system.
+pub fn tick_behavior_trees(world: &mut World) {
+    let delta = world.resource::<Time>().delta_seconds();
+    let registry = world.resource::<BehaviorRegistry>().clone();
+    let entities: Vec<Entity> = world
+        .query_filtered::<Entity, With<Behavior>>()
+        .iter(world)
+        .collect();
+
+    world.resource_scope(|world, trees: Mut<Assets<BehaviorTree>>| {
+        for entity in entities {
+            // Take the memory out of the component so the actions can borrow
+            // the world while the tree is ticked
+            let Some(mut behavior) = world.get_mut::<Behavior>(entity) else {
+                continue;
+            };
+
+            // The tree might still be loading
+            let Some(tree) = trees.get(&behavior.tree) else {
+                continue;
+            };
+            let mut memory = std::mem::take(&mut behavior.memory);
+
+            let mut leaves = WorldLeaves {
+                entity,
+                world,
+                registry: &registry,
+            };
+            let status = memory.tick(tree, delta, &mut leaves);
+
+            // Actions are allowed to despawn the entity
+            if let Some(mut behavior) = world.get_mut::<Behavior>(entity) {
+                behavior.memory = memory;
+                behavior.status = Some(status);
+            }
+        }
+    });
+}
+
+/// Checks loaded trees for leaves that don't exist and starts the trees of
+/// reloaded files over
+pub fn handle_behavior_tree_changes(
+    mut tree_events: EventReader<AssetEvent<BehaviorTree>>,
+    trees: Res<Assets<BehaviorTree>>,
+    registry: Res<BehaviorRegistry>,
+    mut behavior_query: Query<&mut Behavior>,
+) {
+    for event in tree_events.iter() {
+        let (handle, reloaded) = match event {
+            AssetEvent::Created { handle } => (handle, false),
+            AssetEvent::Modified { handle } => (handle, true),
+            AssetEvent::Removed { .. } => continue,
+        };
+        let Some(tree) = trees.get(handle) else {
+            continue;
+        };
+
+        for name in tree
+            .conditions()
+            .filter(|name| !registry.has_condition(name))
+        {
+            warn!("Behavior tree uses unknown condition {:?}", name);
+        }
+        for name in tree.actions().filter(|name| !registry.has_action(name)) {
+            warn!("Behavior tree uses unknown action {:?}", name);
+        }
+
+        if reloaded {
+            info!("Reloaded a behavior tree with {} nodes", tree.nodes.len());
+            for mut behavior in &mut behavior_query {
+                if behavior.tree == *handle {
+                    behavior.memory = BehaviorMemory::new(tree);
+                    behavior.status = None;
+                }
+            }
+        }
+    }
+}
+
+/// Shows or hides the behavior tree debug overlay
+pub fn toggle_behavior_debug(keys: Res<Input<KeyCode>>, mut debug: ResMut<BehaviorDebug>) {
+    if keys.just_pressed(KEYMAP::DEBUG_BEHAVIOR) {
+        debug.enabled = !debug.enabled;
+    }
+}
+
+/// Draws the state of every behavior tree above its entity while the debug
+/// overlay is shown
+pub fn update_behavior_debug(
+    mut commands: Commands,
+    debug: Res<BehaviorDebug>,
+    theme: Res<CurrentTheme>,
+    trees: Res<Assets<BehaviorTree>>,
+    behavior_query: Query<(Entity, &Behavior, &GlobalTransform)>,
+    mut text_query: Query<(Entity, &BehaviorDebugText, &mut Text, &mut Transform)>,
+) {
+    let mut shown = HashSet::new();
+
+    for (text_entity, debug_text, mut text, mut transform) in &mut text_query {
+        let Ok((_, behavior, owner_transform)) = behavior_query.get(debug_text.owner) else {
+            commands.entity(text_entity).despawn_recursive();
+            continue;
+        };
+        if !debug.enabled {
+            commands.entity(text_entity).despawn_recursive();
+            continue;
+        }
+
+        shown.insert(debug_text.owner);
+        transform.translation = owner_transform.translation().truncate().extend(0.0)
+            + Vec3::new(0.0, DEBUG_OFFSET, DRAW_LAYER::EFFECTS);
+        if let Some(tree) = trees.get(&behavior.tree) {
+            text.sections[0].value = describe_tree(tree, &behavior.memory);
+        }
+    }
+
+    if !debug.enabled {
+        return;
+    }
+
+    for (entity, _, transform) in &behavior_query {
+        if shown.contains(&entity) {
+            continue;
+        }
+
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section("", theme.text_style("heading", "debug", "text")),
+                text_anchor: Anchor::BottomCenter,
+                transform: Transform::from_translation(
+                    transform
+                        .translation()
+                        .truncate()
+                        .extend(DRAW_LAYER::EFFECTS),
+                ),
+                ..default()
+            },
+            BehaviorDebugText { owner: entity },
+        ));
+    }
+}
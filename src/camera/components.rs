@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+/// Marker component for the game's camera
+#[derive(Component)]
+pub struct GameCamera;
+
+/// Marks an entity as the one the camera should follow
+///
+/// If there are several targets, the camera follows whichever one it finds
+/// first.
+#[derive(Debug, Component)]
+pub struct CameraTarget;
+
+/// Configures and holds the state of a camera following a [CameraTarget]
+#[derive(Debug, Component)]
+pub struct CameraController {
+    /// How quickly the camera catches up with its focus point.
+    ///
+    /// This is the angular frequency of a critically damped spring, so
+    /// higher values mean a snappier camera.
+    pub smoothing: f32,
+    /// Half the size of the rectangle the target can move within without
+    /// moving the camera, in world units
+    pub deadzone: Vec2,
+    /// The current zoom level. Each level is an integer multiple of the
+    /// pixel size so the pixel art never gets blurry.
+    pub zoom: u32,
+    /// The smallest allowed zoom level
+    pub min_zoom: u32,
+    /// The largest allowed zoom level
+    pub max_zoom: u32,
+    /// The furthest the camera can be pushed away by screen shake, in pixels
+    pub max_shake: f32,
+    /// How much trauma is removed every second
+    pub trauma_decay: f32,
+    /// Current trauma between 0 and 1. Shake grows with the square of it.
+    pub trauma: f32,
+    /// The point the camera is trying to reach
+    pub(super) focus: Option<Vec2>,
+    /// The position of the camera before any shake is applied
+    pub(super) position: Vec2,
+    /// The velocity of the smoothing spring
+    pub(super) velocity: Vec2,
+}
+
+impl Default for CameraController {
+    fn default() -> Self {
+        Self {
+            smoothing: 8.0,
+            deadzone: Vec2::new(48.0, 32.0),
+            zoom: 1,
+            min_zoom: 1,
+            max_zoom: 4,
+            max_shake: 12.0,
+            trauma_decay: 1.5,
+            trauma: 0.0,
+            focus: None,
+            position: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+        }
+    }
+}
+
+impl CameraController {
+    /// Moves the camera straight to the target, skipping any smoothing
+    ///
+    /// This is useful after teleporting the target, like when respawning.
+    pub fn snap_to(&mut self, position: Vec2) {
+        self.focus = Some(position);
+        self.position = position;
+        self.velocity = Vec2::ZERO;
+    }
+}
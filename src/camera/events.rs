@@ -0,0 +1,6 @@
+/// Adds trauma to the camera, making the screen shake
+///
+/// The amount is added to the camera's current trauma which is capped at 1.
+/// Small hits should use values around 0.2 and big explosions around 0.6.
+#[derive(Debug, Clone, Copy)]
+pub struct CameraTraumaEvent(pub f32);
@@ -0,0 +1,14 @@
+//! Module for the game's camera
+//!
+//! This module includes:
+//! - A controller that smoothly follows a target within a deadzone
+//! - Clamping of the camera to the bounds of the loaded map
+//! - Integer zoom levels so the pixel art stays crisp
+//! - Screen shake driven by trauma events
+//! - A plugin to setup the required systems and events
+
+pub mod components;
+pub mod events;
+pub mod plugin;
+pub mod systems;
+pub mod util;
@@ -0,0 +1,27 @@
+use super::{
+    events::CameraTraumaEvent,
+    systems::{add_camera_trauma, handle_zoom_input, update_camera},
+};
+use bevy::{prelude::*, transform::TransformSystem};
+use bevy_rapier2d::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CameraTraumaEvent>();
+
+        app.add_system(handle_zoom_input);
+        app.add_system(add_camera_trauma);
+
+        // The camera has to move after the physics have moved its target
+        app.add_system(
+            update_camera
+                .in_base_set(CoreSet::PostUpdate)
+                .after(PhysicsSet::Writeback)
+                .before(TransformSystem::TransformPropagate),
+        );
+        debug!("CameraPlugin loaded");
+    }
+}
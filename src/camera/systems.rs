@@ -0,0 +1,161 @@
+//! Defines all of the systems used by the camera plugin
+
+use super::{
+    components::{CameraController, CameraTarget, GameCamera},
+    events::CameraTraumaEvent,
+    util::{apply_deadzone, clamp_to_bounds, critically_damped, decay_trauma, shake_noise},
+};
+use crate::{
+    map::{asset::MapAsset, state::MapState},
+    settings::resources::Settings,
+};
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+/// How many pixels of scrolling it takes to change the zoom level on
+/// touchpads and other high precision devices
+const PIXELS_PER_ZOOM_STEP: f32 = 50.0;
+
+/// Changes the zoom level with the mouse wheel or the gamepad's triggers
+pub fn handle_zoom_input(
+    mut wheel_events: EventReader<MouseWheel>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut cameras: Query<&mut CameraController, With<GameCamera>>,
+) {
+    let mut steps: f32 = 0.0;
+
+    for event in wheel_events.iter() {
+        steps += match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_ZOOM_STEP,
+        };
+    }
+
+    for gamepad in gamepads.iter() {
+        if gamepad_buttons
+            .just_pressed(GamepadButton::new(gamepad, GamepadButtonType::RightTrigger))
+        {
+            steps += 1.0;
+        }
+        if gamepad_buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::LeftTrigger))
+        {
+            steps -= 1.0;
+        }
+    }
+
+    let steps = steps.round() as i32;
+    if steps == 0 {
+        return;
+    }
+
+    for mut controller in &mut cameras {
+        let zoom = (controller.zoom as i32 + steps)
+            .clamp(controller.min_zoom as i32, controller.max_zoom as i32);
+        controller.zoom = zoom as u32;
+    }
+}
+
+/// Adds the trauma from every [CameraTraumaEvent] to the cameras, scaled by
+/// the screen shake setting
+pub fn add_camera_trauma(
+    mut trauma_events: EventReader<CameraTraumaEvent>,
+    settings: Res<Settings>,
+    mut cameras: Query<&mut CameraController, With<GameCamera>>,
+) {
+    let trauma: f32 =
+        trauma_events.iter().map(|event| event.0).sum::<f32>() * settings.screen_shake;
+    if trauma <= 0.0 {
+        return;
+    }
+
+    for mut controller in &mut cameras {
+        controller.trauma = (controller.trauma + trauma).min(1.0);
+    }
+}
+
+/// Moves the camera towards its target, keeps it in the map and shakes it
+///
+/// This runs after the physics have moved the target so the camera never
+/// lags a frame behind.
+#[allow(clippy::type_complexity)]
+pub fn update_camera(
+    time: Res<Time>,
+    map_state: Res<MapState>,
+    map_server: Res<Assets<MapAsset>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    // The global transform isn't propagated until after the camera moves, so
+    // it would still hold where the target was last frame
+    targets: Query<&Transform, (With<CameraTarget>, Without<GameCamera>)>,
+    mut cameras: Query<
+        (
+            &mut CameraController,
+            &mut Transform,
+            &mut OrthographicProjection,
+        ),
+        With<GameCamera>,
+    >,
+) {
+    let delta = time.delta_seconds();
+    let target = targets.iter().next().map(|t| t.translation.truncate());
+    let bounds = map_server
+        .get(&map_state.handle)
+        .map(|map| map.pixel_bounds());
+    let window_size = windows
+        .get_single()
+        .map(|window| Vec2::new(window.width(), window.height()))
+        .unwrap_or(Vec2::ZERO);
+
+    for (mut controller, mut transform, mut projection) in &mut cameras {
+        let zoom = controller.zoom.max(1) as f32;
+        let scale = 1.0 / zoom;
+        if projection.scale != scale {
+            projection.scale = scale;
+        }
+
+        // Follow the target, jumping straight to it the first time it's seen
+        if let Some(target) = target {
+            match controller.focus {
+                Some(focus) => {
+                    let deadzone = controller.deadzone;
+                    controller.focus = Some(apply_deadzone(focus, target, deadzone));
+                }
+                None => controller.snap_to(target),
+            }
+        }
+
+        if let Some(focus) = controller.focus {
+            let smoothing = controller.smoothing;
+            let position = controller.position;
+            let mut velocity = controller.velocity;
+            controller.position =
+                critically_damped(position, &mut velocity, focus, smoothing, delta);
+            controller.velocity = velocity;
+        }
+
+        // Keep the view inside the map
+        let mut position = controller.position;
+        if let Some(bounds) = bounds {
+            position = clamp_to_bounds(position, window_size * scale / 2.0, bounds);
+        }
+
+        // Shake grows with the square of the trauma so small hits stay subtle
+        let shake = controller.trauma * controller.trauma;
+        if shake > 0.0 {
+            let elapsed = time.elapsed_seconds();
+            position += Vec2::new(shake_noise(elapsed, 0.0), shake_noise(elapsed, 10.0))
+                * controller.max_shake
+                * shake
+                * scale;
+            controller.trauma = decay_trauma(controller.trauma, controller.trauma_decay, delta);
+        }
+
+        // Snap to the pixel grid so the pixel art stays crisp
+        let position = (position * zoom).round() / zoom;
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
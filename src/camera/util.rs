@@ -0,0 +1,131 @@
+//! Utilities for the camera module
+
+use bevy::prelude::*;
+
+/// Moves `position` towards `target` like a critically damped spring.
+///
+/// `velocity` holds the state of the spring between calls and `omega`
+/// controls how quickly the target is reached. The returned position never
+/// overshoots the target, no matter how long `delta` is.
+pub fn critically_damped(
+    position: Vec2,
+    velocity: &mut Vec2,
+    target: Vec2,
+    omega: f32,
+    delta: f32,
+) -> Vec2 {
+    // Approximation of exp(-omega * delta) that's stable for any delta
+    let x = omega * delta;
+    let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+
+    let change = position - target;
+    let temp = (*velocity + omega * change) * delta;
+    *velocity = (*velocity - omega * temp) * decay;
+
+    target + (change + temp) * decay
+}
+
+/// Moves the focus point just enough to keep `target` within the deadzone.
+///
+/// The deadzone is a rectangle centered on the focus point and `deadzone`
+/// holds half of its size.
+pub fn apply_deadzone(focus: Vec2, target: Vec2, deadzone: Vec2) -> Vec2 {
+    let offset = target - focus;
+    let excess = offset - offset.clamp(-deadzone, deadzone);
+    focus + excess
+}
+
+/// Clamps the camera's center so the visible area stays inside `bounds`.
+///
+/// If the visible area is bigger than the bounds on an axis, the camera is
+/// centered on that axis instead.
+pub fn clamp_to_bounds(center: Vec2, half_view: Vec2, bounds: Rect) -> Vec2 {
+    let clamp_axis = |value: f32, half: f32, min: f32, max: f32| {
+        if max - min <= half * 2.0 {
+            (min + max) / 2.0
+        } else {
+            value.clamp(min + half, max - half)
+        }
+    };
+
+    Vec2::new(
+        clamp_axis(center.x, half_view.x, bounds.min.x, bounds.max.x),
+        clamp_axis(center.y, half_view.y, bounds.min.y, bounds.max.y),
+    )
+}
+
+/// Removes `decay` trauma every second, stopping at zero
+pub fn decay_trauma(trauma: f32, decay: f32, delta: f32) -> f32 {
+    (trauma - decay * delta).max(0.0)
+}
+
+/// Cheap, smooth noise between -1 and 1 used for screen shake
+pub fn shake_noise(time: f32, seed: f32) -> f32 {
+    ((time * 37.0 + seed).sin() + (time * 23.0 + seed * 1.7).sin() * 0.5) / 1.5
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn deadzone_only_moves_the_focus_past_its_edges() {
+        let deadzone = Vec2::new(20.0, 10.0);
+        let focus = Vec2::new(100.0, 100.0);
+
+        assert_eq!(
+            apply_deadzone(focus, Vec2::new(115.0, 95.0), deadzone),
+            focus
+        );
+        assert_eq!(
+            apply_deadzone(focus, Vec2::new(150.0, 80.0), deadzone),
+            Vec2::new(130.0, 90.0)
+        );
+    }
+
+    #[test]
+    fn bounds_keep_the_view_inside_the_map() {
+        let bounds = Rect::new(0.0, 0.0, 1000.0, 500.0);
+        let half_view = Vec2::new(100.0, 50.0);
+
+        assert_eq!(
+            clamp_to_bounds(Vec2::new(500.0, 250.0), half_view, bounds),
+            Vec2::new(500.0, 250.0)
+        );
+        assert_eq!(
+            clamp_to_bounds(Vec2::new(-50.0, 490.0), half_view, bounds),
+            Vec2::new(100.0, 450.0)
+        );
+        // A view wider than the map is centered on it
+        assert_eq!(
+            clamp_to_bounds(Vec2::new(10.0, 10.0), Vec2::new(600.0, 50.0), bounds),
+            Vec2::new(500.0, 50.0)
+        );
+    }
+
+    #[test]
+    fn smoothing_reaches_the_target_without_overshooting() {
+        let target = Vec2::new(100.0, 0.0);
+        let mut position = Vec2::ZERO;
+        let mut velocity = Vec2::ZERO;
+
+        for _ in 0..120 {
+            let next = critically_damped(position, &mut velocity, target, 10.0, 1.0 / 60.0);
+            assert!(next.x >= position.x && next.x <= target.x);
+            position = next;
+        }
+        assert!(position.distance(target) < 0.1);
+
+        // Even a huge step lands on the target rather than past it
+        let mut velocity = Vec2::ZERO;
+        let jumped = critically_damped(Vec2::ZERO, &mut velocity, target, 10.0, 100.0);
+        assert!(jumped.x <= target.x && jumped.distance(target) < 0.1);
+    }
+
+    #[test]
+    fn trauma_decays_to_zero() {
+        assert_eq!(decay_trauma(1.0, 1.5, 0.5), 0.25);
+        assert_eq!(decay_trauma(0.25, 1.5, 0.5), 0.0);
+        assert_eq!(decay_trauma(0.0, 1.5, 0.5), 0.0);
+    }
+}
@@ -0,0 +1,68 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The stats of a melee weapon
+///
+/// Durations are in seconds and distances in pixels.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WeaponDefinition {
+    /// How far from its wielder the weapon hits
+    pub reach: f32,
+    /// The width of the swing in degrees, centered on the facing direction
+    pub arc: f32,
+    pub damage: f32,
+    /// How hard a hit pushes the target away, in pixels per second
+    pub knockback: f32,
+    /// Time between the end of an attack and the start of the next one
+    pub cooldown: f32,
+    /// Time before the hitbox appears
+    pub windup: f32,
+    /// Time the hitbox stays out for
+    pub active: f32,
+    /// Time after the hitbox is gone before the attack is over
+    pub recovery: f32,
+}
+
+/// Asset holding every weapon, keyed by the weapon's id
+#[derive(TypeUuid, Debug, Default, Deserialize)]
+#[uuid = "e0f9a6cb-37f4-4d8e-b6f0-3b8cf6a57a0e"]
+pub struct WeaponDatabase {
+    pub weapons: HashMap<String, WeaponDefinition>,
+}
+
+impl WeaponDatabase {
+    pub fn get(&self, id: &str) -> Option<&WeaponDefinition> {
+        self.weapons.get(id)
+    }
+}
+
+#[derive(Default)]
+pub struct WeaponDatabaseLoader;
+
+impl AssetLoader for WeaponDatabaseLoader {
+    /// Load the weapon database
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext<'_>,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let database: WeaponDatabase = ron::de::from_bytes(bytes)?;
+            debug!("Loaded {} weapon definitions", database.weapons.len());
+            load_context.set_default_asset(LoadedAsset::new(database));
+
+            Ok(())
+        })
+    }
+
+    /// Returns a list of supported extensions
+    fn extensions(&self) -> &[&str] {
+        &["weapons.ron"]
+    }
+}
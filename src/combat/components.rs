@@ -0,0 +1,63 @@
+use super::asset::WeaponDefinition;
+use bevy::{prelude::*, utils::HashSet};
+
+/// The weapon an entity attacks with, by its id in the weapon database
+#[derive(Debug, Clone, Component)]
+pub struct EquippedWeapon(pub String);
+
+/// The phases of a melee attack, in the order they happen
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AttackPhase {
+    /// The attack is being wound up and can't hit anything yet
+    Windup,
+    /// The hitbox is out and damages whatever it touches
+    Active,
+    /// The attack is over but the attacker can't act yet
+    Recovery,
+}
+
+/// A melee attack in progress
+///
+/// Insert this on an entity to make it attack. The component removes itself
+/// once the attack is over and leaves an [AttackCooldown] behind.
+#[derive(Debug, Component)]
+pub struct MeleeAttack {
+    pub weapon: WeaponDefinition,
+    /// The direction the attack is aimed at
+    pub direction: Vec2,
+    pub phase: AttackPhase,
+    pub timer: Timer,
+    /// The hitbox entity while the attack is active
+    pub(super) hitbox: Option<Entity>,
+}
+
+impl MeleeAttack {
+    pub fn new(weapon: WeaponDefinition, direction: Vec2) -> Self {
+        let timer = Timer::from_seconds(weapon.windup, TimerMode::Once);
+        Self {
+            weapon,
+            direction: direction.normalize_or_zero(),
+            phase: AttackPhase::Windup,
+            timer,
+            hitbox: None,
+        }
+    }
+}
+
+/// Stops an entity from attacking again until the timer runs out
+#[derive(Debug, Component)]
+pub struct AttackCooldown(pub Timer);
+
+/// A sensor that damages everything within its arc, once per target
+#[derive(Debug, Component)]
+pub struct AttackHitbox {
+    /// The entity attacking, which never gets hit by its own attack
+    pub owner: Entity,
+    pub direction: Vec2,
+    /// Half of the arc in radians
+    pub half_arc: f32,
+    pub damage: f32,
+    pub knockback: f32,
+    /// Everything that was already hit by this attack
+    pub hit: HashSet<Entity>,
+}
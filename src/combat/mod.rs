@@ -0,0 +1,14 @@
+//! Module for combat
+//!
+//! This module includes:
+//! - An asset holding the stats of every weapon, loaded from a RON file
+//! - Melee attacks made of windup, active and recovery phases
+//! - Sensor hitboxes that damage everything in the attack's arc once
+//! - A plugin to setup the required systems and loaders
+
+pub mod asset;
+pub mod components;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
+pub mod util;
@@ -0,0 +1,37 @@
+use super::{
+    asset::{WeaponDatabase, WeaponDatabaseLoader},
+    resources::WeaponDatabaseHandle,
+    systems::{
+        load_weapon_database, move_hitboxes, resolve_hitbox_hits, start_player_attack,
+        tick_attack_cooldowns, update_melee_attacks,
+    },
+};
+use crate::{
+    dialogue::systems::dialogue_open, health::systems::apply_damage, menu::state::GameMenuState,
+};
+use bevy::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<WeaponDatabase>();
+        app.init_asset_loader::<WeaponDatabaseLoader>();
+
+        app.init_resource::<WeaponDatabaseHandle>();
+
+        app.add_startup_system(load_weapon_database);
+
+        app.add_system(
+            start_player_attack
+                .run_if(not(dialogue_open))
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_system(update_melee_attacks.after(start_player_attack));
+        app.add_system(move_hitboxes.after(update_melee_attacks));
+        app.add_system(resolve_hitbox_hits.before(apply_damage));
+        app.add_system(tick_attack_cooldowns);
+        debug!("CombatPlugin loaded");
+    }
+}
@@ -0,0 +1,9 @@
+use super::asset::WeaponDatabase;
+use bevy::prelude::*;
+
+/// Path to the file defining every weapon
+pub const WEAPON_DATABASE_PATH: &str = "data/core.weapons.ron";
+
+/// Handle to the weapon database used by the game
+#[derive(Debug, Default, Resource)]
+pub struct WeaponDatabaseHandle(pub Handle<WeaponDatabase>);
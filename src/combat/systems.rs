@@ -0,0 +1,199 @@
+//! Defines all of the systems used by the combat plugin
+
+use super::{
+    asset::WeaponDatabase,
+    components::{AttackCooldown, AttackHitbox, AttackPhase, EquippedWeapon, MeleeAttack},
+    resources::{WeaponDatabaseHandle, WEAPON_DATABASE_PATH},
+    util::in_arc,
+};
+use crate::{
+    animation::components::SpriteAnimator,
+    health::{
+        components::{Dead, Health, Staggered},
+        events::DamageEvent,
+    },
+    player::Player,
+    settings::resources::Settings,
+};
+use bevy::{prelude::*, utils::HashSet};
+use bevy_rapier2d::prelude::*;
+
+/// Starts loading the weapon database
+pub fn load_weapon_database(
+    mut weapon_database: ResMut<WeaponDatabaseHandle>,
+    asset_server: Res<AssetServer>,
+) {
+    weapon_database.0 = asset_server.load(WEAPON_DATABASE_PATH);
+}
+
+/// Makes the player attack in the direction they're facing
+#[allow(clippy::type_complexity)]
+pub fn start_player_attack(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    weapon_database: Res<WeaponDatabaseHandle>,
+    weapon_databases: Res<Assets<WeaponDatabase>>,
+    player_query: Query<
+        (Entity, &EquippedWeapon, Option<&SpriteAnimator>),
+        (
+            With<Player>,
+            Without<MeleeAttack>,
+            Without<AttackCooldown>,
+            Without<Staggered>,
+            Without<Dead>,
+        ),
+    >,
+) {
+    if !keys.just_pressed(settings.key_bindings.attack) {
+        return;
+    }
+
+    let Some(weapons) = weapon_databases.get(&weapon_database.0) else {
+        return;
+    };
+
+    for (entity, equipped, animator) in &player_query {
+        let Some(weapon) = weapons.get(&equipped.0) else {
+            warn!("The player has unknown weapon {:?} equipped", equipped.0);
+            continue;
+        };
+
+        let direction = animator.map_or(Vec2::NEG_Y, |animator| animator.facing.as_vec2());
+        commands
+            .entity(entity)
+            .insert(MeleeAttack::new(weapon.clone(), direction));
+    }
+}
+
+/// Moves every melee attack through its phases, spawning and despawning the
+/// hitbox as it goes
+pub fn update_melee_attacks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut attack_query: Query<(Entity, &mut MeleeAttack, &GlobalTransform)>,
+) {
+    for (entity, mut attack, transform) in &mut attack_query {
+        attack.timer.tick(time.delta());
+
+        // Zero length phases are skipped in the same frame
+        while attack.timer.finished() {
+            match attack.phase {
+                AttackPhase::Windup => {
+                    let hitbox = commands
+                        .spawn(TransformBundle::from_transform(
+                            Transform::from_translation(transform.translation()),
+                        ))
+                        .insert(Collider::ball(attack.weapon.reach))
+                        .insert(Sensor)
+                        .insert(ActiveEvents::COLLISION_EVENTS)
+                        .insert(
+                            ActiveCollisionTypes::default()
+                                | ActiveCollisionTypes::KINEMATIC_STATIC,
+                        )
+                        .insert(AttackHitbox {
+                            owner: entity,
+                            direction: attack.direction,
+                            half_arc: attack.weapon.arc.to_radians() / 2.0,
+                            damage: attack.weapon.damage,
+                            knockback: attack.weapon.knockback,
+                            hit: HashSet::new(),
+                        })
+                        .id();
+
+                    attack.hitbox = Some(hitbox);
+                    attack.phase = AttackPhase::Active;
+                    attack.timer = Timer::from_seconds(attack.weapon.active, TimerMode::Once);
+                }
+                AttackPhase::Active => {
+                    if let Some(hitbox) = attack.hitbox.take() {
+                        commands.entity(hitbox).despawn_recursive();
+                    }
+
+                    attack.phase = AttackPhase::Recovery;
+                    attack.timer = Timer::from_seconds(attack.weapon.recovery, TimerMode::Once);
+                }
+                AttackPhase::Recovery => {
+                    commands
+                        .entity(entity)
+                        .remove::<MeleeAttack>()
+                        .insert(AttackCooldown(Timer::from_seconds(
+                            attack.weapon.cooldown,
+                            TimerMode::Once,
+                        )));
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Keeps hitboxes on top of their owner, removing them if the owner is gone
+pub fn move_hitboxes(
+    mut commands: Commands,
+    mut hitbox_query: Query<(Entity, &AttackHitbox, &mut Transform)>,
+    owner_query: Query<&GlobalTransform, Without<AttackHitbox>>,
+) {
+    for (entity, hitbox, mut transform) in &mut hitbox_query {
+        let Ok(owner_transform) = owner_query.get(hitbox.owner) else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+
+        transform.translation = owner_transform.translation();
+    }
+}
+
+/// Damages everything that enters a hitbox within its arc, once per attack
+pub fn resolve_hitbox_hits(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut hitbox_query: Query<(&mut AttackHitbox, &GlobalTransform)>,
+    target_query: Query<&GlobalTransform, (With<Health>, Without<Dead>)>,
+) {
+    for event in collision_events.iter() {
+        let CollisionEvent::Started(a, b, _) = *event else {
+            continue;
+        };
+
+        for (hitbox_entity, target) in [(a, b), (b, a)] {
+            let Ok((mut hitbox, hitbox_transform)) = hitbox_query.get_mut(hitbox_entity) else {
+                continue;
+            };
+            if target == hitbox.owner || hitbox.hit.contains(&target) {
+                continue;
+            }
+            let Ok(target_transform) = target_query.get(target) else {
+                continue;
+            };
+
+            // Only hit what is within the swing's arc
+            let offset =
+                (target_transform.translation() - hitbox_transform.translation()).truncate();
+            if !in_arc(hitbox.direction, offset, hitbox.half_arc) {
+                continue;
+            }
+
+            hitbox.hit.insert(target);
+            damage_events.send(DamageEvent {
+                target,
+                source: Some(hitbox.owner),
+                amount: hitbox.damage,
+                knockback: offset.normalize_or_zero() * hitbox.knockback,
+            });
+        }
+    }
+}
+
+/// Counts down attack cooldowns and removes them once they're over
+pub fn tick_attack_cooldowns(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cooldown_query: Query<(Entity, &mut AttackCooldown)>,
+) {
+    for (entity, mut cooldown) in &mut cooldown_query {
+        if cooldown.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<AttackCooldown>();
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! Utilities for the combat module
+
+use bevy::prelude::*;
+
+/// Whether something at `offset` from a hitbox is within the arc of a swing
+/// towards `direction`, reaching `half_arc` radians to either side
+///
+/// Anything right on top of the hitbox is always hit.
+pub fn in_arc(direction: Vec2, offset: Vec2, half_arc: f32) -> bool {
+    offset == Vec2::ZERO || direction.angle_between(offset).abs() <= half_arc
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn swings_hit_within_their_arc() {
+        let half_arc = 90f32.to_radians() / 2.0;
+
+        assert!(in_arc(Vec2::X, Vec2::new(10.0, 0.0), half_arc));
+        assert!(in_arc(Vec2::X, Vec2::new(10.0, 9.0), half_arc));
+        assert!(!in_arc(Vec2::X, Vec2::new(10.0, 11.0), half_arc));
+        assert!(!in_arc(Vec2::X, Vec2::new(-10.0, 0.0), half_arc));
+        assert!(in_arc(Vec2::X, Vec2::ZERO, half_arc));
+
+        // A full circle hits everything around
+        assert!(in_arc(Vec2::X, Vec2::new(-10.0, 0.0), std::f32::consts::PI));
+    }
+}
@@ -1,6 +1,7 @@
 //! Defines various global constants that are relevant to the whole game
 
 use bevy::prelude::*;
+use bevy_rapier2d::prelude::Group;
 
 #[allow(clippy::upper_case_acronyms)]
 pub struct KEYMAP {}
@@ -10,6 +11,12 @@ impl KEYMAP {
     pub const BACKWARD: KeyCode = KeyCode::S;
     pub const LEFT: KeyCode = KeyCode::A;
     pub const RIGHT: KeyCode = KeyCode::D;
+    pub const INVENTORY: KeyCode = KeyCode::I;
+    pub const ATTACK: KeyCode = KeyCode::Space;
+    pub const THROW: KeyCode = KeyCode::F;
+    pub const DASH: KeyCode = KeyCode::LShift;
+    pub const INTERACT: KeyCode = KeyCode::E;
+    pub const DEBUG_BEHAVIOR: KeyCode = KeyCode::F3;
 }
 
 /// Defines the different draw layers
@@ -21,6 +28,9 @@ impl DRAW_LAYER {
     pub const BASE: f32 = 0.0;
     /// The map layer. All parts of the map are drawn on this layer.
     pub const MAP: f32 = 1.0;
+    /// The props layer. Things placed on the map, like chests and doors, are
+    /// drawn on this layer under the entities.
+    pub const PROPS: f32 = 1.5;
     /// The entities layer. All entities (in-game) are drawn on this layer.
     /// Entities like the player and enemies exist on this layer.
     pub const ENTITIES: f32 = 2.0;
@@ -28,6 +38,19 @@ impl DRAW_LAYER {
     pub const EFFECTS: f32 = 3.0;
 }
 
+/// Defines the physics groups used to filter which colliders push each other
+#[allow(non_camel_case_types)]
+pub struct COLLISION_GROUP {}
+
+impl COLLISION_GROUP {
+    /// The map itself, like walls
+    pub const WORLD: Group = Group::GROUP_1;
+    /// Anything that walks around, like the player and enemies
+    pub const CHARACTERS: Group = Group::GROUP_2;
+    /// Projectiles only bounce off the world and pass through characters
+    pub const PROJECTILES: Group = Group::GROUP_3;
+}
+
 pub const PIXELS_PER_METER: f32 = 32.0;
 
 // Logging
@@ -37,12 +60,13 @@ pub const LOG_FILTER: &str = "info,wgpu_core=warn,wgpu_hal=warn,the_caverns=debu
 #[cfg(not(debug_assertions))]
 pub const LOG_FILTER: &str = "warn,wgpu_core=warn,wgpu_hal=warn,the_caverns=warn";
 
-const STORY_INTRO: [&str; 7] = [
-    "A darkness has fallen upon this once beautiful land.",
-    "What was once full of life is now permeated with the stench of death and decay",
-    "Lake crystal clear and sky pure blue turned red by the smoke that chokes the air.",
-    "Where there were once animals and people living in harmony,",
-    "There are now only creatures of darkness plotting their evil machinations.",
-    "Where there was once a great kingdon of dwarves, their halls filled with splendor,",
-    "There is now only the remains of their dark and dusty halls...",
+/// The messages of the story lines shown one by one before the game starts
+pub const STORY_INTRO: [&str; 7] = [
+    "story-intro-1",
+    "story-intro-2",
+    "story-intro-3",
+    "story-intro-4",
+    "story-intro-5",
+    "story-intro-6",
+    "story-intro-7",
 ];
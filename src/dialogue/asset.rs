@@ -0,0 +1,191 @@
+use crate::locale::asset::LocalizedContent;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A requirement for a line or choice to be available
+#[derive(Debug, Clone, Deserialize)]
+pub enum DialogueCondition {
+    /// The flag is set to anything but zero
+    Flag(String),
+    /// The flag is zero or was never set
+    NotFlag(String),
+    /// The flag is at least the given value
+    FlagAtLeast(String, i32),
+    /// The player carries at least this many of an item
+    HasItem(String, u32),
+}
+
+/// Something that happens when a line is shown or a choice is picked
+#[derive(Debug, Clone, Deserialize)]
+pub enum DialogueEffect {
+    /// Sets the flag to one
+    SetFlag(String),
+    ClearFlag(String),
+    /// Adds to the flag's value
+    AddToFlag(String, i32),
+    /// Gives the player items, dropping whatever doesn't fit at their feet
+    GiveItem(String, u32),
+    /// Takes items from the player
+    TakeItem(String, u32),
+    /// Starts the quest with the given id
+    StartQuest(String),
+}
+
+/// Someone taking part in a conversation
+#[derive(Debug, Clone, Deserialize)]
+pub struct DialogueSpeaker {
+    /// The message with the speaker's name
+    pub name: String,
+    /// Path to an image of the speaker shown next to their lines
+    #[serde(default)]
+    pub portrait: Option<String>,
+}
+
+/// A possible next line, taken when all of its conditions are met
+#[derive(Debug, Clone, Deserialize)]
+pub struct DialogueBranch {
+    pub node: String,
+    #[serde(default)]
+    pub conditions: Vec<DialogueCondition>,
+}
+
+/// An answer the player can give
+#[derive(Debug, Clone, Deserialize)]
+pub struct DialogueChoice {
+    /// The message with what the player says
+    pub text: String,
+    /// The choice is hidden unless every condition is met
+    #[serde(default)]
+    pub conditions: Vec<DialogueCondition>,
+    #[serde(default)]
+    pub effects: Vec<DialogueEffect>,
+    /// The line the choice leads to, or `None` to end the conversation
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+/// A single line of a conversation
+#[derive(Debug, Clone, Deserialize)]
+pub struct DialogueNode {
+    /// The id of the speaker saying the line, or `None` for narration
+    #[serde(default)]
+    pub speaker: Option<String>,
+    /// The message with what's said
+    pub text: String,
+    /// Applied as soon as the line is shown
+    #[serde(default)]
+    pub effects: Vec<DialogueEffect>,
+    #[serde(default)]
+    pub choices: Vec<DialogueChoice>,
+    /// Where lines without choices lead to. The first branch whose conditions
+    /// are met is taken, and the conversation ends if there is none.
+    #[serde(default)]
+    pub next: Vec<DialogueBranch>,
+}
+
+/// A branching conversation
+#[derive(TypeUuid, Debug, Default, Deserialize)]
+#[uuid = "0e8d7d54-4f1c-4a5e-b1d3-96c2f0a8e417"]
+pub struct Dialogue {
+    #[serde(default)]
+    pub speakers: HashMap<String, DialogueSpeaker>,
+    /// The first branch whose conditions are met is where the conversation
+    /// starts
+    pub start: Vec<DialogueBranch>,
+    pub nodes: HashMap<String, DialogueNode>,
+}
+
+impl Dialogue {
+    pub fn node(&self, id: &str) -> Option<&DialogueNode> {
+        self.nodes.get(id)
+    }
+
+    pub fn speaker(&self, node: &DialogueNode) -> Option<&DialogueSpeaker> {
+        node.speaker
+            .as_ref()
+            .and_then(|speaker| self.speakers.get(speaker))
+    }
+
+    /// Checks that every branch, choice and speaker points at something that
+    /// exists
+    pub fn validate(&self) -> Result<(), String> {
+        let check_node = |from: &str, node: &str| {
+            if self.nodes.contains_key(node) {
+                Ok(())
+            } else {
+                Err(format!("{from} leads to unknown node {node:?}"))
+            }
+        };
+
+        for branch in &self.start {
+            check_node("the start", &branch.node)?;
+        }
+
+        for (id, node) in &self.nodes {
+            let from = format!("node {id:?}");
+            if let Some(speaker) = &node.speaker {
+                if !self.speakers.contains_key(speaker) {
+                    return Err(format!("{from} has unknown speaker {speaker:?}"));
+                }
+            }
+            for branch in &node.next {
+                check_node(&from, &branch.node)?;
+            }
+            for choice in &node.choices {
+                if let Some(next) = &choice.next {
+                    check_node(&from, next)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl LocalizedContent for Dialogue {
+    fn message_ids(&self) -> Vec<&str> {
+        let speakers = self.speakers.values().map(|speaker| speaker.name.as_str());
+        let lines = self.nodes.values().flat_map(|node| {
+            let choices = node.choices.iter().map(|choice| choice.text.as_str());
+            std::iter::once(node.text.as_str()).chain(choices)
+        });
+        speakers.chain(lines).collect()
+    }
+}
+
+#[derive(Default)]
+pub struct DialogueLoader;
+
+impl AssetLoader for DialogueLoader {
+    /// Load a conversation
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext<'_>,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let dialogue: Dialogue = ron::de::from_bytes(bytes)?;
+            dialogue.validate().map_err(bevy::asset::Error::msg)?;
+
+            debug!(
+                "Loaded dialogue {:?} with {} nodes",
+                load_context.path(),
+                dialogue.nodes.len()
+            );
+            load_context.set_default_asset(LoadedAsset::new(dialogue));
+
+            Ok(())
+        })
+    }
+
+    /// Returns a list of supported extensions
+    fn extensions(&self) -> &[&str] {
+        &["dialogue.ron"]
+    }
+}
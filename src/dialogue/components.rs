@@ -0,0 +1,24 @@
+use super::asset::Dialogue;
+use bevy::prelude::*;
+
+/// A character the player can talk to
+#[derive(Debug, Clone, Component)]
+pub struct Npc {
+    pub name: String,
+    /// The conversation started when the player interacts with the character
+    pub dialogue: Handle<Dialogue>,
+}
+
+/// Marks the root node of the dialogue panel along with what it shows, so
+/// it's only rebuilt when that changes
+#[derive(Debug, Clone, PartialEq, Eq, Component)]
+pub struct DialoguePanelNode {
+    pub node: String,
+    pub selected: usize,
+    /// Whether the line was fully typed out, which is when the choices show
+    pub typed: bool,
+}
+
+/// Marks the text of the line being said
+#[derive(Debug, Component)]
+pub struct DialogueTextNode;
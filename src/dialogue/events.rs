@@ -0,0 +1,17 @@
+use super::asset::Dialogue;
+use bevy::prelude::*;
+
+/// Starts a conversation, unless one is already going on
+#[derive(Debug, Clone)]
+pub struct StartDialogueEvent {
+    pub dialogue: Handle<Dialogue>,
+    /// The character the player is talking to, if any
+    pub npc: Option<Entity>,
+}
+
+/// Sent when a conversation comes to an end
+#[derive(Debug, Clone)]
+pub struct DialogueEndedEvent {
+    /// The character the player was talking to, if any
+    pub npc: Option<Entity>,
+}
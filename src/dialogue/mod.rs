@@ -0,0 +1,17 @@
+//! Module for talking to the characters of the world
+//!
+//! This module includes:
+//! - An asset describing a branching conversation, loaded from a RON file
+//! - Conditions and effects tying conversations to game flags and items
+//! - NPCs spawned from Tiled objects that start a conversation when the
+//!   player interacts with them
+//! - A dialogue panel with a portrait, typewriter text and choices
+//! - A plugin to setup the required systems, loaders and events
+
+pub mod asset;
+pub mod components;
+pub mod events;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
+pub mod util;
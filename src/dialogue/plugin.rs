@@ -0,0 +1,41 @@
+use super::{
+    asset::{Dialogue, DialogueLoader},
+    events::{DialogueEndedEvent, StartDialogueEvent},
+    resources::DialogueRunner,
+    systems::{
+        advance_dialogue, close_dialogue, spawn_map_npcs, start_dialogue, talk_to_npcs,
+        update_dialogue_panel,
+    },
+};
+use crate::{locale::systems::report_missing_content, menu::state::GameMenuState};
+use bevy::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct DialoguePlugin;
+
+impl Plugin for DialoguePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Dialogue>();
+        app.init_asset_loader::<DialogueLoader>();
+        app.add_system(report_missing_content::<Dialogue>);
+
+        app.init_resource::<DialogueRunner>();
+
+        app.add_event::<StartDialogueEvent>();
+        app.add_event::<DialogueEndedEvent>();
+
+        app.add_system(spawn_map_npcs);
+        app.add_systems(
+            (
+                advance_dialogue,
+                talk_to_npcs,
+                start_dialogue,
+                update_dialogue_panel,
+            )
+                .chain()
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_system(close_dialogue.in_schedule(OnExit(GameMenuState::InGame)));
+        debug!("DialoguePlugin loaded");
+    }
+}
@@ -0,0 +1,48 @@
+use super::asset::Dialogue;
+use bevy::prelude::*;
+
+/// The class of the Tiled objects that spawn an [Npc](super::components::Npc)
+pub const NPC_OBJECT_CLASS: &str = "npc";
+/// How fast lines are typed out
+pub const CHARS_PER_SECOND: f32 = 40.0;
+
+/// A conversation going on
+#[derive(Debug, Clone)]
+pub struct Conversation {
+    pub dialogue: Handle<Dialogue>,
+    pub npc: Option<Entity>,
+    /// The id of the line being said
+    pub node: String,
+    /// Time since the line started being typed out
+    pub elapsed: f32,
+    /// Indices of the line's choices whose conditions are met
+    pub choices: Vec<usize>,
+    /// Index into `choices` of the highlighted choice
+    pub selected: usize,
+}
+
+impl Conversation {
+    /// How many characters of the line were typed out so far
+    pub fn typed_chars(&self) -> usize {
+        (self.elapsed * CHARS_PER_SECOND) as usize
+    }
+
+    /// Shows the rest of the line at once
+    pub fn finish_typing(&mut self, text: &str) {
+        self.elapsed = self
+            .elapsed
+            .max(text.chars().count() as f32 / CHARS_PER_SECOND);
+    }
+}
+
+/// Holds the conversation the player is having
+#[derive(Debug, Default, Resource)]
+pub struct DialogueRunner {
+    pub active: Option<Conversation>,
+}
+
+impl DialogueRunner {
+    pub fn is_open(&self) -> bool {
+        self.active.is_some()
+    }
+}
@@ -0,0 +1,539 @@
+//! Defines all of the systems used by the dialogue plugin
+
+use super::{
+    asset::{Dialogue, DialogueEffect},
+    components::{DialoguePanelNode, DialogueTextNode, Npc},
+    events::{DialogueEndedEvent, StartDialogueEvent},
+    resources::{Conversation, DialogueRunner, NPC_OBJECT_CLASS},
+    util::{apply_effects, conditions_met, pick_branch, quests_started, typed_text},
+};
+use crate::{
+    animation::components::SpriteAnimator,
+    constants::{COLLISION_GROUP, DRAW_LAYER},
+    general::resources::GameFlags,
+    interaction::{
+        components::{InteractAction, Interactable},
+        events::InteractEvent,
+    },
+    inventory::{
+        asset::ItemDatabase,
+        components::{Inventory, ItemStack},
+        resources::ItemDatabaseHandle,
+        util::spawn_item_pickup,
+    },
+    locale::resources::Localization,
+    map::{objects::MapObject, state::MapEntity},
+    player::{Player, PLAYER_ANIMATIONS},
+    quest::events::StartQuestEvent,
+    settings::resources::Settings,
+    theme::{components::ThemedText, resources::CurrentTheme},
+};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+const NPC_TINT: Color = Color::rgb(0.95, 0.75, 0.55);
+const NAME_TEXT: ThemedText = ThemedText::new("heading", "dialogue_name", "accent");
+const LINE_TEXT: ThemedText = ThemedText::new("body", "dialogue_text", "text");
+const PORTRAIT_SIZE: f32 = 96.0;
+const TEXT_WIDTH: f32 = 640.0;
+/// Keys picking a choice directly, in order
+const CHOICE_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+/// Run condition that holds while the player is in a conversation
+pub fn dialogue_open(runner: Res<DialogueRunner>) -> bool {
+    runner.is_open()
+}
+
+/// Run condition that holds while there's no conversation, for the parts of
+/// the game a conversation pauses
+pub fn dialogue_closed(runner: Res<DialogueRunner>) -> bool {
+    !runner.is_open()
+}
+
+/// Spawns a character for every `npc` object on the map
+///
+/// The object's `dialogue` property is the path of the conversation the
+/// character starts and its optional `name` property overrides the object's
+/// name.
+pub fn spawn_map_npcs(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    object_query: Query<(&MapObject, &Transform), Added<MapObject>>,
+) {
+    for (object, transform) in &object_query {
+        if object.class != NPC_OBJECT_CLASS {
+            continue;
+        }
+
+        let Some(dialogue) = object.string_property("dialogue") else {
+            warn!("NPC object {} has no dialogue", object.id);
+            continue;
+        };
+        let name = object.string_property("name").unwrap_or(&object.name);
+        debug!("Spawning NPC {:?} from map object {}", name, object.id);
+
+        commands
+            .spawn((
+                SpriteSheetBundle {
+                    sprite: TextureAtlasSprite {
+                        color: NPC_TINT,
+                        custom_size: Some(Vec2::new(32.0, 32.0)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(
+                        transform
+                            .translation
+                            .truncate()
+                            .extend(DRAW_LAYER::ENTITIES),
+                    ),
+                    ..default()
+                },
+                SpriteAnimator::new(asset_server.load(PLAYER_ANIMATIONS)),
+                Npc {
+                    name: name.into(),
+                    dialogue: asset_server.load(dialogue),
+                },
+                Interactable::new(InteractAction::Talk, "prompt-talk"),
+                MapEntity,
+            ))
+            .insert(RigidBody::Fixed)
+            .insert(Collider::cuboid(12.0, 16.0))
+            .insert(SolverGroups::new(COLLISION_GROUP::CHARACTERS, Group::ALL));
+    }
+}
+
+/// Starts talking to characters the player interacts with
+pub fn talk_to_npcs(
+    mut interact_events: EventReader<InteractEvent>,
+    mut dialogue_events: EventWriter<StartDialogueEvent>,
+    npc_query: Query<&Npc>,
+) {
+    let talked = interact_events
+        .iter()
+        .filter(|event| event.action == InteractAction::Talk);
+    for event in talked {
+        let Ok(npc) = npc_query.get(event.entity) else {
+            continue;
+        };
+
+        debug!("Talking to {:?}", npc.name);
+        dialogue_events.send(StartDialogueEvent {
+            dialogue: npc.dialogue.clone(),
+            npc: Some(event.entity),
+        });
+    }
+}
+
+/// Moves a conversation to another line, applying its effects
+///
+/// Returns the items given to the player that didn't fit in their
+/// inventory.
+fn enter_node(
+    conversation: &mut Conversation,
+    dialogue: &Dialogue,
+    node_id: &str,
+    flags: &mut GameFlags,
+    mut inventory: Option<&mut Inventory>,
+    items: Option<&ItemDatabase>,
+    quest_events: &mut EventWriter<StartQuestEvent>,
+) -> Vec<ItemStack> {
+    conversation.node = node_id.into();
+    conversation.elapsed = 0.0;
+    conversation.selected = 0;
+    conversation.choices.clear();
+
+    let Some(node) = dialogue.node(node_id) else {
+        return vec![];
+    };
+    let left_over = apply_effects(&node.effects, flags, inventory.as_deref_mut(), items);
+    send_quest_starts(&node.effects, quest_events);
+
+    // Choices are checked after the effects so they can unlock each other
+    conversation.choices = node
+        .choices
+        .iter()
+        .enumerate()
+        .filter(|(_, choice)| conditions_met(&choice.conditions, flags, inventory.as_deref()))
+        .map(|(index, _)| index)
+        .collect();
+
+    left_over
+}
+
+fn send_quest_starts(effects: &[DialogueEffect], quest_events: &mut EventWriter<StartQuestEvent>) {
+    for quest in quests_started(effects) {
+        quest_events.send(StartQuestEvent {
+            quest: quest.into(),
+        });
+    }
+}
+
+/// Starts the conversations asked for by [StartDialogueEvent]s
+#[allow(clippy::too_many_arguments)]
+pub fn start_dialogue(
+    mut commands: Commands,
+    mut dialogue_events: EventReader<StartDialogueEvent>,
+    mut quest_events: EventWriter<StartQuestEvent>,
+    mut runner: ResMut<DialogueRunner>,
+    mut flags: ResMut<GameFlags>,
+    dialogues: Res<Assets<Dialogue>>,
+    item_database: Res<ItemDatabaseHandle>,
+    item_databases: Res<Assets<ItemDatabase>>,
+    mut player_query: Query<(&mut Inventory, &mut Velocity, &Transform), With<Player>>,
+) {
+    let items = item_databases.get(&item_database.0);
+
+    for event in dialogue_events.iter() {
+        if runner.is_open() {
+            continue;
+        }
+        let Some(dialogue) = dialogues.get(&event.dialogue) else {
+            warn!("Tried to start a dialogue that isn't loaded");
+            continue;
+        };
+
+        let mut player = player_query.get_single_mut().ok();
+        let Some(start) = pick_branch(
+            &dialogue.start,
+            &flags,
+            player.as_ref().map(|(inventory, ..)| inventory.as_ref()),
+        ) else {
+            debug!("No dialogue start matches the current flags");
+            continue;
+        };
+
+        let mut conversation = Conversation {
+            dialogue: event.dialogue.clone(),
+            npc: event.npc,
+            node: String::new(),
+            elapsed: 0.0,
+            choices: vec![],
+            selected: 0,
+        };
+        let left_over = enter_node(
+            &mut conversation,
+            dialogue,
+            start,
+            &mut flags,
+            player.as_mut().map(|(inventory, ..)| inventory.as_mut()),
+            items,
+            &mut quest_events,
+        );
+
+        if let Some((_, velocity, transform)) = &mut player {
+            // The player stands still while talking
+            velocity.linvel = Vec2::ZERO;
+            if let Some(items) = items {
+                for stack in left_over {
+                    spawn_item_pickup(
+                        &mut commands,
+                        items,
+                        stack,
+                        transform.translation.truncate(),
+                    );
+                }
+            }
+        }
+
+        runner.active = Some(conversation);
+    }
+}
+
+/// Types out the current line and handles the player's answers
+#[allow(clippy::too_many_arguments)]
+pub fn advance_dialogue(
+    mut commands: Commands,
+    mut keys: ResMut<Input<KeyCode>>,
+    settings: Res<Settings>,
+    mut quest_events: EventWriter<StartQuestEvent>,
+    mut ended_events: EventWriter<DialogueEndedEvent>,
+    mut runner: ResMut<DialogueRunner>,
+    mut flags: ResMut<GameFlags>,
+    time: Res<Time>,
+    localization: Res<Localization>,
+    dialogues: Res<Assets<Dialogue>>,
+    item_database: Res<ItemDatabaseHandle>,
+    item_databases: Res<Assets<ItemDatabase>>,
+    mut player_query: Query<(&mut Inventory, &Transform), With<Player>>,
+) {
+    let Some(conversation) = runner.active.as_mut() else {
+        return;
+    };
+    let Some((dialogue, node)) = dialogues
+        .get(&conversation.dialogue)
+        .and_then(|dialogue| Some((dialogue, dialogue.node(&conversation.node)?)))
+    else {
+        runner.active = None;
+        return;
+    };
+
+    conversation.elapsed += time.delta_seconds();
+    let text = localization.text(&node.text);
+    let typed = conversation.typed_chars() >= text.chars().count();
+
+    // Pick a choice
+    let mut picked = None;
+    if typed && !conversation.choices.is_empty() {
+        let count = conversation.choices.len();
+        if keys.any_just_pressed([settings.key_bindings.forward, KeyCode::Up]) {
+            conversation.selected = (conversation.selected + count - 1) % count;
+        }
+        if keys.any_just_pressed([settings.key_bindings.backward, KeyCode::Down]) {
+            conversation.selected = (conversation.selected + 1) % count;
+        }
+        picked = CHOICE_KEYS
+            .iter()
+            .take(count)
+            .position(|key| keys.just_pressed(*key));
+    }
+
+    let confirmed = keys.any_just_pressed([settings.key_bindings.interact, KeyCode::Return]);
+    if confirmed {
+        keys.clear_just_pressed(settings.key_bindings.interact);
+        keys.clear_just_pressed(KeyCode::Return);
+        if !typed {
+            conversation.finish_typing(&text);
+            return;
+        }
+        if !conversation.choices.is_empty() {
+            picked = Some(conversation.selected);
+        }
+    } else if picked.is_none() {
+        return;
+    }
+
+    let items = item_databases.get(&item_database.0);
+    let mut player = player_query.get_single_mut().ok();
+
+    let next = match picked {
+        Some(index) => {
+            let choice = &node.choices[conversation.choices[index]];
+            let left_over = apply_effects(
+                &choice.effects,
+                &mut flags,
+                player.as_mut().map(|(inventory, _)| inventory.as_mut()),
+                items,
+            );
+            send_quest_starts(&choice.effects, &mut quest_events);
+            drop_left_overs(
+                &mut commands,
+                left_over,
+                items,
+                player.as_ref().map(|p| p.1),
+            );
+            choice.next.clone()
+        }
+        None => pick_branch(
+            &node.next,
+            &flags,
+            player.as_ref().map(|(inventory, _)| inventory.as_ref()),
+        )
+        .map(String::from),
+    };
+
+    match next {
+        Some(next) => {
+            let left_over = enter_node(
+                conversation,
+                dialogue,
+                &next,
+                &mut flags,
+                player.as_mut().map(|(inventory, _)| inventory.as_mut()),
+                items,
+                &mut quest_events,
+            );
+            drop_left_overs(
+                &mut commands,
+                left_over,
+                items,
+                player.as_ref().map(|p| p.1),
+            );
+        }
+        None => {
+            debug!("Dialogue ended");
+            ended_events.send(DialogueEndedEvent {
+                npc: conversation.npc,
+            });
+            runner.active = None;
+        }
+    }
+}
+
+/// Drops the items that didn't fit in the player's inventory at their feet
+fn drop_left_overs(
+    commands: &mut Commands,
+    left_over: Vec<ItemStack>,
+    items: Option<&ItemDatabase>,
+    player: Option<&Transform>,
+) {
+    let (Some(items), Some(player)) = (items, player) else {
+        return;
+    };
+    for stack in left_over {
+        spawn_item_pickup(commands, items, stack, player.translation.truncate());
+    }
+}
+
+/// Shows the current line of the conversation, rebuilding the panel when
+/// the line, the highlighted choice or the language changes
+#[allow(clippy::too_many_arguments)]
+pub fn update_dialogue_panel(
+    mut commands: Commands,
+    runner: Res<DialogueRunner>,
+    asset_server: Res<AssetServer>,
+    theme: Res<CurrentTheme>,
+    localization: Res<Localization>,
+    dialogues: Res<Assets<Dialogue>>,
+    panel_query: Query<(Entity, &DialoguePanelNode)>,
+    mut text_query: Query<&mut Text, With<DialogueTextNode>>,
+) {
+    let shown = runner.active.as_ref().and_then(|conversation| {
+        let dialogue = dialogues.get(&conversation.dialogue)?;
+        let node = dialogue.node(&conversation.node)?;
+        Some((conversation, dialogue, node, localization.text(&node.text)))
+    });
+
+    let wanted = shown
+        .as_ref()
+        .map(|(conversation, _, _, line)| DialoguePanelNode {
+            node: conversation.node.clone(),
+            selected: conversation.selected,
+            typed: conversation.typed_chars() >= line.chars().count(),
+        });
+
+    let mut up_to_date = false;
+    for (entity, panel) in &panel_query {
+        if Some(panel) == wanted.as_ref() && !localization.is_changed() {
+            up_to_date = true;
+        } else {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    let (Some((conversation, dialogue, node, line)), Some(wanted)) = (shown, wanted) else {
+        return;
+    };
+    let text = typed_text(&line, conversation.typed_chars());
+
+    if up_to_date {
+        for mut line in &mut text_query {
+            if line.sections[0].value != text {
+                line.sections[0].value = text.into();
+            }
+        }
+        return;
+    }
+
+    let speaker = dialogue.speaker(node);
+    let typed = wanted.typed;
+    let text_style = theme.themed_text_style(LINE_TEXT);
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::width(Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: Val::Px(20.0),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(wanted)
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(theme.spacing("dialogue_padding"))),
+                        gap: Size::all(Val::Px(theme.spacing("dialogue_padding"))),
+                        ..default()
+                    },
+                    background_color: theme.color("dialogue_panel").into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    if let Some(portrait) = speaker.and_then(|s| s.portrait.as_deref()) {
+                        parent.spawn(ImageBundle {
+                            style: Style {
+                                size: Size::all(Val::Px(PORTRAIT_SIZE)),
+                                ..default()
+                            },
+                            image: asset_server.load(portrait).into(),
+                            ..default()
+                        });
+                    }
+
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                gap: Size::all(Val::Px(theme.spacing("dialogue_choices"))),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            if let Some(speaker) = speaker {
+                                parent
+                                    .spawn(theme.text(localization.text(&speaker.name), NAME_TEXT));
+                            }
+
+                            parent
+                                .spawn(
+                                    TextBundle::from_section(text, text_style.clone()).with_style(
+                                        Style {
+                                            max_size: Size::width(Val::Px(TEXT_WIDTH)),
+                                            ..default()
+                                        },
+                                    ),
+                                )
+                                .insert(DialogueTextNode);
+
+                            if !typed {
+                                return;
+                            }
+                            for (position, index) in conversation.choices.iter().enumerate() {
+                                let selected = position == conversation.selected;
+                                parent.spawn(TextBundle::from_section(
+                                    format!(
+                                        "{}. {}",
+                                        position + 1,
+                                        localization.text(&node.choices[*index].text)
+                                    ),
+                                    TextStyle {
+                                        color: if selected {
+                                            theme.color("accent")
+                                        } else {
+                                            theme.color("muted")
+                                        },
+                                        ..text_style.clone()
+                                    },
+                                ));
+                            }
+                        });
+                });
+        });
+}
+
+/// Ends any conversation and removes the panel when leaving the game
+pub fn close_dialogue(
+    mut commands: Commands,
+    mut runner: ResMut<DialogueRunner>,
+    panel_query: Query<Entity, With<DialoguePanelNode>>,
+) {
+    runner.active = None;
+    panel_query.for_each(|node| commands.entity(node).despawn_recursive());
+}
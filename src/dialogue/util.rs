@@ -0,0 +1,199 @@
+use super::asset::{DialogueBranch, DialogueCondition, DialogueEffect};
+use crate::{
+    general::resources::GameFlags,
+    inventory::{
+        asset::ItemDatabase,
+        components::{Inventory, ItemStack},
+    },
+};
+use bevy::prelude::*;
+
+impl DialogueCondition {
+    /// Whether the condition holds, where a missing inventory counts as empty
+    pub fn is_met(&self, flags: &GameFlags, inventory: Option<&Inventory>) -> bool {
+        match self {
+            Self::Flag(flag) => flags.is_set(flag),
+            Self::NotFlag(flag) => !flags.is_set(flag),
+            Self::FlagAtLeast(flag, value) => flags.get(flag) >= *value,
+            Self::HasItem(item, count) => inventory.is_some_and(|i| i.count(item) >= *count),
+        }
+    }
+}
+
+/// Whether every condition holds
+pub fn conditions_met(
+    conditions: &[DialogueCondition],
+    flags: &GameFlags,
+    inventory: Option<&Inventory>,
+) -> bool {
+    conditions
+        .iter()
+        .all(|condition| condition.is_met(flags, inventory))
+}
+
+/// Picks the first branch whose conditions hold
+pub fn pick_branch<'a>(
+    branches: &'a [DialogueBranch],
+    flags: &GameFlags,
+    inventory: Option<&Inventory>,
+) -> Option<&'a str> {
+    branches
+        .iter()
+        .find(|branch| conditions_met(&branch.conditions, flags, inventory))
+        .map(|branch| branch.node.as_str())
+}
+
+/// Applies effects to the flags and the player's inventory
+///
+/// Starting quests is left to [quests_started].
+///
+/// Returns the items that were given but didn't fit in the inventory.
+pub fn apply_effects(
+    effects: &[DialogueEffect],
+    flags: &mut GameFlags,
+    mut inventory: Option<&mut Inventory>,
+    items: Option<&ItemDatabase>,
+) -> Vec<ItemStack> {
+    let mut left_over = vec![];
+
+    for effect in effects {
+        match effect {
+            DialogueEffect::SetFlag(flag) => flags.set(flag.clone(), 1),
+            DialogueEffect::ClearFlag(flag) => flags.clear(flag),
+            DialogueEffect::AddToFlag(flag, amount) => {
+                flags.add(flag, *amount);
+            }
+            DialogueEffect::GiveItem(item, count) => {
+                let Some(definition) = items.and_then(|items| items.get(item)) else {
+                    warn!("Dialogue tried to give unknown item {:?}", item);
+                    continue;
+                };
+                let remaining = match inventory.as_deref_mut() {
+                    Some(inventory) => inventory.add(definition, *count),
+                    None => *count,
+                };
+                if remaining > 0 {
+                    left_over.push(ItemStack::new(item.clone(), remaining));
+                }
+            }
+            DialogueEffect::TakeItem(item, count) => {
+                let taken = inventory
+                    .as_deref_mut()
+                    .is_some_and(|inventory| inventory.remove(item, *count).is_ok());
+                if !taken {
+                    warn!(
+                        "Dialogue couldn't take {} {:?} from the player",
+                        count, item
+                    );
+                }
+            }
+            // Quests are started by the dialogue systems, which can send
+            // events
+            DialogueEffect::StartQuest(_) => {}
+        }
+    }
+
+    left_over
+}
+
+/// The ids of the quests started by some effects
+pub fn quests_started(effects: &[DialogueEffect]) -> impl Iterator<Item = &str> {
+    effects.iter().filter_map(|effect| match effect {
+        DialogueEffect::StartQuest(quest) => Some(quest.as_str()),
+        _ => None,
+    })
+}
+
+/// The start of a line being typed out, never cutting a character in half
+pub fn typed_text(text: &str, chars: usize) -> &str {
+    let end = text
+        .char_indices()
+        .nth(chars)
+        .map_or(text.len(), |(end, _)| end);
+    &text[..end]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        dialogue::asset::Dialogue,
+        inventory::asset::{ItemCategory, ItemDefinition},
+    };
+
+    fn dialogue() -> Dialogue {
+        ron::from_str(
+            r#"(
+                speakers: { "dwarf": (name: "Borin") },
+                start: [
+                    (node: "again", conditions: [Flag("met")]),
+                    (node: "hello"),
+                ],
+                nodes: {
+                    "hello": (
+                        speaker: Some("dwarf"),
+                        text: "Hello",
+                        effects: [SetFlag("met"), AddToFlag("talks", 1)],
+                        choices: [
+                            (text: "Trade", conditions: [HasItem("cave_gem", 2)], next: Some("again")),
+                            (text: "Bye"),
+                        ],
+                    ),
+                    "again": (speaker: Some("dwarf"), text: "You again"),
+                },
+            )"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn starts_on_the_first_branch_that_holds() {
+        let dialogue = dialogue();
+        assert!(dialogue.validate().is_ok());
+
+        let mut flags = GameFlags::default();
+        assert_eq!(pick_branch(&dialogue.start, &flags, None), Some("hello"));
+
+        let hello = dialogue.node("hello").unwrap();
+        apply_effects(&hello.effects, &mut flags, None, None);
+        apply_effects(&hello.effects, &mut flags, None, None);
+        assert_eq!(flags.get("talks"), 2);
+        assert_eq!(pick_branch(&dialogue.start, &flags, None), Some("again"));
+    }
+
+    #[test]
+    fn choices_check_the_inventory() {
+        let dialogue = dialogue();
+        let trade = &dialogue.node("hello").unwrap().choices[0];
+        let flags = GameFlags::default();
+
+        assert!(!conditions_met(&trade.conditions, &flags, None));
+        let gem = ItemDefinition {
+            id: "cave_gem".into(),
+            name: "Cave Gem".into(),
+            icon: 0,
+            stack_size: 20,
+            category: ItemCategory::Material,
+        };
+        let mut inventory = Inventory::new(4);
+        inventory.add(&gem, 1);
+        assert!(!conditions_met(&trade.conditions, &flags, Some(&inventory)));
+        inventory.add(&gem, 1);
+        assert!(conditions_met(&trade.conditions, &flags, Some(&inventory)));
+    }
+
+    #[test]
+    fn rejects_broken_links() {
+        let broken: Dialogue = ron::from_str(
+            r#"(start: [(node: "missing")], nodes: { "a": (text: "A", speaker: Some("nobody")) })"#,
+        )
+        .unwrap();
+        assert!(broken.validate().is_err());
+    }
+
+    #[test]
+    fn types_whole_characters() {
+        assert_eq!(typed_text("Grüß dich", 3), "Grü");
+        assert_eq!(typed_text("Hi", 10), "Hi");
+    }
+}
@@ -0,0 +1,117 @@
+use crate::flock::components::FlockSettings;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// How an enemy attacks once it's in range
+#[derive(Debug, Clone, Deserialize)]
+pub enum EnemyAttack {
+    /// Swings a weapon from the weapon database
+    Melee { weapon: String },
+    /// Fires a projectile from the projectile database
+    Ranged { projectile: String, cooldown: f32 },
+    /// Only hurts by touching
+    Contact,
+}
+
+/// The stats and behavior of a type of enemy
+///
+/// Durations are in seconds, distances in pixels and angles in degrees.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnemyDefinition {
+    pub health: f32,
+    /// How fast the enemy wanders around, in pixels per second
+    pub speed: f32,
+    /// How fast the enemy moves while chasing or fleeing
+    pub chase_speed: f32,
+    /// Damage dealt to the player by touching them
+    pub contact_damage: f32,
+    pub contact_knockback: f32,
+    /// The radius of the enemy's collider
+    pub radius: f32,
+    /// How far the enemy can see
+    pub sight_range: f32,
+    /// The width of the enemy's sight cone
+    pub sight_angle: f32,
+    /// How far away the enemy can hear noises from
+    pub hearing_range: f32,
+    /// How close the enemy has to be to attack
+    pub attack_range: f32,
+    pub attack: EnemyAttack,
+    /// The fraction of health under which the enemy runs away.
+    /// Enemies with zero never flee.
+    #[serde(default)]
+    pub flee_below: f32,
+    /// How long the enemy runs away for before calming down
+    #[serde(default)]
+    pub flee_time: f32,
+    /// How far from its spawn point the enemy wanders
+    pub patrol_radius: f32,
+    /// How long the enemy stands around between patrols
+    pub idle_time: f32,
+    /// How long the enemy looks around after losing track of the player
+    pub investigate_time: f32,
+    /// Path to the enemy's animation set
+    pub animations: String,
+    /// Makes the enemy flock with others of its kind instead of thinking for
+    /// itself
+    #[serde(default)]
+    pub flock: Option<FlockSettings>,
+    /// Path to a behavior tree driving the enemy instead of the built in
+    /// state machine
+    #[serde(default)]
+    pub behavior: Option<String>,
+    /// Id of the loot table rolled when the enemy dies
+    #[serde(default)]
+    pub loot: Option<String>,
+    /// Color the enemy's sprite is tinted with
+    #[serde(default = "default_tint")]
+    pub tint: (f32, f32, f32),
+}
+
+fn default_tint() -> (f32, f32, f32) {
+    (1.0, 1.0, 1.0)
+}
+
+/// Asset holding every enemy type, keyed by the type's id
+#[derive(TypeUuid, Debug, Default, Deserialize)]
+#[uuid = "3d7c59a2-91f4-4a0c-8d65-2b7e0f4c1a93"]
+pub struct EnemyDatabase {
+    pub enemies: HashMap<String, EnemyDefinition>,
+}
+
+impl EnemyDatabase {
+    pub fn get(&self, id: &str) -> Option<&EnemyDefinition> {
+        self.enemies.get(id)
+    }
+}
+
+#[derive(Default)]
+pub struct EnemyDatabaseLoader;
+
+impl AssetLoader for EnemyDatabaseLoader {
+    /// Load the enemy database
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext<'_>,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let database: EnemyDatabase = ron::de::from_bytes(bytes)?;
+            debug!("Loaded {} enemy definitions", database.enemies.len());
+            load_context.set_default_asset(LoadedAsset::new(database));
+
+            Ok(())
+        })
+    }
+
+    /// Returns a list of supported extensions
+    fn extensions(&self) -> &[&str] {
+        &["enemies.ron"]
+    }
+}
@@ -0,0 +1,159 @@
+//! The behavior tree leaves enemies use
+//!
+//! Actions switch the enemy's [EnemyBrain] into a state, leaving the moving
+//! and attacking to the same systems the state machine relies on.
+
+use super::components::{Enemy, EnemyBrain, EnemyState, Perception};
+use crate::{
+    behavior::{resources::ActionContext, runtime::BehaviorStatus},
+    health::components::Health,
+};
+use bevy::prelude::*;
+
+/// The fraction of health under which an enemy counts as hurt
+const HURT_HEALTH: f32 = 0.5;
+
+/// Whether the enemy can see the player
+pub fn sees_target(entity: Entity, world: &World) -> bool {
+    world
+        .get::<Perception>(entity)
+        .is_some_and(|perception| perception.target.is_some())
+}
+
+/// Whether the enemy can see the player and is close enough to attack them
+pub fn target_in_attack_range(entity: Entity, world: &World) -> bool {
+    let (Some(enemy), Some(perception), Some(transform)) = (
+        world.get::<Enemy>(entity),
+        world.get::<Perception>(entity),
+        world.get::<GlobalTransform>(entity),
+    ) else {
+        return false;
+    };
+
+    let position = transform.translation().truncate();
+    perception
+        .target
+        .and(perception.last_seen)
+        .is_some_and(|target| target.distance(position) <= enemy.definition.attack_range)
+}
+
+/// Whether the enemy has lost at least half of its health
+pub fn hurt(entity: Entity, world: &World) -> bool {
+    world
+        .get::<Health>(entity)
+        .is_some_and(|health| health.current < health.max * HURT_HEALTH)
+}
+
+/// Whether the enemy heard something or lost track of the player
+pub fn has_lead(entity: Entity, world: &World) -> bool {
+    world
+        .get::<Perception>(entity)
+        .is_some_and(|perception| perception.heard.is_some() || perception.last_seen.is_some())
+}
+
+/// Switches the enemy into a state on the action's first tick, or whenever
+/// something else switched it out of that state
+///
+/// Returns the brain so the action can check on its progress.
+fn enter_state<'w>(
+    context: &'w mut ActionContext,
+    state: EnemyState,
+    duration: impl Fn(&Enemy) -> f32,
+) -> Option<Mut<'w, EnemyBrain>> {
+    let entity = context.entity;
+    let duration = duration(context.world.get::<Enemy>(entity)?);
+    let current = context.world.get::<EnemyBrain>(entity)?.state;
+
+    if context.running_for == 0.0 || current != state {
+        let mut perception = std::mem::take(&mut *context.world.get_mut::<Perception>(entity)?);
+        if let Some(mut brain) = context.world.get_mut::<EnemyBrain>(entity) {
+            brain.enter_state(state, duration, &mut perception);
+        }
+        *context.world.get_mut::<Perception>(entity)? = perception;
+    }
+
+    context.world.get_mut::<EnemyBrain>(entity)
+}
+
+/// Chases the player until they're in attack range
+pub fn chase(context: &mut ActionContext) -> BehaviorStatus {
+    if !sees_target(context.entity, context.world) {
+        return BehaviorStatus::Failure;
+    }
+    if target_in_attack_range(context.entity, context.world) {
+        return BehaviorStatus::Success;
+    }
+
+    match enter_state(context, EnemyState::Chase, |_| 0.0) {
+        Some(_) => BehaviorStatus::Running,
+        None => BehaviorStatus::Failure,
+    }
+}
+
+/// Attacks the player for as long as they stay in range
+pub fn attack(context: &mut ActionContext) -> BehaviorStatus {
+    if !target_in_attack_range(context.entity, context.world) {
+        return BehaviorStatus::Failure;
+    }
+
+    match enter_state(context, EnemyState::Attack, |_| 0.0) {
+        Some(_) => BehaviorStatus::Running,
+        None => BehaviorStatus::Failure,
+    }
+}
+
+/// Looks around where the player was last seen or heard
+pub fn investigate(context: &mut ActionContext) -> BehaviorStatus {
+    if context.running_for == 0.0 && !has_lead(context.entity, context.world) {
+        return BehaviorStatus::Failure;
+    }
+
+    match enter_state(context, EnemyState::Investigate, |enemy| {
+        enemy.definition.investigate_time
+    }) {
+        Some(brain) if brain.timer.finished() => BehaviorStatus::Success,
+        Some(_) => BehaviorStatus::Running,
+        None => BehaviorStatus::Failure,
+    }
+}
+
+/// Wanders to a spot near home
+pub fn patrol(context: &mut ActionContext) -> BehaviorStatus {
+    let Some(position) = context
+        .world
+        .get::<GlobalTransform>(context.entity)
+        .map(|transform| transform.translation().truncate())
+    else {
+        return BehaviorStatus::Failure;
+    };
+
+    // The spot to wander to is only picked after the first tick
+    let started = context.running_for > 0.0;
+    match enter_state(context, EnemyState::Patrol, |_| 0.0) {
+        Some(brain) if started && brain.has_arrived(position) => BehaviorStatus::Success,
+        Some(_) => BehaviorStatus::Running,
+        None => BehaviorStatus::Failure,
+    }
+}
+
+/// Stands around for a while
+pub fn idle(context: &mut ActionContext) -> BehaviorStatus {
+    match enter_state(context, EnemyState::Idle, |enemy| {
+        enemy.definition.idle_time
+    }) {
+        Some(brain) if brain.timer.finished() => BehaviorStatus::Success,
+        Some(_) => BehaviorStatus::Running,
+        None => BehaviorStatus::Failure,
+    }
+}
+
+/// Runs away from the player for a while
+pub fn flee(context: &mut ActionContext) -> BehaviorStatus {
+    match enter_state(context, EnemyState::Flee, |enemy| {
+        enemy.definition.flee_time
+    }) {
+        Some(brain) if brain.timer.finished() => BehaviorStatus::Success,
+        Some(_) => BehaviorStatus::Running,
+        None => BehaviorStatus::Failure,
+    }
+}
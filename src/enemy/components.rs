@@ -0,0 +1,130 @@
+use super::asset::EnemyDefinition;
+use bevy::prelude::*;
+
+/// How close an enemy has to get to its goal to have arrived
+const ARRIVE_DISTANCE: f32 = 8.0;
+
+/// An enemy along with the definition of its type
+#[derive(Debug, Component)]
+pub struct Enemy {
+    /// The id of the enemy's type
+    pub kind: String,
+    pub definition: EnemyDefinition,
+}
+
+/// The states of an enemy's state machine
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum EnemyState {
+    /// Standing around
+    #[default]
+    Idle,
+    /// Wandering to a spot near home
+    Patrol,
+    /// Checking out where the player was last seen or heard
+    Investigate,
+    /// Running after the player
+    Chase,
+    /// Close enough to the player to attack
+    Attack,
+    /// Running away from the player
+    Flee,
+}
+
+/// What an enemy knows about its surroundings
+#[derive(Debug, Default, Component)]
+pub struct Perception {
+    /// The player, if the enemy can currently see them
+    pub target: Option<Entity>,
+    /// Where the player was the last time the enemy saw them
+    pub last_seen: Option<Vec2>,
+    /// Where the most recent noise the enemy heard came from
+    pub heard: Option<Vec2>,
+}
+
+/// The state machine driving an enemy
+#[derive(Debug, Component)]
+pub struct EnemyBrain {
+    pub state: EnemyState,
+    /// Counts down the time left in the current state, when that matters
+    pub timer: Timer,
+    /// Where the enemy spawned, which it patrols around
+    pub home: Vec2,
+    /// Where the enemy is currently heading
+    pub goal: Option<Vec2>,
+    /// The direction the enemy is looking in
+    pub facing: Vec2,
+    /// The waypoints left to reach the goal
+    pub(super) path: Vec<Vec2>,
+    /// The cell the current path leads to
+    pub(super) path_goal: Option<IVec2>,
+    /// Counts down until the path is recalculated
+    pub(super) repath_timer: Timer,
+    /// Counts down until a ranged attack can be used again
+    pub(super) attack_timer: Timer,
+    /// Used to spread out the patrol spots of different enemies
+    pub(super) wander_seed: u32,
+}
+
+impl EnemyBrain {
+    pub fn new(home: Vec2, wander_seed: u32) -> Self {
+        Self {
+            state: EnemyState::Idle,
+            timer: Timer::from_seconds(0.0, TimerMode::Once),
+            home,
+            goal: None,
+            facing: Vec2::NEG_Y,
+            path: vec![],
+            path_goal: None,
+            repath_timer: Timer::from_seconds(0.0, TimerMode::Once),
+            attack_timer: Timer::from_seconds(0.0, TimerMode::Once),
+            wander_seed,
+        }
+    }
+
+    /// Switches to another state, starting its timer over
+    pub fn transition(&mut self, state: EnemyState, duration: f32) {
+        if self.state != state {
+            debug!("Enemy switching from {:?} to {:?}", self.state, state);
+        }
+        self.state = state;
+        self.timer = Timer::from_seconds(duration, TimerMode::Once);
+        self.goal = None;
+    }
+
+    /// Switches to another state and acts on what the enemy perceived
+    ///
+    /// Investigating heads towards the latest lead, and going idle forgets
+    /// about any leads.
+    pub fn enter_state(&mut self, state: EnemyState, duration: f32, perception: &mut Perception) {
+        self.transition(state, duration);
+
+        match state {
+            EnemyState::Investigate => {
+                self.goal = perception.heard.take().or(perception.last_seen.take());
+            }
+            EnemyState::Idle => {
+                perception.heard = None;
+                perception.last_seen = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the enemy has reached where it was heading, or isn't heading
+    /// anywhere
+    pub fn has_arrived(&self, position: Vec2) -> bool {
+        self.goal
+            .is_none_or(|goal| goal.distance(position) <= ARRIVE_DISTANCE)
+    }
+}
+
+/// Counts down until a dead enemy's body disappears
+#[derive(Debug, Component)]
+pub struct Corpse(pub Timer);
+
+/// Placed on the map objects enemies were spawned from, pointing at the
+/// enemies
+///
+/// This is empty when the object didn't describe a valid enemy.
+#[derive(Debug, Component)]
+pub struct SpawnedEnemy(pub Vec<Entity>);
@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+
+/// A noise enemies might hear
+///
+/// Enemies hear a noise when they are within both their hearing range and
+/// the noise's radius.
+#[derive(Debug, Clone, Copy)]
+pub struct NoiseEvent {
+    pub position: Vec2,
+    /// How far the noise carries, in pixels
+    pub radius: f32,
+}
+
+/// Spawns enemies outside of the map's `enemy` objects, like when the player
+/// walks into an ambush
+#[derive(Debug, Clone)]
+pub struct SpawnEnemiesEvent {
+    /// The enemy type to spawn
+    pub kind: String,
+    pub count: u32,
+    pub position: Vec2,
+}
@@ -0,0 +1,21 @@
+//! Module for enemies
+//!
+//! This module includes:
+//! - An asset holding the stats and behavior of every enemy type, loaded
+//!   from a RON file
+//! - Perception through sight cones blocked by walls and hearing
+//! - A state machine deciding what every enemy does, or behavior tree leaves
+//!   for enemies that need smarter logic
+//! - Pathfinding over the map's navigation grid, with a shared flow field
+//!   for everyone chasing the player
+//! - Spawning of the enemies placed on the map as `enemy` objects
+//! - A plugin to setup the required systems, loaders and events
+
+pub mod asset;
+pub mod behavior;
+pub mod components;
+pub mod events;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
+pub mod util;
@@ -0,0 +1,80 @@
+use super::{
+    asset::{EnemyDatabase, EnemyDatabaseLoader},
+    behavior::{
+        attack, chase, flee, has_lead, hurt, idle, investigate, patrol, sees_target,
+        target_in_attack_range,
+    },
+    events::{NoiseEvent, SpawnEnemiesEvent},
+    resources::{EnemyDatabaseHandle, PlayerFlowField},
+    systems::{
+        decay_corpses, enemy_attacks, halt_enemies, handle_enemy_death, load_enemy_database,
+        make_player_noise, move_enemies, perceive, spawn_map_enemies, spawn_requested_enemies,
+        steer, think, update_player_flow_field,
+    },
+};
+use crate::{
+    behavior::{plugin::BehaviorAppExt, systems::tick_behavior_trees},
+    combat::systems::update_melee_attacks,
+    dialogue::systems::{dialogue_closed, dialogue_open},
+    health::systems::apply_damage,
+    menu::state::GameMenuState,
+};
+use bevy::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct EnemyPlugin;
+
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<EnemyDatabase>();
+        app.init_asset_loader::<EnemyDatabaseLoader>();
+
+        app.init_resource::<EnemyDatabaseHandle>();
+        app.init_resource::<PlayerFlowField>();
+
+        app.add_event::<NoiseEvent>();
+        app.add_event::<SpawnEnemiesEvent>();
+
+        app.add_startup_system(load_enemy_database);
+
+        app.add_system(spawn_map_enemies);
+        app.add_system(spawn_requested_enemies);
+        // Enemies wait for the player to finish talking, like they do while
+        // the game is paused
+        app.add_systems(
+            (make_player_noise, perceive, think)
+                .chain()
+                .distributive_run_if(dialogue_closed)
+                .before(tick_behavior_trees)
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_systems(
+            (steer, update_player_flow_field, move_enemies, enemy_attacks)
+                .chain()
+                .distributive_run_if(dialogue_closed)
+                .after(tick_behavior_trees)
+                .before(update_melee_attacks)
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_system(
+            halt_enemies
+                .run_if(dialogue_open)
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_system(handle_enemy_death.after(apply_damage));
+        app.add_system(decay_corpses);
+
+        // Behavior tree leaves
+        app.register_behavior_condition("sees_target", sees_target)
+            .register_behavior_condition("target_in_attack_range", target_in_attack_range)
+            .register_behavior_condition("hurt", hurt)
+            .register_behavior_condition("has_lead", has_lead)
+            .register_behavior_action("chase", chase)
+            .register_behavior_action("attack", attack)
+            .register_behavior_action("investigate", investigate)
+            .register_behavior_action("patrol", patrol)
+            .register_behavior_action("idle", idle)
+            .register_behavior_action("flee", flee);
+        debug!("EnemyPlugin loaded");
+    }
+}
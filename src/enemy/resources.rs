@@ -0,0 +1,17 @@
+use super::asset::EnemyDatabase;
+use crate::map::flow_field::FlowField;
+use bevy::prelude::*;
+
+/// Path to the file defining every enemy type
+pub const ENEMY_DATABASE_PATH: &str = "data/core.enemies.ron";
+
+/// The class of the map objects that spawn enemies
+pub const ENEMY_OBJECT_CLASS: &str = "enemy";
+
+/// Handle to the enemy database used by the game
+#[derive(Debug, Default, Resource)]
+pub struct EnemyDatabaseHandle(pub Handle<EnemyDatabase>);
+
+/// A flow field leading to the player, shared by every enemy chasing them
+#[derive(Debug, Default, Resource)]
+pub struct PlayerFlowField(pub FlowField);
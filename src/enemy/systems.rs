@@ -0,0 +1,723 @@
+//! Defines all of the systems used by the enemy plugin
+
+use super::{
+    asset::{EnemyAttack, EnemyDatabase, EnemyDefinition},
+    components::{Corpse, Enemy, EnemyBrain, EnemyState, Perception, SpawnedEnemy},
+    events::{NoiseEvent, SpawnEnemiesEvent},
+    resources::{EnemyDatabaseHandle, PlayerFlowField, ENEMY_DATABASE_PATH, ENEMY_OBJECT_CLASS},
+    util::{find_waypoints, in_sight_cone, next_state, wander_point, Situation},
+};
+use crate::{
+    animation::{
+        asset::{AnimationState, Facing},
+        components::SpriteAnimator,
+    },
+    behavior::components::Behavior,
+    combat::{
+        asset::WeaponDatabase,
+        components::{AttackCooldown, EquippedWeapon, MeleeAttack},
+        resources::WeaponDatabaseHandle,
+    },
+    constants::{COLLISION_GROUP, DRAW_LAYER, PIXELS_PER_METER},
+    flock::components::Boid,
+    general::rng::GameRng,
+    health::{
+        components::{Damage, Dead, Health, Staggered, Team},
+        events::DeathEvent,
+    },
+    loot::events::DropLootEvent,
+    map::{
+        navigation::NavigationGrid,
+        objects::MapObject,
+        state::{MapEntity, MapState},
+        util::map_id,
+    },
+    player::Player,
+    projectile::events::{FireProjectileEvent, ProjectileImpactEvent},
+    save::resources::WorldChanges,
+};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+/// How close an enemy has to get to a waypoint before moving on to the next
+const WAYPOINT_DISTANCE: f32 = 4.0;
+
+/// How often paths are recalculated while the goal stays in the same cell
+const REPATH_TIME: f32 = 0.5;
+
+/// How far a fleeing enemy tries to get ahead of the player each time
+const FLEE_DISTANCE: f32 = PIXELS_PER_METER * 3.0;
+
+/// How many spots are tried when looking for somewhere walkable to wander to
+const WANDER_ATTEMPTS: u32 = 8;
+
+/// How far the player's footsteps can be heard
+const FOOTSTEP_NOISE_RADIUS: f32 = PIXELS_PER_METER * 3.0;
+
+/// How far the player's attacks can be heard
+const ATTACK_NOISE_RADIUS: f32 = PIXELS_PER_METER * 6.0;
+
+/// How far a projectile hitting something can be heard
+const IMPACT_NOISE_RADIUS: f32 = PIXELS_PER_METER * 8.0;
+
+/// How fast the player has to move for their footsteps to be heard
+const FOOTSTEP_SPEED: f32 = 10.0;
+
+/// How long a dead enemy's body stays around, in seconds
+const CORPSE_TIME: f32 = 3.0;
+
+/// How far from their map object the members of a group are spawned
+const GROUP_SPAWN_RADIUS: f32 = PIXELS_PER_METER;
+
+/// Keeps the seeds of enemies spawned from different objects apart
+const SPAWN_SEED_STRIDE: u32 = 1000;
+
+/// How many cells worth of work go into the player's flow field every frame
+const FLOW_FIELD_BUDGET: usize = 4096;
+
+/// Starts loading the enemy database
+pub fn load_enemy_database(
+    mut enemy_database: ResMut<EnemyDatabaseHandle>,
+    asset_server: Res<AssetServer>,
+) {
+    enemy_database.0 = asset_server.load(ENEMY_DATABASE_PATH);
+}
+
+/// Spawns the enemies for every `enemy` object on the map
+///
+/// The object's `kind` property picks the enemy type and its optional
+/// `count` property how many are spawned, which is mostly useful for swarms.
+/// Objects with the `boss` property don't spawn again once their enemies were
+/// killed. Objects are only processed once the enemy database has loaded.
+pub fn spawn_map_enemies(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    map_state: Res<MapState>,
+    world: Res<WorldChanges>,
+    enemy_database: Res<EnemyDatabaseHandle>,
+    enemy_databases: Res<Assets<EnemyDatabase>>,
+    object_query: Query<(Entity, &MapObject, &Transform), Without<SpawnedEnemy>>,
+) {
+    let Some(enemies) = enemy_databases.get(&enemy_database.0) else {
+        return;
+    };
+
+    for (entity, object, transform) in &object_query {
+        if object.class != ENEMY_OBJECT_CLASS {
+            continue;
+        }
+
+        let map_path = map_id(&asset_server, &map_state.handle).unwrap_or_default();
+        if world.is_boss_killed(&map_path, object.id) {
+            debug!("Not spawning boss object {}, it was killed", object.id);
+            commands.entity(entity).insert(SpawnedEnemy(vec![]));
+            continue;
+        }
+
+        let Some(kind) = object.string_property("kind") else {
+            warn!("Enemy object {} has no kind", object.id);
+            commands.entity(entity).insert(SpawnedEnemy(vec![]));
+            continue;
+        };
+        let Some(definition) = enemies.get(kind) else {
+            warn!("Enemy object {} has unknown kind {:?}", object.id, kind);
+            commands.entity(entity).insert(SpawnedEnemy(vec![]));
+            continue;
+        };
+
+        let count = object.int_property("count").unwrap_or(1).max(1) as u32;
+        debug!("Spawning {} {} from map object {}", count, kind, object.id);
+
+        let spawned = (0..count)
+            .map(|index| {
+                // Spread groups out around the object
+                let seed = object
+                    .id
+                    .wrapping_mul(SPAWN_SEED_STRIDE)
+                    .wrapping_add(index);
+                let offset = if count > 1 {
+                    wander_point(Vec2::ZERO, GROUP_SPAWN_RADIUS, seed)
+                } else {
+                    Vec2::ZERO
+                };
+
+                spawn_enemy(
+                    &mut commands,
+                    &asset_server,
+                    kind,
+                    definition,
+                    transform.translation + offset.extend(0.0),
+                    seed,
+                )
+            })
+            .collect();
+
+        commands.entity(entity).insert(SpawnedEnemy(spawned));
+    }
+}
+
+/// Spawns the enemies asked for by [SpawnEnemiesEvent]s
+pub fn spawn_requested_enemies(
+    mut commands: Commands,
+    mut spawn_events: EventReader<SpawnEnemiesEvent>,
+    mut rng: ResMut<GameRng>,
+    asset_server: Res<AssetServer>,
+    enemy_database: Res<EnemyDatabaseHandle>,
+    enemy_databases: Res<Assets<EnemyDatabase>>,
+) {
+    let Some(enemies) = enemy_databases.get(&enemy_database.0) else {
+        spawn_events.clear();
+        return;
+    };
+
+    for event in spawn_events.iter() {
+        let Some(definition) = enemies.get(&event.kind) else {
+            warn!("Tried to spawn unknown enemy kind {:?}", event.kind);
+            continue;
+        };
+        debug!(
+            "Spawning {} {} at {}",
+            event.count, event.kind, event.position
+        );
+
+        for _ in 0..event.count {
+            let seed = rng.range(0, u32::MAX);
+            let offset = if event.count > 1 {
+                wander_point(Vec2::ZERO, GROUP_SPAWN_RADIUS, seed)
+            } else {
+                Vec2::ZERO
+            };
+
+            spawn_enemy(
+                &mut commands,
+                &asset_server,
+                &event.kind,
+                definition,
+                (event.position + offset).extend(DRAW_LAYER::ENTITIES),
+                seed,
+            );
+        }
+    }
+}
+
+/// Spawns a single enemy
+fn spawn_enemy(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    kind: &str,
+    definition: &EnemyDefinition,
+    position: Vec3,
+    seed: u32,
+) -> Entity {
+    let (red, green, blue) = definition.tint;
+
+    let mut enemy = commands.spawn((
+        SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
+                color: Color::rgb(red, green, blue),
+                ..default()
+            },
+            transform: Transform::from_translation(position),
+            ..default()
+        },
+        SpriteAnimator::new(asset_server.load(definition.animations.as_str())),
+        Enemy {
+            kind: kind.into(),
+            definition: definition.clone(),
+        },
+        MapEntity,
+    ));
+
+    enemy
+        .insert(RigidBody::Dynamic)
+        .insert(GravityScale(0.0))
+        .insert(Sleeping::disabled())
+        .insert(Collider::ball(definition.radius))
+        .insert(ActiveEvents::COLLISION_EVENTS)
+        .insert(LockedAxes::ROTATION_LOCKED)
+        .insert(Damping {
+            linear_damping: 0.5,
+            angular_damping: 1.0,
+        })
+        .insert(Health::new(definition.health))
+        .insert(Team::Enemy)
+        .insert(Damage {
+            amount: definition.contact_damage,
+            knockback: definition.contact_knockback,
+        });
+
+    if let Some(flock) = definition.flock {
+        // Flocking enemies fly over everything but the walls, while still
+        // hurting whatever they touch
+        let heading = wander_point(Vec2::ZERO, 1.0, seed).normalize_or_zero();
+        enemy
+            .insert(Boid { settings: flock })
+            .insert(Velocity::linear(heading * flock.min_speed))
+            .insert(SolverGroups::new(
+                COLLISION_GROUP::CHARACTERS,
+                COLLISION_GROUP::WORLD,
+            ));
+    } else {
+        enemy
+            .insert(EnemyBrain::new(position.truncate(), seed))
+            .insert(Perception::default())
+            .insert(Velocity::zero())
+            .insert(SolverGroups::new(COLLISION_GROUP::CHARACTERS, Group::ALL));
+    }
+
+    if let EnemyAttack::Melee { weapon } = &definition.attack {
+        enemy.insert(EquippedWeapon(weapon.clone()));
+    }
+
+    if let Some(behavior) = &definition.behavior {
+        enemy.insert(Behavior::new(asset_server.load(behavior.as_str())));
+    }
+
+    enemy.id()
+}
+
+/// Turns what the player does into [NoiseEvent]s
+#[allow(clippy::type_complexity)]
+pub fn make_player_noise(
+    mut noise_events: EventWriter<NoiseEvent>,
+    mut impact_events: EventReader<ProjectileImpactEvent>,
+    player_query: Query<(&GlobalTransform, &Velocity, Option<Ref<MeleeAttack>>), With<Player>>,
+) {
+    for (transform, velocity, attack) in &player_query {
+        let position = transform.translation().truncate();
+
+        if velocity.linvel.length() > FOOTSTEP_SPEED {
+            noise_events.send(NoiseEvent {
+                position,
+                radius: FOOTSTEP_NOISE_RADIUS,
+            });
+        }
+
+        if attack.is_some_and(|attack| attack.is_added()) {
+            noise_events.send(NoiseEvent {
+                position,
+                radius: ATTACK_NOISE_RADIUS,
+            });
+        }
+    }
+
+    // Only the player's own projectiles give them away
+    for impact in impact_events.iter().filter(|impact| {
+        impact
+            .owner
+            .is_some_and(|owner| player_query.contains(owner))
+    }) {
+        noise_events.send(NoiseEvent {
+            position: impact.position,
+            radius: IMPACT_NOISE_RADIUS,
+        });
+    }
+}
+
+/// Updates what every enemy can see and hear
+#[allow(clippy::type_complexity)]
+pub fn perceive(
+    navigation: Option<Res<NavigationGrid>>,
+    mut noise_events: EventReader<NoiseEvent>,
+    mut enemy_query: Query<(&Enemy, &EnemyBrain, &mut Perception, &GlobalTransform), Without<Dead>>,
+    player_query: Query<(Entity, &GlobalTransform), (With<Player>, Without<Dead>)>,
+) {
+    let noises: Vec<NoiseEvent> = noise_events.iter().copied().collect();
+
+    for (enemy, brain, mut perception, transform) in &mut enemy_query {
+        let position = transform.translation().truncate();
+        let definition = &enemy.definition;
+
+        perception.target = None;
+        for (player, player_transform) in &player_query {
+            let player_position = player_transform.translation().truncate();
+            let offset = player_position - position;
+
+            // Enemies can always tell when the player is right next to them
+            let close = offset.length() <= definition.radius * 2.0;
+            let visible = offset.length() <= definition.sight_range
+                && in_sight_cone(brain.facing, offset, definition.sight_angle)
+                && navigation
+                    .as_ref()
+                    .is_none_or(|navigation| navigation.line_of_sight(position, player_position));
+
+            if close || visible {
+                perception.target = Some(player);
+                perception.last_seen = Some(player_position);
+                break;
+            }
+        }
+
+        // Remember the closest noise that was loud enough to hear
+        let heard = noises
+            .iter()
+            .filter(|noise| {
+                noise.position.distance(position) <= noise.radius.min(definition.hearing_range)
+            })
+            .min_by(|a, b| {
+                a.position
+                    .distance_squared(position)
+                    .total_cmp(&b.position.distance_squared(position))
+            });
+        if let Some(noise) = heard {
+            perception.heard = Some(noise.position);
+        }
+    }
+}
+
+/// Runs the state machine of every enemy without a behavior tree
+#[allow(clippy::type_complexity)]
+pub fn think(
+    mut enemy_query: Query<
+        (
+            &Enemy,
+            &mut EnemyBrain,
+            &mut Perception,
+            &Health,
+            &GlobalTransform,
+        ),
+        (Without<Dead>, Without<Behavior>),
+    >,
+) {
+    for (enemy, mut brain, mut perception, health, transform) in &mut enemy_query {
+        let position = transform.translation().truncate();
+
+        let situation = Situation {
+            target_distance: perception
+                .target
+                .and(perception.last_seen)
+                .map(|target| target.distance(position)),
+            lead: perception.heard.is_some() || perception.last_seen.is_some(),
+            health: health.current / health.max,
+            timer_finished: brain.timer.finished(),
+            arrived: brain.has_arrived(position),
+        };
+
+        if let Some((state, duration)) = next_state(brain.state, &enemy.definition, &situation) {
+            brain.enter_state(state, duration, &mut perception);
+        }
+    }
+}
+
+/// Counts down every enemy's timers and picks where it's heading based on
+/// its state
+pub fn steer(
+    time: Res<Time>,
+    navigation: Option<Res<NavigationGrid>>,
+    mut enemy_query: Query<
+        (&Enemy, &mut EnemyBrain, &mut Perception, &GlobalTransform),
+        Without<Dead>,
+    >,
+) {
+    for (enemy, mut brain, mut perception, transform) in &mut enemy_query {
+        let position = transform.translation().truncate();
+
+        brain.timer.tick(time.delta());
+        brain.attack_timer.tick(time.delta());
+
+        match brain.state {
+            EnemyState::Attack if matches!(enemy.definition.attack, EnemyAttack::Contact) => {
+                brain.goal = perception.last_seen;
+            }
+            EnemyState::Idle | EnemyState::Attack => brain.goal = None,
+            EnemyState::Patrol => {
+                if brain.goal.is_none() {
+                    brain.goal = Some(pick_wander_point(
+                        &mut brain,
+                        enemy.definition.patrol_radius,
+                        &navigation,
+                    ));
+                }
+            }
+            EnemyState::Investigate => {
+                // Newer noises are more interesting than old ones
+                if let Some(heard) = perception.heard.take() {
+                    brain.goal = Some(heard);
+                }
+            }
+            EnemyState::Chase => brain.goal = perception.last_seen,
+            EnemyState::Flee => {
+                if let Some(threat) = perception.last_seen {
+                    let away = (position - threat).normalize_or_zero();
+                    brain.goal = Some(position + away * FLEE_DISTANCE);
+                }
+            }
+        }
+    }
+}
+
+/// Picks a walkable spot near the enemy's home to wander to
+fn pick_wander_point(
+    brain: &mut EnemyBrain,
+    radius: f32,
+    navigation: &Option<Res<NavigationGrid>>,
+) -> Vec2 {
+    for _ in 0..WANDER_ATTEMPTS {
+        brain.wander_seed = brain.wander_seed.wrapping_add(1);
+        let point = wander_point(brain.home, radius, brain.wander_seed);
+
+        let walkable = navigation
+            .as_ref()
+            .is_none_or(|navigation| navigation.is_walkable(navigation.world_to_cell(point)));
+        if walkable {
+            return point;
+        }
+    }
+
+    brain.home
+}
+
+/// Points the player's flow field at wherever the player is
+pub fn update_player_flow_field(
+    navigation: Option<Res<NavigationGrid>>,
+    mut flow_field: ResMut<PlayerFlowField>,
+    player_query: Query<&GlobalTransform, (With<Player>, Without<Dead>)>,
+) {
+    let Some(navigation) = navigation else {
+        return;
+    };
+
+    let goals = player_query
+        .iter()
+        .map(|transform| navigation.world_to_cell(transform.translation().truncate()));
+
+    flow_field.0.set_goals(&navigation, goals);
+    // A new map can have the same size as the old one, and a field built for
+    // the old walls can't be repaired to fit it
+    if navigation.is_changed() {
+        flow_field.0.rebuild(&navigation);
+    }
+    flow_field.0.update(&navigation, FLOW_FIELD_BUDGET);
+}
+
+/// Moves every enemy towards its goal, following a path around the walls
+///
+/// Enemies chasing a player they can see follow the shared flow field
+/// instead of finding their own path.
+#[allow(clippy::type_complexity)]
+pub fn move_enemies(
+    time: Res<Time>,
+    navigation: Option<Res<NavigationGrid>>,
+    flow_field: Res<PlayerFlowField>,
+    mut enemy_query: Query<
+        (
+            &Enemy,
+            &mut EnemyBrain,
+            &Perception,
+            &mut Velocity,
+            &GlobalTransform,
+        ),
+        (Without<Dead>, Without<Staggered>, Without<MeleeAttack>),
+    >,
+) {
+    for (enemy, mut brain, perception, mut velocity, transform) in &mut enemy_query {
+        let position = transform.translation().truncate();
+
+        if brain.state == EnemyState::Chase && perception.target.is_some() {
+            // The field has no direction in the player's own cell, where
+            // walking straight at them works fine
+            let direction = flow_field.0.sample(position);
+            if direction != Vec2::ZERO {
+                brain.path.clear();
+                brain.path_goal = None;
+                brain.facing = direction;
+                velocity.linvel = direction * enemy.definition.chase_speed;
+                continue;
+            }
+        }
+
+        let Some(goal) = brain.goal else {
+            velocity.linvel = Vec2::ZERO;
+            brain.path.clear();
+            brain.path_goal = None;
+            continue;
+        };
+
+        brain.repath_timer.tick(time.delta());
+
+        // Find a new path whenever the goal moves to another cell, and every
+        // so often in case the enemy got pushed off its path
+        let goal_cell = navigation
+            .as_ref()
+            .map_or(IVec2::ZERO, |navigation| navigation.world_to_cell(goal));
+        if brain.path_goal != Some(goal_cell) || brain.repath_timer.finished() {
+            brain.path = find_waypoints(navigation.as_deref(), position, goal);
+            brain.path_goal = Some(goal_cell);
+            brain.repath_timer = Timer::from_seconds(REPATH_TIME, TimerMode::Once);
+        }
+
+        while brain
+            .path
+            .first()
+            .is_some_and(|waypoint| waypoint.distance(position) <= WAYPOINT_DISTANCE)
+        {
+            brain.path.remove(0);
+        }
+
+        let Some(waypoint) = brain.path.first() else {
+            velocity.linvel = Vec2::ZERO;
+            continue;
+        };
+
+        let direction = (*waypoint - position).normalize_or_zero();
+        let speed = match brain.state {
+            EnemyState::Chase | EnemyState::Flee => enemy.definition.chase_speed,
+            _ => enemy.definition.speed,
+        };
+
+        velocity.linvel = direction * speed;
+        if direction != Vec2::ZERO {
+            brain.facing = direction;
+        }
+    }
+}
+
+/// Stops enemies in their tracks while their thinking is paused for a
+/// conversation
+pub fn halt_enemies(mut enemy_query: Query<&mut Velocity, (With<Enemy>, Without<Dead>)>) {
+    for mut velocity in &mut enemy_query {
+        velocity.linvel = Vec2::ZERO;
+    }
+}
+
+/// Makes enemies in the [Attack](EnemyState::Attack) state attack the
+/// player
+#[allow(clippy::type_complexity)]
+pub fn enemy_attacks(
+    mut commands: Commands,
+    weapon_database: Res<WeaponDatabaseHandle>,
+    weapon_databases: Res<Assets<WeaponDatabase>>,
+    mut fire_events: EventWriter<FireProjectileEvent>,
+    mut enemy_query: Query<
+        (
+            Entity,
+            &Enemy,
+            &mut EnemyBrain,
+            &Perception,
+            &mut Velocity,
+            &GlobalTransform,
+            Option<&mut SpriteAnimator>,
+        ),
+        (
+            Without<Dead>,
+            Without<Staggered>,
+            Without<MeleeAttack>,
+            Without<AttackCooldown>,
+        ),
+    >,
+    target_query: Query<&GlobalTransform>,
+) {
+    for (entity, enemy, mut brain, perception, mut velocity, transform, animator) in
+        &mut enemy_query
+    {
+        if brain.state != EnemyState::Attack {
+            continue;
+        }
+        let Some(target) = perception
+            .target
+            .and_then(|target| target_query.get(target).ok())
+        else {
+            continue;
+        };
+
+        let position = transform.translation().truncate();
+        let direction = (target.translation().truncate() - position).normalize_or_zero();
+        if direction == Vec2::ZERO {
+            continue;
+        }
+
+        // Enemies that only hurt by touching keep running into the player
+        if let EnemyAttack::Contact = enemy.definition.attack {
+            continue;
+        }
+
+        velocity.linvel = Vec2::ZERO;
+        brain.facing = direction;
+        if let Some(mut animator) = animator {
+            if let Some(facing) = Facing::from_direction(direction) {
+                animator.facing = facing;
+            }
+        }
+
+        match &enemy.definition.attack {
+            EnemyAttack::Melee { weapon } => {
+                let Some(weapon) = weapon_databases
+                    .get(&weapon_database.0)
+                    .and_then(|weapons| weapons.get(weapon))
+                else {
+                    continue;
+                };
+                commands
+                    .entity(entity)
+                    .insert(MeleeAttack::new(weapon.clone(), direction));
+            }
+            EnemyAttack::Ranged {
+                projectile,
+                cooldown,
+            } => {
+                if !brain.attack_timer.finished() {
+                    continue;
+                }
+                fire_events.send(FireProjectileEvent {
+                    kind: projectile.clone(),
+                    owner: Some(entity),
+                    position: position + direction * (enemy.definition.radius + 4.0),
+                    direction,
+                });
+                brain.attack_timer = Timer::from_seconds(*cooldown, TimerMode::Once);
+            }
+            EnemyAttack::Contact => {}
+        }
+    }
+}
+
+/// Stops dead enemies and leaves their body behind for a while
+pub fn handle_enemy_death(
+    mut commands: Commands,
+    mut death_events: EventReader<DeathEvent>,
+    mut loot_events: EventWriter<DropLootEvent>,
+    mut enemy_query: Query<(
+        &Enemy,
+        &Transform,
+        &mut Velocity,
+        Option<&mut SpriteAnimator>,
+    )>,
+) {
+    for event in death_events.iter() {
+        let Ok((enemy, transform, mut velocity, animator)) = enemy_query.get_mut(event.entity)
+        else {
+            continue;
+        };
+
+        if let Some(table) = &enemy.definition.loot {
+            loot_events.send(DropLootEvent {
+                table: table.clone(),
+                position: transform.translation.truncate(),
+            });
+        }
+
+        velocity.linvel = Vec2::ZERO;
+        if let Some(mut animator) = animator {
+            animator.play_action(AnimationState::Death);
+        }
+
+        commands
+            .entity(event.entity)
+            .remove::<Damage>()
+            .insert(ColliderDisabled)
+            .insert(Corpse(Timer::from_seconds(CORPSE_TIME, TimerMode::Once)));
+    }
+}
+
+/// Removes the bodies of dead enemies once their time is up
+pub fn decay_corpses(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut corpse_query: Query<(Entity, &mut Corpse)>,
+) {
+    for (entity, mut corpse) in &mut corpse_query {
+        if corpse.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! Decision making for enemies, kept apart from the ECS so it's easy to test
+
+use super::{asset::EnemyDefinition, components::EnemyState};
+use crate::map::navigation::NavigationGrid;
+use bevy::prelude::*;
+
+/// Everything an enemy considers when deciding what to do next
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Situation {
+    /// How far away the player is, if the enemy can see them
+    pub target_distance: Option<f32>,
+    /// Whether the enemy has somewhere to investigate
+    pub lead: bool,
+    /// The enemy's health as a fraction of its maximum
+    pub health: f32,
+    /// Whether the timer of the current state has run out
+    pub timer_finished: bool,
+    /// Whether the enemy has reached where it was heading
+    pub arrived: bool,
+}
+
+/// Picks the state an enemy should switch to, along with how long it should
+/// stay in it
+///
+/// Returns `None` when the enemy should keep doing what it's doing.
+pub fn next_state(
+    current: EnemyState,
+    definition: &EnemyDefinition,
+    situation: &Situation,
+) -> Option<(EnemyState, f32)> {
+    let next = if current == EnemyState::Flee && !situation.timer_finished {
+        return None;
+    } else if let Some(distance) = situation.target_distance {
+        if situation.health < definition.flee_below {
+            (EnemyState::Flee, definition.flee_time)
+        } else if distance <= definition.attack_range {
+            (EnemyState::Attack, 0.0)
+        } else {
+            (EnemyState::Chase, 0.0)
+        }
+    } else {
+        match current {
+            // The player got away, so go look for them
+            EnemyState::Chase | EnemyState::Attack | EnemyState::Flee => {
+                (EnemyState::Investigate, definition.investigate_time)
+            }
+            EnemyState::Idle | EnemyState::Patrol if situation.lead => {
+                (EnemyState::Investigate, definition.investigate_time)
+            }
+            EnemyState::Idle if situation.timer_finished => (EnemyState::Patrol, 0.0),
+            EnemyState::Patrol if situation.arrived => (EnemyState::Idle, definition.idle_time),
+            EnemyState::Investigate if situation.timer_finished => {
+                (EnemyState::Idle, definition.idle_time)
+            }
+            _ => return None,
+        }
+    };
+
+    (next.0 != current).then_some(next)
+}
+
+/// Whether `offset` lies within a sight cone `angle` degrees wide looking
+/// towards `facing`
+pub fn in_sight_cone(facing: Vec2, offset: Vec2, angle: f32) -> bool {
+    if offset == Vec2::ZERO || angle >= 360.0 {
+        return true;
+    }
+
+    facing.angle_between(offset).abs() <= angle.to_radians() / 2.0
+}
+
+/// Picks a spot within `radius` of `home` to wander to
+///
+/// The spot only depends on the seed, so every enemy wanders differently
+/// without needing a random number generator.
+pub fn wander_point(home: Vec2, radius: f32, seed: u32) -> Vec2 {
+    // A small integer hash spreads consecutive seeds out
+    let mut hash = seed.wrapping_mul(0x9e37_79b9);
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+
+    let angle = (hash & 0xffff) as f32 / 65535.0 * std::f32::consts::TAU;
+    let distance = (hash >> 16) as f32 / 65535.0 * radius;
+    home + Vec2::from_angle(angle) * distance
+}
+
+/// Finds the waypoints leading from `start` to `goal`
+///
+/// Falls back to walking straight at the goal when there's no navigation grid
+/// or no path.
+pub fn find_waypoints(navigation: Option<&NavigationGrid>, start: Vec2, goal: Vec2) -> Vec<Vec2> {
+    let Some(navigation) = navigation else {
+        return vec![goal];
+    };
+
+    let path = navigation.find_path(
+        navigation.world_to_cell(start),
+        navigation.world_to_cell(goal),
+    );
+    let Some(path) = path else {
+        return vec![goal];
+    };
+
+    // The path already leaves out the cell the enemy is in, so only the last
+    // cell is swapped for the exact goal
+    let mut waypoints: Vec<Vec2> = path
+        .into_iter()
+        .map(|cell| navigation.cell_to_world(cell))
+        .collect();
+    waypoints.pop();
+    waypoints.push(goal);
+    waypoints
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{enemy::asset::EnemyAttack, map::navigation::test::grid_from_rows};
+
+    fn definition() -> EnemyDefinition {
+        EnemyDefinition {
+            health: 10.0,
+            speed: 40.0,
+            chase_speed: 80.0,
+            contact_damage: 5.0,
+            contact_knockback: 100.0,
+            radius: 10.0,
+            sight_range: 200.0,
+            sight_angle: 120.0,
+            hearing_range: 100.0,
+            attack_range: 30.0,
+            attack: EnemyAttack::Melee {
+                weapon: "fists".into(),
+            },
+            flee_below: 0.25,
+            flee_time: 2.0,
+            patrol_radius: 64.0,
+            idle_time: 1.0,
+            investigate_time: 3.0,
+            animations: String::new(),
+            flock: None,
+            behavior: None,
+            loot: None,
+            tint: (1.0, 1.0, 1.0),
+        }
+    }
+
+    #[test]
+    fn spotting_the_player_starts_a_chase_or_attack() {
+        let definition = definition();
+        let mut situation = Situation {
+            target_distance: Some(100.0),
+            health: 1.0,
+            ..default()
+        };
+
+        assert_eq!(
+            next_state(EnemyState::Patrol, &definition, &situation),
+            Some((EnemyState::Chase, 0.0))
+        );
+
+        situation.target_distance = Some(20.0);
+        assert_eq!(
+            next_state(EnemyState::Chase, &definition, &situation),
+            Some((EnemyState::Attack, 0.0))
+        );
+        assert_eq!(
+            next_state(EnemyState::Attack, &definition, &situation),
+            None
+        );
+    }
+
+    #[test]
+    fn hurt_enemies_flee_until_the_timer_runs_out() {
+        let definition = definition();
+        let mut situation = Situation {
+            target_distance: Some(20.0),
+            health: 0.1,
+            ..default()
+        };
+
+        assert_eq!(
+            next_state(EnemyState::Attack, &definition, &situation),
+            Some((EnemyState::Flee, 2.0))
+        );
+        assert_eq!(next_state(EnemyState::Flee, &definition, &situation), None);
+
+        situation.target_distance = None;
+        situation.timer_finished = true;
+        assert_eq!(
+            next_state(EnemyState::Flee, &definition, &situation),
+            Some((EnemyState::Investigate, 3.0))
+        );
+    }
+
+    #[test]
+    fn idle_enemies_patrol_and_investigate_leads() {
+        let definition = definition();
+        let mut situation = Situation {
+            health: 1.0,
+            ..default()
+        };
+
+        assert_eq!(next_state(EnemyState::Idle, &definition, &situation), None);
+
+        situation.timer_finished = true;
+        assert_eq!(
+            next_state(EnemyState::Idle, &definition, &situation),
+            Some((EnemyState::Patrol, 0.0))
+        );
+
+        situation.lead = true;
+        assert_eq!(
+            next_state(EnemyState::Patrol, &definition, &situation),
+            Some((EnemyState::Investigate, 3.0))
+        );
+    }
+
+    #[test]
+    fn sight_cone() {
+        assert!(in_sight_cone(Vec2::X, Vec2::new(10.0, 5.0), 90.0));
+        assert!(!in_sight_cone(Vec2::X, Vec2::new(-10.0, 0.0), 90.0));
+        assert!(in_sight_cone(Vec2::X, Vec2::new(-10.0, 0.0), 360.0));
+    }
+
+    #[test]
+    fn wander_points_stay_near_home() {
+        let home = Vec2::new(100.0, 100.0);
+        for seed in 0..100 {
+            assert!(wander_point(home, 50.0, seed).distance(home) <= 50.0 + f32::EPSILON);
+        }
+        assert_ne!(wander_point(home, 50.0, 1), wander_point(home, 50.0, 2));
+    }
+
+    #[test]
+    fn waypoints_turn_at_the_corner_of_a_wall() {
+        let grid = grid_from_rows(&[
+            "....", //
+            ".#..", //
+            ".##.", //
+            "....", //
+        ]);
+        let start = grid.cell_to_world(IVec2::new(0, 2));
+        let goal = grid.cell_to_world(IVec2::new(3, 2)) + Vec2::new(3.0, -2.0);
+
+        let waypoints = find_waypoints(Some(&grid), start, goal);
+
+        // The only way around is up past the corner of the wall first
+        assert_eq!(
+            waypoints.first(),
+            Some(&grid.cell_to_world(IVec2::new(0, 3)))
+        );
+        assert_eq!(waypoints.last(), Some(&goal));
+        assert!(waypoints
+            .iter()
+            .all(|waypoint| grid.is_walkable(grid.world_to_cell(*waypoint))));
+        assert_eq!(find_waypoints(None, start, goal), vec![goal]);
+    }
+}
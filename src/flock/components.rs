@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// How a flock reacts to the player
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Deserialize)]
+pub enum PlayerResponse {
+    /// Swoops towards the player
+    Follow,
+    /// Scatters away from the player
+    Flee,
+    /// Doesn't care about the player
+    #[default]
+    Ignore,
+}
+
+/// How a boid flies
+///
+/// Distances are in pixels, speeds in pixels per second and forces in pixels
+/// per second squared. The weights scale how much each steering behavior
+/// counts.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct FlockSettings {
+    pub min_speed: f32,
+    pub max_speed: f32,
+    /// The strongest each steering behavior can push
+    pub max_force: f32,
+    /// How far away other boids count as neighbors
+    pub neighbor_radius: f32,
+    /// How close other boids have to be before they're pushed away from
+    pub separation_radius: f32,
+    pub separation: f32,
+    pub alignment: f32,
+    pub cohesion: f32,
+    /// How far ahead the boid looks for walls
+    pub wall_distance: f32,
+    pub wall_avoidance: f32,
+    #[serde(default)]
+    pub player: PlayerResponse,
+    /// How close the player has to be for the boid to react
+    #[serde(default)]
+    pub player_range: f32,
+    #[serde(default)]
+    pub player_weight: f32,
+}
+
+/// An agent steered by flocking with the boids around it
+#[derive(Debug, Component)]
+pub struct Boid {
+    pub settings: FlockSettings,
+}
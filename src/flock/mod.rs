@@ -0,0 +1,14 @@
+//! Module for flocking
+//!
+//! This module includes:
+//! - A spatial hash keeping neighbor lookups cheap with hundreds of agents
+//! - Separation, alignment and cohesion steering, along with steering around
+//!   walls and towards or away from the player
+//! - Systems driving every [Boid](components::Boid) through its `Velocity`
+//! - A plugin to setup the required systems and resources
+
+pub mod components;
+pub mod plugin;
+pub mod spatial_hash;
+pub mod systems;
+pub mod util;
@@ -0,0 +1,29 @@
+use super::{
+    spatial_hash::SpatialHash,
+    systems::{build_boid_hash, steer_boids, BoidNeighbor},
+};
+use crate::{
+    constants::PIXELS_PER_METER, dialogue::systems::dialogue_closed, menu::state::GameMenuState,
+};
+use bevy::prelude::*;
+
+/// The size of the spatial hash's cells, which should be close to the
+/// neighbor radius of most boids
+const BOID_CELL_SIZE: f32 = PIXELS_PER_METER * 2.0;
+
+#[derive(Debug, Default)]
+pub struct FlockPlugin;
+
+impl Plugin for FlockPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SpatialHash::<BoidNeighbor>::new(BOID_CELL_SIZE));
+
+        app.add_systems(
+            (build_boid_hash, steer_boids)
+                .chain()
+                .distributive_run_if(dialogue_closed)
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        debug!("FlockPlugin loaded");
+    }
+}
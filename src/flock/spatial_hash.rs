@@ -0,0 +1,90 @@
+use bevy::{prelude::*, utils::HashMap};
+
+/// Buckets positions into a grid of square cells so everything near a point
+/// can be found without checking every entry
+#[derive(Debug, Resource)]
+pub struct SpatialHash<T: Send + Sync + 'static> {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<(Vec2, T)>>,
+}
+
+impl<T: Send + Sync + 'static> SpatialHash<T> {
+    /// Creates an empty hash. Lookups are cheapest when the cells are about
+    /// as big as the radius searched.
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::default(),
+        }
+    }
+
+    /// The cell a position falls into
+    pub fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    /// Removes every entry, holding onto the memory for the next frame
+    pub fn clear(&mut self) {
+        for entries in self.cells.values_mut() {
+            entries.clear();
+        }
+    }
+
+    pub fn insert(&mut self, position: Vec2, value: T) {
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push((position, value));
+    }
+
+    /// Iterates over every entry within `radius` of `position`
+    pub fn query(&self, position: Vec2, radius: f32) -> impl Iterator<Item = &(Vec2, T)> + '_ {
+        let min = self.cell(position - Vec2::splat(radius));
+        let max = self.cell(position + Vec2::splat(radius));
+        let radius_squared = radius * radius;
+
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .filter(move |(other, _)| other.distance_squared(position) <= radius_squared)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn query_matches_brute_force() {
+        let mut hash = SpatialHash::new(16.0);
+        let mut points = vec![];
+        for i in 0..200 {
+            // Spread the points out over negative and positive coordinates
+            let point = Vec2::new(
+                ((i * 37) % 101) as f32 - 50.0,
+                ((i * 61) % 89) as f32 - 44.0,
+            );
+            hash.insert(point, i);
+            points.push(point);
+        }
+
+        let center = Vec2::new(3.5, -7.25);
+        let mut found: Vec<i32> = hash.query(center, 20.0).map(|(_, i)| *i).collect();
+        found.sort();
+
+        let expected: Vec<i32> = (0..200)
+            .filter(|&i| points[i as usize].distance(center) <= 20.0)
+            .collect();
+        assert_eq!(found, expected);
+        assert!(!expected.is_empty());
+    }
+
+    #[test]
+    fn clear_empties_every_cell() {
+        let mut hash = SpatialHash::new(8.0);
+        hash.insert(Vec2::ZERO, ());
+        hash.insert(Vec2::new(100.0, 100.0), ());
+        hash.clear();
+
+        assert_eq!(hash.query(Vec2::ZERO, 1000.0).count(), 0);
+    }
+}
@@ -0,0 +1,100 @@
+//! Defines all of the systems used by the flock plugin
+
+use super::{
+    components::Boid,
+    spatial_hash::SpatialHash,
+    util::{flocking_force, integrate, keep_nearest, player_force, wall_avoidance_force},
+};
+use crate::{
+    health::components::{Dead, Staggered},
+    map::navigation::NavigationGrid,
+    player::Player,
+};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+/// The most neighbors a boid pays attention to, the closest ones, which
+/// keeps dense swarms from getting expensive
+const MAX_NEIGHBORS: usize = 12;
+
+/// A boid as seen by its neighbors
+#[derive(Debug, Clone, Copy)]
+pub struct BoidNeighbor {
+    pub entity: Entity,
+    pub velocity: Vec2,
+}
+
+/// Puts every boid into the spatial hash for this frame's neighbor lookups
+#[allow(clippy::type_complexity)]
+pub fn build_boid_hash(
+    mut hash: ResMut<SpatialHash<BoidNeighbor>>,
+    boid_query: Query<(Entity, &Transform, &Velocity), (With<Boid>, Without<Dead>)>,
+) {
+    hash.clear();
+
+    for (entity, transform, velocity) in &boid_query {
+        hash.insert(
+            transform.translation.truncate(),
+            BoidNeighbor {
+                entity,
+                velocity: velocity.linvel,
+            },
+        );
+    }
+}
+
+/// Steers every boid by flocking with its neighbors, avoiding walls and
+/// reacting to the player
+#[allow(clippy::type_complexity)]
+pub fn steer_boids(
+    time: Res<Time>,
+    hash: Res<SpatialHash<BoidNeighbor>>,
+    navigation: Option<Res<NavigationGrid>>,
+    player_query: Query<&Transform, (With<Player>, Without<Dead>)>,
+    mut boid_query: Query<
+        (Entity, &Boid, &Transform, &mut Velocity),
+        (Without<Dead>, Without<Staggered>),
+    >,
+) {
+    let delta = time.delta_seconds();
+    let players: Vec<Vec2> = player_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    let mut neighbors = Vec::with_capacity(MAX_NEIGHBORS);
+
+    for (entity, boid, transform, mut velocity) in &mut boid_query {
+        let settings = &boid.settings;
+        let position = transform.translation.truncate();
+
+        neighbors.clear();
+        neighbors.extend(
+            hash.query(position, settings.neighbor_radius)
+                .filter(|(_, neighbor)| neighbor.entity != entity)
+                .map(|(other, neighbor)| (*other, neighbor.velocity)),
+        );
+        keep_nearest(position, &mut neighbors, MAX_NEIGHBORS);
+
+        let mut force = flocking_force(
+            position,
+            velocity.linvel,
+            neighbors.iter().copied(),
+            settings,
+        );
+
+        if let Some(navigation) = &navigation {
+            force += wall_avoidance_force(navigation, position, velocity.linvel, settings);
+        }
+
+        // React to whichever player is closest
+        let closest = players.iter().min_by(|a, b| {
+            a.distance_squared(position)
+                .total_cmp(&b.distance_squared(position))
+        });
+        if let Some(player) = closest {
+            force += player_force(position, velocity.linvel, *player, settings);
+        }
+
+        velocity.linvel = integrate(velocity.linvel, force, delta, settings);
+    }
+}
@@ -0,0 +1,255 @@
+//! Steering behaviors for flocking, kept apart from the ECS so they're easy
+//! to test
+
+use super::components::{FlockSettings, PlayerResponse};
+use crate::map::navigation::NavigationGrid;
+use bevy::prelude::*;
+
+/// The angles, in radians, of the feelers a boid looks for walls with
+const WALL_FEELERS: [f32; 3] = [0.0, 0.6, -0.6];
+
+/// The force turning `velocity` towards `direction` at full speed
+pub fn steer_towards(direction: Vec2, velocity: Vec2, settings: &FlockSettings) -> Vec2 {
+    if direction == Vec2::ZERO {
+        return Vec2::ZERO;
+    }
+
+    (direction.normalize() * settings.max_speed - velocity).clamp_length_max(settings.max_force)
+}
+
+/// Combines separation, alignment and cohesion with the given neighbors,
+/// which are pairs of positions and velocities
+pub fn flocking_force(
+    position: Vec2,
+    velocity: Vec2,
+    neighbors: impl Iterator<Item = (Vec2, Vec2)>,
+    settings: &FlockSettings,
+) -> Vec2 {
+    let mut separation = Vec2::ZERO;
+    let mut heading = Vec2::ZERO;
+    let mut center = Vec2::ZERO;
+    let mut count = 0;
+
+    for (other, other_velocity) in neighbors {
+        let offset = position - other;
+        let distance = offset.length();
+
+        // Closer neighbors push harder
+        if distance > 0.0 && distance < settings.separation_radius {
+            separation += offset / (distance * distance);
+        }
+
+        heading += other_velocity;
+        center += other;
+        count += 1;
+    }
+
+    if count == 0 {
+        return Vec2::ZERO;
+    }
+
+    let count = count as f32;
+    steer_towards(separation, velocity, settings) * settings.separation
+        + steer_towards(heading / count, velocity, settings) * settings.alignment
+        + steer_towards(center / count - position, velocity, settings) * settings.cohesion
+}
+
+/// Steers away from walls ahead of the boid
+pub fn wall_avoidance_force(
+    navigation: &NavigationGrid,
+    position: Vec2,
+    velocity: Vec2,
+    settings: &FlockSettings,
+) -> Vec2 {
+    let heading = velocity.normalize_or_zero();
+    if heading == Vec2::ZERO {
+        return Vec2::ZERO;
+    }
+
+    let mut away = Vec2::ZERO;
+    for angle in WALL_FEELERS {
+        let feeler = Vec2::from_angle(angle).rotate(heading);
+        let probe = position + feeler * settings.wall_distance;
+        if !navigation.is_walkable(navigation.world_to_cell(probe)) {
+            away -= feeler;
+        }
+    }
+
+    steer_towards(away, velocity, settings) * settings.wall_avoidance
+}
+
+/// Steers towards or away from the player when they're close enough
+pub fn player_force(
+    position: Vec2,
+    velocity: Vec2,
+    player: Vec2,
+    settings: &FlockSettings,
+) -> Vec2 {
+    if position.distance(player) > settings.player_range {
+        return Vec2::ZERO;
+    }
+
+    let direction = match settings.player {
+        PlayerResponse::Follow => player - position,
+        PlayerResponse::Flee => position - player,
+        PlayerResponse::Ignore => return Vec2::ZERO,
+    };
+
+    steer_towards(direction, velocity, settings) * settings.player_weight
+}
+
+/// Applies a steering force, keeping the speed between the boid's limits
+pub fn integrate(velocity: Vec2, force: Vec2, delta: f32, settings: &FlockSettings) -> Vec2 {
+    let velocity = (velocity + force * delta).clamp_length_max(settings.max_speed);
+
+    // Keep flying even when all the forces cancel out
+    if velocity.length() < settings.min_speed {
+        let heading = if velocity == Vec2::ZERO {
+            Vec2::X
+        } else {
+            velocity.normalize()
+        };
+        return heading * settings.min_speed;
+    }
+
+    velocity
+}
+
+/// Keeps only the `max` neighbors closest to `position`, in no particular
+/// order. Neighbors are pairs of positions and velocities.
+pub fn keep_nearest(position: Vec2, neighbors: &mut Vec<(Vec2, Vec2)>, max: usize) {
+    if neighbors.len() <= max {
+        return;
+    }
+
+    if max > 0 {
+        neighbors.select_nth_unstable_by(max - 1, |a, b| {
+            a.0.distance_squared(position)
+                .total_cmp(&b.0.distance_squared(position))
+        });
+    }
+    neighbors.truncate(max);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::map::navigation::test::grid_from_rows;
+
+    fn settings() -> FlockSettings {
+        FlockSettings {
+            min_speed: 20.0,
+            max_speed: 100.0,
+            max_force: 200.0,
+            neighbor_radius: 48.0,
+            separation_radius: 16.0,
+            separation: 1.0,
+            alignment: 0.0,
+            cohesion: 0.0,
+            wall_distance: 24.0,
+            wall_avoidance: 1.0,
+            player: PlayerResponse::Flee,
+            player_range: 100.0,
+            player_weight: 1.0,
+        }
+    }
+
+    #[test]
+    fn separation_pushes_away_from_close_neighbors() {
+        let settings = settings();
+        let force = flocking_force(
+            Vec2::ZERO,
+            Vec2::ZERO,
+            [(Vec2::new(4.0, 0.0), Vec2::ZERO)].into_iter(),
+            &settings,
+        );
+
+        assert!(force.x < 0.0);
+        assert!(force.y.abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn cohesion_and_alignment_follow_the_group() {
+        let settings = FlockSettings {
+            separation: 0.0,
+            cohesion: 1.0,
+            ..settings()
+        };
+        let neighbors = [
+            (Vec2::new(30.0, 10.0), Vec2::ZERO),
+            (Vec2::new(30.0, -10.0), Vec2::ZERO),
+        ];
+        let force = flocking_force(Vec2::ZERO, Vec2::ZERO, neighbors.into_iter(), &settings);
+        assert!(force.x > 0.0);
+
+        let settings = FlockSettings {
+            separation: 0.0,
+            alignment: 1.0,
+            ..settings
+        };
+        let neighbors = [(Vec2::new(30.0, 0.0), Vec2::new(0.0, 50.0))];
+        let force = flocking_force(Vec2::ZERO, Vec2::ZERO, neighbors.into_iter(), &settings);
+        assert!(force.y > 0.0);
+    }
+
+    #[test]
+    fn no_neighbors_means_no_force() {
+        let force = flocking_force(Vec2::ZERO, Vec2::X, std::iter::empty(), &settings());
+        assert_eq!(force, Vec2::ZERO);
+    }
+
+    #[test]
+    fn walls_ahead_push_back() {
+        let navigation = grid_from_rows(&["....#", "....#", "....#"]);
+        let position = navigation.cell_to_world(IVec2::new(3, 1));
+
+        let force = wall_avoidance_force(&navigation, position, Vec2::new(50.0, 0.0), &settings());
+        assert!(force.x < 0.0);
+
+        let force = wall_avoidance_force(&navigation, position, Vec2::new(-50.0, 0.0), &settings());
+        assert_eq!(force, Vec2::ZERO);
+    }
+
+    #[test]
+    fn player_response() {
+        let settings = settings();
+        let force = player_force(Vec2::ZERO, Vec2::ZERO, Vec2::new(50.0, 0.0), &settings);
+        assert!(force.x < 0.0);
+
+        let follow = FlockSettings {
+            player: PlayerResponse::Follow,
+            ..settings
+        };
+        let force = player_force(Vec2::ZERO, Vec2::ZERO, Vec2::new(50.0, 0.0), &follow);
+        assert!(force.x > 0.0);
+
+        let force = player_force(Vec2::ZERO, Vec2::ZERO, Vec2::new(500.0, 0.0), &follow);
+        assert_eq!(force, Vec2::ZERO);
+    }
+
+    #[test]
+    fn speed_stays_within_limits() {
+        let settings = settings();
+        assert_eq!(
+            integrate(Vec2::ZERO, Vec2::ZERO, 0.1, &settings).length(),
+            20.0
+        );
+        assert!(integrate(Vec2::X * 90.0, Vec2::X * 500.0, 1.0, &settings).length() <= 100.0);
+    }
+
+    #[test]
+    fn only_the_nearest_neighbors_are_kept() {
+        let mut neighbors: Vec<(Vec2, Vec2)> = [9.0, 2.0, 7.0, 1.0, 5.0, 3.0]
+            .into_iter()
+            .map(|x| (Vec2::new(x, 0.0), Vec2::ZERO))
+            .collect();
+
+        keep_nearest(Vec2::ZERO, &mut neighbors, 3);
+        let mut kept: Vec<f32> = neighbors.iter().map(|(position, _)| position.x).collect();
+        kept.sort_by(f32::total_cmp);
+        assert_eq!(kept, vec![1.0, 2.0, 3.0]);
+
+        keep_nearest(Vec2::ZERO, &mut neighbors, 0);
+        assert!(neighbors.is_empty());
+    }
+}
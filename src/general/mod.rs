@@ -1 +1,3 @@
 pub mod components;
+pub mod resources;
+pub mod rng;
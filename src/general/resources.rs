@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How hard the game is
+#[derive(
+    Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Resource,
+)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+/// Named values tracking what happened in the game so far, like which
+/// characters the player talked to
+///
+/// A flag that was never set counts as zero.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, Resource)]
+pub struct GameFlags(HashMap<String, i32>);
+
+impl GameFlags {
+    pub fn get(&self, flag: &str) -> i32 {
+        self.0.get(flag).copied().unwrap_or(0)
+    }
+
+    /// Whether the flag holds anything other than zero
+    pub fn is_set(&self, flag: &str) -> bool {
+        self.get(flag) != 0
+    }
+
+    pub fn set(&mut self, flag: impl Into<String>, value: i32) {
+        self.0.insert(flag.into(), value);
+    }
+
+    pub fn clear(&mut self, flag: &str) {
+        self.0.remove(flag);
+    }
+
+    /// Adds to the flag's value, returning the new value
+    pub fn add(&mut self, flag: &str, amount: i32) -> i32 {
+        let value = self.get(flag) + amount;
+        self.set(flag, value);
+        value
+    }
+}
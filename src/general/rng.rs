@@ -0,0 +1,87 @@
+//! A small seedable random number generator
+//!
+//! Gameplay randomness goes through [GameRng] so a run can be replayed from
+//! its seed and tests get the same results every time. The generator is
+//! SplitMix64, which is fast and produces the same numbers on every platform.
+
+use bevy::prelude::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The environment variable that fixes the seed of a run
+pub const SEED_VARIABLE: &str = "THE_CAVERNS_SEED";
+
+#[derive(Debug, Clone, Resource)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Uses the seed from the environment if there is one, falling back to
+    /// the current time
+    pub fn from_env() -> Self {
+        let seed = std::env::var(SEED_VARIABLE)
+            .ok()
+            .and_then(|seed| seed.parse().ok())
+            .unwrap_or_else(|| {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |time| time.as_nanos() as u64)
+            });
+
+        info!("Random seed is {}", seed);
+        Self::new(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0.0..1.0`
+    pub fn f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// A number in `min..=max`
+    pub fn range(&mut self, min: u32, max: u32) -> u32 {
+        let (min, max) = (min.min(max), min.max(max));
+        let span = (max - min) as u64 + 1;
+        min + (self.next_u64() % span) as u32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut a = GameRng::new(42);
+        let mut b = GameRng::new(42);
+        let mut c = GameRng::new(43);
+
+        let a: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        let b: Vec<u64> = (0..8).map(|_| b.next_u64()).collect();
+        let c: Vec<u64> = (0..8).map(|_| c.next_u64()).collect();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn ranges_are_inclusive() {
+        let mut rng = GameRng::new(7);
+        let rolls: Vec<u32> = (0..1000).map(|_| rng.range(2, 4)).collect();
+
+        assert!(rolls.iter().all(|roll| (2..=4).contains(roll)));
+        assert!(rolls.contains(&2) && rolls.contains(&4));
+        assert_eq!(rng.range(5, 5), 5);
+        assert!((0..1000).all(|_| (0.0..1.0).contains(&rng.f32())));
+    }
+}
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+
+/// The amount of health an entity has
+///
+/// Once `current` reaches zero a [DeathEvent](super::events::DeathEvent) is
+/// sent for the entity.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+
+    /// Restores the health back to its maximum
+    pub fn refill(&mut self) {
+        self.current = self.max;
+    }
+}
+
+/// Deals damage to any entity with [Health] it touches
+///
+/// The entity needs a collider with collision events enabled.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Damage {
+    pub amount: f32,
+    /// How hard the victim is pushed away, in pixels per second
+    pub knockback: f32,
+}
+
+/// How long an entity ignores damage for after getting hit
+#[derive(Debug, Clone, Copy, Component)]
+pub struct InvulnerabilityOnHit(pub f32);
+
+impl Default for InvulnerabilityOnHit {
+    fn default() -> Self {
+        Self(0.75)
+    }
+}
+
+/// Makes an entity ignore all damage until the timer runs out
+#[derive(Debug, Component)]
+pub struct Invulnerable {
+    pub timer: Timer,
+}
+
+impl Invulnerable {
+    pub fn new(seconds: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+        }
+    }
+}
+
+/// Marks an entity as reeling from knockback
+///
+/// Staggered entities can't be controlled and come to a stop once the timer
+/// runs out.
+#[derive(Debug, Component)]
+pub struct Staggered {
+    pub timer: Timer,
+}
+
+/// Which side an entity fights on
+///
+/// Damage between entities on the same team is ignored, so enemies don't
+/// hurt each other by bumping into one another.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Component)]
+pub enum Team {
+    Player,
+    Enemy,
+}
+
+/// Marks an entity as dead so it isn't killed more than once
+#[derive(Debug, Component)]
+pub struct Dead;
+
+/// A place the player respawns at after touching it
+///
+/// Checkpoints only work once, and this is removed after the player respawns
+/// at it. The entity needs a sensor collider with collision events enabled.
+#[derive(Debug, Component)]
+pub struct Checkpoint;
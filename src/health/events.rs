@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+
+/// Requests that damage is dealt to an entity
+///
+/// This is the only way damage should be dealt, so invulnerability,
+/// knockback and death are handled the same way for everything.
+#[derive(Debug, Clone, Copy)]
+pub struct DamageEvent {
+    /// The entity taking the damage
+    pub target: Entity,
+    /// The entity dealing the damage, if there is one
+    pub source: Option<Entity>,
+    pub amount: f32,
+    /// The velocity the target is pushed with
+    pub knockback: Vec2,
+}
+
+/// Sent once when an entity's health reaches zero
+#[derive(Debug, Clone, Copy)]
+pub struct DeathEvent {
+    pub entity: Entity,
+    /// The entity that dealt the killing blow, if there is one
+    pub killer: Option<Entity>,
+}
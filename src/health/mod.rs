@@ -0,0 +1,18 @@
+//! Module for health, damage and death
+//!
+//! This module includes:
+//! - Components to give entities health and to let them deal damage
+//! - A single [DamageEvent](events::DamageEvent) path used by everything
+//!   that can hurt something, be it an enemy, a hazard or a projectile
+//! - Invulnerability frames and knockback after getting hit
+//! - A state system for the player's death and respawn, with a number of
+//!   lives and single use checkpoints before the game is over
+//! - A plugin to setup the required systems, events and states
+
+pub mod components;
+pub mod events;
+pub mod plugin;
+pub mod resources;
+pub mod state;
+pub mod systems;
+pub mod util;
@@ -0,0 +1,55 @@
+use super::{
+    events::{DamageEvent, DeathEvent},
+    resources::{Contacts, LastCheckpoint, Lives, RespawnTimer},
+    state::PlayerLifeState,
+    systems::{
+        apply_damage, clear_checkpoint, deal_contact_damage, handle_player_death, reset_life_state,
+        respawn_player, start_respawn_timer, tick_invulnerability, tick_respawn_timer,
+        tick_stagger, touch_checkpoint, track_contacts,
+    },
+};
+use crate::{
+    dialogue::systems::dialogue_closed, map::state::MapReadinessState, menu::state::GameMenuState,
+};
+use bevy::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<PlayerLifeState>();
+
+        app.add_event::<DamageEvent>();
+        app.add_event::<DeathEvent>();
+
+        app.init_resource::<Contacts>();
+        app.init_resource::<LastCheckpoint>();
+        app.init_resource::<Lives>();
+        app.init_resource::<RespawnTimer>();
+
+        // Damage
+        app.add_system(track_contacts);
+        // Nothing gets hurt by touching while the player is talking
+        app.add_system(
+            deal_contact_damage
+                .run_if(dialogue_closed)
+                .after(track_contacts),
+        );
+        app.add_system(apply_damage.after(deal_contact_damage));
+        app.add_system(tick_invulnerability);
+        app.add_system(tick_stagger);
+
+        // Death & respawning
+        app.add_system(handle_player_death.after(apply_damage));
+        app.add_system(start_respawn_timer.in_schedule(OnEnter(PlayerLifeState::Dead)));
+        app.add_system(tick_respawn_timer.in_set(OnUpdate(PlayerLifeState::Dead)));
+        app.add_system(respawn_player.in_schedule(OnExit(PlayerLifeState::Dead)));
+        app.add_system(reset_life_state.in_schedule(OnExit(GameMenuState::InGame)));
+
+        // Checkpoints
+        app.add_system(touch_checkpoint);
+        app.add_system(clear_checkpoint.in_schedule(OnEnter(MapReadinessState::Loading)));
+        debug!("HealthPlugin loaded");
+    }
+}
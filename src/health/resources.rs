@@ -0,0 +1,77 @@
+use bevy::{prelude::*, utils::HashSet};
+
+/// How long the death plays out before the player respawns, in seconds
+pub const RESPAWN_DELAY: f32 = 2.0;
+
+/// How many lives the player starts a game with
+pub const PLAYER_LIVES: u32 = 3;
+
+/// The last checkpoint the player touched
+///
+/// A checkpoint is used up once the player respawns at it. This is cleared
+/// whenever a new map is loaded so the player respawns at the map's spawn
+/// point instead.
+#[derive(Debug, Default, Resource)]
+pub struct LastCheckpoint(pub Option<Entity>);
+
+/// How many lives the player has left, counting the one they're on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct Lives(pub u32);
+
+impl Default for Lives {
+    fn default() -> Self {
+        Self(PLAYER_LIVES)
+    }
+}
+
+impl Lives {
+    /// Takes a life for dying, returning whether the player gets back up
+    ///
+    /// A checkpoint catches the player without costing a life, and losing
+    /// the last life ends the game.
+    pub fn lose(&mut self, has_checkpoint: bool) -> bool {
+        if has_checkpoint {
+            return true;
+        }
+        self.0 = self.0.saturating_sub(1);
+        self.0 > 0
+    }
+}
+
+/// The [Damage](super::components::Damage) dealers touching something with
+/// [Health](super::components::Health), as pairs of the source and its
+/// target
+///
+/// Contact damage is dealt for as long as they touch, so standing against an
+/// enemy hurts again once invulnerability wears off.
+#[derive(Debug, Default, Resource)]
+pub struct Contacts(pub HashSet<(Entity, Entity)>);
+
+/// Counts down until the player respawns
+#[derive(Debug, Resource)]
+pub struct RespawnTimer(pub Timer);
+
+impl Default for RespawnTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(RESPAWN_DELAY, TimerMode::Once))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn the_player_gets_every_life_and_no_more() {
+        let mut lives = Lives::default();
+        let respawns = (0..10).take_while(|_| lives.lose(false)).count();
+        assert_eq!(respawns as u32, PLAYER_LIVES - 1);
+        assert_eq!(lives, Lives(0));
+
+        // Checkpoints don't cost a life
+        let mut lives = Lives(1);
+        assert!(lives.lose(true));
+        assert_eq!(lives, Lives(1));
+        assert!(!lives.lose(false));
+    }
+}
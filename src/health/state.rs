@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+
+/// Whether the player is alive or currently dying
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, States)]
+pub enum PlayerLifeState {
+    #[default]
+    Alive,
+    /// The player died and the death is playing out before respawning
+    Dead,
+}
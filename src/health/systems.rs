@@ -0,0 +1,420 @@
+//! Defines all of the systems used by the health plugin
+
+use super::{
+    components::{
+        Checkpoint, Damage, Dead, Health, InvulnerabilityOnHit, Invulnerable, Staggered, Team,
+    },
+    events::{DamageEvent, DeathEvent},
+    resources::{Contacts, LastCheckpoint, Lives, RespawnTimer},
+    state::PlayerLifeState,
+    util::{blink_hidden, can_hurt, knockback, take_damage},
+};
+use crate::{
+    animation::{asset::AnimationState, components::SpriteAnimator},
+    camera::{
+        components::{CameraController, GameCamera},
+        events::CameraTraumaEvent,
+    },
+    map::state::MapState,
+    menu::state::GameMenuState,
+    player::Player,
+};
+use bevy::{prelude::*, utils::HashSet};
+use bevy_rapier2d::prelude::*;
+
+/// How long knockback takes control away from its victim, in seconds
+const STAGGER_TIME: f32 = 0.2;
+
+/// How much the screen shakes when the player gets hit
+const PLAYER_HIT_TRAUMA: f32 = 0.35;
+
+/// Keeps track of which [Damage] dealing entities touch something with
+/// [Health]
+pub fn track_contacts(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut contacts: ResMut<Contacts>,
+    damage_query: Query<(), With<Damage>>,
+    victim_query: Query<(), With<Health>>,
+) {
+    for event in collision_events.iter() {
+        let (a, b, started) = match *event {
+            CollisionEvent::Started(a, b, _) => (a, b, true),
+            CollisionEvent::Stopped(a, b, _) => (a, b, false),
+        };
+
+        // Either entity could be the one dealing damage
+        for pair in [(a, b), (b, a)] {
+            if !started {
+                contacts.0.remove(&pair);
+            } else if damage_query.contains(pair.0) && victim_query.contains(pair.1) {
+                contacts.0.insert(pair);
+            }
+        }
+    }
+}
+
+/// Turns [Damage] dealing entities touching something with [Health] into
+/// [DamageEvent]s, for as long as they touch
+///
+/// Teammates and victims that are invulnerable or dead are skipped, so
+/// only hits that land send an event.
+#[allow(clippy::type_complexity)]
+pub fn deal_contact_damage(
+    mut contacts: ResMut<Contacts>,
+    mut damage_events: EventWriter<DamageEvent>,
+    damage_query: Query<(&Damage, &GlobalTransform)>,
+    victim_query: Query<(&GlobalTransform, Option<&Invulnerable>, Option<&Dead>), With<Health>>,
+    team_query: Query<&Team>,
+) {
+    // Forget the contacts of anything that was despawned
+    contacts.0.retain(|(source, target)| {
+        damage_query.contains(*source) && victim_query.contains(*target)
+    });
+
+    for (source, target) in contacts.0.iter().copied() {
+        let Ok((damage, source_transform)) = damage_query.get(source) else {
+            continue;
+        };
+        let Ok((target_transform, None, None)) = victim_query.get(target) else {
+            continue;
+        };
+        let source_team = team_query.get(source).ok().copied();
+        let target_team = team_query.get(target).ok().copied();
+        if !can_hurt(source_team, target_team) {
+            continue;
+        }
+
+        damage_events.send(DamageEvent {
+            target,
+            source: Some(source),
+            amount: damage.amount,
+            knockback: knockback(
+                source_transform.translation().truncate(),
+                target_transform.translation().truncate(),
+                damage.knockback,
+            ),
+        });
+    }
+}
+
+/// Applies every [DamageEvent], taking care of invulnerability, knockback and
+/// death
+#[allow(clippy::type_complexity)]
+pub fn apply_damage(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut death_events: EventWriter<DeathEvent>,
+    mut trauma_events: EventWriter<CameraTraumaEvent>,
+    mut victim_query: Query<
+        (
+            &mut Health,
+            Option<&InvulnerabilityOnHit>,
+            Option<&mut Velocity>,
+            Option<&mut SpriteAnimator>,
+            Option<&Player>,
+        ),
+        (Without<Invulnerable>, Without<Dead>),
+    >,
+    team_query: Query<&Team>,
+) {
+    // Invulnerability and death only kick in once the commands are applied,
+    // so keep track of who became immune to further hits this frame. Anyone
+    // else takes every hit that lands on them.
+    let mut immune = HashSet::new();
+
+    for event in damage_events.iter() {
+        if immune.contains(&event.target) {
+            continue;
+        }
+
+        // Teammates can't hurt each other
+        let source_team = event
+            .source
+            .and_then(|source| team_query.get(source).ok())
+            .copied();
+        let target_team = team_query.get(event.target).ok().copied();
+        if !can_hurt(source_team, target_team) {
+            continue;
+        }
+
+        let Ok((mut health, invulnerability, velocity, animator, player)) =
+            victim_query.get_mut(event.target)
+        else {
+            continue;
+        };
+
+        health.current = take_damage(health.current, event.amount);
+        debug!(
+            "{:?} took {} damage, {} health left",
+            event.target, event.amount, health.current
+        );
+
+        if player.is_some() {
+            trauma_events.send(CameraTraumaEvent(PLAYER_HIT_TRAUMA));
+        }
+
+        if health.is_dead() {
+            immune.insert(event.target);
+            commands.entity(event.target).insert(Dead);
+            death_events.send(DeathEvent {
+                entity: event.target,
+                killer: event.source,
+            });
+            continue;
+        }
+
+        if let Some(invulnerability) = invulnerability {
+            immune.insert(event.target);
+            commands
+                .entity(event.target)
+                .insert(Invulnerable::new(invulnerability.0));
+        }
+
+        if let Some(mut velocity) = velocity {
+            if event.knockback != Vec2::ZERO {
+                velocity.linvel = event.knockback;
+                commands.entity(event.target).insert(Staggered {
+                    timer: Timer::from_seconds(STAGGER_TIME, TimerMode::Once),
+                });
+            }
+        }
+
+        if let Some(mut animator) = animator {
+            animator.play_action(AnimationState::Hurt);
+        }
+    }
+}
+
+/// Counts down invulnerability and makes invulnerable entities blink
+pub fn tick_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut invulnerable_query: Query<(Entity, &mut Invulnerable, &mut Visibility)>,
+) {
+    for (entity, mut invulnerable, mut visibility) in &mut invulnerable_query {
+        invulnerable.timer.tick(time.delta());
+
+        if invulnerable.timer.finished() {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<Invulnerable>();
+            continue;
+        }
+
+        *visibility = if blink_hidden(invulnerable.timer.elapsed_secs()) {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+    }
+}
+
+/// Brings staggered entities to a stop once the knockback is over
+pub fn tick_stagger(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut staggered_query: Query<(Entity, &mut Staggered, &mut Velocity)>,
+) {
+    for (entity, mut staggered, mut velocity) in &mut staggered_query {
+        staggered.timer.tick(time.delta());
+
+        if staggered.timer.finished() {
+            velocity.linvel = Vec2::ZERO;
+            commands.entity(entity).remove::<Staggered>();
+        }
+    }
+}
+
+/// Puts the game into the [Dead](PlayerLifeState::Dead) state when the
+/// player dies
+pub fn handle_player_death(
+    mut death_events: EventReader<DeathEvent>,
+    mut player_query: Query<(&mut Velocity, Option<&mut SpriteAnimator>), With<Player>>,
+    mut next_life_state: ResMut<NextState<PlayerLifeState>>,
+) {
+    for event in death_events.iter() {
+        let Ok((mut velocity, animator)) = player_query.get_mut(event.entity) else {
+            continue;
+        };
+
+        info!("The player died");
+        velocity.linvel = Vec2::ZERO;
+        if let Some(mut animator) = animator {
+            animator.play_action(AnimationState::Death);
+        }
+        next_life_state.set(PlayerLifeState::Dead);
+    }
+}
+
+/// Starts counting down to the respawn
+pub fn start_respawn_timer(mut respawn_timer: ResMut<RespawnTimer>) {
+    respawn_timer.0.reset();
+}
+
+/// Brings the player back to life once the death has played out, or ends
+/// the game when there are no lives or checkpoints left
+pub fn tick_respawn_timer(
+    time: Res<Time>,
+    mut lives: ResMut<Lives>,
+    last_checkpoint: Res<LastCheckpoint>,
+    mut respawn_timer: ResMut<RespawnTimer>,
+    mut next_life_state: ResMut<NextState<PlayerLifeState>>,
+    mut next_state: ResMut<NextState<GameMenuState>>,
+) {
+    if !respawn_timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    if lives.lose(last_checkpoint.0.is_some()) {
+        next_life_state.set(PlayerLifeState::Alive);
+    } else {
+        info!("The player is out of lives");
+        next_state.set(GameMenuState::GameOver);
+    }
+}
+
+/// Respawns the player at the last checkpoint, using it up, or at the map's
+/// spawn point
+#[allow(clippy::type_complexity)]
+pub fn respawn_player(
+    mut commands: Commands,
+    mut last_checkpoint: ResMut<LastCheckpoint>,
+    map_state: Res<MapState>,
+    checkpoint_query: Query<&GlobalTransform, With<Checkpoint>>,
+    mut player_query: Query<
+        (
+            Entity,
+            &mut Health,
+            &mut Transform,
+            &mut Velocity,
+            Option<&InvulnerabilityOnHit>,
+            Option<&mut SpriteAnimator>,
+        ),
+        With<Player>,
+    >,
+    mut cameras: Query<&mut CameraController, With<GameCamera>>,
+) {
+    let Ok((entity, mut health, mut transform, mut velocity, invulnerability, animator)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+
+    health.refill();
+    velocity.linvel = Vec2::ZERO;
+    if let Some(mut animator) = animator {
+        animator.action = None;
+    }
+
+    let mut entity_commands = commands.entity(entity);
+    entity_commands.remove::<Dead>();
+    if let Some(invulnerability) = invulnerability {
+        entity_commands.insert(Invulnerable::new(invulnerability.0));
+    }
+
+    let checkpoint = last_checkpoint
+        .0
+        .take()
+        .and_then(|checkpoint| Some((checkpoint, checkpoint_query.get(checkpoint).ok()?)));
+    let position = match checkpoint {
+        Some((checkpoint, transform)) => {
+            commands.entity(checkpoint).remove::<Checkpoint>();
+            Some(transform.translation().truncate())
+        }
+        None => map_state.spawn_point,
+    };
+
+    if let Some(position) = position {
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+
+        for mut controller in &mut cameras {
+            controller.snap_to(position);
+        }
+    }
+
+    info!("The player respawned");
+}
+
+/// Brings the player back to life with all of their lives when leaving the
+/// game, so a death that was still playing out doesn't carry over into the
+/// next game
+pub fn reset_life_state(
+    mut next_life_state: ResMut<NextState<PlayerLifeState>>,
+    mut lives: ResMut<Lives>,
+    mut last_checkpoint: ResMut<LastCheckpoint>,
+) {
+    next_life_state.set(PlayerLifeState::Alive);
+    *lives = Lives::default();
+    last_checkpoint.0 = None;
+}
+
+/// Remembers the last checkpoint the player touched
+pub fn touch_checkpoint(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut last_checkpoint: ResMut<LastCheckpoint>,
+    checkpoint_query: Query<&GlobalTransform, With<Checkpoint>>,
+    player_query: Query<(), With<Player>>,
+) {
+    for event in collision_events.iter() {
+        let CollisionEvent::Started(a, b, _) = *event else {
+            continue;
+        };
+
+        for (checkpoint, player) in [(a, b), (b, a)] {
+            if !player_query.contains(player) {
+                continue;
+            }
+
+            if let Ok(transform) = checkpoint_query.get(checkpoint) {
+                if last_checkpoint.0 != Some(checkpoint) {
+                    debug!(
+                        "Reached checkpoint at {:?}",
+                        transform.translation().truncate()
+                    );
+                    last_checkpoint.0 = Some(checkpoint);
+                }
+            }
+        }
+    }
+}
+
+/// Forgets the last checkpoint, since it belongs to the previous map
+pub fn clear_checkpoint(mut last_checkpoint: ResMut<LastCheckpoint>) {
+    last_checkpoint.0 = None;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hit(target: Entity) -> DamageEvent {
+        DamageEvent {
+            target,
+            source: None,
+            amount: 10.0,
+            knockback: Vec2::ZERO,
+        }
+    }
+
+    #[test]
+    fn only_invulnerability_stops_hits_in_the_same_frame() {
+        let mut app = App::new();
+        app.add_event::<DamageEvent>()
+            .add_event::<DeathEvent>()
+            .add_event::<CameraTraumaEvent>()
+            .add_system(apply_damage);
+        let enemy = app.world.spawn(Health::new(100.0)).id();
+        let player = app
+            .world
+            .spawn((Health::new(100.0), InvulnerabilityOnHit::default()))
+            .id();
+
+        // Like a swing and a thrown dagger landing together
+        app.world
+            .send_event_batch([hit(enemy), hit(enemy), hit(player), hit(player)]);
+        app.update();
+
+        assert_eq!(app.world.get::<Health>(enemy).unwrap().current, 80.0);
+        assert_eq!(app.world.get::<Health>(player).unwrap().current, 90.0);
+    }
+}
@@ -0,0 +1,67 @@
+//! Utilities for the health module
+
+use super::components::Team;
+use bevy::prelude::*;
+
+/// The health left after taking `amount` damage, which never drops below
+/// zero
+pub fn take_damage(current: f32, amount: f32) -> f32 {
+    (current - amount).max(0.0)
+}
+
+/// The velocity something at `target` is knocked back with when hit from
+/// `source`, pushing it straight away from the source
+pub fn knockback(source: Vec2, target: Vec2, strength: f32) -> Vec2 {
+    (target - source).normalize_or_zero() * strength
+}
+
+/// Whether a hit can hurt its target, which it can't when both are on the
+/// same team. Entities without a team can hurt and be hurt by anything.
+pub fn can_hurt(source: Option<Team>, target: Option<Team>) -> bool {
+    match (source, target) {
+        (Some(source), Some(target)) => source != target,
+        _ => true,
+    }
+}
+
+/// Whether an invulnerable entity is hidden after being invulnerable for
+/// `elapsed` seconds, which makes it blink ten times a second
+pub fn blink_hidden(elapsed: f32) -> bool {
+    ((elapsed * 10.0) as u32).is_multiple_of(2)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn damage_stops_at_zero() {
+        assert_eq!(take_damage(10.0, 4.0), 6.0);
+        assert_eq!(take_damage(3.0, 4.0), 0.0);
+    }
+
+    #[test]
+    fn knockback_pushes_away_from_the_source() {
+        assert_eq!(
+            knockback(Vec2::ZERO, Vec2::new(0.0, -5.0), 100.0),
+            Vec2::new(0.0, -100.0)
+        );
+        // Nothing to push along when both are in the same spot
+        assert_eq!(knockback(Vec2::ONE, Vec2::ONE, 100.0), Vec2::ZERO);
+    }
+
+    #[test]
+    fn teammates_cant_hurt_each_other() {
+        assert!(!can_hurt(Some(Team::Enemy), Some(Team::Enemy)));
+        assert!(can_hurt(Some(Team::Player), Some(Team::Enemy)));
+        assert!(can_hurt(None, Some(Team::Player)));
+        assert!(can_hurt(Some(Team::Enemy), None));
+    }
+
+    #[test]
+    fn invulnerability_blinks() {
+        assert!(blink_hidden(0.0));
+        assert!(!blink_hidden(0.15));
+        assert!(blink_hidden(0.25));
+    }
+}
@@ -0,0 +1,76 @@
+use super::resources::INTERACT_RADIUS;
+use bevy::prelude::*;
+
+/// What happens when the player interacts with something
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InteractAction {
+    Talk,
+    OpenChest,
+    PullLever,
+    UseDoor,
+}
+
+/// Something the player can interact with when they are close enough
+#[derive(Debug, Clone, Component)]
+pub struct Interactable {
+    /// How close the player has to be, in pixels
+    pub radius: f32,
+    /// What the prompt says the interaction does, as a message like
+    /// `prompt-open` or as plain text
+    pub prompt: String,
+    pub action: InteractAction,
+    /// Disabled interactables don't show a prompt and can't be used
+    pub enabled: bool,
+}
+
+impl Interactable {
+    pub fn new(action: InteractAction, prompt: impl Into<String>) -> Self {
+        Self {
+            radius: INTERACT_RADIUS,
+            prompt: prompt.into(),
+            action,
+            enabled: true,
+        }
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+}
+
+/// A chest that drops loot the first time it's opened
+#[derive(Debug, Clone, Component)]
+pub struct Chest {
+    /// The id of the loot table rolled when the chest opens
+    pub loot: String,
+    pub opened: bool,
+}
+
+/// A lever toggling the map objects it's linked to whenever it's pulled
+#[derive(Debug, Clone, Component)]
+pub struct Lever {
+    pub on: bool,
+    /// The ids of the linked map objects
+    pub targets: Vec<u32>,
+}
+
+/// A door that blocks the way while closed
+#[derive(Debug, Clone, Component)]
+pub struct Door {
+    pub open: bool,
+    /// Index of the tile shown while closed
+    pub closed_tile: usize,
+    /// Index of the tile shown while open
+    pub open_tile: usize,
+    /// The size of the door in pixels, which is how much of the navigation
+    /// grid it blocks while closed
+    pub size: Vec2,
+    /// Whether the map gave the door its own prompt, which is shown instead
+    /// of the usual open and close prompts
+    pub custom_prompt: bool,
+}
+
+/// Marks the text showing what interacting does
+#[derive(Debug, Component)]
+pub struct InteractionPrompt;
@@ -0,0 +1,16 @@
+use super::components::InteractAction;
+use bevy::prelude::*;
+
+/// Sent when the player interacts with something
+#[derive(Debug, Clone)]
+pub struct InteractEvent {
+    pub entity: Entity,
+    /// What the interaction does, so each kind is only handled once
+    pub action: InteractAction,
+}
+
+/// Toggles something linked to a lever, like opening or closing a door
+#[derive(Debug, Clone)]
+pub struct ToggleEvent {
+    pub target: Entity,
+}
@@ -0,0 +1,16 @@
+//! Module for the things the player can interact with
+//!
+//! This module includes:
+//! - A generic interactable component with a radius, a prompt and an action
+//! - A prompt over the nearest interactable in range, which the interact key
+//!   acts upon
+//! - Chests rolling loot, levers toggling the objects they are linked to and
+//!   doors swapping their tile and collider, all placed from Tiled objects
+//! - A plugin to setup the required systems and events
+
+pub mod components;
+pub mod events;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
+pub mod util;
@@ -0,0 +1,51 @@
+use super::{
+    events::{InteractEvent, ToggleEvent},
+    resources::InteractionFocus,
+    systems::{
+        focus_interactables, hide_interaction_prompt, interact, open_chests, pull_levers,
+        setup_interaction_prompt, spawn_map_interactables, toggle_doors, update_doors,
+        update_interaction_prompt,
+    },
+};
+use crate::{
+    dialogue::systems::{advance_dialogue, talk_to_npcs},
+    menu::state::GameMenuState,
+};
+use bevy::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct InteractionPlugin;
+
+impl Plugin for InteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InteractionFocus>();
+
+        app.add_event::<InteractEvent>();
+        app.add_event::<ToggleEvent>();
+
+        app.add_startup_system(setup_interaction_prompt);
+
+        app.add_system(spawn_map_interactables);
+        app.add_systems(
+            (focus_interactables, interact)
+                .chain()
+                .after(advance_dialogue)
+                .before(talk_to_npcs)
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_systems(
+            (
+                open_chests,
+                pull_levers,
+                toggle_doors,
+                update_doors,
+                update_interaction_prompt,
+            )
+                .chain()
+                .after(interact)
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_system(hide_interaction_prompt.in_schedule(OnExit(GameMenuState::InGame)));
+        debug!("InteractionPlugin loaded");
+    }
+}
@@ -0,0 +1,21 @@
+use bevy::prelude::*;
+
+/// The class of the Tiled objects that spawn a [Chest](super::components::Chest)
+pub const CHEST_OBJECT_CLASS: &str = "chest";
+/// The class of the Tiled objects that spawn a [Lever](super::components::Lever)
+pub const LEVER_OBJECT_CLASS: &str = "lever";
+/// The class of the Tiled objects that spawn a [Door](super::components::Door)
+pub const DOOR_OBJECT_CLASS: &str = "door";
+/// How close the player has to be to interact with something, unless the
+/// object's `radius` property says otherwise, in pixels
+pub const INTERACT_RADIUS: f32 = 48.0;
+/// The loot table of chests without a `loot` property
+pub const DEFAULT_CHEST_LOOT: &str = "common";
+/// The tile of closed doors without a `closed_tile` property
+pub const DEFAULT_CLOSED_DOOR_TILE: &str = "cave_dirt_wall_top";
+/// The tile of open doors without an `open_tile` property
+pub const DEFAULT_OPEN_DOOR_TILE: &str = "cave_dirt_floor";
+
+/// The interactable the player would use by pressing the interact key
+#[derive(Debug, Default, Resource)]
+pub struct InteractionFocus(pub Option<Entity>);
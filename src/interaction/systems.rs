@@ -0,0 +1,434 @@
+//! Defines all of the systems used by the interaction plugin
+
+use super::{
+    components::{Chest, Door, InteractAction, Interactable, InteractionPrompt, Lever},
+    events::{InteractEvent, ToggleEvent},
+    resources::{
+        InteractionFocus, CHEST_OBJECT_CLASS, DEFAULT_CHEST_LOOT, DEFAULT_CLOSED_DOOR_TILE,
+        DEFAULT_OPEN_DOOR_TILE, DOOR_OBJECT_CLASS, LEVER_OBJECT_CLASS,
+    },
+    util::{linked_objects, nearest_in_range, prompt_text},
+};
+use crate::{
+    constants::DRAW_LAYER,
+    dialogue::resources::DialogueRunner,
+    health::components::Dead,
+    locale::resources::Localization,
+    loot::events::DropLootEvent,
+    map::{
+        asset::MapAsset,
+        navigation::NavigationGrid,
+        objects::{MapObject, MapObjectShape},
+        state::MapState,
+        util::{find_named_tile, map_id},
+    },
+    player::Player,
+    save::resources::WorldChanges,
+    settings::resources::Settings,
+    theme::{components::ThemedText, resources::CurrentTheme},
+};
+use bevy::{prelude::*, sprite::Anchor};
+use bevy_rapier2d::prelude::*;
+
+const CHEST_COLOR: Color = Color::rgb(0.55, 0.35, 0.15);
+const OPENED_CHEST_COLOR: Color = Color::rgb(0.3, 0.2, 0.1);
+const CHEST_SIZE: Vec2 = Vec2::new(28.0, 20.0);
+const LEVER_ON_COLOR: Color = Color::rgb(0.3, 0.8, 0.3);
+const LEVER_OFF_COLOR: Color = Color::rgb(0.8, 0.3, 0.3);
+const LEVER_SIZE: Vec2 = Vec2::new(8.0, 20.0);
+const PROMPT_TEXT: ThemedText = ThemedText::new("heading", "prompt", "text");
+/// How far above an interactable its prompt shows, in pixels
+const PROMPT_OFFSET: f32 = 24.0;
+
+/// Spawns the text prompting the player to interact, hidden until there is
+/// something to interact with
+pub fn setup_interaction_prompt(mut commands: Commands, theme: Res<CurrentTheme>) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section("", theme.themed_text_style(PROMPT_TEXT)),
+            text_anchor: Anchor::BottomCenter,
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        InteractionPrompt,
+        PROMPT_TEXT,
+    ));
+}
+
+/// Turns `chest`, `lever` and `door` objects into interactables
+///
+/// Every one of them can override how close the player has to be with a
+/// `radius` property and what its prompt says with a `prompt` property.
+/// - Chests roll the loot table in their `loot` property, and chests opened
+///   on an earlier visit spawn already open
+/// - Levers start out in the state of their `on` property and are linked to
+///   the objects in their `target` properties
+/// - Doors start out in the state of their `open` property and show the
+///   tiles named by their `closed_tile` and `open_tile` properties. Doors with
+///   `locked` set can only be opened by a lever.
+pub fn spawn_map_interactables(
+    mut commands: Commands,
+    map_state: Res<MapState>,
+    maps: Res<Assets<MapAsset>>,
+    world: Res<WorldChanges>,
+    asset_server: Res<AssetServer>,
+    object_query: Query<(Entity, &MapObject, &Transform), Added<MapObject>>,
+) {
+    let map_path = map_id(&asset_server, &map_state.handle).unwrap_or_default();
+
+    for (entity, object, transform) in &object_query {
+        let (action, default_prompt) = match object.class.as_str() {
+            CHEST_OBJECT_CLASS => (InteractAction::OpenChest, "prompt-open"),
+            LEVER_OBJECT_CLASS => (InteractAction::PullLever, "prompt-pull"),
+            DOOR_OBJECT_CLASS => (InteractAction::UseDoor, "prompt-open"),
+            _ => continue,
+        };
+        debug!("Spawning {} from map object {}", object.class, object.id);
+
+        let mut interactable = Interactable::new(
+            action,
+            object.string_property("prompt").unwrap_or(default_prompt),
+        );
+        if let Some(radius) = object.float_property("radius") {
+            interactable = interactable.with_radius(radius);
+        }
+        let transform =
+            Transform::from_translation(transform.translation.truncate().extend(DRAW_LAYER::PROPS));
+
+        let mut prop = commands.entity(entity);
+        match action {
+            InteractAction::OpenChest => {
+                // Chests opened in an earlier visit stay empty
+                let opened = world.is_chest_opened(&map_path, object.id);
+                interactable.enabled = !opened;
+
+                prop.insert((
+                    Chest {
+                        loot: object
+                            .string_property("loot")
+                            .unwrap_or(DEFAULT_CHEST_LOOT)
+                            .into(),
+                        opened,
+                    },
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: if opened {
+                                OPENED_CHEST_COLOR
+                            } else {
+                                CHEST_COLOR
+                            },
+                            custom_size: Some(CHEST_SIZE),
+                            ..default()
+                        },
+                        transform,
+                        ..default()
+                    },
+                    RigidBody::Fixed,
+                    Collider::cuboid(CHEST_SIZE.x / 2.0, CHEST_SIZE.y / 2.0),
+                    interactable,
+                ));
+            }
+            InteractAction::PullLever => {
+                let lever = Lever {
+                    on: object.bool_property("on").unwrap_or(false),
+                    targets: linked_objects(&object.properties),
+                };
+                if lever.targets.is_empty() {
+                    warn!("Lever object {} isn't linked to anything", object.id);
+                }
+
+                prop.insert((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: lever_color(&lever),
+                            custom_size: Some(LEVER_SIZE),
+                            ..default()
+                        },
+                        transform,
+                        ..default()
+                    },
+                    lever,
+                    interactable,
+                ));
+            }
+            InteractAction::UseDoor => {
+                let Some(map) = maps.get(&map_state.handle) else {
+                    continue;
+                };
+                let closed_tile = object
+                    .string_property("closed_tile")
+                    .unwrap_or(DEFAULT_CLOSED_DOOR_TILE);
+                let open_tile = object
+                    .string_property("open_tile")
+                    .unwrap_or(DEFAULT_OPEN_DOOR_TILE);
+                let (Some((tileset, closed_tile)), Some((_, open_tile))) = (
+                    find_named_tile(&map.map, closed_tile),
+                    find_named_tile(&map.map, open_tile),
+                ) else {
+                    warn!("Door object {} has unknown tiles", object.id);
+                    continue;
+                };
+                let Some(atlas) = map_state.texture_atlases.get(&tileset) else {
+                    continue;
+                };
+                let size = match object.shape {
+                    MapObjectShape::Rect { size } => size,
+                    _ => Vec2::splat(32.0),
+                };
+
+                prop.insert((
+                    Door {
+                        open: object.bool_property("open").unwrap_or(false),
+                        closed_tile,
+                        open_tile,
+                        size,
+                        custom_prompt: object.string_property("prompt").is_some(),
+                    },
+                    SpriteSheetBundle {
+                        sprite: TextureAtlasSprite {
+                            index: closed_tile,
+                            custom_size: Some(size),
+                            ..default()
+                        },
+                        texture_atlas: atlas.clone(),
+                        transform,
+                        ..default()
+                    },
+                    RigidBody::Fixed,
+                    Collider::cuboid(size.x / 2.0, size.y / 2.0),
+                ));
+                if !object.bool_property("locked").unwrap_or(false) {
+                    prop.insert(interactable);
+                }
+            }
+            InteractAction::Talk => {}
+        }
+    }
+}
+
+fn lever_color(lever: &Lever) -> Color {
+    if lever.on {
+        LEVER_ON_COLOR
+    } else {
+        LEVER_OFF_COLOR
+    }
+}
+
+/// Picks the interactable closest to the player, if any are in range
+pub fn focus_interactables(
+    mut focus: ResMut<InteractionFocus>,
+    runner: Res<DialogueRunner>,
+    player_query: Query<&GlobalTransform, (With<Player>, Without<Dead>)>,
+    interactable_query: Query<(Entity, &Interactable, &GlobalTransform)>,
+) {
+    let nearest = match player_query.get_single() {
+        Ok(player) if !runner.is_open() => nearest_in_range(
+            player.translation().truncate(),
+            interactable_query
+                .iter()
+                .filter(|(_, interactable, _)| interactable.enabled)
+                .map(|(entity, interactable, transform)| {
+                    (
+                        entity,
+                        transform.translation().truncate(),
+                        interactable.radius,
+                    )
+                }),
+        ),
+        _ => None,
+    };
+
+    if focus.0 != nearest {
+        focus.0 = nearest;
+    }
+}
+
+/// Interacts with the focused interactable when the interact key is pressed
+pub fn interact(
+    mut keys: ResMut<Input<KeyCode>>,
+    settings: Res<Settings>,
+    mut interact_events: EventWriter<InteractEvent>,
+    focus: Res<InteractionFocus>,
+    interactable_query: Query<&Interactable>,
+) {
+    if !keys.just_pressed(settings.key_bindings.interact) {
+        return;
+    }
+    let Some(interactable) = focus
+        .0
+        .and_then(|entity| interactable_query.get(entity).ok())
+    else {
+        return;
+    };
+
+    // Stop the same key press from being used again, like skipping the first
+    // line of a conversation
+    keys.clear_just_pressed(settings.key_bindings.interact);
+    interact_events.send(InteractEvent {
+        entity: focus.0.unwrap(),
+        action: interactable.action,
+    });
+}
+
+/// Drops the loot of chests the first time they are opened
+pub fn open_chests(
+    mut interact_events: EventReader<InteractEvent>,
+    mut loot_events: EventWriter<DropLootEvent>,
+    mut chest_query: Query<(&mut Chest, &mut Interactable, &mut Sprite, &GlobalTransform)>,
+) {
+    let opened = interact_events
+        .iter()
+        .filter(|event| event.action == InteractAction::OpenChest);
+    for event in opened {
+        let Ok((mut chest, mut interactable, mut sprite, transform)) =
+            chest_query.get_mut(event.entity)
+        else {
+            continue;
+        };
+        if chest.opened {
+            continue;
+        }
+
+        debug!("Opening a chest with loot {:?}", chest.loot);
+        chest.opened = true;
+        interactable.enabled = false;
+        sprite.color = OPENED_CHEST_COLOR;
+        loot_events.send(DropLootEvent {
+            table: chest.loot.clone(),
+            position: transform.translation().truncate(),
+        });
+    }
+}
+
+/// Flips levers when they are pulled, toggling the objects linked to them
+pub fn pull_levers(
+    mut interact_events: EventReader<InteractEvent>,
+    mut toggle_events: EventWriter<ToggleEvent>,
+    mut lever_query: Query<(&mut Lever, &mut Sprite)>,
+    object_query: Query<(Entity, &MapObject)>,
+) {
+    let pulled = interact_events
+        .iter()
+        .filter(|event| event.action == InteractAction::PullLever);
+    for event in pulled {
+        let Ok((mut lever, mut sprite)) = lever_query.get_mut(event.entity) else {
+            continue;
+        };
+
+        lever.on = !lever.on;
+        sprite.color = lever_color(&lever);
+        debug!("Pulled a lever {}", if lever.on { "on" } else { "off" });
+
+        for (target, object) in &object_query {
+            if lever.targets.contains(&object.id) {
+                toggle_events.send(ToggleEvent { target });
+            }
+        }
+    }
+}
+
+/// Opens and closes doors that are used or toggled by a lever
+pub fn toggle_doors(
+    mut interact_events: EventReader<InteractEvent>,
+    mut toggle_events: EventReader<ToggleEvent>,
+    mut door_query: Query<&mut Door>,
+) {
+    let used = interact_events
+        .iter()
+        .filter(|event| event.action == InteractAction::UseDoor)
+        .map(|event| event.entity);
+    let toggled = toggle_events.iter().map(|event| event.target);
+
+    for entity in used.chain(toggled) {
+        if let Ok(mut door) = door_query.get_mut(entity) {
+            door.open = !door.open;
+        }
+    }
+}
+
+/// Swaps the tile and collider of doors whose state changed, keeping the
+/// navigation grid in sync so enemies path through open doors only
+#[allow(clippy::type_complexity)]
+pub fn update_doors(
+    mut commands: Commands,
+    mut navigation: Option<ResMut<NavigationGrid>>,
+    mut door_query: Query<(
+        Entity,
+        Ref<Door>,
+        &GlobalTransform,
+        &mut TextureAtlasSprite,
+        Option<&mut Interactable>,
+    )>,
+) {
+    for (entity, door, transform, mut sprite, interactable) in &mut door_query {
+        if !door.is_changed() {
+            continue;
+        }
+
+        if let Some(navigation) = navigation.as_mut() {
+            let center = transform.translation().truncate();
+            let cells = navigation.cells_in_rect(Rect::from_center_size(center, door.size));
+            for cell in cells {
+                navigation.set_walkable(cell, door.open);
+            }
+        }
+        if door.open {
+            sprite.index = door.open_tile;
+            commands.entity(entity).insert(ColliderDisabled);
+        } else {
+            sprite.index = door.closed_tile;
+            commands.entity(entity).remove::<ColliderDisabled>();
+        }
+        if let Some(mut interactable) = interactable.filter(|_| !door.custom_prompt) {
+            interactable.prompt = if door.open {
+                "prompt-close"
+            } else {
+                "prompt-open"
+            }
+            .into();
+        }
+    }
+}
+
+/// Shows the prompt over the focused interactable
+pub fn update_interaction_prompt(
+    focus: Res<InteractionFocus>,
+    settings: Res<Settings>,
+    localization: Res<Localization>,
+    interactable_query: Query<(&Interactable, &GlobalTransform)>,
+    mut prompt_query: Query<(&mut Text, &mut Transform, &mut Visibility), With<InteractionPrompt>>,
+) {
+    let Ok((mut text, mut transform, mut visibility)) = prompt_query.get_single_mut() else {
+        return;
+    };
+    let Some((interactable, target)) = focus
+        .0
+        .and_then(|entity| interactable_query.get(entity).ok())
+    else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    *visibility = Visibility::Inherited;
+    transform.translation =
+        target.translation().truncate().extend(DRAW_LAYER::EFFECTS) + Vec3::Y * PROMPT_OFFSET;
+    let prompt = prompt_text(
+        &localization,
+        settings.key_bindings.interact,
+        &interactable.prompt,
+    );
+    if text.sections[0].value != prompt {
+        text.sections[0].value = prompt;
+    }
+}
+
+/// Hides the prompt once the game stops
+pub fn hide_interaction_prompt(
+    mut focus: ResMut<InteractionFocus>,
+    mut prompt_query: Query<&mut Visibility, With<InteractionPrompt>>,
+) {
+    focus.0 = None;
+    for mut visibility in &mut prompt_query {
+        *visibility = Visibility::Hidden;
+    }
+}
@@ -0,0 +1,96 @@
+//! Utilities for the interaction module
+
+use crate::locale::{components::LocalizedText, resources::Localization};
+use bevy::prelude::*;
+
+/// Picks the closest candidate whose radius reaches the player
+///
+/// Candidates are given as their entity, position and radius.
+pub fn nearest_in_range(
+    player: Vec2,
+    candidates: impl IntoIterator<Item = (Entity, Vec2, f32)>,
+) -> Option<Entity> {
+    candidates
+        .into_iter()
+        .map(|(entity, position, radius)| (entity, position.distance(player), radius))
+        .filter(|(_, distance, radius)| distance <= radius)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(entity, _, _)| entity)
+}
+
+/// The text shown over an interactable, like "E — Open"
+///
+/// The prompt is looked up as a message, and shown as it is when there's no
+/// message by that name.
+pub fn prompt_text(localization: &Localization, key: KeyCode, prompt: &str) -> String {
+    localization.format(
+        &LocalizedText::new("interact-prompt")
+            .with_arg("key", format!("{:?}", key))
+            .with_arg("action", localization.text(prompt)),
+    )
+}
+
+/// Returns the ids of the objects linked through the object properties
+/// starting with `target`, so an object can be linked to several others with
+/// `target`, `target_2` and so on
+pub fn linked_objects(properties: &tiled::Properties) -> Vec<u32> {
+    let mut links: Vec<(&String, u32)> = properties
+        .iter()
+        .filter(|(name, _)| name.starts_with("target"))
+        .filter_map(|(name, value)| match value {
+            tiled::PropertyValue::ObjectValue(id) if *id != 0 => Some((name, *id)),
+            _ => None,
+        })
+        .collect();
+    links.sort();
+
+    links.into_iter().map(|(_, id)| id).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::locale::resources::test::english;
+
+    #[test]
+    fn nearest_in_range_respects_radius() {
+        let near = Entity::from_raw(1);
+        let far = Entity::from_raw(2);
+        let wide = Entity::from_raw(3);
+        let candidates = [
+            (near, Vec2::new(30.0, 0.0), 48.0),
+            (far, Vec2::new(100.0, 0.0), 48.0),
+            (wide, Vec2::new(0.0, 40.0), 200.0),
+        ];
+
+        assert_eq!(nearest_in_range(Vec2::ZERO, candidates), Some(near));
+        // Only the wide one reaches this far
+        assert_eq!(
+            nearest_in_range(Vec2::new(0.0, 150.0), candidates),
+            Some(wide)
+        );
+        assert_eq!(nearest_in_range(Vec2::new(500.0, 500.0), candidates), None);
+    }
+
+    #[test]
+    fn linked_objects_are_read_in_order() {
+        let properties = tiled::Properties::from([
+            ("target_2".into(), tiled::PropertyValue::ObjectValue(12)),
+            ("target".into(), tiled::PropertyValue::ObjectValue(4)),
+            ("target_3".into(), tiled::PropertyValue::ObjectValue(0)),
+            ("on".into(), tiled::PropertyValue::BoolValue(true)),
+        ]);
+
+        assert_eq!(linked_objects(&properties), vec![4, 12]);
+
+        let localization = english();
+        assert_eq!(
+            prompt_text(&localization, KeyCode::E, "prompt-open"),
+            "E — Open"
+        );
+        assert_eq!(
+            prompt_text(&localization, KeyCode::F, "Read the sign"),
+            "F — Read the sign"
+        );
+    }
+}
@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+/// Marks the overlay covering the screen while the intro plays
+#[derive(Debug, Component)]
+pub struct IntroRootNode;
+
+/// Marks the text showing the current line of the intro
+#[derive(Debug, Component)]
+pub struct IntroText;
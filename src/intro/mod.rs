@@ -0,0 +1,12 @@
+//! Module for the story intro played between the start screen and gameplay
+//!
+//! This module includes:
+//! - A sequence showing the lines of the intro one at a time with a
+//!   typewriter effect and fades
+//! - A UI overlay hiding the map while it loads in the background
+//! - A plugin to setup the required systems
+
+pub mod components;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
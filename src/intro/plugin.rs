@@ -0,0 +1,20 @@
+use super::systems::{cleanup_intro, setup_intro, skip_intro, update_intro};
+use crate::menu::state::GameMenuState;
+use bevy::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct IntroPlugin;
+
+impl Plugin for IntroPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(setup_intro.in_schedule(OnEnter(GameMenuState::Intro)));
+        app.add_system(cleanup_intro.in_schedule(OnExit(GameMenuState::Intro)));
+
+        app.add_systems(
+            (skip_intro, update_intro)
+                .chain()
+                .in_set(OnUpdate(GameMenuState::Intro)),
+        );
+        debug!("IntroPlugin loaded");
+    }
+}
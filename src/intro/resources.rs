@@ -0,0 +1,179 @@
+use bevy::prelude::*;
+
+/// How long the text and the overlay take to fade in or out, in seconds
+pub const FADE_TIME: f32 = 0.75;
+/// How fast the lines are typed out
+pub const CHARS_PER_SECOND: f32 = 30.0;
+/// How long a line stays on screen once it's fully typed out, in seconds
+pub const HOLD_TIME: f32 = 2.0;
+
+/// Where the intro is at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntroPhase {
+    /// Showing the line with the given index
+    Line(usize),
+    /// Every line was shown, and the overlay fades out once the map is ready
+    FadingOut,
+    /// The intro is over and gameplay can start
+    Done,
+}
+
+/// Plays the lines of the story intro one after the other
+#[derive(Debug, Clone, Resource)]
+pub struct IntroSequence {
+    lines: Vec<String>,
+    phase: IntroPhase,
+    /// Time spent in the current phase
+    elapsed: f32,
+}
+
+impl IntroSequence {
+    pub fn new(lines: &[impl AsRef<str>]) -> Self {
+        Self {
+            lines: lines.iter().map(|line| line.as_ref().to_string()).collect(),
+            phase: if lines.is_empty() {
+                IntroPhase::FadingOut
+            } else {
+                IntroPhase::Line(0)
+            },
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.phase == IntroPhase::Done
+    }
+
+    /// Moves the intro along
+    ///
+    /// The overlay only starts fading out once `map_ready` is true, so the
+    /// map never shows up half loaded.
+    pub fn tick(&mut self, delta: f32, map_ready: bool) {
+        match self.phase {
+            IntroPhase::Line(index) => {
+                self.elapsed += delta;
+                let duration = line_duration(&self.lines[index]);
+                if self.elapsed >= duration {
+                    self.elapsed -= duration;
+                    self.phase = if index + 1 < self.lines.len() {
+                        IntroPhase::Line(index + 1)
+                    } else {
+                        IntroPhase::FadingOut
+                    };
+                }
+            }
+            IntroPhase::FadingOut => {
+                if !map_ready {
+                    self.elapsed = 0.0;
+                    return;
+                }
+
+                self.elapsed += delta;
+                if self.elapsed >= FADE_TIME {
+                    self.phase = IntroPhase::Done;
+                }
+            }
+            IntroPhase::Done => {}
+        }
+    }
+
+    /// Jumps straight to the end of the intro
+    pub fn skip(&mut self) {
+        if let IntroPhase::Line(_) = self.phase {
+            self.phase = IntroPhase::FadingOut;
+            self.elapsed = 0.0;
+        }
+    }
+
+    /// The part of the current line typed out so far
+    pub fn text(&self) -> &str {
+        let IntroPhase::Line(index) = self.phase else {
+            return "";
+        };
+
+        let line = &self.lines[index];
+        let visible = (self.elapsed * CHARS_PER_SECOND) as usize;
+        let end = line
+            .char_indices()
+            .nth(visible)
+            .map_or(line.len(), |(end, _)| end);
+        &line[..end]
+    }
+
+    /// How opaque the text is, fading in as a line starts and out as it ends
+    pub fn text_alpha(&self) -> f32 {
+        let IntroPhase::Line(index) = self.phase else {
+            return 0.0;
+        };
+
+        let remaining = line_duration(&self.lines[index]) - self.elapsed;
+        (self.elapsed.min(remaining) / FADE_TIME).clamp(0.0, 1.0)
+    }
+
+    /// How opaque the overlay hiding the map is
+    pub fn overlay_alpha(&self) -> f32 {
+        match self.phase {
+            IntroPhase::Line(_) => 1.0,
+            IntroPhase::FadingOut => 1.0 - (self.elapsed / FADE_TIME).clamp(0.0, 1.0),
+            IntroPhase::Done => 0.0,
+        }
+    }
+}
+
+/// How long a line stays on screen, from the start of its fade in to the end
+/// of its fade out
+fn line_duration(line: &str) -> f32 {
+    line.chars().count() as f32 / CHARS_PER_SECOND + HOLD_TIME + FADE_TIME
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LINES: [&str; 2] = ["Hello there", "Ünïcode"];
+
+    #[test]
+    fn types_lines_out() {
+        let mut sequence = IntroSequence::new(&LINES);
+        assert_eq!(sequence.text(), "");
+
+        sequence.tick(5.0 / CHARS_PER_SECOND, false);
+        assert_eq!(sequence.text(), "Hello");
+        assert!(sequence.text_alpha() > 0.0 && sequence.text_alpha() < 1.0);
+
+        sequence.tick(1.0, false);
+        assert_eq!(sequence.text(), "Hello there");
+        assert_eq!(sequence.text_alpha(), 1.0);
+    }
+
+    #[test]
+    fn moves_through_every_line() {
+        let mut sequence = IntroSequence::new(&LINES);
+        sequence.tick(line_duration(LINES[0]), false);
+        assert_eq!(sequence.phase, IntroPhase::Line(1));
+
+        // Multi-byte characters are never cut in half
+        sequence.tick(2.0 / CHARS_PER_SECOND, false);
+        assert_eq!(sequence.text(), "Ün");
+
+        sequence.tick(line_duration(LINES[1]), false);
+        assert_eq!(sequence.phase, IntroPhase::FadingOut);
+        assert_eq!(sequence.text(), "");
+    }
+
+    #[test]
+    fn waits_for_the_map() {
+        let mut sequence = IntroSequence::new(&LINES);
+        sequence.skip();
+        assert_eq!(sequence.phase, IntroPhase::FadingOut);
+
+        sequence.tick(10.0, false);
+        assert_eq!(sequence.overlay_alpha(), 1.0);
+        assert!(!sequence.is_done());
+
+        sequence.tick(FADE_TIME / 2.0, true);
+        assert!(sequence.overlay_alpha() < 1.0);
+        sequence.tick(FADE_TIME, true);
+        assert!(sequence.is_done());
+    }
+}
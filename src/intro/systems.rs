@@ -0,0 +1,105 @@
+//! Defines all of the systems used by the intro plugin
+
+use super::{
+    components::{IntroRootNode, IntroText},
+    resources::IntroSequence,
+};
+use crate::{
+    constants::STORY_INTRO, locale::resources::Localization, map::state::MapReadinessState,
+    menu::state::GameMenuState, theme::resources::CurrentTheme,
+};
+use bevy::prelude::*;
+
+/// Starts the intro and spawns the overlay it's shown on
+pub fn setup_intro(
+    mut commands: Commands,
+    theme: Res<CurrentTheme>,
+    localization: Res<Localization>,
+) {
+    debug!("Starting the story intro");
+    let lines = STORY_INTRO.map(|key| localization.text(key));
+    commands.insert_resource(IntroSequence::new(&lines));
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::all(Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                padding: UiRect::all(Val::Percent(10.0)),
+                ..default()
+            },
+            background_color: theme.color("background").into(),
+            z_index: ZIndex::Global(100),
+            ..default()
+        })
+        .insert(IntroRootNode)
+        .with_children(|parent| {
+            parent
+                .spawn(
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            color: Color::NONE,
+                            ..theme.text_style("body", "story", "text")
+                        },
+                    )
+                    .with_text_alignment(TextAlignment::Center),
+                )
+                .insert(IntroText);
+        });
+}
+
+/// Skips the intro when any key or mouse button is pressed
+pub fn skip_intro(
+    keys: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    mut sequence: ResMut<IntroSequence>,
+) {
+    if keys.get_just_pressed().next().is_some() || mouse_buttons.get_just_pressed().next().is_some()
+    {
+        debug!("Skipping the story intro");
+        sequence.skip();
+    }
+}
+
+/// Plays the intro and starts the game once it's over
+pub fn update_intro(
+    time: Res<Time>,
+    theme: Res<CurrentTheme>,
+    map_readiness: Res<State<MapReadinessState>>,
+    mut sequence: ResMut<IntroSequence>,
+    mut next_state: ResMut<NextState<GameMenuState>>,
+    mut overlay_query: Query<&mut BackgroundColor, With<IntroRootNode>>,
+    mut text_query: Query<&mut Text, With<IntroText>>,
+) {
+    let map_ready = map_readiness.0 == MapReadinessState::Loaded;
+    sequence.tick(time.delta_seconds(), map_ready);
+
+    for mut background in &mut overlay_query {
+        background.0.set_a(sequence.overlay_alpha());
+    }
+
+    for mut text in &mut text_query {
+        let section = &mut text.sections[0];
+        if section.value != sequence.text() {
+            section.value = sequence.text().to_string();
+        }
+        section.style.color = *theme.color("text").set_a(sequence.text_alpha());
+    }
+
+    if sequence.is_done() {
+        next_state.set(GameMenuState::InGame);
+    }
+}
+
+/// Despawns the overlay once the intro is over
+pub fn cleanup_intro(mut commands: Commands, root_query: Query<Entity, With<IntroRootNode>>) {
+    debug!("Cleaning up the story intro");
+    commands.remove_resource::<IntroSequence>();
+
+    for root in &root_query {
+        commands.entity(root).despawn_recursive();
+    }
+}
@@ -0,0 +1,138 @@
+use crate::locale::asset::LocalizedContent;
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The broad kind of an item
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize)]
+pub enum ItemCategory {
+    Weapon,
+    Ammo,
+    Consumable,
+    Material,
+    Key,
+    Currency,
+}
+
+impl ItemCategory {
+    /// The key of the category's name in the locale files
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Weapon => "item-category-weapon",
+            Self::Ammo => "item-category-ammo",
+            Self::Consumable => "item-category-consumable",
+            Self::Material => "item-category-material",
+            Self::Key => "item-category-key",
+            Self::Currency => "item-category-currency",
+        }
+    }
+}
+
+/// Describes a kind of item
+#[derive(Debug, Clone, Deserialize)]
+pub struct ItemDefinition {
+    /// Unique id used to refer to the item everywhere else
+    pub id: String,
+    /// The message with the name shown to the player
+    pub name: String,
+    /// Index of the item's icon in the item sprite sheet
+    pub icon: usize,
+    /// How many of the item fit in a single inventory slot
+    pub stack_size: u32,
+    pub category: ItemCategory,
+}
+
+/// Asset holding the definition of every item
+#[derive(TypeUuid, Debug, Default, Deserialize)]
+#[uuid = "c1e3b4a4-0b8e-4f57-a0a4-8f9e3f5b4d21"]
+pub struct ItemDatabase {
+    /// Path to the sprite sheet holding the item icons
+    pub icons: String,
+    /// The size of a single icon in pixels
+    pub icon_size: (f32, f32),
+    /// The number of columns in the sprite sheet
+    pub columns: usize,
+    /// The number of rows in the sprite sheet
+    pub rows: usize,
+    /// The definitions, in the order they are listed in the file
+    pub(super) items: Vec<ItemDefinition>,
+    /// Maps an item's id to its index in `items`
+    #[serde(skip)]
+    lookup: HashMap<String, usize>,
+    /// The texture atlas built from the icon sprite sheet
+    #[serde(skip)]
+    pub atlas: Handle<TextureAtlas>,
+}
+
+impl ItemDatabase {
+    /// Looks up the definition of an item by its id
+    pub fn get(&self, id: &str) -> Option<&ItemDefinition> {
+        self.lookup.get(id).map(|index| &self.items[*index])
+    }
+
+    pub(super) fn build_lookup(&mut self) {
+        self.lookup = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (item.id.clone(), index))
+            .collect();
+    }
+}
+
+impl LocalizedContent for ItemDatabase {
+    fn message_ids(&self) -> Vec<&str> {
+        self.items.iter().map(|item| item.name.as_str()).collect()
+    }
+}
+
+#[derive(Default)]
+pub struct ItemDatabaseLoader;
+
+impl AssetLoader for ItemDatabaseLoader {
+    /// Load the item database along with the icon sprite sheet
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext<'_>,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let mut database: ItemDatabase = ron::de::from_bytes(bytes)?;
+            database.build_lookup();
+
+            if database.lookup.len() != database.items.len() {
+                warn!(
+                    "{:?} defines some items more than once",
+                    load_context.path()
+                );
+            }
+            debug!("Loaded {} item definitions", database.items.len());
+
+            let icons_path = AssetPath::from(database.icons.as_str()).to_owned();
+            let icons: Handle<Image> = load_context.get_handle(icons_path.clone());
+            let atlas = TextureAtlas::from_grid(
+                icons,
+                Vec2::new(database.icon_size.0, database.icon_size.1),
+                database.columns,
+                database.rows,
+                None,
+                None,
+            );
+            database.atlas = load_context.set_labeled_asset("atlas", LoadedAsset::new(atlas));
+
+            load_context.set_default_asset(LoadedAsset::new(database).with_dependency(icons_path));
+
+            Ok(())
+        })
+    }
+
+    /// Returns a list of supported extensions
+    fn extensions(&self) -> &[&str] {
+        &["items.ron"]
+    }
+}
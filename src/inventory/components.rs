@@ -0,0 +1,316 @@
+use super::asset::{ItemDatabase, ItemDefinition};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A number of items of the same kind
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ItemStack {
+    /// The id of the item
+    pub item: String,
+    pub count: u32,
+}
+
+impl ItemStack {
+    pub fn new(item: impl Into<String>, count: u32) -> Self {
+        Self {
+            item: item.into(),
+            count,
+        }
+    }
+}
+
+/// The reasons an inventory operation can fail
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum InventoryError {
+    /// The slot doesn't exist
+    InvalidSlot,
+    /// The inventory doesn't hold enough of the item
+    NotEnoughItems,
+    /// The item isn't in the item database
+    UnknownItem,
+}
+
+/// A fixed number of slots holding stacks of items
+///
+/// A slot holds at most the item's `stack_size`. Adding items always tops up
+/// existing stacks before starting new ones.
+#[derive(Debug, Clone, Component)]
+pub struct Inventory {
+    slots: Vec<Option<ItemStack>>,
+}
+
+impl Inventory {
+    /// Creates an empty inventory with the given number of slots
+    pub fn new(size: usize) -> Self {
+        Self {
+            slots: vec![None; size],
+        }
+    }
+
+    /// Creates an inventory holding the given slots, like ones from a save
+    pub fn from_slots(slots: Vec<Option<ItemStack>>) -> Self {
+        Self { slots }
+    }
+
+    pub fn slots(&self) -> &[Option<ItemStack>] {
+        &self.slots
+    }
+
+    /// Returns how many of an item the inventory holds across every slot
+    pub fn count(&self, item: &str) -> u32 {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|stack| stack.item == item)
+            .map(|stack| stack.count)
+            .sum()
+    }
+
+    /// Adds as many of the item as will fit.
+    ///
+    /// Returns how many of the items didn't fit.
+    pub fn add(&mut self, item: &ItemDefinition, count: u32) -> u32 {
+        let stack_size = item.stack_size.max(1);
+        let mut remaining = count;
+
+        // Top up the existing stacks first
+        for stack in self.slots.iter_mut().flatten() {
+            if remaining == 0 {
+                break;
+            }
+
+            if stack.item == item.id && stack.count < stack_size {
+                let added = remaining.min(stack_size - stack.count);
+                stack.count += added;
+                remaining -= added;
+            }
+        }
+
+        // Then start new stacks in the empty slots
+        for slot in self.slots.iter_mut().filter(|slot| slot.is_none()) {
+            if remaining == 0 {
+                break;
+            }
+
+            let added = remaining.min(stack_size);
+            *slot = Some(ItemStack::new(item.id.clone(), added));
+            remaining -= added;
+        }
+
+        remaining
+    }
+
+    /// Removes a number of an item, taking from the last stacks first.
+    ///
+    /// Nothing is removed if the inventory doesn't hold enough of the item.
+    pub fn remove(&mut self, item: &str, count: u32) -> Result<(), InventoryError> {
+        if self.count(item) < count {
+            return Err(InventoryError::NotEnoughItems);
+        }
+
+        let mut remaining = count;
+        for slot in self.slots.iter_mut().rev() {
+            if remaining == 0 {
+                break;
+            }
+
+            let Some(stack) = slot else {
+                continue;
+            };
+            if stack.item != item {
+                continue;
+            }
+
+            let removed = remaining.min(stack.count);
+            stack.count -= removed;
+            remaining -= removed;
+
+            if stack.count == 0 {
+                *slot = None;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves the stack in one slot to another slot.
+    ///
+    /// If the other slot holds the same item, as much as fits is merged into
+    /// it and the rest stays behind. If it holds a different item, the two
+    /// stacks swap places.
+    pub fn move_stack(
+        &mut self,
+        from: usize,
+        to: usize,
+        items: &ItemDatabase,
+    ) -> Result<(), InventoryError> {
+        if from >= self.slots.len() || to >= self.slots.len() {
+            return Err(InventoryError::InvalidSlot);
+        }
+
+        if from == to {
+            return Ok(());
+        }
+
+        let (Some(source), Some(target)) = (&self.slots[from], &self.slots[to]) else {
+            self.slots.swap(from, to);
+            return Ok(());
+        };
+
+        if source.item != target.item {
+            self.slots.swap(from, to);
+            return Ok(());
+        }
+
+        let stack_size = items
+            .get(&source.item)
+            .ok_or(InventoryError::UnknownItem)?
+            .stack_size
+            .max(1);
+        let moved = source.count.min(stack_size.saturating_sub(target.count));
+
+        if let Some(target) = &mut self.slots[to] {
+            target.count += moved;
+        }
+        if let Some(source) = &mut self.slots[from] {
+            source.count -= moved;
+            if source.count == 0 {
+                self.slots[from] = None;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An item lying in the world, waiting to be picked up
+///
+/// The entity needs a sensor collider with collision events enabled.
+#[derive(Debug, Clone, Component)]
+pub struct ItemPickup {
+    pub stack: ItemStack,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::inventory::asset::ItemCategory;
+
+    fn item(id: &str, stack_size: u32) -> ItemDefinition {
+        ItemDefinition {
+            id: id.into(),
+            name: id.into(),
+            icon: 0,
+            stack_size,
+            category: ItemCategory::Material,
+        }
+    }
+
+    fn database() -> ItemDatabase {
+        let mut database = ItemDatabase::default();
+        database.items = vec![item("stone", 10), item("key", 1)];
+        database.build_lookup();
+        database
+    }
+
+    #[test]
+    fn add_tops_up_existing_stacks_first() {
+        let stone = item("stone", 10);
+        let mut inventory = Inventory::new(3);
+
+        assert_eq!(inventory.add(&stone, 4), 0);
+        assert_eq!(inventory.add(&stone, 4), 0);
+
+        assert_eq!(inventory.slots()[0], Some(ItemStack::new("stone", 8)));
+        assert_eq!(inventory.slots()[1], None);
+    }
+
+    #[test]
+    fn add_splits_into_new_stacks() {
+        let stone = item("stone", 10);
+        let mut inventory = Inventory::new(3);
+
+        assert_eq!(inventory.add(&stone, 25), 0);
+
+        assert_eq!(inventory.slots()[0], Some(ItemStack::new("stone", 10)));
+        assert_eq!(inventory.slots()[1], Some(ItemStack::new("stone", 10)));
+        assert_eq!(inventory.slots()[2], Some(ItemStack::new("stone", 5)));
+    }
+
+    #[test]
+    fn add_returns_what_does_not_fit() {
+        let key = item("key", 1);
+        let mut inventory = Inventory::new(2);
+
+        assert_eq!(inventory.add(&key, 3), 1);
+        assert_eq!(inventory.count("key"), 2);
+    }
+
+    #[test]
+    fn remove_takes_from_last_stacks() {
+        let stone = item("stone", 10);
+        let mut inventory = Inventory::new(3);
+        inventory.add(&stone, 15);
+
+        assert_eq!(inventory.remove("stone", 7), Ok(()));
+
+        assert_eq!(inventory.slots()[0], Some(ItemStack::new("stone", 8)));
+        assert_eq!(inventory.slots()[1], None);
+        assert_eq!(inventory.count("stone"), 8);
+    }
+
+    #[test]
+    fn remove_leaves_inventory_untouched_when_short() {
+        let stone = item("stone", 10);
+        let mut inventory = Inventory::new(3);
+        inventory.add(&stone, 5);
+
+        assert_eq!(
+            inventory.remove("stone", 6),
+            Err(InventoryError::NotEnoughItems)
+        );
+        assert_eq!(inventory.count("stone"), 5);
+    }
+
+    #[test]
+    fn move_stack_into_empty_slot() {
+        let database = database();
+        let mut inventory = Inventory::new(3);
+        inventory.add(database.get("stone").unwrap(), 4);
+
+        assert_eq!(inventory.move_stack(0, 2, &database), Ok(()));
+
+        assert_eq!(inventory.slots()[0], None);
+        assert_eq!(inventory.slots()[2], Some(ItemStack::new("stone", 4)));
+    }
+
+    #[test]
+    fn move_stack_merges_same_items() {
+        let database = database();
+        let stone = database.get("stone").unwrap();
+        let mut inventory = Inventory::new(3);
+        inventory.add(stone, 16);
+
+        // Slot 1 only has room for 4 more, so the other 6 stay behind
+        assert_eq!(inventory.move_stack(0, 1, &database), Ok(()));
+        assert_eq!(inventory.slots()[0], Some(ItemStack::new("stone", 6)));
+        assert_eq!(inventory.slots()[1], Some(ItemStack::new("stone", 10)));
+    }
+
+    #[test]
+    fn move_stack_swaps_different_items() {
+        let database = database();
+        let mut inventory = Inventory::new(2);
+        inventory.add(database.get("stone").unwrap(), 2);
+        inventory.add(database.get("key").unwrap(), 1);
+
+        assert_eq!(inventory.move_stack(0, 1, &database), Ok(()));
+
+        assert_eq!(inventory.slots()[0], Some(ItemStack::new("key", 1)));
+        assert_eq!(inventory.slots()[1], Some(ItemStack::new("stone", 2)));
+        assert_eq!(
+            inventory.move_stack(0, 2, &database),
+            Err(InventoryError::InvalidSlot)
+        );
+    }
+}
@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+
+/// Sent whenever an entity picks up an item lying in the world
+#[derive(Debug, Clone)]
+pub struct ItemPickedUpEvent {
+    /// The entity that picked the item up
+    pub entity: Entity,
+    /// The id of the item
+    pub item: String,
+    /// How many of the item were picked up
+    pub count: u32,
+}
@@ -0,0 +1,17 @@
+//! Module for items and inventories
+//!
+//! This module includes:
+//! - An asset holding the definition of every item, loaded from a RON file
+//! - An inventory component with slots and stacking rules
+//! - Items lying in the world that get picked up on touch
+//! - A UI panel listing the player's inventory, where stacks can be moved
+//!   between slots
+//! - A plugin to setup the required systems, loaders and events
+
+pub mod asset;
+pub mod components;
+pub mod events;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
+pub mod util;
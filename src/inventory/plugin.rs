@@ -0,0 +1,44 @@
+use super::{
+    asset::{ItemDatabase, ItemDatabaseLoader},
+    events::ItemPickedUpEvent,
+    resources::{InventoryPanel, ItemDatabaseHandle},
+    systems::{
+        close_inventory_panel, load_item_database, navigate_inventory_panel, pick_up_items,
+        toggle_inventory_panel, update_inventory_panel,
+    },
+};
+use crate::{locale::systems::report_missing_content, menu::state::GameMenuState};
+use bevy::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<ItemDatabase>();
+        app.init_asset_loader::<ItemDatabaseLoader>();
+        app.add_system(report_missing_content::<ItemDatabase>);
+
+        app.add_event::<ItemPickedUpEvent>();
+
+        app.init_resource::<ItemDatabaseHandle>();
+        app.init_resource::<InventoryPanel>();
+
+        app.add_startup_system(load_item_database);
+
+        app.add_system(pick_up_items);
+        app.add_systems(
+            (toggle_inventory_panel, navigate_inventory_panel)
+                .chain()
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_system(
+            update_inventory_panel
+                .after(navigate_inventory_panel)
+                .after(pick_up_items)
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_system(close_inventory_panel.in_schedule(OnExit(GameMenuState::InGame)));
+        debug!("InventoryPlugin loaded");
+    }
+}
@@ -0,0 +1,19 @@
+use super::asset::ItemDatabase;
+use bevy::prelude::*;
+
+/// Path to the file defining every item
+pub const ITEM_DATABASE_PATH: &str = "data/core.items.ron";
+
+/// Handle to the item database used by the game
+#[derive(Debug, Default, Resource)]
+pub struct ItemDatabaseHandle(pub Handle<ItemDatabase>);
+
+/// Whether the inventory panel is shown, and where its cursor is
+#[derive(Debug, Default, Resource)]
+pub struct InventoryPanel {
+    pub open: bool,
+    /// The slot the cursor is on
+    pub selected: usize,
+    /// The slot picked up to be put down somewhere else, if any
+    pub held: Option<usize>,
+}
@@ -0,0 +1,311 @@
+//! Defines all of the systems used by the inventory plugin
+
+use super::{
+    asset::ItemDatabase,
+    components::{Inventory, ItemPickup},
+    events::ItemPickedUpEvent,
+    resources::{InventoryPanel, ItemDatabaseHandle, ITEM_DATABASE_PATH},
+    util::step_cursor,
+};
+use crate::{
+    locale::{components::LocalizedText, resources::Localization},
+    player::Player,
+    settings::resources::Settings,
+    theme::resources::CurrentTheme,
+};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+const PANEL_COLUMNS: usize = 5;
+const SLOT_SIZE: f32 = 96.0;
+
+/// Marks the root node of the inventory panel
+#[derive(Debug, Component)]
+pub struct InventoryPanelNode;
+
+/// Starts loading the item database
+pub fn load_item_database(
+    mut item_database: ResMut<ItemDatabaseHandle>,
+    asset_server: Res<AssetServer>,
+) {
+    item_database.0 = asset_server.load(ITEM_DATABASE_PATH);
+}
+
+/// Moves items that were touched into the toucher's inventory
+pub fn pick_up_items(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut picked_up_events: EventWriter<ItemPickedUpEvent>,
+    item_database: Res<ItemDatabaseHandle>,
+    item_databases: Res<Assets<ItemDatabase>>,
+    mut pickup_query: Query<&mut ItemPickup>,
+    mut inventory_query: Query<&mut Inventory>,
+) {
+    let Some(items) = item_databases.get(&item_database.0) else {
+        return;
+    };
+
+    for event in collision_events.iter() {
+        let CollisionEvent::Started(a, b, _) = *event else {
+            continue;
+        };
+
+        for (pickup_entity, collector) in [(a, b), (b, a)] {
+            let Ok(mut pickup) = pickup_query.get_mut(pickup_entity) else {
+                continue;
+            };
+            let Ok(mut inventory) = inventory_query.get_mut(collector) else {
+                continue;
+            };
+            let Some(definition) = items.get(&pickup.stack.item) else {
+                warn!("Can't pick up unknown item {:?}", pickup.stack.item);
+                continue;
+            };
+
+            let left_over = inventory.add(definition, pickup.stack.count);
+            let picked_up = pickup.stack.count - left_over;
+            if picked_up == 0 {
+                continue;
+            }
+
+            picked_up_events.send(ItemPickedUpEvent {
+                entity: collector,
+                item: pickup.stack.item.clone(),
+                count: picked_up,
+            });
+
+            // Whatever didn't fit stays on the ground
+            pickup.stack.count = left_over;
+            if left_over == 0 {
+                commands.entity(pickup_entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+/// Opens and closes the inventory panel
+pub fn toggle_inventory_panel(
+    keys: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    mut panel: ResMut<InventoryPanel>,
+) {
+    if keys.just_pressed(settings.key_bindings.inventory) {
+        panel.open = !panel.open;
+        panel.held = None;
+    }
+}
+
+/// Moves the inventory panel's cursor with the arrow keys, and moves stacks
+/// between slots by picking one up with Enter and putting it down with Enter
+pub fn navigate_inventory_panel(
+    keys: Res<Input<KeyCode>>,
+    mut panel: ResMut<InventoryPanel>,
+    item_database: Res<ItemDatabaseHandle>,
+    item_databases: Res<Assets<ItemDatabase>>,
+    mut inventory_query: Query<&mut Inventory, With<Player>>,
+) {
+    let Ok(mut inventory) = inventory_query.get_single_mut() else {
+        return;
+    };
+    if !panel.open {
+        return;
+    }
+
+    let steps = [
+        (KeyCode::Left, IVec2::NEG_X),
+        (KeyCode::Right, IVec2::X),
+        (KeyCode::Up, IVec2::NEG_Y),
+        (KeyCode::Down, IVec2::Y),
+    ];
+    for (key, step) in steps {
+        if keys.just_pressed(key) {
+            let selected =
+                step_cursor(panel.selected, inventory.slots().len(), PANEL_COLUMNS, step);
+            if selected != panel.selected {
+                panel.selected = selected;
+            }
+        }
+    }
+
+    if !keys.just_pressed(KeyCode::Return) {
+        return;
+    }
+
+    let selected = panel.selected;
+    match panel.held {
+        None => {
+            if inventory.slots().get(selected).is_some_and(Option::is_some) {
+                panel.held = Some(selected);
+            }
+        }
+        Some(held) => {
+            panel.held = None;
+            let Some(items) = item_databases.get(&item_database.0) else {
+                return;
+            };
+            if let Err(error) = inventory.move_stack(held, selected, items) {
+                warn!(
+                    "Couldn't move slot {} to slot {}: {:?}",
+                    held, selected, error
+                );
+            }
+        }
+    }
+}
+
+/// Rebuilds the inventory panel whenever it's toggled, its cursor moves, the
+/// player's inventory changes, or the theme or language changes
+#[allow(clippy::too_many_arguments)]
+pub fn update_inventory_panel(
+    mut commands: Commands,
+    panel: Res<InventoryPanel>,
+    theme: Res<CurrentTheme>,
+    localization: Res<Localization>,
+    settings: Res<Settings>,
+    item_database: Res<ItemDatabaseHandle>,
+    item_databases: Res<Assets<ItemDatabase>>,
+    inventory_query: Query<Ref<Inventory>, With<Player>>,
+    panel_query: Query<Entity, With<InventoryPanelNode>>,
+) {
+    let inventory = inventory_query.get_single().ok();
+    let inventory_changed = inventory.as_ref().is_some_and(|i| i.is_changed());
+    if !panel.is_changed()
+        && !inventory_changed
+        && !theme.is_changed()
+        && !localization.is_changed()
+    {
+        return;
+    }
+
+    panel_query.for_each(|node| commands.entity(node).despawn_recursive());
+
+    let (true, Some(inventory)) = (panel.open, inventory) else {
+        return;
+    };
+
+    let items = item_databases.get(&item_database.0);
+    let text_style = theme.text_style("heading", "inventory", "text");
+    // Around the panel's contents and every slot
+    let padding = theme.spacing("inventory_padding");
+    let rows = inventory.slots().len().div_ceil(PANEL_COLUMNS);
+    // The name and category of the item under the cursor
+    let details = inventory
+        .slots()
+        .get(panel.selected)
+        .and_then(Option::as_ref)
+        .and_then(|stack| items?.get(&stack.item))
+        .map(|item| {
+            localization.format(
+                &LocalizedText::new("inventory-details")
+                    .with_arg("name", localization.text(&item.name))
+                    .with_arg("category", localization.text(item.category.label())),
+            )
+        })
+        .unwrap_or_default();
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::width(Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: Val::Px(20.0),
+                    ..default()
+                },
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(InventoryPanelNode)
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size::new(
+                            Val::Px(
+                                PANEL_COLUMNS as f32 * (SLOT_SIZE + padding * 2.0) + padding * 2.0,
+                            ),
+                            Val::Px(rows as f32 * (SLOT_SIZE + padding * 2.0) + padding * 2.0),
+                        ),
+                        flex_wrap: FlexWrap::Wrap,
+                        align_content: AlignContent::FlexStart,
+                        padding: UiRect::all(Val::Px(padding)),
+                        ..default()
+                    },
+                    background_color: theme.color("inventory_panel").into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (index, slot) in inventory.slots().iter().enumerate() {
+                        // UI images can't use texture atlases, so the slots
+                        // show the item's name instead of its icon
+                        let label = match slot {
+                            Some(stack) => {
+                                let name =
+                                    items.and_then(|items| items.get(&stack.item)).map_or_else(
+                                        || stack.item.clone(),
+                                        |item| localization.text(&item.name),
+                                    );
+                                format!("{name}\nx{}", stack.count)
+                            }
+                            None => String::new(),
+                        };
+
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    size: Size::all(Val::Px(SLOT_SIZE)),
+                                    margin: UiRect::all(Val::Px(padding)),
+                                    padding: UiRect::all(Val::Px(padding)),
+                                    align_items: AlignItems::Center,
+                                    justify_content: JustifyContent::Center,
+                                    ..default()
+                                },
+                                background_color: theme.color(slot_color(&panel, index)).into(),
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                parent.spawn(
+                                    TextBundle::from_section(label, text_style.clone())
+                                        .with_text_alignment(TextAlignment::Center),
+                                );
+                            });
+                    }
+                });
+
+            let hint = LocalizedText::new("inventory-hint")
+                .with_arg("throw", format!("{:?}", settings.key_bindings.throw));
+            for line in [details, localization.format(&hint)] {
+                parent.spawn(
+                    TextBundle::from_section(line, text_style.clone()).with_style(Style {
+                        margin: UiRect::top(Val::Px(padding)),
+                        ..default()
+                    }),
+                );
+            }
+        });
+}
+
+/// The name of the color of a slot in the inventory panel
+fn slot_color(panel: &InventoryPanel, index: usize) -> &'static str {
+    if panel.held == Some(index) {
+        "inventory_slot_held"
+    } else if panel.selected == index {
+        "inventory_slot_focus"
+    } else {
+        "inventory_slot"
+    }
+}
+
+/// Closes the inventory panel and removes it from the screen
+pub fn close_inventory_panel(
+    mut commands: Commands,
+    mut panel: ResMut<InventoryPanel>,
+    panel_query: Query<Entity, With<InventoryPanelNode>>,
+) {
+    panel.open = false;
+    panel.held = None;
+    panel_query.for_each(|node| commands.entity(node).despawn_recursive());
+}
@@ -0,0 +1,81 @@
+//! Utilities for the inventory module
+
+use super::{
+    asset::ItemDatabase,
+    components::{ItemPickup, ItemStack},
+};
+use crate::{constants::DRAW_LAYER, map::state::MapEntity};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+/// The size of an item lying in the world
+pub const PICKUP_SIZE: f32 = 24.0;
+
+/// Moves the inventory panel's cursor a step across the grid of slots, where
+/// a positive `y` steps down a row
+///
+/// The cursor stays put when the step would leave the grid.
+pub fn step_cursor(selected: usize, slots: usize, columns: usize, step: IVec2) -> usize {
+    let (column, row) = ((selected % columns) as i32, (selected / columns) as i32);
+    let (column, row) = (column + step.x, row + step.y);
+    if column < 0 || column >= columns as i32 || row < 0 {
+        return selected;
+    }
+
+    let next = row as usize * columns + column as usize;
+    if next < slots {
+        next
+    } else {
+        selected
+    }
+}
+
+/// Spawns an item lying in the world that can be picked up
+pub fn spawn_item_pickup(
+    commands: &mut Commands,
+    items: &ItemDatabase,
+    stack: ItemStack,
+    position: Vec2,
+) -> Option<Entity> {
+    let Some(definition) = items.get(&stack.item) else {
+        warn!("Tried to spawn unknown item {:?}", stack.item);
+        return None;
+    };
+
+    let entity = commands
+        .spawn(SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
+                index: definition.icon,
+                custom_size: Some(Vec2::splat(PICKUP_SIZE)),
+                ..default()
+            },
+            texture_atlas: items.atlas.clone(),
+            transform: Transform::from_translation(position.extend(DRAW_LAYER::ENTITIES)),
+            ..default()
+        })
+        .insert(Collider::ball(PICKUP_SIZE / 2.0))
+        .insert(Sensor)
+        .insert(ActiveEvents::COLLISION_EVENTS)
+        .insert(ItemPickup { stack })
+        .insert(MapEntity)
+        .id();
+
+    Some(entity)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn the_cursor_stays_on_the_grid() {
+        // Two full rows of three and one slot on the last row
+        assert_eq!(step_cursor(0, 7, 3, IVec2::X), 1);
+        assert_eq!(step_cursor(2, 7, 3, IVec2::X), 2);
+        assert_eq!(step_cursor(3, 7, 3, IVec2::NEG_X), 3);
+        assert_eq!(step_cursor(1, 7, 3, IVec2::Y), 4);
+        assert_eq!(step_cursor(1, 7, 3, IVec2::NEG_Y), 1);
+        assert_eq!(step_cursor(3, 7, 3, IVec2::Y), 6);
+        assert_eq!(step_cursor(4, 7, 3, IVec2::Y), 4);
+    }
+}
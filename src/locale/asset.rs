@@ -0,0 +1,52 @@
+use super::fluent::{parse_ftl, Messages};
+use bevy::{
+    asset::{Asset, AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+
+/// The messages of one language
+#[derive(TypeUuid, Debug, Default, Clone)]
+#[uuid = "c4a9e2d1-5b3f-4e87-a6d0-91f2b7c83e5a"]
+pub struct Locale {
+    pub messages: Messages,
+}
+
+/// Content whose text is written as message ids, like the names of items or
+/// the lines of conversations
+pub trait LocalizedContent: Asset {
+    /// The ids of every message the content shows
+    fn message_ids(&self) -> Vec<&str>;
+}
+
+#[derive(Default)]
+pub struct LocaleLoader;
+
+impl AssetLoader for LocaleLoader {
+    /// Load the messages of a language
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext<'_>,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let source = std::str::from_utf8(bytes)?;
+            let messages = parse_ftl(source).map_err(bevy::asset::Error::msg)?;
+
+            debug!(
+                "Loaded {} messages from {:?}",
+                messages.len(),
+                load_context.path()
+            );
+            load_context.set_default_asset(LoadedAsset::new(Locale { messages }));
+
+            Ok(())
+        })
+    }
+
+    /// Returns a list of supported extensions
+    fn extensions(&self) -> &[&str] {
+        &["ftl"]
+    }
+}
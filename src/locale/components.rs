@@ -0,0 +1,41 @@
+use super::fluent::LocaleArg;
+use bevy::prelude::*;
+use std::borrow::Cow;
+
+/// A message to show in the player's language, along with its arguments
+///
+/// As a component it keeps the text of its entity in the current language,
+/// even when the language changes while it's on screen.
+#[derive(Debug, Clone, PartialEq, Component)]
+pub struct LocalizedText {
+    /// The id of the message, which is shown as it is when no language has
+    /// it
+    pub key: Cow<'static, str>,
+    pub args: Vec<(&'static str, LocaleArg)>,
+}
+
+impl LocalizedText {
+    pub fn new(key: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            key: key.into(),
+            args: vec![],
+        }
+    }
+
+    pub fn with_arg(mut self, name: &'static str, value: impl Into<LocaleArg>) -> Self {
+        self.args.push((name, value.into()));
+        self
+    }
+}
+
+impl From<&'static str> for LocalizedText {
+    fn from(key: &'static str) -> Self {
+        Self::new(key)
+    }
+}
+
+impl From<String> for LocalizedText {
+    fn from(key: String) -> Self {
+        Self::new(key)
+    }
+}
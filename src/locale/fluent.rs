@@ -0,0 +1,566 @@
+//! # Fluent
+//!
+//! A small take on [Fluent](https://projectfluent.org), which the locale
+//! files are written in. Every message has an id and a pattern of text and
+//! placeables:
+//!
+//! ```ftl
+//! # Comments start with a hash
+//! menu-start = Start
+//! slot-empty = Slot { $slot }: Empty
+//! items-left = { $count ->
+//!     [0] Nothing left
+//!     [one] One item left
+//!    *[other] { $count } items left
+//! }
+//! welcome = Welcome to { game-title }
+//! ```
+//!
+//! Placeables hold a variable like `{ $slot }`, a string literal like
+//! `{ "{" }`, another message like `{ game-title }`, or a select expression
+//! picking a variant by a variable's value. Numbers are matched against the
+//! variants holding that exact number first and against their plural
+//! category, like `one` or `other`, after that. The variant starting with a
+//! `*` is picked when nothing else matches. Lines after the first belong to
+//! the message as long as they're indented.
+//!
+//! Attributes, terms and functions aren't supported.
+
+use std::collections::HashMap;
+
+/// How many messages can reference each other before formatting gives up,
+/// which keeps messages referencing themselves from looping forever
+const MAX_DEPTH: usize = 8;
+
+/// A value passed to a message
+#[derive(Debug, Clone, PartialEq)]
+pub enum LocaleArg {
+    Number(f64),
+    Text(String),
+}
+
+impl From<f64> for LocaleArg {
+    fn from(value: f64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<f32> for LocaleArg {
+    fn from(value: f32) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+impl From<u32> for LocaleArg {
+    fn from(value: u32) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+impl From<i32> for LocaleArg {
+    fn from(value: i32) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+impl From<usize> for LocaleArg {
+    fn from(value: usize) -> Self {
+        Self::Number(value as f64)
+    }
+}
+
+impl From<String> for LocaleArg {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for LocaleArg {
+    fn from(value: &str) -> Self {
+        Self::Text(value.into())
+    }
+}
+
+/// What a variant of a select expression matches
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariantKey {
+    Number(f64),
+    /// A plural category or a text value
+    Name(String),
+}
+
+/// A part of a message
+#[derive(Debug, Clone, PartialEq)]
+pub enum Element {
+    Text(String),
+    Variable(String),
+    /// Another message, formatted with the same arguments
+    Reference(String),
+    Select {
+        variable: String,
+        variants: Vec<(VariantKey, Pattern)>,
+        /// The index of the variant picked when nothing else matches
+        default: usize,
+    },
+}
+
+/// The text of a message, with placeables to fill in
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pattern(pub Vec<Element>);
+
+/// Every message of a locale file by its id
+pub type Messages = HashMap<String, Pattern>;
+
+fn is_identifier(id: &str) -> bool {
+    let mut chars = id.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Reads a locale file
+///
+/// Errors point out the line of the message that couldn't be read.
+pub fn parse_ftl(source: &str) -> Result<Messages, String> {
+    let mut messages = Messages::new();
+    // The id, the line it's on and its text so far
+    let mut entry: Option<(String, usize, String)> = None;
+
+    let mut finish = |entry: Option<(String, usize, String)>| -> Result<(), String> {
+        let Some((id, line, text)) = entry else {
+            return Ok(());
+        };
+        let pattern = Parser::new(text.trim_end())
+            .pattern_until_end()
+            .map_err(|error| format!("line {line}, message {id:?}: {error}"))?;
+        if messages.insert(id.clone(), pattern).is_some() {
+            return Err(format!("line {line}: {id:?} is defined more than once"));
+        }
+        Ok(())
+    };
+
+    for (index, line) in source.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // Indented lines carry on the message above, as do the closing
+        // braces of select expressions
+        if line.starts_with(char::is_whitespace) || line.starts_with('}') {
+            let Some((_, _, text)) = &mut entry else {
+                return Err(format!(
+                    "line {}: indented text outside a message",
+                    index + 1
+                ));
+            };
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(line.trim());
+            continue;
+        }
+
+        let Some((id, value)) = line.split_once('=') else {
+            return Err(format!("line {}: expected `id = text`", index + 1));
+        };
+        let id = id.trim();
+        if !is_identifier(id) {
+            return Err(format!("line {}: {id:?} isn't a valid id", index + 1));
+        }
+
+        finish(entry.take())?;
+        entry = Some((id.to_string(), index + 1, value.trim().to_string()));
+    }
+    finish(entry)?;
+
+    Ok(messages)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Self {
+        Self {
+            chars: source.chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(format!("expected {c:?}"))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, String> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            self.position += 1;
+        }
+        let id: String = self.chars[start..self.position].iter().collect();
+        if is_identifier(&id) {
+            Ok(id)
+        } else {
+            Err("expected an id".into())
+        }
+    }
+
+    fn pattern_until_end(&mut self) -> Result<Pattern, String> {
+        let pattern = self.pattern(false)?;
+        match self.peek() {
+            Some(c) => Err(format!("unexpected {c:?}")),
+            None => Ok(pattern),
+        }
+    }
+
+    /// Reads text and placeables, up to the end of the line when reading a
+    /// variant of a select expression
+    fn pattern(&mut self, in_variant: bool) -> Result<Pattern, String> {
+        let mut elements = vec![];
+        let mut text = String::new();
+
+        while let Some(c) = self.peek() {
+            match c {
+                '{' => {
+                    self.position += 1;
+                    if !text.is_empty() {
+                        elements.push(Element::Text(std::mem::take(&mut text)));
+                    }
+                    elements.push(self.placeable()?);
+                }
+                '}' => break,
+                '\n' if in_variant => break,
+                _ => {
+                    text.push(c);
+                    self.position += 1;
+                }
+            }
+        }
+
+        if in_variant {
+            let trimmed = text.trim_end().len();
+            text.truncate(trimmed);
+        }
+        if !text.is_empty() {
+            elements.push(Element::Text(text));
+        }
+        Ok(Pattern(elements))
+    }
+
+    /// Reads a placeable, just past its opening brace
+    fn placeable(&mut self) -> Result<Element, String> {
+        self.skip_whitespace();
+        let element = match self.peek() {
+            Some('$') => {
+                self.position += 1;
+                let variable = self.identifier()?;
+                self.skip_whitespace();
+                if self.eat('-') {
+                    self.expect('>')?;
+                    return self.select(variable);
+                }
+                Element::Variable(variable)
+            }
+            Some('"') => {
+                self.position += 1;
+                Element::Text(self.string_literal()?)
+            }
+            Some(_) => Element::Reference(self.identifier()?),
+            None => return Err("unclosed placeable".into()),
+        };
+
+        self.skip_whitespace();
+        self.expect('}')?;
+        Ok(element)
+    }
+
+    fn string_literal(&mut self) -> Result<String, String> {
+        let mut text = String::new();
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.position += 1;
+                    return Ok(text);
+                }
+                Some('\\') => {
+                    self.position += 1;
+                    match self.peek() {
+                        Some(c @ ('"' | '\\')) => text.push(c),
+                        _ => return Err("unknown escape in a string".into()),
+                    }
+                    self.position += 1;
+                }
+                Some('\n') | None => return Err("unclosed string".into()),
+                Some(c) => {
+                    text.push(c);
+                    self.position += 1;
+                }
+            }
+        }
+    }
+
+    /// Reads the variants of a select expression, just past its arrow
+    fn select(&mut self, variable: String) -> Result<Element, String> {
+        let mut variants = vec![];
+        let mut default = None;
+
+        loop {
+            self.skip_whitespace();
+            if self.eat('}') {
+                break;
+            }
+
+            if self.eat('*') {
+                if default.is_some() {
+                    return Err("more than one default variant".into());
+                }
+                default = Some(variants.len());
+            }
+            self.expect('[')?;
+            let start = self.position;
+            while self.peek().is_some_and(|c| c != ']' && c != '\n') {
+                self.position += 1;
+            }
+            let key: String = self.chars[start..self.position].iter().collect();
+            let key = key.trim();
+            self.expect(']')?;
+            let key = match key.parse::<f64>() {
+                Ok(number) => VariantKey::Number(number),
+                Err(_) if is_identifier(key) => VariantKey::Name(key.to_string()),
+                Err(_) => return Err(format!("{key:?} isn't a valid variant key")),
+            };
+
+            self.skip_inline_whitespace();
+            variants.push((key, self.pattern(true)?));
+        }
+
+        let Some(default) = default else {
+            return Err("a select expression needs a default variant".into());
+        };
+        Ok(Element::Select {
+            variable,
+            variants,
+            default,
+        })
+    }
+
+    fn skip_inline_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            self.position += 1;
+        }
+    }
+}
+
+/// The plural category of a number in a language, like `one` or `other`
+pub fn plural_category(language: &str, number: f64) -> &'static str {
+    let whole = number.fract() == 0.0;
+    match language {
+        // Zero counts as one in French and Portuguese
+        "fr" | "pt" if (0.0..2.0).contains(&number) => "one",
+        "fr" | "pt" => "other",
+        "ja" | "ko" | "zh" => "other",
+        _ if whole && number == 1.0 => "one",
+        _ => "other",
+    }
+}
+
+fn format_number(number: f64) -> String {
+    if number.fract() == 0.0 && number.abs() < 1e15 {
+        format!("{}", number as i64)
+    } else {
+        number.to_string()
+    }
+}
+
+/// Fills in a pattern with the arguments given, looking up the messages it
+/// references
+///
+/// Unknown variables and messages are written out as they appear in the
+/// file, so they're easy to spot on screen.
+pub fn format_pattern(
+    pattern: &Pattern,
+    messages: &Messages,
+    language: &str,
+    args: &[(&str, LocaleArg)],
+) -> String {
+    let mut out = String::new();
+    write_pattern(&mut out, pattern, messages, language, args, 0);
+    out
+}
+
+fn write_pattern(
+    out: &mut String,
+    pattern: &Pattern,
+    messages: &Messages,
+    language: &str,
+    args: &[(&str, LocaleArg)],
+    depth: usize,
+) {
+    let arg = |name: &str| args.iter().find(|(arg, _)| *arg == name).map(|(_, v)| v);
+
+    for element in &pattern.0 {
+        match element {
+            Element::Text(text) => out.push_str(text),
+            Element::Variable(name) => match arg(name) {
+                Some(LocaleArg::Number(number)) => out.push_str(&format_number(*number)),
+                Some(LocaleArg::Text(text)) => out.push_str(text),
+                None => out.push_str(&format!("{{${name}}}")),
+            },
+            Element::Reference(id) => match messages.get(id) {
+                Some(referenced) if depth < MAX_DEPTH => {
+                    write_pattern(out, referenced, messages, language, args, depth + 1);
+                }
+                _ => out.push_str(&format!("{{{id}}}")),
+            },
+            Element::Select {
+                variable,
+                variants,
+                default,
+            } => {
+                let matches = |key: &VariantKey| match (key, arg(variable)) {
+                    (VariantKey::Number(key), Some(LocaleArg::Number(number))) => key == number,
+                    (VariantKey::Name(key), Some(LocaleArg::Number(number))) => {
+                        key == plural_category(language, *number)
+                    }
+                    (VariantKey::Name(key), Some(LocaleArg::Text(text))) => key == text,
+                    _ => false,
+                };
+
+                // Exact numbers win over plural categories
+                let picked = variants
+                    .iter()
+                    .position(|(key, _)| matches!(key, VariantKey::Number(_)) && matches(key))
+                    .or_else(|| variants.iter().position(|(key, _)| matches(key)))
+                    .unwrap_or(*default);
+                write_pattern(out, &variants[picked].1, messages, language, args, depth);
+            }
+        }
+    }
+}
+
+/// The message ids a locale is missing out of the ones asked about, like
+/// the ids of a reference locale or the ones some content uses, sorted
+pub fn missing_keys<'a>(ids: impl IntoIterator<Item = &'a str>, locale: &Messages) -> Vec<&'a str> {
+    let mut missing: Vec<&str> = ids
+        .into_iter()
+        .filter(|id| !locale.contains_key(*id))
+        .collect();
+    missing.sort();
+    missing.dedup();
+    missing
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SOURCE: &str = r#"
+# A comment
+title = The Caverns
+welcome = Welcome to { title }!
+slot = Slot { $slot }: { $map }
+items = { $count ->
+    [0] Nothing left
+    [one] One item left
+   *[other] { $count } items left
+}
+braces = { "{" }braces{ "}" }
+story =
+    First line
+    Second line
+loop = { loop }
+"#;
+
+    fn format(messages: &Messages, language: &str, id: &str, args: &[(&str, LocaleArg)]) -> String {
+        format_pattern(&messages[id], messages, language, args)
+    }
+
+    #[test]
+    fn messages_are_filled_in() {
+        let messages = parse_ftl(SOURCE).unwrap();
+
+        assert_eq!(format(&messages, "en", "title", &[]), "The Caverns");
+        assert_eq!(
+            format(&messages, "en", "welcome", &[]),
+            "Welcome to The Caverns!"
+        );
+        assert_eq!(
+            format(
+                &messages,
+                "en",
+                "slot",
+                &[("slot", 2.into()), ("map", "deep_halls".into())]
+            ),
+            "Slot 2: deep_halls"
+        );
+        assert_eq!(format(&messages, "en", "slot", &[]), "Slot {$slot}: {$map}");
+        assert_eq!(format(&messages, "en", "braces", &[]), "{braces}");
+        assert_eq!(
+            format(&messages, "en", "story", &[]),
+            "First line\nSecond line"
+        );
+        assert_eq!(format(&messages, "en", "loop", &[]), "{loop}");
+    }
+
+    #[test]
+    fn plurals_follow_the_language() {
+        let messages = parse_ftl(SOURCE).unwrap();
+        let items =
+            |language, count: u32| format(&messages, language, "items", &[("count", count.into())]);
+
+        assert_eq!(items("en", 0), "Nothing left");
+        assert_eq!(items("en", 1), "One item left");
+        assert_eq!(items("en", 3), "3 items left");
+        assert_eq!(plural_category("fr", 0.0), "one");
+        assert_eq!(plural_category("en", 0.0), "other");
+        assert_eq!(plural_category("en", 1.5), "other");
+    }
+
+    #[test]
+    fn broken_files_are_reported() {
+        assert!(parse_ftl("title = { $slot").unwrap_err().contains("line 1"));
+        assert!(parse_ftl("a = x\na = y")
+            .unwrap_err()
+            .contains("more than once"));
+        assert!(parse_ftl("items = { $count ->\n  [one] One\n}")
+            .unwrap_err()
+            .contains("default variant"));
+        assert!(parse_ftl("  stray").is_err());
+        assert!(parse_ftl("no equals sign").is_err());
+    }
+
+    #[test]
+    fn missing_keys_are_found() {
+        let reference = parse_ftl("a = A\nb = B\nc = C").unwrap();
+        let locale = parse_ftl("b = Be").unwrap();
+        let ids = reference.keys().map(String::as_str);
+        assert_eq!(missing_keys(ids, &locale), vec!["a", "c"]);
+        assert_eq!(missing_keys(["c", "b", "c", "a"], &locale), vec!["a", "c"]);
+    }
+}
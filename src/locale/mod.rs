@@ -0,0 +1,25 @@
+//! Module for showing text in the player's language
+//!
+//! This module includes:
+//! - A small take on the Fluent format the locale files are written in, with
+//!   arguments, plurals and references to other messages
+//! - An asset holding the messages of a language, loaded from a `.ftl` file
+//! - A resource looking messages up in the player's language, falling back
+//!   to English
+//! - A component keeping text on screen in the current language
+//! - Warnings about messages a language is missing
+//! - A plugin to setup the required systems and loaders
+//!
+//! Game content names its text by message too: the lines of dialogues, the
+//! names and descriptions of quests and items, the text of `ShowText`
+//! trigger actions and scripts, and the `prompt` property of map objects.
+//! Text with no message of that name is shown as it is written. Content is
+//! checked for messages the locales are missing as it loads, and by the
+//! tests for everything under `assets`.
+
+pub mod asset;
+pub mod components;
+pub mod fluent;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
@@ -0,0 +1,31 @@
+use super::{
+    asset::{Locale, LocaleLoader},
+    resources::{LocaleHandles, Localization},
+    systems::{
+        apply_loaded_locales, load_locales, refresh_localized_text, update_localization,
+        update_window_title,
+    },
+};
+use crate::menu::state::GameMenuState;
+use bevy::prelude::*;
+
+/// Loads the messages of every language and keeps the text on screen in the
+/// player's language
+#[derive(Debug, Default)]
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Locale>();
+        app.init_asset_loader::<LocaleLoader>();
+
+        app.init_resource::<LocaleHandles>();
+        app.init_resource::<Localization>();
+
+        app.add_startup_system(load_locales);
+        app.add_system(apply_loaded_locales.in_schedule(OnExit(GameMenuState::Loading)));
+        app.add_system(update_localization.run_if(not(in_state(GameMenuState::Loading))));
+        app.add_systems((refresh_localized_text, update_window_title).after(update_localization));
+        debug!("LocalePlugin loaded");
+    }
+}
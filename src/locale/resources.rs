@@ -0,0 +1,211 @@
+use super::{
+    asset::Locale,
+    components::LocalizedText,
+    fluent::{format_pattern, LocaleArg, Messages},
+};
+use bevy::{prelude::*, utils::HashMap};
+
+/// Every language the game can be played in
+pub const LANGUAGES: [&str; 2] = ["en", "es"];
+/// The language messages fall back to when the current one doesn't have
+/// them, which every other language is checked against
+pub const FALLBACK_LANGUAGE: &str = "en";
+
+/// Path to the file with the messages of a language
+pub fn locale_path(language: &str) -> String {
+    format!("locales/{language}.ftl")
+}
+
+/// Handles to the locale file of every language, kept around so they reload
+/// when they change
+#[derive(Debug, Default, Resource)]
+pub struct LocaleHandles(pub HashMap<&'static str, Handle<Locale>>);
+
+/// The messages of the player's language, which every bit of text on screen
+/// is looked up in
+///
+/// Messages the language doesn't have come from the
+/// [fallback language](FALLBACK_LANGUAGE), and ones neither has show their
+/// key instead.
+#[derive(Debug, Default, Resource)]
+pub struct Localization {
+    pub language: String,
+    messages: Messages,
+    fallback: Messages,
+}
+
+impl Localization {
+    pub fn new(language: impl Into<String>, messages: Messages, fallback: Messages) -> Self {
+        Self {
+            language: language.into(),
+            messages,
+            fallback,
+        }
+    }
+
+    /// The text of a message without arguments
+    pub fn text(&self, key: &str) -> String {
+        self.format_key(key, &[])
+    }
+
+    pub fn format(&self, text: &LocalizedText) -> String {
+        self.format_key(&text.key, &text.args)
+    }
+
+    fn format_key(&self, key: &str, args: &[(&str, LocaleArg)]) -> String {
+        if let Some(pattern) = self.messages.get(key) {
+            format_pattern(pattern, &self.messages, &self.language, args)
+        } else if let Some(pattern) = self.fallback.get(key) {
+            format_pattern(pattern, &self.fallback, FALLBACK_LANGUAGE, args)
+        } else {
+            key.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::{
+        dialogue::asset::Dialogue,
+        inventory::asset::ItemDatabase,
+        locale::{
+            asset::LocalizedContent,
+            fluent::{missing_keys, parse_ftl},
+        },
+        quest::asset::QuestDatabase,
+        trigger::actions::{parse_actions, TriggerAction},
+    };
+    use std::path::Path;
+
+    /// The English messages, for checking the text shown in tests
+    pub(crate) fn english() -> Localization {
+        let messages = parse_ftl(include_str!("../../assets/locales/en.ftl"))
+            .expect("the English locale should be readable");
+        Localization::new(FALLBACK_LANGUAGE, messages.clone(), messages)
+    }
+
+    fn read_locale(language: &str) -> Messages {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("assets")
+            .join(locale_path(language));
+        let source = std::fs::read_to_string(&path).unwrap();
+        parse_ftl(&source).unwrap_or_else(|error| panic!("{path:?}: {error}"))
+    }
+
+    #[test]
+    fn every_locale_is_complete() {
+        let fallback = read_locale(FALLBACK_LANGUAGE);
+        for language in LANGUAGES {
+            let ids = fallback.keys().map(String::as_str);
+            let missing = missing_keys(ids, &read_locale(language));
+            assert!(missing.is_empty(), "{language} is missing {missing:?}");
+        }
+    }
+
+    fn read_asset(path: &str) -> String {
+        std::fs::read_to_string(
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("assets")
+                .join(path),
+        )
+        .unwrap()
+    }
+
+    fn asset_files(directory: &str) -> Vec<String> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("assets")
+            .join(directory);
+        let mut files: Vec<String> = std::fs::read_dir(path)
+            .unwrap()
+            .map(|entry| {
+                format!(
+                    "{directory}/{}",
+                    entry.unwrap().file_name().to_string_lossy()
+                )
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// The `ShowText` lines of the trigger actions in every map
+    fn map_texts() -> Vec<String> {
+        let mut texts = Vec::new();
+        for path in asset_files("tiled")
+            .into_iter()
+            .filter(|path| path.ends_with(".tmx"))
+        {
+            for value in read_asset(&path).split("value=\"").skip(1) {
+                let value = value.split('"').next().unwrap();
+                let value = value
+                    .replace("&quot;", "\"")
+                    .replace("&lt;", "<")
+                    .replace("&gt;", ">")
+                    .replace("&amp;", "&");
+                let Ok(actions) = parse_actions(&value) else {
+                    continue;
+                };
+                texts.extend(actions.into_iter().filter_map(|action| match action {
+                    TriggerAction::ShowText(text) => Some(text),
+                    _ => None,
+                }));
+            }
+        }
+        texts
+    }
+
+    /// The text scripts pass to `show_text`
+    fn script_texts() -> Vec<String> {
+        asset_files("scripts")
+            .iter()
+            .flat_map(|path| {
+                read_asset(path)
+                    .split("show_text(\"")
+                    .skip(1)
+                    .map(|text| text.split('"').next().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn content_has_messages_in_every_locale() {
+        let items: ItemDatabase = ron::from_str(&read_asset("data/core.items.ron")).unwrap();
+        let quests: QuestDatabase = ron::from_str(&read_asset("data/core.quests.ron")).unwrap();
+        let dialogues: Vec<Dialogue> = asset_files("dialogues")
+            .iter()
+            .map(|path| ron::from_str(&read_asset(path)).unwrap())
+            .collect();
+        let map_texts = map_texts();
+        let script_texts = script_texts();
+        assert!(!map_texts.is_empty() && !script_texts.is_empty());
+
+        let mut ids = items.message_ids();
+        ids.extend(quests.message_ids());
+        ids.extend(dialogues.iter().flat_map(Dialogue::message_ids));
+        ids.extend(map_texts.iter().map(String::as_str));
+        ids.extend(script_texts.iter().map(String::as_str));
+
+        for language in LANGUAGES {
+            let missing = missing_keys(ids.iter().copied(), &read_locale(language));
+            assert!(missing.is_empty(), "{language} is missing {missing:?}");
+        }
+    }
+
+    #[test]
+    fn missing_messages_fall_back() {
+        let english = read_locale("en");
+        let mut spanish = read_locale("es");
+        spanish.remove("menu-quit");
+        let localization = Localization::new("es", spanish, english);
+
+        assert_eq!(localization.text("menu-start"), "Empezar");
+        assert_eq!(localization.text("menu-quit"), "Quit");
+        assert_eq!(localization.text("no-such-message"), "no-such-message");
+        assert_eq!(
+            localization.format(&LocalizedText::new("slot-empty").with_arg("slot", 2)),
+            "Ranura 2: Vacía"
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! Defines all of the systems used by the locale plugin
+
+use super::{
+    asset::{Locale, LocalizedContent},
+    components::LocalizedText,
+    fluent::missing_keys,
+    resources::{locale_path, LocaleHandles, Localization, FALLBACK_LANGUAGE, LANGUAGES},
+};
+use crate::{menu::resources::LoadingAssets, settings::resources::Settings};
+use bevy::{asset::HandleId, prelude::*, window::PrimaryWindow};
+
+/// Starts loading the locale file of every language
+pub fn load_locales(
+    mut handles: ResMut<LocaleHandles>,
+    mut loading: ResMut<LoadingAssets>,
+    asset_server: Res<AssetServer>,
+) {
+    for language in LANGUAGES {
+        let handle: Handle<Locale> = asset_server.load(locale_path(language));
+        loading.0.push(handle.clone_untyped());
+        handles.0.insert(language, handle);
+    }
+}
+
+/// Warns about the messages a language is missing, compared to the fallback
+/// language
+fn report_missing_keys(language: &str, handles: &LocaleHandles, locales: &Assets<Locale>) {
+    let get = |language| {
+        handles
+            .0
+            .get(language)
+            .and_then(|handle| locales.get(handle))
+    };
+    let (Some(fallback), Some(locale)) = (get(FALLBACK_LANGUAGE), get(language)) else {
+        return;
+    };
+
+    let ids = fallback.messages.keys().map(String::as_str);
+    let missing = missing_keys(ids, &locale.messages);
+    if !missing.is_empty() {
+        warn!(
+            "The {:?} locale is missing {} messages: {}",
+            language,
+            missing.len(),
+            missing.join(", ")
+        );
+    }
+}
+
+/// Warns about the messages some content uses that the languages are
+/// missing, as the content loads and whenever a locale file changes
+pub fn report_missing_content<A: LocalizedContent>(
+    mut content_events: EventReader<AssetEvent<A>>,
+    mut locale_events: EventReader<AssetEvent<Locale>>,
+    contents: Res<Assets<A>>,
+    handles: Res<LocaleHandles>,
+    locales: Res<Assets<Locale>>,
+    asset_server: Res<AssetServer>,
+) {
+    let locales_changed = locale_events.iter().count() > 0;
+    let changed: Vec<HandleId> = content_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => Some(handle.id()),
+            AssetEvent::Removed { .. } => None,
+        })
+        .collect();
+    let checked: Vec<HandleId> = if locales_changed {
+        contents.ids().collect()
+    } else {
+        changed
+    };
+
+    for id in checked {
+        let Some(content) = contents.get(&contents.get_handle(id)) else {
+            continue;
+        };
+        let ids = content.message_ids();
+        let path = asset_server.get_handle_path(id);
+
+        for (language, handle) in &handles.0 {
+            let Some(locale) = locales.get(handle) else {
+                continue;
+            };
+            let missing = missing_keys(ids.iter().copied(), &locale.messages);
+            if !missing.is_empty() {
+                warn!(
+                    "The {:?} locale is missing {} messages used by {:?}: {}",
+                    language,
+                    missing.len(),
+                    path.as_ref().map(|path| path.path()),
+                    missing.join(", ")
+                );
+            }
+        }
+    }
+}
+
+/// Puts together the messages of a language and the fallback language
+fn build_localization(
+    language: &str,
+    handles: &LocaleHandles,
+    locales: &Assets<Locale>,
+) -> Localization {
+    let messages = |language| {
+        handles
+            .0
+            .get(language)
+            .and_then(|handle| locales.get(handle))
+            .map(|locale| locale.messages.clone())
+            .unwrap_or_default()
+    };
+
+    Localization::new(language, messages(language), messages(FALLBACK_LANGUAGE))
+}
+
+/// Switches to the player's language once the locale files have loaded
+pub fn apply_loaded_locales(
+    settings: Res<Settings>,
+    handles: Res<LocaleHandles>,
+    locales: Res<Assets<Locale>>,
+    mut localization: ResMut<Localization>,
+) {
+    for language in LANGUAGES {
+        report_missing_keys(language, &handles, &locales);
+    }
+    *localization = build_localization(&settings.language, &handles, &locales);
+}
+
+/// Switches languages when the setting changes, and picks up changes to the
+/// locale files
+pub fn update_localization(
+    mut locale_events: EventReader<AssetEvent<Locale>>,
+    settings: Res<Settings>,
+    handles: Res<LocaleHandles>,
+    locales: Res<Assets<Locale>>,
+    mut localization: ResMut<Localization>,
+) {
+    let mut reloaded = false;
+    for event in locale_events.iter() {
+        let AssetEvent::Modified { handle } = event else {
+            continue;
+        };
+        if let Some((language, _)) = handles.0.iter().find(|(_, other)| *other == handle) {
+            info!("Reloaded the {:?} locale", language);
+            report_missing_keys(language, &handles, &locales);
+            reloaded = true;
+        }
+    }
+
+    if reloaded || settings.language != localization.language {
+        debug!("Switching to the {:?} locale", settings.language);
+        *localization = build_localization(&settings.language, &handles, &locales);
+    }
+}
+
+/// Rewrites the localized text on screen whenever the language changes
+pub fn refresh_localized_text(
+    localization: Res<Localization>,
+    mut text_query: Query<(&LocalizedText, &mut Text)>,
+) {
+    if !localization.is_changed() || localization.is_added() {
+        return;
+    }
+
+    for (localized, mut text) in &mut text_query {
+        text.sections[0].value = localization.format(localized);
+    }
+}
+
+/// Names the window in the player's language
+pub fn update_window_title(
+    localization: Res<Localization>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !localization.is_changed() || localization.is_added() {
+        return;
+    }
+
+    for mut window in &mut windows {
+        window.title = localization.text("game-title");
+    }
+}
@@ -0,0 +1,343 @@
+use crate::{
+    general::resources::Difficulty, general::rng::GameRng, inventory::components::ItemStack,
+};
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// How deep tables can be nested inside each other before rolling gives up,
+/// which stops tables that include themselves from looping forever
+pub const MAX_NESTING: usize = 8;
+
+/// What the conditions of a loot entry are checked against
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LootContext {
+    /// How deep into the caves the loot drops
+    pub depth: u32,
+    pub difficulty: Difficulty,
+}
+
+/// A requirement for a loot entry to be picked
+#[derive(Debug, Clone, Deserialize)]
+pub enum LootCondition {
+    MinDepth(u32),
+    MaxDepth(u32),
+    MinDifficulty(Difficulty),
+    MaxDifficulty(Difficulty),
+}
+
+impl LootCondition {
+    pub fn is_met(&self, context: &LootContext) -> bool {
+        match self {
+            Self::MinDepth(depth) => context.depth >= *depth,
+            Self::MaxDepth(depth) => context.depth <= *depth,
+            Self::MinDifficulty(difficulty) => context.difficulty >= *difficulty,
+            Self::MaxDifficulty(difficulty) => context.difficulty <= *difficulty,
+        }
+    }
+}
+
+/// What a loot entry drops
+#[derive(Debug, Clone, Deserialize)]
+pub enum LootDrop {
+    /// Drops nothing, which makes the other entries rarer
+    Nothing,
+    /// Drops between `count.0` and `count.1` of an item
+    Item { item: String, count: (u32, u32) },
+    /// Rolls another table
+    Table(String),
+}
+
+/// A drop along with how likely it is to be picked
+#[derive(Debug, Clone, Deserialize)]
+pub struct LootEntry {
+    /// How likely the entry is picked compared to the others. Guaranteed
+    /// entries ignore it.
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    pub drop: LootDrop,
+    /// Every condition has to be met for the entry to be picked
+    #[serde(default)]
+    pub conditions: Vec<LootCondition>,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+impl LootEntry {
+    fn is_available(&self, context: &LootContext) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.is_met(context))
+    }
+}
+
+/// A weighted table of drops
+#[derive(Debug, Clone, Deserialize)]
+pub struct LootTable {
+    /// How many times an entry is picked, anywhere from `rolls.0` to
+    /// `rolls.1`
+    #[serde(default = "default_rolls")]
+    pub rolls: (u32, u32),
+    /// Entries that always drop when their conditions are met
+    #[serde(default)]
+    pub guaranteed: Vec<LootEntry>,
+    #[serde(default)]
+    pub entries: Vec<LootEntry>,
+}
+
+fn default_rolls() -> (u32, u32) {
+    (1, 1)
+}
+
+/// Asset holding every loot table, keyed by the table's id
+#[derive(TypeUuid, Debug, Default, Deserialize)]
+#[uuid = "6a0f3e5c-2d7b-4b81-a9c4-58e1d0f7b326"]
+pub struct LootTables {
+    pub tables: HashMap<String, LootTable>,
+}
+
+impl LootTables {
+    pub fn get(&self, id: &str) -> Option<&LootTable> {
+        self.tables.get(id)
+    }
+
+    /// Checks that every nested table exists
+    pub fn validate(&self) -> Result<(), String> {
+        for (id, table) in &self.tables {
+            for entry in table.guaranteed.iter().chain(&table.entries) {
+                if let LootDrop::Table(nested) = &entry.drop {
+                    if !self.tables.contains_key(nested) {
+                        return Err(format!(
+                            "table {:?} includes unknown table {:?}",
+                            id, nested
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rolls a table, returning the items that dropped with stacks of the
+    /// same item merged together
+    pub fn roll(&self, id: &str, context: &LootContext, rng: &mut GameRng) -> Vec<ItemStack> {
+        let mut drops = vec![];
+        self.roll_table(id, context, rng, 0, &mut drops);
+        drops
+    }
+
+    fn roll_table(
+        &self,
+        id: &str,
+        context: &LootContext,
+        rng: &mut GameRng,
+        nesting: usize,
+        drops: &mut Vec<ItemStack>,
+    ) {
+        if nesting >= MAX_NESTING {
+            warn!("Loot table {:?} is nested too deeply", id);
+            return;
+        }
+        let Some(table) = self.get(id) else {
+            warn!("Tried to roll unknown loot table {:?}", id);
+            return;
+        };
+
+        for entry in &table.guaranteed {
+            if entry.is_available(context) {
+                self.apply_drop(&entry.drop, context, rng, nesting, drops);
+            }
+        }
+
+        let available: Vec<&LootEntry> = table
+            .entries
+            .iter()
+            .filter(|entry| entry.is_available(context) && entry.weight > 0)
+            .collect();
+        let total: u32 = available.iter().map(|entry| entry.weight).sum();
+        if total == 0 {
+            return;
+        }
+
+        for _ in 0..rng.range(table.rolls.0, table.rolls.1) {
+            let mut pick = rng.range(0, total - 1);
+            for entry in &available {
+                if pick < entry.weight {
+                    self.apply_drop(&entry.drop, context, rng, nesting, drops);
+                    break;
+                }
+                pick -= entry.weight;
+            }
+        }
+    }
+
+    fn apply_drop(
+        &self,
+        drop: &LootDrop,
+        context: &LootContext,
+        rng: &mut GameRng,
+        nesting: usize,
+        drops: &mut Vec<ItemStack>,
+    ) {
+        match drop {
+            LootDrop::Nothing => {}
+            LootDrop::Item { item, count } => {
+                let count = rng.range(count.0, count.1);
+                if count == 0 {
+                    return;
+                }
+
+                match drops.iter_mut().find(|stack| stack.item == *item) {
+                    Some(stack) => stack.count += count,
+                    None => drops.push(ItemStack::new(item.clone(), count)),
+                }
+            }
+            LootDrop::Table(table) => self.roll_table(table, context, rng, nesting + 1, drops),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LootTablesLoader;
+
+impl AssetLoader for LootTablesLoader {
+    /// Load the loot tables
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext<'_>,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let tables: LootTables = ron::de::from_bytes(bytes)?;
+            tables.validate().map_err(bevy::asset::Error::msg)?;
+
+            debug!("Loaded {} loot tables", tables.tables.len());
+            load_context.set_default_asset(LoadedAsset::new(tables));
+
+            Ok(())
+        })
+    }
+
+    /// Returns a list of supported extensions
+    fn extensions(&self) -> &[&str] {
+        &["loot.ron"]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tables() -> LootTables {
+        ron::from_str(
+            r#"(
+                tables: {
+                    "rat": (
+                        guaranteed: [(drop: Item(item: "bone", count: (1, 1)))],
+                        entries: [
+                            (weight: 1, drop: Nothing),
+                            (weight: 1, drop: Item(item: "gold_coin", count: (2, 4))),
+                        ],
+                    ),
+                    "chest": (
+                        rolls: (3, 3),
+                        entries: [
+                            (drop: Table("gems")),
+                            (drop: Table("rat"), conditions: [MaxDifficulty(Easy)]),
+                        ],
+                    ),
+                    "gems": (
+                        entries: [
+                            (drop: Item(item: "cave_gem", count: (1, 1))),
+                        ],
+                        guaranteed: [
+                            (drop: Item(item: "glow_mushroom", count: (1, 1)), conditions: [MinDepth(3)]),
+                        ],
+                    ),
+                    "loop": (entries: [(drop: Table("loop"))]),
+                },
+            )"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn same_seed_same_loot() {
+        let tables = tables();
+        let context = LootContext::default();
+
+        let first = tables.roll("chest", &context, &mut GameRng::new(1));
+        let second = tables.roll("chest", &context, &mut GameRng::new(1));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn guaranteed_drops_and_quantity_ranges() {
+        let tables = tables();
+        let mut rng = GameRng::new(5);
+
+        for _ in 0..100 {
+            let drops = tables.roll("rat", &LootContext::default(), &mut rng);
+            assert!(drops.contains(&ItemStack::new("bone", 1)));
+            for stack in drops.iter().filter(|stack| stack.item == "gold_coin") {
+                assert!((2..=4).contains(&stack.count));
+            }
+        }
+    }
+
+    #[test]
+    fn nested_tables_and_conditions() {
+        let tables = tables();
+        let mut rng = GameRng::new(9);
+
+        // Only the gems table is available on normal difficulty, and its
+        // mushroom needs more depth
+        let drops = tables.roll("chest", &LootContext::default(), &mut rng);
+        assert_eq!(drops, vec![ItemStack::new("cave_gem", 3)]);
+
+        let deep = LootContext {
+            depth: 3,
+            ..default()
+        };
+        let drops = tables.roll("chest", &deep, &mut rng);
+        assert!(drops.contains(&ItemStack::new("glow_mushroom", 3)));
+    }
+
+    #[test]
+    fn weights_are_respected() {
+        let tables = tables();
+        let mut rng = GameRng::new(3);
+
+        // About half of the rats should drop coins
+        let coins = (0..1000)
+            .filter(|_| {
+                tables
+                    .roll("rat", &LootContext::default(), &mut rng)
+                    .iter()
+                    .any(|stack| stack.item == "gold_coin")
+            })
+            .count();
+        assert!((400..600).contains(&coins));
+    }
+
+    #[test]
+    fn loops_stop_and_unknown_tables_fail_validation() {
+        let tables = tables();
+        assert!(tables
+            .roll("loop", &LootContext::default(), &mut GameRng::new(0))
+            .is_empty());
+        assert!(tables.validate().is_ok());
+
+        let broken: LootTables =
+            ron::from_str(r#"(tables: {"a": (entries: [(drop: Table("missing"))])})"#).unwrap();
+        assert!(broken.validate().is_err());
+    }
+}
@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+/// Rolls a loot table and drops whatever comes out at a position
+#[derive(Debug, Clone)]
+pub struct DropLootEvent {
+    /// The id of the table to roll
+    pub table: String,
+    pub position: Vec2,
+}
@@ -0,0 +1,14 @@
+//! Module for loot
+//!
+//! This module includes:
+//! - An asset holding weighted loot tables, loaded from a RON file
+//! - Rolling tables with nesting, guaranteed drops, quantity ranges and
+//!   conditions on things like map depth and difficulty
+//! - An event that drops rolled loot into the world as item pickups
+//! - A plugin to setup the required systems, loaders and events
+
+pub mod asset;
+pub mod events;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
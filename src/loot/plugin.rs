@@ -0,0 +1,26 @@
+use super::{
+    asset::{LootTables, LootTablesLoader},
+    events::DropLootEvent,
+    resources::LootTablesHandle,
+    systems::{drop_loot, load_loot_tables},
+};
+use bevy::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct LootPlugin;
+
+impl Plugin for LootPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<LootTables>();
+        app.init_asset_loader::<LootTablesLoader>();
+
+        app.init_resource::<LootTablesHandle>();
+
+        app.add_event::<DropLootEvent>();
+
+        app.add_startup_system(load_loot_tables);
+
+        app.add_system(drop_loot);
+        debug!("LootPlugin loaded");
+    }
+}
@@ -0,0 +1,9 @@
+use super::asset::LootTables;
+use bevy::prelude::*;
+
+/// Path to the file defining every loot table
+pub const LOOT_TABLES_PATH: &str = "data/core.loot.ron";
+
+/// Handle to the loot tables used by the game
+#[derive(Debug, Default, Resource)]
+pub struct LootTablesHandle(pub Handle<LootTables>);
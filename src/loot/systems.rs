@@ -0,0 +1,59 @@
+//! Defines all of the systems used by the loot plugin
+
+use super::{
+    asset::{LootContext, LootTables},
+    events::DropLootEvent,
+    resources::{LootTablesHandle, LOOT_TABLES_PATH},
+};
+use crate::{
+    general::{resources::Difficulty, rng::GameRng},
+    inventory::{asset::ItemDatabase, resources::ItemDatabaseHandle, util::spawn_item_pickup},
+    map::state::MapState,
+};
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+/// How far from where loot drops the items can land, in pixels
+const LOOT_SCATTER: f32 = 16.0;
+
+/// Starts loading the loot tables
+pub fn load_loot_tables(mut loot_tables: ResMut<LootTablesHandle>, asset_server: Res<AssetServer>) {
+    loot_tables.0 = asset_server.load(LOOT_TABLES_PATH);
+}
+
+/// Rolls the table of every [DropLootEvent] and spawns the items as pickups
+#[allow(clippy::too_many_arguments)]
+pub fn drop_loot(
+    mut commands: Commands,
+    mut loot_events: EventReader<DropLootEvent>,
+    mut rng: ResMut<GameRng>,
+    loot_tables: Res<LootTablesHandle>,
+    loot_table_assets: Res<Assets<LootTables>>,
+    item_database: Res<ItemDatabaseHandle>,
+    item_databases: Res<Assets<ItemDatabase>>,
+    map_state: Res<MapState>,
+    difficulty: Res<Difficulty>,
+) {
+    let (Some(tables), Some(items)) = (
+        loot_table_assets.get(&loot_tables.0),
+        item_databases.get(&item_database.0),
+    ) else {
+        loot_events.clear();
+        return;
+    };
+
+    let context = LootContext {
+        depth: map_state.depth,
+        difficulty: *difficulty,
+    };
+
+    for event in loot_events.iter() {
+        let drops = tables.roll(&event.table, &context, &mut rng);
+        debug!("Loot table {:?} dropped {:?}", event.table, drops);
+
+        for stack in drops {
+            let offset = Vec2::from_angle(rng.f32() * TAU) * rng.f32() * LOOT_SCATTER;
+            spawn_item_pickup(&mut commands, items, stack, event.position + offset);
+        }
+    }
+}
@@ -1,30 +1,71 @@
+mod animation;
+mod audio;
+mod behavior;
+mod camera;
+mod combat;
 mod constants;
+mod dialogue;
+mod enemy;
+mod flock;
 mod general;
+mod health;
+mod interaction;
+mod intro;
+mod inventory;
+mod locale;
+mod loot;
 mod map;
 mod menu;
-mod obstacle;
 mod player;
+mod projectile;
+mod quest;
+mod save;
+mod script;
+mod settings;
+mod theme;
+mod trigger;
 
 use bevy::{
-    app::AppExit,
     diagnostic::{Diagnostics, FrameTimeDiagnosticsPlugin},
     log::LogPlugin,
     prelude::*,
 };
 use bevy_rapier2d::prelude::*;
 
+use animation::plugin::SpriteAnimationPlugin;
+use audio::plugin::GameAudioPlugin;
+use behavior::plugin::BehaviorPlugin;
+use camera::{
+    components::{CameraController, GameCamera},
+    plugin::CameraPlugin,
+};
+use combat::plugin::CombatPlugin;
 use constants::{LOG_FILTER, PIXELS_PER_METER};
-use map::{
-    plugin::MapPlugin,
-    state::{MapReadinessState, MapState},
+use dialogue::{plugin::DialoguePlugin, systems::dialogue_closed};
+use enemy::plugin::EnemyPlugin;
+use flock::plugin::FlockPlugin;
+use general::{
+    resources::{Difficulty, GameFlags},
+    rng::GameRng,
 };
-use menu::plugin::MenuPlugin;
-use obstacle::spawn_obstacle;
-use player::{player_movement, spawn_player};
-
-// Marker component for the game's camera
-#[derive(Component)]
-struct GameCamera;
+use health::plugin::HealthPlugin;
+use interaction::plugin::InteractionPlugin;
+use intro::plugin::IntroPlugin;
+use inventory::plugin::InventoryPlugin;
+use locale::{components::LocalizedText, plugin::LocalePlugin, resources::Localization};
+use loot::plugin::LootPlugin;
+use map::{plugin::MapPlugin, state::MapReadinessState};
+use menu::{plugin::MenuPlugin, state::GameMenuState};
+use player::{end_dash, place_player_at_spawn, player_movement, start_dash};
+use projectile::plugin::ProjectilePlugin;
+use quest::plugin::QuestPlugin;
+use save::plugin::SavePlugin;
+use script::plugin::ScriptPlugin;
+use settings::{plugin::SettingsPlugin, resources::Settings, util::load_settings};
+use theme::{components::ThemedText, plugin::ThemePlugin, resources::CurrentTheme};
+use trigger::plugin::TriggerPlugin;
+
+const FPS_TEXT: ThemedText = ThemedText::new("heading", "fps", "fps");
 
 // Marker component for the FPS text
 #[derive(Component)]
@@ -33,15 +74,30 @@ struct FPSText {
 }
 
 fn main() {
+    // The settings decide how the window is made, so they load first
+    let (settings, settings_file) = load_settings();
+    let mut window = Window {
+        title: "The Caverns".into(),
+        ..default()
+    };
+    settings.apply_to_window(&mut window);
+
     App::new()
         .insert_resource(ClearColor(Color::rgb(0.0, 0.0, 0.0)))
+        .insert_resource(GameRng::from_env())
+        .init_resource::<Difficulty>()
+        .init_resource::<GameFlags>()
+        .insert_resource(settings)
+        .insert_resource(settings_file)
         .add_plugins(
             DefaultPlugins
                 .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        title: "The Caverns".into(),
-                        ..default()
-                    }),
+                    primary_window: Some(window),
+                    ..default()
+                })
+                .set(AssetPlugin {
+                    // Reload assets like behavior trees when their files change
+                    watch_for_changes: true,
                     ..default()
                 })
                 .set(LogPlugin {
@@ -54,76 +110,103 @@ fn main() {
             PIXELS_PER_METER,
         ))
         .add_plugin(RapierDebugRenderPlugin::default())
+        .add_plugin(SettingsPlugin)
+        .add_plugin(ThemePlugin)
+        .add_plugin(LocalePlugin)
+        .add_plugin(GameAudioPlugin)
+        .add_plugin(SpriteAnimationPlugin)
+        .add_plugin(CameraPlugin)
         .add_plugin(MapPlugin::default())
+        .add_plugin(HealthPlugin)
+        .add_plugin(InventoryPlugin)
+        .add_plugin(LootPlugin)
+        .add_plugin(CombatPlugin)
+        .add_plugin(ProjectilePlugin)
+        .add_plugin(BehaviorPlugin)
+        .add_plugin(EnemyPlugin)
+        .add_plugin(FlockPlugin)
+        .add_plugin(DialoguePlugin)
+        .add_plugin(InteractionPlugin)
+        .add_plugin(TriggerPlugin)
+        .add_plugin(ScriptPlugin)
+        .add_plugin(QuestPlugin)
+        .add_plugin(SavePlugin)
         .add_plugin(MenuPlugin)
+        .add_plugin(IntroPlugin)
         .add_startup_system(setup)
-        // .add_startup_system(create_fps_text)
-        // .add_startup_system(spawn_obstacles)
+        .add_startup_system(create_fps_text)
         // .add_startup_system(spawn_player)
         .add_system(fps_text_system)
-        .add_system(handle_input)
-        .add_system(player_movement.after(handle_input))
+        .add_system(show_fps_text)
+        .add_systems(
+            (player_movement, start_dash)
+                .chain()
+                .distributive_run_if(dialogue_closed)
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        )
+        // A dash runs out even when a conversation starts in the middle of it
+        .add_system(
+            end_dash
+                .after(start_dash)
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        )
+        .add_system(place_player_at_spawn.in_schedule(OnEnter(MapReadinessState::Loaded)))
         .run();
 }
 
 /// Setup system that loads everything needed to get the game off the ground
 fn setup(mut commands: Commands) {
     // Camera
-    commands.spawn((Camera2dBundle::default(), GameCamera));
+    commands.spawn((
+        Camera2dBundle::default(),
+        GameCamera,
+        CameraController::default(),
+    ));
     debug!("Setup camera");
 }
 
-fn spawn_obstacles(mut commands: Commands) {
-    /* spawn_obstacle(
-        &mut commands,
-        Color::rgb(0.1, 0.1, 0.1),
-        Some(Vec2::new(64.0, 64.0)),
-        Transform::from_translation(Vec3::new(-150.0, 0.0, 0.0)),
-    );
-
-    spawn_obstacle(
-        &mut commands,
-        Color::rgb(0.5, 0.7, 0.0),
-        Some(Vec2::new(32.0, 48.0)),
-        Transform::from_translation(Vec3::new(200.0, 150.0, 0.0)),
-    ); */
-}
-
-fn create_fps_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+/// Spawns the FPS text, which gets its look and words once the theme and
+/// locales load
+fn create_fps_text(mut commands: Commands, theme: Res<CurrentTheme>) {
+    let localized = LocalizedText::new("fps").with_arg("fps", "-1");
+    let (text, themed) = theme.text("", FPS_TEXT);
     commands.spawn((
-        TextBundle::from_section(
-            "FPS: -1",
-            TextStyle {
-                font: asset_server.load("fonts/joystix monospace.otf"),
-                font_size: 25.0,
-                color: Color::GREEN,
-            },
-        )
-        .with_text_alignment(TextAlignment::Center)
-        .with_style(Style {
-            position_type: PositionType::Absolute,
-            position: UiRect {
-                top: Val::Px(5.0),
-                left: Val::Px(5.0),
+        text.with_text_alignment(TextAlignment::Center)
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(5.0),
+                    left: Val::Px(5.0),
+                    ..default()
+                },
                 ..default()
-            },
-            ..default()
-        }),
+            }),
         FPSText { timer: 0.0 },
+        themed,
+        localized,
     ));
 }
 
-fn handle_input(keys: Res<Input<KeyCode>>, mut exit: EventWriter<AppExit>) {
-    // Quit
-    if keys.pressed(KeyCode::Escape) {
-        exit.send(AppExit);
+/// Shows the FPS text only when the settings ask for it
+fn show_fps_text(settings: Res<Settings>, mut labels: Query<&mut Visibility, With<FPSText>>) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for mut visibility in &mut labels {
+        *visibility = if settings.show_fps {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
     }
 }
 
 fn fps_text_system(
     diagnostics: Res<Diagnostics>,
     time: Res<Time>,
-    mut labels: Query<(&mut Text, &mut FPSText)>,
+    localization: Res<Localization>,
+    mut labels: Query<(&mut Text, &mut FPSText, &mut LocalizedText)>,
 ) {
     for mut text in &mut labels {
         text.1.timer += time.delta_seconds();
@@ -135,7 +218,8 @@ fn fps_text_system(
 
         if let Some(fps) = diagnostics.get(FrameTimeDiagnosticsPlugin::FPS) {
             if let Some(value) = fps.smoothed() {
-                text.0.sections[0].value = format!("FPS: {value:.2}");
+                *text.2 = LocalizedText::new("fps").with_arg("fps", format!("{value:.2}"));
+                text.0.sections[0].value = localization.format(&text.2);
             }
         }
     }
@@ -0,0 +1,189 @@
+//! # Areas
+//!
+//! Shapes on the map that notice the player walking into them.
+//!
+//! Objects of class `area` send an [AreaEnteredEvent] with the object's
+//! name, which quests and the like listen for. Objects of class `portal`
+//! take the player to the map in their `map` property. A portal with a
+//! `requires_flag` property stays closed until that
+//! [game flag](crate::general::resources::GameFlags) is set.
+
+use super::{
+    events::AreaEnteredEvent,
+    objects::{MapObject, MapObjectShape},
+    state::{MapReadinessState, MapState},
+};
+use crate::{general::resources::GameFlags, player::Player};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+/// The class of the objects that become an [Area]
+pub const AREA_OBJECT_CLASS: &str = "area";
+/// The class of the objects that become a [Portal]
+pub const PORTAL_OBJECT_CLASS: &str = "portal";
+
+const PORTAL_OPEN_COLOR: Color = Color::rgba(0.6, 0.3, 0.9, 0.6);
+const PORTAL_CLOSED_COLOR: Color = Color::rgba(0.3, 0.3, 0.3, 0.6);
+
+/// A named part of the map
+#[derive(Debug, Clone, Component)]
+pub struct Area {
+    pub name: String,
+}
+
+/// Takes the player to another map
+#[derive(Debug, Clone, Component)]
+pub struct Portal {
+    /// Path to the map the portal leads to
+    pub map: String,
+    /// The flag that has to be set for the portal to open
+    pub requires_flag: Option<String>,
+}
+
+impl Portal {
+    pub fn is_open(&self, flags: &GameFlags) -> bool {
+        self.requires_flag
+            .as_ref()
+            .is_none_or(|flag| flags.is_set(flag))
+    }
+}
+
+/// Builds a collider matching the shape of an object, if it has an area
+pub fn object_collider(shape: &MapObjectShape) -> Option<Collider> {
+    match shape {
+        MapObjectShape::Rect { size } => Some(Collider::cuboid(size.x / 2.0, size.y / 2.0)),
+        // Close enough, since ellipses mostly mark round rooms
+        MapObjectShape::Ellipse { size } => Some(Collider::ball(size.min_element() / 2.0)),
+        MapObjectShape::Polygon { points } => Collider::convex_hull(points),
+        MapObjectShape::Point => None,
+    }
+}
+
+/// Turns `area` and `portal` objects into sensors
+pub fn spawn_map_areas(
+    mut commands: Commands,
+    object_query: Query<(Entity, &MapObject, &Transform), Added<MapObject>>,
+) {
+    for (entity, object, transform) in &object_query {
+        if object.class != AREA_OBJECT_CLASS && object.class != PORTAL_OBJECT_CLASS {
+            continue;
+        }
+
+        let Some(collider) = object_collider(&object.shape) else {
+            warn!("{} object {} has no area", object.class, object.id);
+            continue;
+        };
+
+        let mut area = commands.entity(entity);
+        area.insert(collider)
+            .insert(Sensor)
+            .insert(ActiveEvents::COLLISION_EVENTS);
+
+        if object.class == AREA_OBJECT_CLASS {
+            area.insert(Area {
+                name: object.name.clone(),
+            });
+            continue;
+        }
+
+        let Some(map) = object.string_property("map") else {
+            warn!("Portal object {} has no map", object.id);
+            continue;
+        };
+        let size = match object.shape {
+            MapObjectShape::Rect { size } | MapObjectShape::Ellipse { size } => size,
+            _ => Vec2::splat(32.0),
+        };
+
+        area.insert(Portal {
+            map: map.into(),
+            requires_flag: object.string_property("requires_flag").map(String::from),
+        })
+        .insert(SpriteBundle {
+            sprite: Sprite {
+                color: PORTAL_CLOSED_COLOR,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: *transform,
+            ..default()
+        });
+    }
+}
+
+/// Sends an [AreaEnteredEvent] whenever the player walks into an area
+pub fn detect_area_entries(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut area_events: EventWriter<AreaEnteredEvent>,
+    area_query: Query<&Area>,
+    player_query: Query<(), With<Player>>,
+) {
+    for event in collision_events.iter() {
+        let CollisionEvent::Started(a, b, _) = *event else {
+            continue;
+        };
+
+        for (area_entity, player) in [(a, b), (b, a)] {
+            let (Ok(area), true) = (area_query.get(area_entity), player_query.contains(player))
+            else {
+                continue;
+            };
+
+            debug!("Player entered area {:?}", area.name);
+            area_events.send(AreaEnteredEvent {
+                name: area.name.clone(),
+            });
+        }
+    }
+}
+
+/// Takes the player to another map when they walk into an open portal
+pub fn use_portals(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut map_state: ResMut<MapState>,
+    mut next_map_readiness: ResMut<NextState<MapReadinessState>>,
+    asset_server: Res<AssetServer>,
+    flags: Res<GameFlags>,
+    portal_query: Query<&Portal>,
+    player_query: Query<(), With<Player>>,
+) {
+    for event in collision_events.iter() {
+        let CollisionEvent::Started(a, b, _) = *event else {
+            continue;
+        };
+
+        for (portal_entity, player) in [(a, b), (b, a)] {
+            let (Ok(portal), true) = (
+                portal_query.get(portal_entity),
+                player_query.contains(player),
+            ) else {
+                continue;
+            };
+            if !portal.is_open(&flags) || map_state.next_map.is_some() {
+                continue;
+            }
+
+            info!("Taking a portal to {:?}", portal.map);
+            map_state.next_map = Some(asset_server.load(portal.map.as_str()));
+            next_map_readiness.set(MapReadinessState::Unloading);
+        }
+    }
+}
+
+/// Shows whether each portal is open
+pub fn update_portal_colors(
+    flags: Res<GameFlags>,
+    mut portal_query: Query<(Ref<Portal>, &mut Sprite)>,
+) {
+    for (portal, mut sprite) in &mut portal_query {
+        if !flags.is_changed() && !portal.is_added() {
+            continue;
+        }
+
+        sprite.color = if portal.is_open(&flags) {
+            PORTAL_OPEN_COLOR
+        } else {
+            PORTAL_CLOSED_COLOR
+        };
+    }
+}
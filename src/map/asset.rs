@@ -16,6 +16,20 @@ pub struct MapAsset {
     pub map: tiled::Map,
 }
 
+impl MapAsset {
+    /// Returns the area covered by the map in world coordinates
+    ///
+    /// The map's bottom left corner sits at the origin.
+    pub fn pixel_bounds(&self) -> Rect {
+        Rect::new(
+            0.0,
+            0.0,
+            (self.map.width * self.map.tile_width) as f32,
+            (self.map.height * self.map.tile_height) as f32,
+        )
+    }
+}
+
 #[derive(Default)]
 pub struct MapAssetLoader;
 
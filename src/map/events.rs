@@ -0,0 +1,6 @@
+/// Sent when the player walks into an `area` object
+#[derive(Debug, Clone)]
+pub struct AreaEnteredEvent {
+    /// The name of the area's object
+    pub name: String,
+}
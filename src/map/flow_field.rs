@@ -0,0 +1,522 @@
+//! # Flow Field
+//!
+//! A direction for every walkable cell of the map leading towards the
+//! closest of one or more goals. It costs about as much to build as a single
+//! pathfinding search over the whole map, but once it's built any number of
+//! agents can look up which way to go in constant time.
+//!
+//! When a goal moves to another cell the field is repaired instead of built
+//! again: the cells that led to the old goal are cleared and filled back in
+//! from the cells around them, and the new goal spreads out only as far as
+//! it's closer than the goals already there. Cells that keep leading to the
+//! same goal are left alone. The repair can be spread over several frames
+//! with a budget, and until it's done the previous field keeps being sampled
+//! so agents never stall.
+
+use super::navigation::NavigationGrid;
+use crate::constants::PIXELS_PER_METER;
+use bevy::prelude::*;
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// Cost of the cells that can't reach any goal
+const UNREACHABLE: u32 = u32::MAX;
+
+/// Source of the cells that can't reach any goal
+const NO_SOURCE: usize = usize::MAX;
+
+/// A flow field being built or repaired
+#[derive(Debug, Clone)]
+struct FlowFieldBuild {
+    /// The goals the field is being built for
+    goals: Vec<IVec2>,
+    /// Whether the field is built from nothing instead of repaired
+    from_scratch: bool,
+    costs: Vec<u32>,
+    sources: Vec<usize>,
+    directions: Vec<Vec2>,
+    /// Goals that went away, as cell indices
+    removed: Vec<usize>,
+    /// Goals that were added, seeded once the removed ones are cleared
+    added: Vec<usize>,
+    /// Cells to clear if they lead to a removed goal
+    stale: Vec<usize>,
+    /// Cells waiting to be expanded, cheapest first
+    open: BinaryHeap<Reverse<(u32, usize)>>,
+    /// Cells whose cost changed, so they and their neighbors need a new
+    /// direction
+    changed: Vec<usize>,
+    /// The next changed cell to pick directions for, once every cost is known
+    next_direction: usize,
+}
+
+/// Directions towards the closest goal for every cell of a [NavigationGrid]
+#[derive(Debug, Default, Clone)]
+pub struct FlowField {
+    width: i32,
+    height: i32,
+    goals: Vec<IVec2>,
+    /// The goals the finished field leads to
+    built_goals: Vec<IVec2>,
+    /// The cost of reaching the closest goal from every cell
+    costs: Vec<u32>,
+    /// The goal every cell leads to, as a cell index
+    sources: Vec<usize>,
+    /// The direction to move in from every cell
+    directions: Vec<Vec2>,
+    pending: Option<FlowFieldBuild>,
+}
+
+impl FlowField {
+    /// Whether the field is done building for the current goals
+    pub fn is_ready(&self) -> bool {
+        self.pending.is_none() && !self.costs.is_empty()
+    }
+
+    /// Changes the goals, starting a repair if they moved to other cells or a
+    /// rebuild if the grid changed size
+    ///
+    /// Returns whether the field has to be updated.
+    pub fn set_goals(
+        &mut self,
+        grid: &NavigationGrid,
+        goals: impl IntoIterator<Item = IVec2>,
+    ) -> bool {
+        let mut goals: Vec<IVec2> = goals
+            .into_iter()
+            .filter(|goal| grid.is_walkable(*goal))
+            .collect();
+        goals.sort_by_key(|goal| (goal.x, goal.y));
+        goals.dedup();
+
+        let resized = self.width != grid.width() || self.height != grid.height();
+        if goals == self.goals && !resized {
+            return false;
+        }
+
+        self.goals = goals;
+        let from_scratch = resized
+            || self.costs.is_empty()
+            || self
+                .pending
+                .as_ref()
+                .is_some_and(|build| build.from_scratch);
+        if from_scratch {
+            self.rebuild(grid);
+        } else {
+            self.repair(grid);
+        }
+        true
+    }
+
+    /// Throws away the field and starts building it again, like when the
+    /// grid changed
+    pub fn rebuild(&mut self, grid: &NavigationGrid) {
+        if self.width != grid.width() || self.height != grid.height() {
+            self.width = grid.width();
+            self.height = grid.height();
+            self.costs.clear();
+            self.sources.clear();
+            self.directions.clear();
+        }
+
+        let cells = (self.width * self.height) as usize;
+        self.pending = Some(FlowFieldBuild {
+            goals: self.goals.clone(),
+            from_scratch: true,
+            costs: vec![UNREACHABLE; cells],
+            sources: vec![NO_SOURCE; cells],
+            directions: vec![Vec2::ZERO; cells],
+            removed: vec![],
+            added: self
+                .goals
+                .iter()
+                .filter_map(|goal| grid.index(*goal))
+                .collect(),
+            stale: vec![],
+            open: BinaryHeap::new(),
+            changed: vec![],
+            next_direction: 0,
+        });
+    }
+
+    /// Starts repairing the finished field for the goals that moved since it
+    /// was built
+    fn repair(&mut self, grid: &NavigationGrid) {
+        let removed: Vec<usize> = self
+            .built_goals
+            .iter()
+            .filter(|goal| !self.goals.contains(goal))
+            .filter_map(|goal| grid.index(*goal))
+            .collect();
+        let added = self
+            .goals
+            .iter()
+            .filter(|goal| !self.built_goals.contains(goal))
+            .filter_map(|goal| grid.index(*goal))
+            .collect();
+
+        self.pending = Some(FlowFieldBuild {
+            goals: self.goals.clone(),
+            from_scratch: false,
+            costs: self.costs.clone(),
+            sources: self.sources.clone(),
+            directions: self.directions.clone(),
+            stale: removed.clone(),
+            removed,
+            added,
+            open: BinaryHeap::new(),
+            changed: vec![],
+            next_direction: 0,
+        });
+    }
+
+    /// Continues building or repairing the field, doing at most `budget`
+    /// cells worth of work
+    ///
+    /// Every cell touched takes a few units of work, to clear it, find its
+    /// cost and pick its direction. Returns whether the field is ready.
+    pub fn update(&mut self, grid: &NavigationGrid, budget: usize) -> bool {
+        let Some(build) = &mut self.pending else {
+            return self.is_ready();
+        };
+        let mut budget = budget;
+
+        // Clear the cells leading to a removed goal, queueing the cells
+        // around them to fill them back in
+        while budget > 0 {
+            let Some(index) = build.stale.pop() else {
+                break;
+            };
+            budget -= 1;
+
+            let source = build.sources[index];
+            if source == NO_SOURCE || !build.removed.contains(&source) {
+                continue;
+            }
+            build.costs[index] = UNREACHABLE;
+            build.sources[index] = NO_SOURCE;
+            build.changed.push(index);
+
+            for (neighbor, _) in grid.neighbors(cell_at(self.width, index)) {
+                let Some(neighbor_index) = grid.index(neighbor) else {
+                    continue;
+                };
+                let neighbor_source = build.sources[neighbor_index];
+                if build.removed.contains(&neighbor_source) {
+                    build.stale.push(neighbor_index);
+                } else if neighbor_source != NO_SOURCE {
+                    let cost = build.costs[neighbor_index];
+                    build.open.push(Reverse((cost, neighbor_index)));
+                }
+            }
+        }
+
+        if !build.stale.is_empty() {
+            return false;
+        }
+
+        for index in build.added.drain(..) {
+            build.costs[index] = 0;
+            build.sources[index] = index;
+            build.changed.push(index);
+            build.open.push(Reverse((0, index)));
+        }
+
+        // Find the cost of every cell that got cheaper with Dijkstra's
+        // algorithm
+        while budget > 0 {
+            let Some(Reverse((cost, index))) = build.open.pop() else {
+                break;
+            };
+            budget -= 1;
+
+            // Cells can be queued more than once, and only their current cost
+            // counts
+            if cost != build.costs[index] {
+                continue;
+            }
+
+            let cell = cell_at(self.width, index);
+            for (neighbor, step) in grid.neighbors(cell) {
+                let Some(neighbor_index) = grid.index(neighbor) else {
+                    continue;
+                };
+                let new_cost = cost + step;
+                if new_cost < build.costs[neighbor_index] {
+                    build.costs[neighbor_index] = new_cost;
+                    build.sources[neighbor_index] = build.sources[index];
+                    build.changed.push(neighbor_index);
+                    build.open.push(Reverse((new_cost, neighbor_index)));
+                }
+            }
+        }
+
+        if !build.open.is_empty() {
+            return false;
+        }
+
+        if build.next_direction == 0 {
+            build.changed.sort_unstable();
+            build.changed.dedup();
+        }
+
+        // Point every changed cell and its neighbors at their cheapest
+        // neighbor
+        while budget > 0 && build.next_direction < build.changed.len() {
+            let index = build.changed[build.next_direction];
+            build.next_direction += 1;
+            budget -= 1;
+
+            let cell = cell_at(self.width, index);
+            build.directions[index] = downhill(grid, &build.costs, cell);
+            for (neighbor, _) in grid.neighbors(cell) {
+                if let Some(neighbor_index) = grid.index(neighbor) {
+                    build.directions[neighbor_index] = downhill(grid, &build.costs, neighbor);
+                }
+            }
+        }
+
+        if build.next_direction < build.changed.len() {
+            return false;
+        }
+
+        let build = self.pending.take().unwrap();
+        self.built_goals = build.goals;
+        self.costs = build.costs;
+        self.sources = build.sources;
+        self.directions = build.directions;
+        true
+    }
+
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        let in_bounds = cell.x >= 0 && cell.y >= 0 && cell.x < self.width && cell.y < self.height;
+        let index = (cell.y * self.width + cell.x) as usize;
+        (in_bounds && index < self.costs.len()).then_some(index)
+    }
+
+    /// The direction to move in from a cell
+    ///
+    /// This is zero on the goals themselves and in cells that can't reach a
+    /// goal.
+    pub fn direction(&self, cell: IVec2) -> Vec2 {
+        self.index(cell)
+            .map_or(Vec2::ZERO, |index| self.directions[index])
+    }
+
+    /// The direction to move in from a world position
+    pub fn sample(&self, position: Vec2) -> Vec2 {
+        self.direction((position / PIXELS_PER_METER).floor().as_ivec2())
+    }
+}
+
+fn cell_at(width: i32, index: usize) -> IVec2 {
+    IVec2::new(index as i32 % width, index as i32 / width)
+}
+
+/// The direction from a cell towards its cheapest neighbor, or zero if no
+/// neighbor is cheaper
+fn downhill(grid: &NavigationGrid, costs: &[u32], cell: IVec2) -> Vec2 {
+    let Some(cost) = grid.index(cell).map(|index| costs[index]) else {
+        return Vec2::ZERO;
+    };
+    if cost == 0 || cost == UNREACHABLE {
+        return Vec2::ZERO;
+    }
+
+    let best = grid
+        .neighbors(cell)
+        .filter_map(|(neighbor, _)| {
+            grid.index(neighbor)
+                .map(|neighbor_index| (neighbor, costs[neighbor_index]))
+        })
+        .min_by_key(|(_, neighbor_cost)| *neighbor_cost);
+
+    match best {
+        Some((neighbor, neighbor_cost)) if neighbor_cost < cost => {
+            (neighbor - cell).as_vec2().normalize()
+        }
+        _ => Vec2::ZERO,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::map::navigation::test::grid_from_rows;
+
+    /// Builds a field in one go
+    fn build(grid: &NavigationGrid, goals: &[IVec2]) -> FlowField {
+        let mut field = FlowField::default();
+        field.set_goals(grid, goals.iter().copied());
+        assert!(field.update(grid, usize::MAX));
+        field
+    }
+
+    /// The cost of reaching the closest goal from a cell, or `None` if no
+    /// goal can be reached
+    fn cost(field: &FlowField, cell: IVec2) -> Option<u32> {
+        field
+            .index(cell)
+            .map(|index| field.costs[index])
+            .filter(|cost| *cost != UNREACHABLE)
+    }
+
+    /// Follows the field from a cell, returning the cells visited on the
+    /// way to a goal
+    fn follow(field: &FlowField, start: IVec2) -> Vec<IVec2> {
+        let mut path = vec![start];
+        let mut cell = start;
+        while cost(field, cell) != Some(0) {
+            cell += field.direction(cell).round().as_ivec2();
+            path.push(cell);
+            assert!(path.len() < 100, "The field loops");
+        }
+        path
+    }
+
+    #[test]
+    fn corridor_points_at_goal() {
+        let grid = grid_from_rows(&["....."]);
+        let field = build(&grid, &[IVec2::new(0, 0)]);
+
+        assert_eq!(field.direction(IVec2::new(4, 0)), Vec2::NEG_X);
+        assert_eq!(cost(&field, IVec2::new(4, 0)), Some(40));
+        assert_eq!(field.direction(IVec2::new(0, 0)), Vec2::ZERO);
+    }
+
+    #[test]
+    fn routes_around_walls() {
+        // The goal sits on the other side of a wall from the start
+        let grid = grid_from_rows(&[
+            ".....", //
+            ".###.", //
+            "..#..", //
+        ]);
+        let goal = IVec2::new(1, 0);
+        let field = build(&grid, &[goal]);
+
+        let path = follow(&field, IVec2::new(3, 0));
+        assert_eq!(path.last(), Some(&goal));
+        assert!(path.iter().all(|cell| grid.is_walkable(*cell)));
+        // Around the top of the wall and back down, without cutting any
+        // corners
+        assert_eq!(path.len(), 11);
+    }
+
+    #[test]
+    fn leads_to_the_closest_goal() {
+        let grid = grid_from_rows(&["......."]);
+        let field = build(&grid, &[IVec2::new(0, 0), IVec2::new(6, 0)]);
+
+        assert_eq!(field.direction(IVec2::new(2, 0)), Vec2::NEG_X);
+        assert_eq!(field.direction(IVec2::new(4, 0)), Vec2::X);
+        assert_eq!(cost(&field, IVec2::new(3, 0)), Some(30));
+    }
+
+    #[test]
+    fn walled_off_cells_are_unreachable() {
+        let grid = grid_from_rows(&["..#.."]);
+        let field = build(&grid, &[IVec2::new(0, 0)]);
+
+        assert_eq!(cost(&field, IVec2::new(4, 0)), None);
+        assert_eq!(field.direction(IVec2::new(4, 0)), Vec2::ZERO);
+        assert_eq!(cost(&field, IVec2::new(2, 0)), None);
+    }
+
+    #[test]
+    fn updates_only_when_goals_change_cells() {
+        let grid = grid_from_rows(&["....", "...."]);
+        let mut field = build(&grid, &[IVec2::new(0, 0)]);
+
+        assert!(!field.set_goals(&grid, [IVec2::new(0, 0)]));
+        assert!(field.is_ready());
+        assert!(field.set_goals(&grid, [IVec2::new(3, 1)]));
+        assert!(!field.is_ready());
+    }
+
+    #[test]
+    fn repairs_match_a_fresh_build() {
+        let grid = grid_from_rows(&[
+            "........", //
+            ".###..#.", //
+            "...#..#.", //
+            ".#...##.", //
+            ".#......", //
+        ]);
+        let moves: [&[IVec2]; 5] = [
+            &[IVec2::new(0, 0)],
+            &[IVec2::new(7, 4), IVec2::new(0, 0)],
+            &[IVec2::new(7, 4), IVec2::new(2, 2)],
+            &[IVec2::new(4, 2)],
+            &[IVec2::new(4, 2), IVec2::new(0, 4), IVec2::new(7, 0)],
+        ];
+
+        let mut field = FlowField::default();
+        for goals in moves {
+            field.set_goals(&grid, goals.iter().copied());
+            // A small budget so every repair takes a few updates
+            while !field.update(&grid, 5) {}
+
+            let fresh = build(&grid, goals);
+            assert_eq!(field.costs, fresh.costs);
+            assert_eq!(field.directions, fresh.directions);
+        }
+    }
+
+    #[test]
+    fn repairs_leave_other_goals_alone() {
+        // Moving the goal in the small room on the right shouldn't touch the
+        // big room on the left
+        let row = format!("{}#.....", ".".repeat(30));
+        let grid = grid_from_rows(&[&row]);
+        let left = IVec2::new(0, 0);
+
+        let updates = |field: &mut FlowField| {
+            let mut updates = 1;
+            while !field.update(&grid, 1) {
+                updates += 1;
+            }
+            updates
+        };
+
+        let mut repaired = build(&grid, &[left, IVec2::new(35, 0)]);
+        repaired.set_goals(&grid, [left, IVec2::new(33, 0)]);
+        let repair_updates = updates(&mut repaired);
+
+        let mut fresh = FlowField::default();
+        fresh.set_goals(&grid, [left, IVec2::new(33, 0)]);
+        let fresh_updates = updates(&mut fresh);
+
+        assert_eq!(repaired.costs, fresh.costs);
+        assert!(repair_updates * 2 < fresh_updates);
+    }
+
+    #[test]
+    fn budgeted_updates_keep_the_old_field() {
+        let grid = grid_from_rows(&["......"]);
+        let mut field = build(&grid, &[IVec2::new(0, 0)]);
+
+        field.set_goals(&grid, [IVec2::new(5, 0)]);
+        assert!(!field.update(&grid, 3));
+        // Still leading to the old goal while the new field builds
+        assert_eq!(field.direction(IVec2::new(2, 0)), Vec2::NEG_X);
+
+        let mut updates = 1;
+        while !field.update(&grid, 3) {
+            updates += 1;
+        }
+        assert!(updates > 1);
+        assert_eq!(field.direction(IVec2::new(2, 0)), Vec2::X);
+    }
+
+    #[test]
+    fn samples_world_positions() {
+        let grid = grid_from_rows(&["...", "..."]);
+        let field = build(&grid, &[IVec2::new(2, 1)]);
+
+        let position = Vec2::new(0.5, 0.5) * PIXELS_PER_METER;
+        let direction = field.sample(position);
+        assert!(direction.x > 0.0 && direction.y > 0.0);
+        assert!((direction.length() - 1.0).abs() < 1e-5);
+    }
+}
@@ -40,20 +40,59 @@
 //!
 //! ### Loading to Loaded
 //!
-//! Due to limitations in how asset loaders work, entering the
-//! [Loading](MapReadinessState::Loading) state will trigger a system that will
-//! run once the map asset has finished loading, and will place the map into
-//! the [Loaded](MapReadinessState::Loaded) state.
+//! Due to limitations in how asset loaders work, while in the
+//! [Loading](MapReadinessState::Loading) state a system checks every frame
+//! whether the map asset has finished loading. Once it has, the map is set up
+//! and placed into the [Loaded](MapReadinessState::Loaded) state.
+//!
+//! ### Loaded to Unloading
+//!
+//! To leave a map, the caller places it into the
+//! [Unloading](MapReadinessState::Unloading) state, which despawns every
+//! entity marked with [MapEntity]. If [MapState::next_map] holds a map it
+//! starts loading right away, otherwise the map ends up
+//! [Unloaded](MapReadinessState::Unloaded).
+//!
+//! ```rust
+//! // Example
+//!
+//! // Travel to another map
+//! map_state.next_map = Some(asset_server.load("tiled/deep_halls.tmx"));
+//! next_map_readiness.set(MapReadinessState::Unloading);
+//! ```
+//!
+//! ## Map Contents
+//!
+//! While loading, the position of the `player_spawn` tile is stored in
+//! [MapState::spawn_point] so the player can be placed on the map, and a
+//! [NavigationGrid](super::navigation::NavigationGrid) resource is built from
+//! the floor and wall tiles. Every object on an object layer is spawned with
+//! a [MapObject] component for the other plugins to pick up. The map's
+//! `depth`, `final` and `music` properties end up in [MapState] too.
 
 use super::{
     asset::MapAsset,
-    state::{MapReadinessState, MapState},
-    util::map_cord_to_world_cord,
+    navigation::NavigationGridBuilder,
+    objects::MapObject,
+    state::{MapEntity, MapReadinessState, MapState},
+    tiles::WallTile,
+    util::{is_wall_tile, map_cord_to_world_cord, tile_center, tile_name},
 };
 use crate::constants::{DRAW_LAYER, PIXELS_PER_METER};
 use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
 use std::path::PathBuf;
 
+/// The name of the tile marking where the player spawns
+const PLAYER_SPAWN_TILE: &str = "player_spawn";
+
+/// The map property holding how deep into the caves the map is
+const DEPTH_PROPERTY: &str = "depth";
+/// The map property marking the map that wins the game once completed
+const FINAL_PROPERTY: &str = "final";
+/// The map property holding the path to the music the map plays
+const MUSIC_PROPERTY: &str = "music";
+
 /// Sets up the world's current map.
 ///
 /// This system should only run when a new map has been loaded.
@@ -68,18 +107,18 @@ pub fn setup_map(
     map_server: Res<Assets<MapAsset>>,
     asset_server: Res<AssetServer>,
 ) {
-    // Switch into the Loaded state since the asset loader can't do that on its own
-    next_map_readiness.set(MapReadinessState::Loaded);
-
     // Attempt to grab the map asset
     let map_asset = map_server.get(&map_state.handle);
 
-    // We can't work on a non-existant map
+    // The asset server might still be busy loading the map, so try again
+    // next frame
     if map_asset.is_none() {
-        warn!("MapReadinessState is Loading yet the map asset is None.");
         return;
     }
 
+    // Switch into the Loaded state since the asset loader can't do that on its own
+    next_map_readiness.set(MapReadinessState::Loaded);
+
     // Actually get the map
     let map = map_asset.unwrap();
 
@@ -91,6 +130,19 @@ pub fn setup_map(
         }
     );
 
+    map_state.depth = match map.map.properties.get(DEPTH_PROPERTY) {
+        Some(tiled::PropertyValue::IntValue(depth)) => (*depth).max(0) as u32,
+        _ => 0,
+    };
+    map_state.is_final = matches!(
+        map.map.properties.get(FINAL_PROPERTY),
+        Some(tiled::PropertyValue::BoolValue(true))
+    );
+    map_state.music = match map.map.properties.get(MUSIC_PROPERTY) {
+        Some(tiled::PropertyValue::StringValue(path)) if !path.is_empty() => Some(path.clone()),
+        _ => None,
+    };
+
     info!("Loading tilesets");
     let tilesets = map.map.tilesets();
 
@@ -152,6 +204,9 @@ pub fn setup_map(
             _ => None,
         });
 
+    // Keep track of the floors and walls to know where things can walk
+    let mut navigation = NavigationGridBuilder::new(map.map.width, map.map.height);
+
     // Process each tile layer
     for layer in tile_layers {
         let width = layer.width().unwrap();
@@ -171,6 +226,18 @@ pub fn setup_map(
                 // tile variable for simplicity
                 let tile = tile.unwrap();
 
+                let name = tile_name(&tile);
+
+                if let Some(name) = &name {
+                    let cell = IVec2::new(x as i32, map_cord_to_world_cord(&map.map, y) as i32);
+                    navigation.add_tile(cell, name);
+
+                    // Remember where the player should spawn
+                    if name == PLAYER_SPAWN_TILE {
+                        map_state.spawn_point = Some(tile_center(&map.map, x, y));
+                    }
+                }
+
                 // The texture atlas that corresponds with this tile is just
                 // the name of it's tileset
                 let texture_key = tile.get_tileset().name.clone();
@@ -186,40 +253,112 @@ pub fn setup_map(
                     .unwrap_or_else(|| {
                         panic!(
                             "Texture atlases should have entry for key '{}'!",
-                            &texture_key
+                            texture_key
                         )
                     });
 
                 // Spawn the tile
-                commands.spawn(SpriteSheetBundle {
-                    sprite: TextureAtlasSprite {
-                        index: texture_index,
-                        custom_size: Some(Vec2::new(PIXELS_PER_METER, PIXELS_PER_METER)),
-                        anchor: bevy::sprite::Anchor::BottomLeft,
+                let mut tile_entity = commands.spawn((
+                    SpriteSheetBundle {
+                        sprite: TextureAtlasSprite {
+                            index: texture_index,
+                            custom_size: Some(Vec2::new(PIXELS_PER_METER, PIXELS_PER_METER)),
+                            anchor: bevy::sprite::Anchor::BottomLeft,
+                            ..default()
+                        },
+                        transform: Transform::from_translation(Vec3::new(
+                            x as f32 * PIXELS_PER_METER,
+                            map_cord_to_world_cord(&map.map, y) as f32 * PIXELS_PER_METER,
+                            DRAW_LAYER::MAP,
+                        )),
+                        texture_atlas: atlas_handle.clone(),
                         ..default()
                     },
-                    transform: Transform::from_translation(Vec3::new(
-                        x as f32 * PIXELS_PER_METER,
-                        map_cord_to_world_cord(&map.map, y) as f32 * PIXELS_PER_METER,
-                        DRAW_LAYER::MAP,
-                    )),
-                    texture_atlas: atlas_handle.clone(),
-                    ..default()
-                });
+                    MapEntity,
+                ));
+
+                // Walls block movement, so give them a collider. The tile is
+                // anchored at its bottom left corner, hence the offset.
+                if name.as_deref().is_some_and(is_wall_tile) {
+                    tile_entity
+                        .insert(WallTile)
+                        .insert(Collider::compound(vec![(
+                            Vec2::splat(PIXELS_PER_METER / 2.0),
+                            0.0,
+                            Collider::cuboid(PIXELS_PER_METER / 2.0, PIXELS_PER_METER / 2.0),
+                        )]));
+                }
             }
         }
     }
 
+    commands.insert_resource(navigation.build());
+
+    // Spawn every object so the other plugins can pick them up
+    let map_height = map.pixel_bounds().height();
+    for layer in map.map.layers() {
+        let tiled::LayerType::Objects(object_layer) = layer.layer_type() else {
+            continue;
+        };
+
+        for object in object_layer.objects() {
+            let (map_object, position) = MapObject::from_tiled(&object, map_height);
+            debug!(
+                "Spawning map object {} of class {:?}",
+                map_object.id, map_object.class
+            );
+            commands.spawn((
+                map_object,
+                TransformBundle::from_transform(Transform::from_translation(
+                    position.extend(DRAW_LAYER::ENTITIES),
+                )),
+                MapEntity,
+            ));
+        }
+    }
+
     let width = map.map.width * map.map.tile_width;
     let height = map.map.height * map.map.tile_height;
-    commands.spawn(SpriteBundle {
-        sprite: Sprite {
-            color: Color::rgb(1.0, 1.0, 1.0),
-            custom_size: Some(Vec2::new(width as f32, height as f32)),
-            anchor: bevy::sprite::Anchor::BottomLeft,
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgb(1.0, 1.0, 1.0),
+                custom_size: Some(Vec2::new(width as f32, height as f32)),
+                anchor: bevy::sprite::Anchor::BottomLeft,
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, DRAW_LAYER::BASE)),
             ..default()
         },
-        transform: Transform::from_translation(Vec3::new(0.0, 0.0, DRAW_LAYER::BASE)),
-        ..default()
-    });
+        MapEntity,
+    ));
+}
+
+/// Despawns everything belonging to the current map
+///
+/// Moves on to loading [MapState::next_map] if there is one.
+pub fn unload_map(
+    mut commands: Commands,
+    mut map_state: ResMut<MapState>,
+    mut next_map_readiness: ResMut<NextState<MapReadinessState>>,
+    map_entity_query: Query<Entity, With<MapEntity>>,
+) {
+    info!("Unloading map");
+    map_entity_query.for_each(|entity| commands.entity(entity).despawn_recursive());
+
+    map_state.spawn_point = None;
+    map_state.depth = 0;
+    map_state.is_final = false;
+    map_state.music = None;
+
+    match map_state.next_map.take() {
+        Some(next_map) => {
+            map_state.handle = next_map;
+            next_map_readiness.set(MapReadinessState::Loading);
+        }
+        None => {
+            map_state.handle = Handle::default();
+            next_map_readiness.set(MapReadinessState::Unloaded);
+        }
+    }
 }
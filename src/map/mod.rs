@@ -6,9 +6,19 @@
 //! - A loader to load maps into the world
 //! - A state system to handle the flow of map loading and unloading
 //! - A bunch of components to mark or hold info for the various different tiles
+//! - A navigation grid for pathfinding and line of sight checks
+//! - A flow field leading any number of agents towards shared goals
+//! - Entities for the objects placed on the map
+//! - Areas noticing the player and portals leading to other maps
+//! - Events sent when the player moves around the map
 
+pub mod areas;
 pub mod asset;
+pub mod events;
+pub mod flow_field;
 pub mod loader;
+pub mod navigation;
+pub mod objects;
 pub mod plugin;
 pub mod state;
 pub mod tiles;
@@ -0,0 +1,347 @@
+//! # Navigation
+//!
+//! A grid of walkable cells built from the map, used by anything that needs
+//! to find its way around or check what it can see.
+//!
+//! A cell is walkable when it holds a floor tile and no wall tile. Designers
+//! can block extra cells by painting a `nav_block` tile on the `Navigation`
+//! layer.
+
+use super::util::{is_floor_tile, is_wall_tile};
+use crate::constants::PIXELS_PER_METER;
+use bevy::prelude::*;
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+/// The name of the tile that marks a cell as not walkable
+pub const NAV_BLOCK_TILE: &str = "nav_block";
+
+/// Cost of moving straight to a neighboring cell
+const STRAIGHT_COST: u32 = 10;
+/// Cost of moving diagonally to a neighboring cell
+const DIAGONAL_COST: u32 = 14;
+
+/// The walkable cells of the current map
+///
+/// Cell (0, 0) is the bottom left tile of the map, matching world
+/// coordinates.
+#[derive(Debug, Default, Clone, Resource)]
+pub struct NavigationGrid {
+    width: i32,
+    height: i32,
+    walkable: Vec<bool>,
+}
+
+impl NavigationGrid {
+    /// Creates a grid where no cell is walkable
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width: width as i32,
+            height: height as i32,
+            walkable: vec![false; (width * height) as usize],
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn in_bounds(&self, cell: IVec2) -> bool {
+        cell.x >= 0 && cell.y >= 0 && cell.x < self.width && cell.y < self.height
+    }
+
+    /// Returns the index of the cell in a flat array of every cell
+    pub fn index(&self, cell: IVec2) -> Option<usize> {
+        self.in_bounds(cell)
+            .then(|| (cell.y * self.width + cell.x) as usize)
+    }
+
+    pub fn is_walkable(&self, cell: IVec2) -> bool {
+        self.index(cell).is_some_and(|index| self.walkable[index])
+    }
+
+    pub fn set_walkable(&mut self, cell: IVec2, walkable: bool) {
+        if let Some(index) = self.index(cell) {
+            self.walkable[index] = walkable;
+        }
+    }
+
+    /// Returns the cell containing the world position
+    pub fn world_to_cell(&self, position: Vec2) -> IVec2 {
+        (position / PIXELS_PER_METER).floor().as_ivec2()
+    }
+
+    /// Returns the world position of the center of the cell
+    pub fn cell_to_world(&self, cell: IVec2) -> Vec2 {
+        (cell.as_vec2() + 0.5) * PIXELS_PER_METER
+    }
+
+    /// Returns the cells a rectangle in world space covers
+    ///
+    /// A rectangle lying exactly on the edges of cells only covers the cells
+    /// inside it, not the ones it touches.
+    pub fn cells_in_rect(&self, rect: Rect) -> impl Iterator<Item = IVec2> {
+        let margin = Vec2::splat(PIXELS_PER_METER / 64.0);
+        let min = self.world_to_cell(rect.min + margin);
+        let max = self.world_to_cell(rect.max - margin).max(min);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+    }
+
+    /// Returns the walkable neighbors of a cell along with the cost of
+    /// moving to each of them.
+    ///
+    /// Diagonal moves are only allowed when both cells next to the diagonal
+    /// are walkable, so nothing cuts the corner of a wall.
+    pub fn neighbors(&self, cell: IVec2) -> impl Iterator<Item = (IVec2, u32)> + '_ {
+        const OFFSETS: [IVec2; 8] = [
+            IVec2::new(1, 0),
+            IVec2::new(-1, 0),
+            IVec2::new(0, 1),
+            IVec2::new(0, -1),
+            IVec2::new(1, 1),
+            IVec2::new(1, -1),
+            IVec2::new(-1, 1),
+            IVec2::new(-1, -1),
+        ];
+
+        OFFSETS.iter().filter_map(move |offset| {
+            let neighbor = cell + *offset;
+            if !self.is_walkable(neighbor) {
+                return None;
+            }
+
+            if offset.x != 0 && offset.y != 0 {
+                let side_a = IVec2::new(cell.x + offset.x, cell.y);
+                let side_b = IVec2::new(cell.x, cell.y + offset.y);
+                if !self.is_walkable(side_a) || !self.is_walkable(side_b) {
+                    return None;
+                }
+                Some((neighbor, DIAGONAL_COST))
+            } else {
+                Some((neighbor, STRAIGHT_COST))
+            }
+        })
+    }
+
+    /// Finds the shortest path between two cells with A*.
+    ///
+    /// The returned path starts with the cell after `start` and ends with
+    /// `goal`. Returns `None` if the goal can't be reached.
+    pub fn find_path(&self, start: IVec2, goal: IVec2) -> Option<Vec<IVec2>> {
+        if !self.is_walkable(goal) {
+            return None;
+        }
+        if start == goal {
+            return Some(vec![]);
+        }
+
+        let heuristic = |cell: IVec2| {
+            let delta = (cell - goal).abs();
+            let (min, max) = (delta.x.min(delta.y), delta.x.max(delta.y));
+            min as u32 * DIAGONAL_COST + (max - min) as u32 * STRAIGHT_COST
+        };
+
+        let mut open = BinaryHeap::new();
+        let mut came_from: HashMap<IVec2, IVec2> = HashMap::new();
+        let mut costs: HashMap<IVec2, u32> = HashMap::new();
+
+        costs.insert(start, 0);
+        open.push(OpenCell {
+            cell: start,
+            estimate: heuristic(start),
+        });
+
+        while let Some(OpenCell { cell, .. }) = open.pop() {
+            if cell == goal {
+                let mut path = vec![goal];
+                let mut current = goal;
+                while let Some(previous) = came_from.get(&current) {
+                    if *previous == start {
+                        break;
+                    }
+                    path.push(*previous);
+                    current = *previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            let cost = costs[&cell];
+            for (neighbor, step) in self.neighbors(cell) {
+                let new_cost = cost + step;
+                if costs.get(&neighbor).is_none_or(|old| new_cost < *old) {
+                    costs.insert(neighbor, new_cost);
+                    came_from.insert(neighbor, cell);
+                    open.push(OpenCell {
+                        cell: neighbor,
+                        estimate: new_cost + heuristic(neighbor),
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Whether there is nothing blocking the straight line between two world
+    /// positions
+    pub fn line_of_sight(&self, from: Vec2, to: Vec2) -> bool {
+        // Check a few points per cell along the line
+        let steps = (from.distance(to) / (PIXELS_PER_METER / 4.0)).ceil() as i32;
+
+        (1..steps).all(|step| {
+            let point = from.lerp(to, step as f32 / steps as f32);
+            self.is_walkable(self.world_to_cell(point))
+        })
+    }
+}
+
+/// Tracks which cells of a map are floors and walls while it loads
+#[derive(Debug)]
+pub struct NavigationGridBuilder {
+    grid: NavigationGrid,
+    blocked: Vec<bool>,
+}
+
+impl NavigationGridBuilder {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            grid: NavigationGrid::new(width, height),
+            blocked: vec![false; (width * height) as usize],
+        }
+    }
+
+    /// Records a tile placed on the map
+    pub fn add_tile(&mut self, cell: IVec2, name: &str) {
+        let Some(index) = self.grid.index(cell) else {
+            return;
+        };
+
+        if is_wall_tile(name) || name == NAV_BLOCK_TILE {
+            self.blocked[index] = true;
+        } else if is_floor_tile(name) {
+            self.grid.walkable[index] = true;
+        }
+    }
+
+    pub fn build(mut self) -> NavigationGrid {
+        for (walkable, blocked) in self.grid.walkable.iter_mut().zip(self.blocked) {
+            *walkable &= !blocked;
+        }
+        self.grid
+    }
+}
+
+/// A cell waiting to be explored by A*, ordered so the cheapest comes first
+#[derive(Debug, Eq, PartialEq)]
+struct OpenCell {
+    cell: IVec2,
+    estimate: u32,
+}
+
+impl Ord for OpenCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .cmp(&self.estimate)
+            .then_with(|| self.cell.x.cmp(&other.cell.x))
+            .then_with(|| self.cell.y.cmp(&other.cell.y))
+    }
+}
+
+impl PartialOrd for OpenCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+
+    /// Creates a grid from rows of text, where `#` is a wall and anything
+    /// else is walkable. The first row is the top of the grid.
+    pub(crate) fn grid_from_rows(rows: &[&str]) -> NavigationGrid {
+        let height = rows.len() as u32;
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0) as u32;
+        let mut grid = NavigationGrid::new(width, height);
+
+        for (row_index, row) in rows.iter().enumerate() {
+            let y = height as i32 - 1 - row_index as i32;
+            for (x, cell) in row.chars().enumerate() {
+                grid.set_walkable(IVec2::new(x as i32, y), cell != '#');
+            }
+        }
+
+        grid
+    }
+
+    #[test]
+    fn find_path_goes_around_walls() {
+        let grid = grid_from_rows(&[
+            ".....", //
+            ".###.", //
+            ".....", //
+        ]);
+
+        let path = grid
+            .find_path(IVec2::new(0, 1), IVec2::new(4, 1))
+            .expect("The goal should be reachable");
+
+        assert_eq!(path.last(), Some(&IVec2::new(4, 1)));
+        assert!(path.iter().all(|cell| grid.is_walkable(*cell)));
+        assert_eq!(path.len(), 6);
+    }
+
+    #[test]
+    fn rects_cover_the_cells_inside_them() {
+        let grid = NavigationGrid::new(4, 4);
+        let cell = PIXELS_PER_METER;
+
+        // A door two cells tall lying exactly on the cell edges
+        let door = Rect::from_center_size(Vec2::new(1.5, 2.0) * cell, Vec2::new(1.0, 2.0) * cell);
+        assert_eq!(
+            grid.cells_in_rect(door).collect::<Vec<_>>(),
+            vec![IVec2::new(1, 1), IVec2::new(1, 2)]
+        );
+
+        // Something smaller than a cell still covers the cell it's in
+        let lever = Rect::from_center_size(Vec2::new(2.5, 0.5) * cell, Vec2::splat(4.0));
+        assert_eq!(
+            grid.cells_in_rect(lever).collect::<Vec<_>>(),
+            vec![IVec2::new(2, 0)]
+        );
+    }
+
+    #[test]
+    fn find_path_fails_when_walled_off() {
+        let grid = grid_from_rows(&[
+            "..#..", //
+            "..#..", //
+        ]);
+
+        assert_eq!(grid.find_path(IVec2::new(0, 0), IVec2::new(4, 0)), None);
+    }
+
+    #[test]
+    fn line_of_sight_is_blocked_by_walls() {
+        let grid = grid_from_rows(&[
+            ".....", //
+            "..#..", //
+            ".....", //
+        ]);
+
+        let left = grid.cell_to_world(IVec2::new(0, 1));
+        let right = grid.cell_to_world(IVec2::new(4, 1));
+        let top_right = grid.cell_to_world(IVec2::new(4, 2));
+
+        assert!(!grid.line_of_sight(left, right));
+        assert!(grid.line_of_sight(grid.cell_to_world(IVec2::new(0, 2)), top_right));
+    }
+}
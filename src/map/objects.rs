@@ -0,0 +1,115 @@
+//! # Objects
+//!
+//! Every object placed on an object layer in Tiled is spawned as an entity
+//! with a [MapObject] component. Other plugins give objects their behavior
+//! by looking for the objects with their class, for example `enemy`.
+
+use bevy::prelude::*;
+
+/// The shape of a map object, in world units relative to its entity
+#[derive(Debug, Clone)]
+pub enum MapObjectShape {
+    /// A point, or any other shape without an area like a line
+    Point,
+    Rect {
+        size: Vec2,
+    },
+    Ellipse {
+        size: Vec2,
+    },
+    Polygon {
+        points: Vec<Vec2>,
+    },
+}
+
+/// An object placed on the map in Tiled
+///
+/// The entity's transform is placed at the center of rectangles and ellipses
+/// and at the origin of every other shape.
+#[derive(Debug, Clone, Component)]
+pub struct MapObject {
+    /// The object's id, unique within its map
+    pub id: u32,
+    pub name: String,
+    /// The object's class, which decides what it turns into
+    pub class: String,
+    pub shape: MapObjectShape,
+    pub properties: tiled::Properties,
+}
+
+impl MapObject {
+    /// Builds a map object from a Tiled object, returning it along with its
+    /// world position
+    pub fn from_tiled(object: &tiled::ObjectData, map_height: f32) -> (Self, Vec2) {
+        // Tiled's y axis points down while the world's points up
+        let origin = Vec2::new(object.x, map_height - object.y);
+        let flip = |points: &[(f32, f32)]| {
+            points
+                .iter()
+                .map(|(x, y)| Vec2::new(*x, -*y))
+                .collect::<Vec<_>>()
+        };
+
+        let (shape, position) = match &object.shape {
+            tiled::ObjectShape::Rect { width, height } => {
+                let size = Vec2::new(*width, *height);
+                // Rectangles hang down from their top left corner
+                let center = origin + Vec2::new(size.x, -size.y) / 2.0;
+                (MapObjectShape::Rect { size }, center)
+            }
+            tiled::ObjectShape::Ellipse { width, height } => {
+                let size = Vec2::new(*width, *height);
+                let center = origin + Vec2::new(size.x, -size.y) / 2.0;
+                (MapObjectShape::Ellipse { size }, center)
+            }
+            tiled::ObjectShape::Polygon { points } => (
+                MapObjectShape::Polygon {
+                    points: flip(points),
+                },
+                origin,
+            ),
+            _ => (MapObjectShape::Point, origin),
+        };
+
+        let map_object = Self {
+            id: object.id(),
+            name: object.name.clone(),
+            class: object.user_type.clone(),
+            shape,
+            properties: object.properties.clone(),
+        };
+
+        (map_object, position)
+    }
+
+    pub fn string_property(&self, name: &str) -> Option<&str> {
+        match self.properties.get(name)? {
+            tiled::PropertyValue::StringValue(value) | tiled::PropertyValue::FileValue(value) => {
+                Some(value.as_str())
+            }
+            _ => None,
+        }
+    }
+
+    pub fn float_property(&self, name: &str) -> Option<f32> {
+        match self.properties.get(name)? {
+            tiled::PropertyValue::FloatValue(value) => Some(*value),
+            tiled::PropertyValue::IntValue(value) => Some(*value as f32),
+            _ => None,
+        }
+    }
+
+    pub fn int_property(&self, name: &str) -> Option<i32> {
+        match self.properties.get(name)? {
+            tiled::PropertyValue::IntValue(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn bool_property(&self, name: &str) -> Option<bool> {
+        match self.properties.get(name)? {
+            tiled::PropertyValue::BoolValue(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
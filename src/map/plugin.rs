@@ -1,8 +1,11 @@
 use super::{
+    areas::{detect_area_entries, spawn_map_areas, update_portal_colors, use_portals},
     asset::{MapAsset, MapAssetLoader},
-    loader::setup_map,
+    events::AreaEnteredEvent,
+    loader::{setup_map, unload_map},
     state::{MapReadinessState, MapState},
 };
+use crate::menu::state::GameMenuState;
 use bevy::prelude::*;
 
 #[derive(Debug, Default)]
@@ -11,12 +14,20 @@ pub struct MapPlugin {}
 impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
         app.add_state::<MapReadinessState>();
-        app.add_system(setup_map.in_schedule(OnEnter(MapReadinessState::Loading)));
+        app.add_system(setup_map.in_set(OnUpdate(MapReadinessState::Loading)));
+        app.add_system(unload_map.in_schedule(OnEnter(MapReadinessState::Unloading)));
 
         app.insert_resource(MapState { ..default() });
 
         app.add_asset::<MapAsset>();
         app.init_asset_loader::<MapAssetLoader>();
+
+        app.add_event::<AreaEnteredEvent>();
+
+        app.add_system(spawn_map_areas);
+        app.add_system(detect_area_entries);
+        app.add_system(use_portals.in_set(OnUpdate(GameMenuState::InGame)));
+        app.add_system(update_portal_colors);
         debug!("MapPlugin loaded");
     }
 }
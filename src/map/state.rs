@@ -10,14 +10,26 @@ pub struct MapState {
     ///
     /// The key should correspond to the tileset's name.
     pub texture_atlases: HashMap<String, Handle<TextureAtlas>>,
-    /// A HashMap storing the key and offset that maps to a specific texture
-    ///
-    /// The key "cave_dirt_floor" might map to ("Main Tileset", 32, 32) which
-    /// would indicate that the texture for the "cave_dirt_floor" tile is
-    /// located in the "Main Tileset" texture with and offset of (32, 32).
-    pub texture_maps: HashMap<String, (String, u32, u32)>,
+    /// Where the player spawns on the current map, in world coordinates
+    pub spawn_point: Option<Vec2>,
+    /// How deep into the caves the current map is, from the map's `depth`
+    /// property
+    pub depth: u32,
+    /// Whether completing the current map wins the game, from the map's
+    /// `final` property
+    pub is_final: bool,
+    /// Path to the music the current map plays, from the map's `music`
+    /// property
+    pub music: Option<String>,
+    /// The map loaded once the current one is done unloading, if any
+    pub next_map: Option<Handle<MapAsset>>,
 }
 
+/// Marks everything that belongs to the current map, which is all despawned
+/// when the map unloads
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct MapEntity;
+
 /// Represents the various stages of readiness for a map
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, States)]
 pub enum MapReadinessState {
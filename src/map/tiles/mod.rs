@@ -4,18 +4,6 @@
 
 use bevy::prelude::*;
 
-trait MapTile {}
-
+/// Marks a tile as a wall that blocks movement, sight and projectiles
 #[derive(Debug, Component)]
-pub struct UnknownTile {
-    pos: (i32, i32),
-    offset: (u32, u32),
-}
-
-#[derive(Debug, Resource)]
-pub struct UnknowTileTexture {
-    pub loaded: bool,
-    pub handle: Handle<Image>,
-}
-
-impl MapTile for UnknownTile {}
+pub struct WallTile;
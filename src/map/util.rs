@@ -1,14 +1,68 @@
 //! Utilities for the map module
 
+use super::asset::MapAsset;
+use crate::constants::PIXELS_PER_METER;
+use bevy::prelude::*;
+
 /// Converts the provided map coordinate to a world coordinate
 /// based upon the provided map.
 ///
 /// > **Warning**:
 /// > This function only operates on the Y axis and as such, shouldn't be used with
-/// any other values.
+/// > any other values.
 ///
 /// The coordinate is a map style coordinate used by tiled,
 /// and the function will return a world style coordinate used by bevy.
 pub fn map_cord_to_world_cord(map: &tiled::Map, cord: u32) -> u32 {
     map.height - 1 - cord
 }
+
+/// Returns the asset path a map was loaded from, like `tiled/test.tmx`,
+/// which is what identifies the map in saves
+pub fn map_id(asset_server: &AssetServer, handle: &Handle<MapAsset>) -> Option<String> {
+    asset_server
+        .get_handle_path(handle)
+        .map(|path| path.path().to_string_lossy().replace('\\', "/"))
+}
+
+/// Returns the value of the tile's `name` property, if it has one
+pub fn tile_name(tile: &tiled::LayerTile) -> Option<String> {
+    match tile.get_tile()?.properties.get("name")? {
+        tiled::PropertyValue::StringValue(name) => Some(name.clone()),
+        _ => None,
+    }
+}
+
+/// Finds the tile with the given `name` property, returning the name of its
+/// tileset along with its index in the tileset's texture atlas
+pub fn find_named_tile(map: &tiled::Map, name: &str) -> Option<(String, usize)> {
+    map.tilesets().iter().find_map(|tileset| {
+        tileset
+            .tiles()
+            .find_map(|(id, tile)| match tile.properties.get("name")? {
+                tiled::PropertyValue::StringValue(tile_name) if tile_name == name => {
+                    Some((tileset.name.clone(), id as usize))
+                }
+                _ => None,
+            })
+    })
+}
+
+/// Returns the center of the tile at the provided map coordinate in world
+/// coordinates
+pub fn tile_center(map: &tiled::Map, x: u32, y: u32) -> Vec2 {
+    Vec2::new(
+        (x as f32 + 0.5) * PIXELS_PER_METER,
+        (map_cord_to_world_cord(map, y) as f32 + 0.5) * PIXELS_PER_METER,
+    )
+}
+
+/// Whether a tile with the given name blocks movement and sight
+pub fn is_wall_tile(name: &str) -> bool {
+    name.starts_with("cave_dirt_wall")
+}
+
+/// Whether a tile with the given name can be walked on
+pub fn is_floor_tile(name: &str) -> bool {
+    name.ends_with("floor") || name == "player_spawn"
+}
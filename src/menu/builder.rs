@@ -0,0 +1,470 @@
+//! A builder for menus made of buttons, with focus that can be moved with
+//! the keyboard or a gamepad
+//!
+//! Every button of a menu carries an action, which is any [Component] enum
+//! saying what the button does. Picking a button, by clicking it or by
+//! pressing Enter or the gamepad's south button while it has focus, sends a
+//! [MenuActionEvent] with its action. Actions need to be registered with
+//! [add_menu_actions](MenuAppExt::add_menu_actions) once.
+//!
+//! ```rust
+//! // Example
+//!
+//! MenuBuilder::new(BUTTON_SIZE)
+//!     .title("pause-title")
+//!     .overlay(10)
+//!     .item("pause-resume", PauseButton::Resume)
+//!     .item("menu-quit", PauseButton::Quit)
+//!     .spawn(&mut commands, &theme, &localization, PauseMenuNode);
+//! ```
+//!
+//! Menus stack, and only the one on the highest layer takes input, so the
+//! menus underneath an overlay stay put while it's open. Up and Down on the
+//! arrow keys and the D-pad move focus through the buttons in the order they
+//! were added, and hovering a button with the mouse moves focus to it. Left
+//! and Right are left to the menus themselves, see [side_step].
+
+use super::util::{localized_text, spawn_button};
+use crate::{
+    locale::{components::LocalizedText, resources::Localization},
+    theme::{
+        components::{ThemedBackground, ThemedText},
+        resources::CurrentTheme,
+    },
+};
+use bevy::prelude::*;
+use std::marker::PhantomData;
+
+const TITLE_TEXT: ThemedText = ThemedText::new("heading", "heading", "text");
+const LINE_TEXT: ThemedText = ThemedText::new("heading", "menu_line", "text");
+
+/// The systems handling menu input
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub struct MenuSet;
+
+/// A menu and which of its buttons has focus
+#[derive(Debug, Component)]
+pub struct Menu {
+    /// Menus on higher layers go on top and take input from the ones below
+    pub layer: i32,
+    /// The buttons of the menu, in the order focus moves through them
+    pub items: Vec<Entity>,
+    /// The index of the focused button in `items`
+    pub focus: usize,
+}
+
+impl Menu {
+    pub fn new(layer: i32, items: Vec<Entity>) -> Self {
+        Self {
+            layer,
+            items,
+            focus: 0,
+        }
+    }
+
+    /// The button with focus, if the menu has any
+    pub fn focused(&self) -> Option<Entity> {
+        self.items.get(self.focus).copied()
+    }
+}
+
+/// Marks a button as belonging to a menu
+#[derive(Debug, Component)]
+pub struct MenuItem {
+    pub menu: Entity,
+}
+
+/// Sent when a menu button is picked
+#[derive(Debug, Clone, Copy)]
+pub struct MenuActionEvent<A> {
+    pub action: A,
+}
+
+/// Builds a menu stacking a title, lines of text and buttons in a column in
+/// the middle of the screen
+#[derive(Debug)]
+pub struct MenuBuilder<A> {
+    title: Option<(LocalizedText, ThemedText)>,
+    lines: Vec<LocalizedText>,
+    items: Vec<(LocalizedText, A)>,
+    button_size: Vec2,
+    layer: Option<i32>,
+}
+
+impl<A: Component + Copy> MenuBuilder<A> {
+    pub fn new(button_size: Vec2) -> Self {
+        Self {
+            title: None,
+            lines: vec![],
+            items: vec![],
+            button_size,
+            layer: None,
+        }
+    }
+
+    pub fn title(self, title: impl Into<LocalizedText>) -> Self {
+        self.title_style(title, TITLE_TEXT)
+    }
+
+    /// Adds a title styled differently from the usual one
+    pub fn title_style(mut self, title: impl Into<LocalizedText>, style: ThemedText) -> Self {
+        self.title = Some((title.into(), style));
+        self
+    }
+
+    /// Adds lines of text between the title and the buttons
+    pub fn lines(mut self, lines: impl IntoIterator<Item = impl Into<LocalizedText>>) -> Self {
+        self.lines.extend(lines.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn item(mut self, label: impl Into<LocalizedText>, action: A) -> Self {
+        self.items.push((label.into(), action));
+        self
+    }
+
+    /// Adds a button only when the condition holds
+    pub fn item_if(self, condition: bool, label: impl Into<LocalizedText>, action: A) -> Self {
+        if condition {
+            self.item(label, action)
+        } else {
+            self
+        }
+    }
+
+    /// Darkens everything underneath the menu and puts it on the given
+    /// layer, above the menus on lower ones
+    pub fn overlay(mut self, layer: i32) -> Self {
+        self.layer = Some(layer);
+        self
+    }
+
+    /// Spawns the menu with a marker component on its root node, returning
+    /// the root node
+    pub fn spawn(
+        self,
+        commands: &mut Commands,
+        theme: &CurrentTheme,
+        localization: &Localization,
+        marker: impl Component,
+    ) -> Entity {
+        let menu = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    position_type: PositionType::Absolute,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    gap: Size::height(Val::Px(theme.spacing("menu"))),
+                    ..default()
+                },
+                z_index: ZIndex::Global(self.layer.unwrap_or_default()),
+                ..default()
+            })
+            .insert(marker)
+            .id();
+        if self.layer.is_some() {
+            commands.entity(menu).insert((
+                BackgroundColor(theme.color("overlay")),
+                ThemedBackground("overlay"),
+            ));
+        }
+
+        let mut items = vec![];
+        commands.entity(menu).with_children(|parent| {
+            if let Some((title, style)) = self.title {
+                parent.spawn(localized_text(theme, localization, title, style));
+            }
+
+            if !self.lines.is_empty() {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            gap: Size::height(Val::Px(theme.spacing("menu_lines"))),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        for line in self.lines {
+                            parent.spawn(localized_text(theme, localization, line, LINE_TEXT));
+                        }
+                    });
+            }
+
+            for (label, action) in self.items {
+                items.push(spawn_menu_item(
+                    parent,
+                    menu,
+                    theme,
+                    localization,
+                    label,
+                    self.button_size,
+                    action,
+                ));
+            }
+        });
+        commands
+            .entity(menu)
+            .insert(Menu::new(self.layer.unwrap_or_default(), items));
+
+        menu
+    }
+}
+
+/// Spawns a button belonging to a menu, for menus laid out by hand
+///
+/// The button still needs to be added to the menu's `items`.
+pub fn spawn_menu_item<A: Component>(
+    parent: &mut ChildBuilder,
+    menu: Entity,
+    theme: &CurrentTheme,
+    localization: &Localization,
+    label: impl Into<LocalizedText>,
+    size: Vec2,
+    action: A,
+) -> Entity {
+    spawn_button(
+        parent,
+        theme,
+        localization,
+        label,
+        size,
+        (MenuItem { menu }, action),
+    )
+}
+
+/// Moves focus along by a number of steps, wrapping around at either end
+fn step_focus(focus: usize, len: usize, step: i32) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    (focus as i64 + step as i64).rem_euclid(len as i64) as usize
+}
+
+/// How far Up and Down on the arrow keys or the D-pad move focus this frame
+fn focus_step(keys: &Input<KeyCode>, gamepad_buttons: &Input<GamepadButton>) -> i32 {
+    axis_step(
+        keys,
+        gamepad_buttons,
+        [KeyCode::Up, KeyCode::Down],
+        [GamepadButtonType::DPadUp, GamepadButtonType::DPadDown],
+    )
+}
+
+/// How far Left and Right on the arrow keys or the D-pad were pressed this
+/// frame, for menus with buttons that change a value, like the settings
+pub fn side_step(keys: &Input<KeyCode>, gamepad_buttons: &Input<GamepadButton>) -> i32 {
+    axis_step(
+        keys,
+        gamepad_buttons,
+        [KeyCode::Left, KeyCode::Right],
+        [GamepadButtonType::DPadLeft, GamepadButtonType::DPadRight],
+    )
+}
+
+/// Counts the presses of the keys and buttons going back and forward along
+/// an axis this frame
+fn axis_step(
+    keys: &Input<KeyCode>,
+    gamepad_buttons: &Input<GamepadButton>,
+    [back_key, forward_key]: [KeyCode; 2],
+    [back_button, forward_button]: [GamepadButtonType; 2],
+) -> i32 {
+    let mut step = 0;
+    for key in keys.get_just_pressed() {
+        if *key == back_key {
+            step -= 1;
+        } else if *key == forward_key {
+            step += 1;
+        }
+    }
+    for button in gamepad_buttons.get_just_pressed() {
+        if button.button_type == back_button {
+            step -= 1;
+        } else if button.button_type == forward_button {
+            step += 1;
+        }
+    }
+    step
+}
+
+/// Whether Enter or the gamepad's south button was just pressed
+fn confirm_pressed(keys: &Input<KeyCode>, gamepad_buttons: &Input<GamepadButton>) -> bool {
+    keys.any_just_pressed([KeyCode::Return, KeyCode::NumpadEnter])
+        || gamepad_buttons
+            .get_just_pressed()
+            .any(|button| button.button_type == GamepadButtonType::South)
+}
+
+/// The menu on the highest layer, which is the one taking input
+fn top_menu<'a>(menus: impl IntoIterator<Item = (Entity, &'a Menu)>) -> Option<Entity> {
+    menus
+        .into_iter()
+        .max_by_key(|(_, menu)| menu.layer)
+        .map(|(entity, _)| entity)
+}
+
+/// Moves focus through the top menu with the arrow keys or the D-pad
+pub fn navigate_menus(
+    keys: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut menus: Query<(Entity, &mut Menu)>,
+) {
+    let step = focus_step(&keys, &gamepad_buttons);
+    if step == 0 {
+        return;
+    }
+
+    let Some(top) = top_menu(menus.iter()) else {
+        return;
+    };
+    if let Ok((_, mut menu)) = menus.get_mut(top) {
+        menu.focus = step_focus(menu.focus, menu.items.len(), step);
+    }
+}
+
+/// Moves focus to the button the mouse is over
+pub fn sync_hover_focus(
+    item_query: Query<(Entity, &MenuItem, &Interaction), Changed<Interaction>>,
+    mut menus: Query<&mut Menu>,
+) {
+    for (entity, item, interaction) in &item_query {
+        if *interaction == Interaction::None {
+            continue;
+        }
+        let Ok(mut menu) = menus.get_mut(item.menu) else {
+            continue;
+        };
+        if let Some(index) = menu.items.iter().position(|item| *item == entity) {
+            menu.focus = index;
+        }
+    }
+}
+
+/// Highlights the focused button of every menu, and recolors every button
+/// when the theme changes
+pub fn show_menu_focus(
+    theme: Res<CurrentTheme>,
+    menus: Query<Ref<Menu>>,
+    mut item_query: Query<&mut BackgroundColor, With<MenuItem>>,
+) {
+    for menu in &menus {
+        if !menu.is_changed() && !theme.is_changed() {
+            continue;
+        }
+
+        for (index, item) in menu.items.iter().enumerate() {
+            if let Ok(mut color) = item_query.get_mut(*item) {
+                *color = if index == menu.focus {
+                    theme.color("button_focus").into()
+                } else {
+                    theme.color("button").into()
+                };
+            }
+        }
+    }
+}
+
+/// Sends a [MenuActionEvent] when a button of the top menu is clicked, or
+/// picked with Enter or the gamepad's south button while it has focus
+pub fn activate_menu_items<A: Component + Copy>(
+    keys: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    menus: Query<(Entity, &Menu)>,
+    clicked_query: Query<(&MenuItem, &Interaction, &A), Changed<Interaction>>,
+    action_query: Query<&A, With<MenuItem>>,
+    mut action_events: EventWriter<MenuActionEvent<A>>,
+) {
+    let Some(top) = top_menu(menus.iter()) else {
+        return;
+    };
+
+    for (item, interaction, action) in &clicked_query {
+        if item.menu == top && *interaction == Interaction::Clicked {
+            action_events.send(MenuActionEvent { action: *action });
+        }
+    }
+
+    if confirm_pressed(&keys, &gamepad_buttons) {
+        let focused = menus.get(top).ok().and_then(|(_, menu)| menu.focused());
+        if let Some(action) = focused.and_then(|item| action_query.get(item).ok()) {
+            action_events.send(MenuActionEvent { action: *action });
+        }
+    }
+}
+
+/// Registers everything a kind of menu action needs
+struct MenuActionsPlugin<A>(PhantomData<A>);
+
+impl<A: Component + Copy> Plugin for MenuActionsPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MenuActionEvent<A>>();
+        app.add_system(activate_menu_items::<A>.in_set(MenuSet));
+    }
+}
+
+/// Adds menu actions to the app
+pub trait MenuAppExt {
+    /// Lets buttons with the action `A` send [MenuActionEvent]s
+    fn add_menu_actions<A: Component + Copy>(&mut self) -> &mut Self;
+}
+
+impl MenuAppExt for App {
+    fn add_menu_actions<A: Component + Copy>(&mut self) -> &mut Self {
+        self.add_plugin(MenuActionsPlugin::<A>(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn focus_wraps_around() {
+        assert_eq!(step_focus(0, 3, 1), 1);
+        assert_eq!(step_focus(2, 3, 1), 0);
+        assert_eq!(step_focus(0, 3, -1), 2);
+        assert_eq!(step_focus(1, 3, -4), 0);
+        assert_eq!(step_focus(0, 0, 1), 0);
+    }
+
+    #[test]
+    fn arrows_and_the_dpad_move_focus() {
+        let mut keys = Input::<KeyCode>::default();
+        let mut gamepad_buttons = Input::<GamepadButton>::default();
+        assert_eq!(focus_step(&keys, &gamepad_buttons), 0);
+
+        keys.press(KeyCode::Down);
+        assert_eq!(focus_step(&keys, &gamepad_buttons), 1);
+        keys.clear();
+
+        let gamepad = Gamepad::new(0);
+        gamepad_buttons.press(GamepadButton::new(gamepad, GamepadButtonType::DPadUp));
+        assert_eq!(focus_step(&keys, &gamepad_buttons), -1);
+        assert!(!confirm_pressed(&keys, &gamepad_buttons));
+
+        gamepad_buttons.press(GamepadButton::new(gamepad, GamepadButtonType::South));
+        assert!(confirm_pressed(&keys, &gamepad_buttons));
+    }
+
+    #[test]
+    fn only_the_top_menu_takes_input() {
+        let mut world = World::new();
+        let start_screen = world.spawn_empty().id();
+        let overlay = world.spawn_empty().id();
+        let menus = [
+            (start_screen, Menu::new(0, vec![])),
+            (overlay, Menu::new(20, vec![])),
+        ];
+
+        assert_eq!(
+            top_menu(menus.iter().map(|(entity, menu)| (*entity, menu))),
+            Some(overlay)
+        );
+        assert_eq!(top_menu([]), None);
+    }
+}
@@ -0,0 +1,277 @@
+//! Defines the screens shown when a game ends, either in a game over or a
+//! victory
+//!
+//! Both show the stats of the run along with buttons to try again or head
+//! back to the title screen. The game is torn down when leaving
+//! [InGame](GameMenuState::InGame), so nothing from the run is left by the
+//! time either screen shows.
+
+use super::{
+    builder::{MenuActionEvent, MenuBuilder},
+    state::GameMenuState,
+    util::start_game,
+};
+use crate::{
+    enemy::{
+        components::{Enemy, SpawnedEnemy},
+        resources::ENEMY_OBJECT_CLASS,
+    },
+    health::{components::Dead, events::DeathEvent},
+    inventory::events::ItemPickedUpEvent,
+    locale::{components::LocalizedText, resources::Localization},
+    map::{
+        objects::MapObject,
+        state::{MapReadinessState, MapState},
+    },
+    player::Player,
+    quest::events::QuestCompletedEvent,
+    theme::{components::ThemedText, resources::CurrentTheme},
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const END_BUTTON_SIZE: Vec2 = Vec2::new(320.0, 65.0);
+const GAME_OVER_TEXT: ThemedText = ThemedText::new("heading", "title", "danger");
+const VICTORY_TEXT: ThemedText = ThemedText::new("heading", "title", "accent");
+
+/// Marks the root node of the game over and victory screens
+#[derive(Debug, Component)]
+pub struct EndScreenNode;
+
+/// The buttons of the game over and victory screens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum EndButton {
+    /// Starts a new game
+    Retry,
+    Title,
+}
+
+/// What happened during the current run
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Resource)]
+pub struct RunStats {
+    /// How long the run went on for while not paused, in seconds
+    pub play_time: f32,
+    pub enemies_defeated: u32,
+    pub deaths: u32,
+    pub items_collected: u32,
+    pub quests_completed: u32,
+    /// The depth of the deepest map reached
+    pub deepest: u32,
+}
+
+impl RunStats {
+    /// The stats as lines to show on screen
+    pub fn lines(&self) -> Vec<LocalizedText> {
+        vec![
+            LocalizedText::new("stats-time").with_arg("time", format_play_time(self.play_time)),
+            LocalizedText::new("stats-enemies").with_arg("count", self.enemies_defeated),
+            LocalizedText::new("stats-deaths").with_arg("count", self.deaths),
+            LocalizedText::new("stats-items").with_arg("count", self.items_collected),
+            LocalizedText::new("stats-quests").with_arg("count", self.quests_completed),
+            LocalizedText::new("stats-deepest").with_arg("depth", self.deepest),
+        ]
+    }
+}
+
+/// Formats a number of seconds like `1:02:03`, leaving out the hours when
+/// there are none
+fn format_play_time(seconds: f32) -> String {
+    let seconds = seconds.max(0.0) as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// Starts counting the stats of a new run
+pub fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+/// Keeps track of what happens during the run
+#[allow(clippy::too_many_arguments)]
+pub fn track_run_stats(
+    time: Res<Time>,
+    map_state: Res<MapState>,
+    mut stats: ResMut<RunStats>,
+    mut death_events: EventReader<DeathEvent>,
+    mut pickup_events: EventReader<ItemPickedUpEvent>,
+    mut quest_events: EventReader<QuestCompletedEvent>,
+    enemy_query: Query<(), With<Enemy>>,
+    player_query: Query<(), With<Player>>,
+) {
+    stats.play_time += time.delta_seconds();
+    stats.deepest = stats.deepest.max(map_state.depth);
+
+    for event in death_events.iter() {
+        if enemy_query.contains(event.entity) {
+            // Enemies killed by anything else, like traps, weren't defeated
+            // by the player
+            if event
+                .killer
+                .is_some_and(|killer| player_query.contains(killer))
+            {
+                stats.enemies_defeated += 1;
+            }
+        } else if player_query.contains(event.entity) {
+            stats.deaths += 1;
+        }
+    }
+    for event in pickup_events.iter() {
+        if player_query.contains(event.entity) {
+            stats.items_collected += event.count;
+        }
+    }
+    stats.quests_completed += quest_events.iter().count() as u32;
+}
+
+/// Wins the game once the final map is completed, which is when every enemy
+/// spawned from its map objects is dead
+///
+/// A final map without any enemies is completed as soon as it's reached.
+pub fn check_victory(
+    map_state: Res<MapState>,
+    object_query: Query<(&MapObject, Option<&SpawnedEnemy>)>,
+    alive_query: Query<(), (With<Enemy>, Without<Dead>)>,
+    mut next_state: ResMut<NextState<GameMenuState>>,
+) {
+    if !map_state.is_final {
+        return;
+    }
+
+    let completed = object_query
+        .iter()
+        .filter(|(object, _)| object.class == ENEMY_OBJECT_CLASS)
+        .all(|(_, spawned)| {
+            // Objects that haven't spawned their enemies yet aren't beaten
+            spawned.is_some_and(|spawned| {
+                spawned
+                    .0
+                    .iter()
+                    .all(|enemy| alive_query.get(*enemy).is_err())
+            })
+        });
+
+    if completed {
+        info!("The final map was completed");
+        next_state.set(GameMenuState::Victory);
+    }
+}
+
+/// Spawns an end screen with a title, the run's stats and its buttons
+fn spawn_end_screen(
+    commands: &mut Commands,
+    theme: &CurrentTheme,
+    localization: &Localization,
+    title: &'static str,
+    title_style: ThemedText,
+    stats: &RunStats,
+    retry_label: &'static str,
+) {
+    MenuBuilder::new(END_BUTTON_SIZE)
+        .title_style(title, title_style)
+        .lines(stats.lines())
+        .item(retry_label, EndButton::Retry)
+        .item("end-title-screen", EndButton::Title)
+        .spawn(commands, theme, localization, EndScreenNode);
+}
+
+/// Spawns the game over screen
+pub fn setup_game_over(
+    mut commands: Commands,
+    theme: Res<CurrentTheme>,
+    localization: Res<Localization>,
+    stats: Res<RunStats>,
+) {
+    spawn_end_screen(
+        &mut commands,
+        &theme,
+        &localization,
+        "game-over-title",
+        GAME_OVER_TEXT,
+        &stats,
+        "end-retry",
+    );
+}
+
+/// Spawns the victory screen
+pub fn setup_victory(
+    mut commands: Commands,
+    theme: Res<CurrentTheme>,
+    localization: Res<Localization>,
+    stats: Res<RunStats>,
+) {
+    spawn_end_screen(
+        &mut commands,
+        &theme,
+        &localization,
+        "victory-title",
+        VICTORY_TEXT,
+        &stats,
+        "end-play-again",
+    );
+}
+
+/// Despawns the game over or victory screen
+pub fn cleanup_end_screen(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<EndScreenNode>>,
+) {
+    screen_query.for_each(|node| commands.entity(node).despawn_recursive());
+}
+
+/// Starts a new game or heads back to the title screen
+pub fn update_end_buttons(
+    mut commands: Commands,
+    mut action_events: EventReader<MenuActionEvent<EndButton>>,
+    mut map_state: ResMut<MapState>,
+    mut next_map_readiness: ResMut<NextState<MapReadinessState>>,
+    mut next_state: ResMut<NextState<GameMenuState>>,
+    asset_server: Res<AssetServer>,
+) {
+    for event in action_events.iter() {
+        match event.action {
+            // The story was already told, so retrying skips the intro
+            EndButton::Retry => {
+                next_map_readiness.set(MapReadinessState::Loading);
+                next_state.set(GameMenuState::InGame);
+                start_game(&mut commands, &mut map_state, &asset_server);
+            }
+            EndButton::Title => next_state.set(GameMenuState::StartScreen),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::locale::resources::test::english;
+
+    #[test]
+    fn stats_are_readable() {
+        assert_eq!(format_play_time(0.0), "0:00");
+        assert_eq!(format_play_time(65.9), "1:05");
+        assert_eq!(format_play_time(3723.0), "1:02:03");
+
+        let stats = RunStats {
+            play_time: 125.0,
+            enemies_defeated: 12,
+            deaths: 2,
+            deepest: 3,
+            ..default()
+        };
+        let localization = english();
+        let lines: Vec<String> = stats
+            .lines()
+            .iter()
+            .map(|line| localization.format(line))
+            .collect();
+        assert_eq!(lines[0], "Time: 2:05");
+        assert_eq!(lines[1], "Enemies defeated: 12");
+        assert_eq!(lines[2], "Deaths: 2");
+        assert_eq!(lines[5], "Deepest level: 3");
+    }
+}
@@ -1,4 +1,8 @@
+pub mod builder;
+pub mod ending;
+pub mod pause;
 pub mod plugin;
+pub mod resources;
 pub mod state;
 pub mod systems;
 pub mod util;
@@ -0,0 +1,203 @@
+//! Defines the pause menu, which freezes the game while it's open
+//!
+//! Escape or the gamepad's start button opens it during gameplay. From there
+//! the player can resume, save, load another game, head back to the title
+//! screen or quit the game.
+
+use super::{
+    builder::{MenuActionEvent, MenuBuilder},
+    state::{GameMenuState, PauseState},
+};
+use crate::{
+    general::resources::GameFlags,
+    locale::resources::Localization,
+    map::state::{MapReadinessState, MapState},
+    player::Player,
+    save::state::SlotMenuState,
+    settings::state::SettingsMenuState,
+    theme::resources::CurrentTheme,
+};
+use bevy::{app::AppExit, prelude::*};
+use bevy_rapier2d::prelude::*;
+
+const PAUSE_BUTTON_SIZE: Vec2 = Vec2::new(480.0, 65.0);
+
+/// Marks the root node of the pause menu
+#[derive(Debug, Component)]
+pub struct PauseMenuNode;
+
+/// Marks the root node of the quit confirmation
+#[derive(Debug, Component)]
+pub struct ConfirmQuitNode;
+
+/// The buttons of the pause menu and the quit confirmation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum PauseButton {
+    Resume,
+    Save,
+    Load,
+    Settings,
+    Title,
+    Quit,
+    ConfirmQuit,
+    CancelQuit,
+}
+
+/// Whether the pause key or the gamepad's start button was just pressed
+fn pause_pressed(keys: &Input<KeyCode>, gamepad_buttons: &Input<GamepadButton>) -> bool {
+    keys.just_pressed(KeyCode::Escape)
+        || gamepad_buttons
+            .get_just_pressed()
+            .any(|button| button.button_type == GamepadButtonType::Start)
+}
+
+/// Opens and closes the pause menu, backing out of the quit confirmation
+/// first if it's open
+pub fn toggle_pause(
+    keys: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    if !pause_pressed(&keys, &gamepad_buttons) {
+        return;
+    }
+
+    next_pause_state.set(match pause_state.0 {
+        PauseState::Running => PauseState::Paused,
+        PauseState::Paused => PauseState::Running,
+        PauseState::ConfirmQuit => PauseState::Paused,
+    });
+}
+
+/// Stops time and the physics simulation so nothing moves while paused
+pub fn freeze_game(mut time: ResMut<Time>, mut rapier_config: ResMut<RapierConfiguration>) {
+    debug!("Pausing the game");
+    time.pause();
+    rapier_config.physics_pipeline_active = false;
+}
+
+/// Starts time and the physics simulation back up
+pub fn unfreeze_game(mut time: ResMut<Time>, mut rapier_config: ResMut<RapierConfiguration>) {
+    if time.is_paused() {
+        debug!("Resuming the game");
+    }
+    time.unpause();
+    rapier_config.physics_pipeline_active = true;
+}
+
+/// Spawns the pause menu
+pub fn setup_pause_menu(
+    mut commands: Commands,
+    theme: Res<CurrentTheme>,
+    localization: Res<Localization>,
+) {
+    MenuBuilder::new(PAUSE_BUTTON_SIZE)
+        .title("pause-title")
+        .overlay(10)
+        .item("pause-resume", PauseButton::Resume)
+        .item("pause-save", PauseButton::Save)
+        .item("pause-load", PauseButton::Load)
+        .item("menu-settings", PauseButton::Settings)
+        .item("pause-title-screen", PauseButton::Title)
+        .item("menu-quit", PauseButton::Quit)
+        .spawn(&mut commands, &theme, &localization, PauseMenuNode);
+}
+
+/// Despawns the pause menu
+pub fn cleanup_pause_menu(mut commands: Commands, menu_query: Query<Entity, With<PauseMenuNode>>) {
+    menu_query.for_each(|node| commands.entity(node).despawn_recursive());
+}
+
+/// Spawns the quit confirmation
+pub fn setup_confirm_quit(
+    mut commands: Commands,
+    theme: Res<CurrentTheme>,
+    localization: Res<Localization>,
+) {
+    MenuBuilder::new(PAUSE_BUTTON_SIZE)
+        .title("confirm-quit-title")
+        .overlay(10)
+        .item("menu-quit", PauseButton::ConfirmQuit)
+        .item("menu-back", PauseButton::CancelQuit)
+        .spawn(&mut commands, &theme, &localization, ConfirmQuitNode);
+}
+
+/// Despawns the quit confirmation
+pub fn cleanup_confirm_quit(
+    mut commands: Commands,
+    confirm_query: Query<Entity, With<ConfirmQuitNode>>,
+) {
+    confirm_query.for_each(|node| commands.entity(node).despawn_recursive());
+}
+
+/// Does whatever the picked pause menu button does
+pub fn update_pause_buttons(
+    mut action_events: EventReader<MenuActionEvent<PauseButton>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+    mut next_state: ResMut<NextState<GameMenuState>>,
+    mut next_settings_state: ResMut<NextState<SettingsMenuState>>,
+    mut next_slot_state: ResMut<NextState<SlotMenuState>>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    for event in action_events.iter() {
+        match event.action {
+            PauseButton::Resume => next_pause_state.set(PauseState::Running),
+            PauseButton::Save => next_slot_state.set(SlotMenuState::Save),
+            PauseButton::Load => next_slot_state.set(SlotMenuState::Load),
+            PauseButton::Settings => next_settings_state.set(SettingsMenuState::Open),
+            PauseButton::Title => {
+                next_pause_state.set(PauseState::Running);
+                next_state.set(GameMenuState::StartScreen);
+            }
+            PauseButton::Quit => next_pause_state.set(PauseState::ConfirmQuit),
+            PauseButton::ConfirmQuit => exit_events.send(AppExit),
+            PauseButton::CancelQuit => next_pause_state.set(PauseState::Paused),
+        }
+    }
+}
+
+/// Throws away the game in progress once gameplay ends, unloading the map
+/// and despawning the player so the next game starts clean
+pub fn end_game(
+    mut commands: Commands,
+    mut flags: ResMut<GameFlags>,
+    mut map_state: ResMut<MapState>,
+    mut next_map_readiness: ResMut<NextState<MapReadinessState>>,
+    player_query: Query<Entity, With<Player>>,
+) {
+    info!("Ending the game");
+    player_query.for_each(|player| commands.entity(player).despawn_recursive());
+
+    *flags = GameFlags::default();
+    map_state.next_map = None;
+    next_map_readiness.set(MapReadinessState::Unloading);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escape_and_start_pause() {
+        let mut keys = Input::<KeyCode>::default();
+        let mut gamepad_buttons = Input::<GamepadButton>::default();
+        assert!(!pause_pressed(&keys, &gamepad_buttons));
+
+        keys.press(KeyCode::Escape);
+        assert!(pause_pressed(&keys, &gamepad_buttons));
+        keys.clear();
+        assert!(!pause_pressed(&keys, &gamepad_buttons));
+
+        gamepad_buttons.press(GamepadButton::new(
+            Gamepad::new(1),
+            GamepadButtonType::South,
+        ));
+        assert!(!pause_pressed(&keys, &gamepad_buttons));
+        gamepad_buttons.press(GamepadButton::new(
+            Gamepad::new(1),
+            GamepadButtonType::Start,
+        ));
+        assert!(pause_pressed(&keys, &gamepad_buttons));
+    }
+}
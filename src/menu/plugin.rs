@@ -1,10 +1,23 @@
 use super::{
-    state::GameMenuState,
+    builder::{navigate_menus, show_menu_focus, sync_hover_focus, MenuAppExt, MenuSet},
+    ending::{
+        check_victory, cleanup_end_screen, reset_run_stats, setup_game_over, setup_victory,
+        track_run_stats, update_end_buttons, EndButton, RunStats,
+    },
+    pause::{
+        cleanup_confirm_quit, cleanup_pause_menu, end_game, freeze_game, setup_confirm_quit,
+        setup_pause_menu, toggle_pause, unfreeze_game, update_pause_buttons, PauseButton,
+    },
+    resources::LoadingAssets,
+    state::{GameMenuState, PauseState},
     systems::{
-        cleanup_start_screen, setup_start_screen, update_button_hover_state, update_quit_button,
-        update_start_button,
+        cleanup_start_screen, finish_loading, setup_start_screen, update_start_screen, StartButton,
     },
 };
+use crate::{
+    map::state::MapReadinessState, save::state::SlotMenuState, settings::state::SettingsMenuState,
+    theme::systems::apply_ui_theme,
+};
 use bevy::prelude::*;
 
 #[derive(Debug, Default)]
@@ -14,17 +27,63 @@ impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         // Add the basic game state
         app.add_state::<GameMenuState>();
+        app.add_state::<PauseState>();
+
+        // Gameplay only runs while the game isn't paused
+        app.configure_set(OnUpdate(GameMenuState::InGame).run_if(in_state(PauseState::Running)));
+
+        // Wait for the assets the menus need
+        app.init_resource::<LoadingAssets>();
+        app.add_system(finish_loading.in_set(OnUpdate(GameMenuState::Loading)));
 
         // Add the setup system
         app.add_system(setup_start_screen.in_schedule(OnEnter(GameMenuState::StartScreen)));
 
-        // Add the cleanup system
+        // Add the cleanup systems
         app.add_system(cleanup_start_screen.in_schedule(OnExit(GameMenuState::StartScreen)));
+        app.add_system(end_game.in_schedule(OnExit(GameMenuState::InGame)));
+
+        // Add the menu input systems
+        app.add_systems((sync_hover_focus, navigate_menus).chain().in_set(MenuSet));
+        // Focus colors come from the theme, so recolor straight after it changes
+        app.add_system(show_menu_focus.after(MenuSet).after(apply_ui_theme));
+        app.add_menu_actions::<StartButton>();
+        app.add_menu_actions::<PauseButton>();
+        app.add_menu_actions::<EndButton>();
 
         // Add the various update systems
-        app.add_system(update_button_hover_state.in_set(OnUpdate(GameMenuState::StartScreen)));
-        app.add_system(update_start_button.in_set(OnUpdate(GameMenuState::StartScreen)));
-        app.add_system(update_quit_button.in_set(OnUpdate(GameMenuState::StartScreen)));
+        app.add_system(update_start_screen);
+
+        // Add the pause menu, which Escape doesn't toggle while the settings
+        // screen or the save slots are open over it
+        app.add_system(
+            toggle_pause
+                .run_if(in_state(GameMenuState::InGame))
+                .run_if(in_state(SettingsMenuState::Closed))
+                .run_if(in_state(SlotMenuState::Closed)),
+        );
+        app.add_system(update_pause_buttons);
+        app.add_system(freeze_game.in_schedule(OnExit(PauseState::Running)));
+        app.add_system(unfreeze_game.in_schedule(OnEnter(PauseState::Running)));
+        app.add_system(setup_pause_menu.in_schedule(OnEnter(PauseState::Paused)));
+        app.add_system(cleanup_pause_menu.in_schedule(OnExit(PauseState::Paused)));
+        app.add_system(setup_confirm_quit.in_schedule(OnEnter(PauseState::ConfirmQuit)));
+        app.add_system(cleanup_confirm_quit.in_schedule(OnExit(PauseState::ConfirmQuit)));
+
+        // Add the game over and victory screens
+        app.init_resource::<RunStats>();
+        app.add_system(reset_run_stats.in_schedule(OnEnter(GameMenuState::InGame)));
+        app.add_system(track_run_stats.in_set(OnUpdate(GameMenuState::InGame)));
+        app.add_system(
+            check_victory
+                .run_if(in_state(MapReadinessState::Loaded))
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_system(setup_game_over.in_schedule(OnEnter(GameMenuState::GameOver)));
+        app.add_system(setup_victory.in_schedule(OnEnter(GameMenuState::Victory)));
+        app.add_system(cleanup_end_screen.in_schedule(OnExit(GameMenuState::GameOver)));
+        app.add_system(cleanup_end_screen.in_schedule(OnExit(GameMenuState::Victory)));
+        app.add_system(update_end_buttons);
         debug!("MenuPlugin loaded");
     }
 }
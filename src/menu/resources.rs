@@ -0,0 +1,8 @@
+use bevy::prelude::*;
+
+/// Assets the game waits on in [Loading](super::state::GameMenuState::Loading)
+/// before showing the start screen
+///
+/// Plugins add their handles at startup.
+#[derive(Debug, Default, Resource)]
+pub struct LoadingAssets(pub Vec<HandleUntyped>);
@@ -2,7 +2,28 @@ use bevy::prelude::*;
 
 #[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, States)]
 pub enum GameMenuState {
+    /// The UI theme is loading, before anything is shown
     #[default]
+    Loading,
     StartScreen,
+    /// The story intro plays while the map loads
+    Intro,
     InGame,
+    /// The player died with no lives or checkpoints left
+    GameOver,
+    /// The player completed the final map
+    Victory,
+}
+
+/// Whether gameplay is running or frozen behind the pause menu
+///
+/// Nothing in [OnUpdate(GameMenuState::InGame)](GameMenuState::InGame) runs
+/// unless the game is [Running](PauseState::Running).
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, States)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+    /// The pause menu is asking whether to really quit
+    ConfirmQuit,
 }
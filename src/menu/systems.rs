@@ -1,23 +1,34 @@
 //! Defines all of the systems used by the menu plugin
 
-use super::{state::GameMenuState, util::start_game};
-use crate::map::state::{MapReadinessState, MapState};
-use bevy::{app::AppExit, prelude::*};
-
-// Really helpful consts and macros
-const YELLOW: Color = Color::hsl(50.69, 0.9684, 0.5039);
-const BUTTON_COLOR: Color = YELLOW;
-const BUTTON_COLOR_HOVER: Color = Color::hsl(50.69, 0.9684, 0.45);
-const BUTTON_FONT: &str = "fonts/joystix monospace.otf";
-const BUTTON_FONT_SIZE: f32 = 30.0;
-
-/// A marker component for the start button
-#[derive(Debug, Component)]
-pub struct StartButton;
-
-/// A marker component for the quit button
-#[derive(Debug, Component)]
-pub struct QuitButton;
+use super::{
+    builder::{MenuActionEvent, MenuBuilder},
+    resources::LoadingAssets,
+    state::GameMenuState,
+    util::start_game,
+};
+use crate::{
+    locale::resources::Localization,
+    map::state::{MapReadinessState, MapState},
+    save::{events::LoadGameEvent, resources::SaveSlots, state::SlotMenuState},
+    settings::state::SettingsMenuState,
+    theme::{components::ThemedText, resources::CurrentTheme},
+};
+use bevy::{app::AppExit, asset::LoadState, prelude::*};
+
+const TITLE_TEXT: ThemedText = ThemedText::new("heading", "title", "accent");
+const START_BUTTON_SIZE: Vec2 = Vec2::new(260.0, 65.0);
+
+/// The buttons of the start screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum StartButton {
+    /// Continues the latest saved game
+    Continue,
+    Start,
+    /// Opens the save slots to load from
+    Load,
+    Settings,
+    Quit,
+}
 
 /// Marks a UI node as being a root node of the UI
 ///
@@ -26,104 +37,60 @@ pub struct QuitButton;
 #[derive(Debug, Component)]
 pub struct MenuRootNode;
 
-/// Spawns in the UI and is responsible for init of anything the menu needs
-pub fn setup_start_screen(mut commands: Commands, asset_server: Res<AssetServer>) {
-    debug!("Setting up start screen");
-
-    // Create a basic UI
+/// Shows the start screen once every asset it waits on has loaded or failed
+/// to load
+pub fn finish_loading(
+    loading: Res<LoadingAssets>,
+    asset_server: Res<AssetServer>,
+    mut next_state: ResMut<NextState<GameMenuState>>,
+) {
+    let states = loading
+        .0
+        .iter()
+        .map(|handle| (handle, asset_server.get_load_state(handle)));
+    let mut failed = vec![];
+    for (handle, state) in states {
+        match state {
+            LoadState::Loaded => {}
+            LoadState::Failed => failed.push(handle),
+            _ => return,
+        }
+    }
 
-    // Title
-    commands
-        .spawn(NodeBundle {
-            style: Style {
-                size: Size::width(Val::Percent(100.0)),
-                position_type: PositionType::Absolute,
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                flex_direction: FlexDirection::Column,
-                margin: UiRect {
-                    top: Val::Percent(5.0),
-                    ..default()
-                },
-                ..default()
-            },
-            ..default()
-        })
-        .insert(MenuRootNode)
-        .with_children(|parent| {
-            parent.spawn(TextBundle::from_section(
-                "The Caverns",
-                TextStyle {
-                    font: asset_server.load(BUTTON_FONT),
-                    font_size: 80.0,
-                    color: YELLOW,
-                },
-            ));
-        });
+    for handle in failed {
+        error!(
+            "Couldn't load {:?}",
+            asset_server
+                .get_handle_path(handle)
+                .map(|path| path.path().to_owned())
+        );
+    }
+    next_state.set(GameMenuState::StartScreen);
+}
 
-    // Buttons
-    commands
-        .spawn(NodeBundle {
-            style: Style {
-                size: Size::width(Val::Percent(100.0)),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                flex_direction: FlexDirection::Column,
-                gap: Size::new(Val::Px(0.0), Val::Percent(5.0)),
-                ..default()
-            },
-            ..default()
-        })
-        .insert(MenuRootNode)
-        .with_children(|parent| {
-            // Start button
-            parent
-                .spawn(ButtonBundle {
-                    style: Style {
-                        size: Size::new(Val::Px(150.0), Val::Px(65.0)),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        ..default()
-                    },
-                    background_color: BUTTON_COLOR.into(),
-                    ..default()
-                })
-                .insert(StartButton)
-                .with_children(|parent| {
-                    parent.spawn(TextBundle::from_section(
-                        "Start",
-                        TextStyle {
-                            font: asset_server.load(BUTTON_FONT),
-                            font_size: BUTTON_FONT_SIZE,
-                            color: Color::BLACK,
-                        },
-                    ));
-                });
+/// Spawns in the UI and is responsible for init of anything the menu needs
+///
+/// The continue button only shows when there is a saved game to continue.
+pub fn setup_start_screen(
+    mut commands: Commands,
+    theme: Res<CurrentTheme>,
+    localization: Res<Localization>,
+    save_slots: Res<SaveSlots>,
+) {
+    debug!("Setting up start screen");
 
-            // Quit button
-            parent
-                .spawn(ButtonBundle {
-                    style: Style {
-                        size: Size::new(Val::Px(150.0), Val::Px(65.0)),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        ..default()
-                    },
-                    background_color: BUTTON_COLOR.into(),
-                    ..default()
-                })
-                .insert(QuitButton)
-                .with_children(|parent| {
-                    parent.spawn(TextBundle::from_section(
-                        "Quit",
-                        TextStyle {
-                            font: asset_server.load("fonts/joystix monospace.otf"),
-                            font_size: BUTTON_FONT_SIZE,
-                            color: Color::BLACK,
-                        },
-                    ));
-                });
-        });
+    MenuBuilder::new(START_BUTTON_SIZE)
+        .title_style("game-title", TITLE_TEXT)
+        .item_if(
+            save_slots.latest().is_some(),
+            "menu-continue",
+            StartButton::Continue,
+        )
+        .item("menu-start", StartButton::Start)
+        .item("menu-load", StartButton::Load)
+        .item("menu-settings", StartButton::Settings)
+        .item("menu-quit", StartButton::Quit)
+        .spawn(&mut commands, &theme, &localization, MenuRootNode);
 
     debug!("Start screen setup is complete");
 }
@@ -141,65 +108,48 @@ pub fn cleanup_start_screen(
     });
 }
 
-/// Adds hover effects to buttons
-#[allow(clippy::type_complexity)]
-pub fn update_button_hover_state(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor),
-        (Changed<Interaction>, With<Button>),
-    >,
-) {
-    for (interaction, mut color) in &mut interaction_query {
-        match *interaction {
-            Interaction::Hovered => {
-                *color = BUTTON_COLOR_HOVER.into();
-            }
-            Interaction::None => {
-                *color = BUTTON_COLOR.into();
-            }
-            _ => {}
-        }
-    }
-}
-
-/// Adds specific functionality to the start button
+/// Does whatever the picked start screen button does
 ///
-/// The system will detect when the start button has been clicked and will do 3
-/// things:
-/// 1. Switch the game's menu state to [InGame](GameMenuState::InGame)
+/// Starting a game does 3 things:
+/// 1. Switch the game's menu state to [Intro](GameMenuState::Intro), which
+///    plays the story intro before moving on to gameplay
 /// 2. Starts loading in the map by setting the readiness state to
 ///    [Loading](MapReadinessState::Loading)
 /// 3. Calls the [start_game](start_game) utility function to actually start the game.
-pub fn update_start_button(
+#[allow(clippy::too_many_arguments)]
+pub fn update_start_screen(
     mut commands: Commands,
-    mut interaction_query: Query<&Interaction, (Changed<Interaction>, With<StartButton>)>,
+    mut action_events: EventReader<MenuActionEvent<StartButton>>,
     mut map_state: ResMut<MapState>,
     mut next_map_state: ResMut<NextState<MapReadinessState>>,
     mut next_state: ResMut<NextState<GameMenuState>>,
+    mut next_settings_state: ResMut<NextState<SettingsMenuState>>,
+    mut next_slot_state: ResMut<NextState<SlotMenuState>>,
+    mut load_events: EventWriter<LoadGameEvent>,
+    mut exit_events: EventWriter<AppExit>,
+    save_slots: Res<SaveSlots>,
     asset_server: Res<AssetServer>,
 ) {
-    for interaction in &mut interaction_query {
-        if *interaction == Interaction::Clicked {
-            // Set the menu's state
-            next_state.set(GameMenuState::InGame);
-
-            // Start loading the map
-            next_map_state.set(MapReadinessState::Loading);
+    for event in action_events.iter() {
+        match event.action {
+            StartButton::Continue => {
+                if let Some(slot) = save_slots.latest() {
+                    load_events.send(LoadGameEvent { slot });
+                }
+            }
+            StartButton::Start => {
+                // Set the menu's state
+                next_state.set(GameMenuState::Intro);
 
-            // Start the game
-            start_game(&mut commands, &mut map_state, &asset_server);
-        }
-    }
-}
+                // Start loading the map
+                next_map_state.set(MapReadinessState::Loading);
 
-/// Adds functionality to the quit button
-pub fn update_quit_button(
-    mut interaction_query: Query<&Interaction, (Changed<Interaction>, With<QuitButton>)>,
-    mut exit_event: EventWriter<AppExit>,
-) {
-    for interaction in &mut interaction_query {
-        if *interaction == Interaction::Clicked {
-            exit_event.send(AppExit);
+                // Start the game
+                start_game(&mut commands, &mut map_state, &asset_server);
+            }
+            StartButton::Load => next_slot_state.set(SlotMenuState::Load),
+            StartButton::Settings => next_settings_state.set(SettingsMenuState::Open),
+            StartButton::Quit => exit_events.send(AppExit),
         }
     }
 }
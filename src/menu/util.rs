@@ -1,11 +1,66 @@
-use crate::{map::state::MapState, player::spawn_player};
+use crate::{
+    locale::{components::LocalizedText, resources::Localization},
+    map::state::MapState,
+    player::spawn_player,
+    theme::{components::ThemedText, resources::CurrentTheme},
+};
 use bevy::prelude::*;
 
+const BUTTON_TEXT: ThemedText = ThemedText::new("heading", "button", "button_text");
+
 pub fn start_game(
     commands: &mut Commands,
     map_state: &mut ResMut<MapState>,
     asset_server: &Res<AssetServer>,
 ) {
     map_state.handle = asset_server.load("tiled/test.tmx");
-    spawn_player(commands);
+    spawn_player(commands, asset_server);
+}
+
+/// Themed text in the player's language, which keeps up with changes to
+/// either
+pub fn localized_text(
+    theme: &CurrentTheme,
+    localization: &Localization,
+    text: impl Into<LocalizedText>,
+    style: ThemedText,
+) -> (TextBundle, ThemedText, LocalizedText) {
+    let text = text.into();
+    let (bundle, style) = theme.text(localization.format(&text), style);
+    (bundle, style, text)
+}
+
+/// Spawns a menu button with a label and components saying what it does,
+/// returning the button
+///
+/// The label's font size shrinks along with short buttons.
+pub fn spawn_button(
+    parent: &mut ChildBuilder,
+    theme: &CurrentTheme,
+    localization: &Localization,
+    label: impl Into<LocalizedText>,
+    size: Vec2,
+    marker: impl Bundle,
+) -> Entity {
+    parent
+        .spawn(ButtonBundle {
+            style: Style {
+                size: Size::new(Val::Px(size.x), Val::Px(size.y)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: theme.color("button").into(),
+            ..default()
+        })
+        .insert(marker)
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                theme,
+                localization,
+                label,
+                BUTTON_TEXT.with_max_size(size.y * 0.6),
+            ));
+        })
+        .id()
 }
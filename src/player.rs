@@ -1,32 +1,77 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use crate::constants::{DRAW_LAYER, KEYMAP};
+use crate::animation::{asset::AnimationState, components::SpriteAnimator};
+use crate::camera::components::{CameraController, CameraTarget, GameCamera};
+use crate::combat::components::EquippedWeapon;
 use crate::general::components::Speed;
+use crate::health::components::{Dead, Health, InvulnerabilityOnHit, Staggered, Team};
+use crate::inventory::components::Inventory;
+use crate::map::state::MapState;
+use crate::projectile::components::EquippedProjectile;
+use crate::{
+    constants::{COLLISION_GROUP, DRAW_LAYER},
+    settings::resources::Settings,
+};
 
 pub const PLAYER_SPEED: f32 = 200.0;
+pub const PLAYER_HEALTH: f32 = 100.0;
+pub const PLAYER_INVENTORY_SIZE: usize = 20;
+pub const PLAYER_WEAPON: &str = "pickaxe";
+pub const PLAYER_PROJECTILE: &str = "dagger";
+pub const PLAYER_ANIMATIONS: &str = "animations/player.anim.ron";
+/// How fast the player moves while dashing, in pixels per second
+pub const PLAYER_DASH_SPEED: f32 = 600.0;
+/// How long a dash lasts, in seconds
+pub const PLAYER_DASH_TIME: f32 = 0.24;
+/// How long after a dash starts the player can dash again, in seconds
+pub const PLAYER_DASH_COOLDOWN: f32 = 0.6;
 
 // Player component (marker)
 #[derive(Component, Default)]
 pub struct Player;
 
+/// Marks the player as dashing, which moves them on its own until the timer
+/// runs out
+#[derive(Debug, Component)]
+pub struct Dashing {
+    pub timer: Timer,
+}
+
+/// How soon the player can dash again
+#[derive(Debug, Component)]
+pub struct DashCooldown(pub Timer);
+
+impl Default for DashCooldown {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(PLAYER_DASH_COOLDOWN, TimerMode::Once);
+        // Ready to dash right away
+        timer.tick(timer.duration());
+        Self(timer)
+    }
+}
+
 #[derive(Bundle)]
 pub struct PlayerBundle {
     p: Player,
     #[bundle]
     sprite_bundle: SpriteSheetBundle,
+    animator: SpriteAnimator,
 }
 
-pub fn spawn_player(commands: &mut Commands) {
+pub fn spawn_player(commands: &mut Commands, asset_server: &Res<AssetServer>) {
     commands
-        .spawn(SpriteBundle {
-            sprite: Sprite {
-                color: Color::rgb(0.25, 0.25, 0.75),
-                custom_size: Some(Vec2::new(32.0, 32.0)),
+        .spawn(PlayerBundle {
+            p: Player,
+            sprite_bundle: SpriteSheetBundle {
+                sprite: TextureAtlasSprite {
+                    custom_size: Some(Vec2::new(32.0, 32.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(64., 64., DRAW_LAYER::ENTITIES)),
                 ..default()
             },
-            transform: Transform::from_translation(Vec3::new(64., 64., DRAW_LAYER::ENTITIES)),
-            ..default()
+            animator: SpriteAnimator::new(asset_server.load(PLAYER_ANIMATIONS)),
         })
         .insert(RigidBody::Dynamic)
         .insert(Velocity {
@@ -36,47 +81,144 @@ pub fn spawn_player(commands: &mut Commands) {
         .insert(GravityScale(0.0))
         .insert(Sleeping::disabled())
         .insert(Collider::cuboid(16.0, 16.0))
+        .insert(ActiveEvents::COLLISION_EVENTS)
+        .insert(SolverGroups::new(COLLISION_GROUP::CHARACTERS, Group::ALL))
         .insert(LockedAxes::ROTATION_LOCKED)
         .insert(Damping {
             linear_damping: 0.5,
             angular_damping: 1.0,
         })
         .insert(Speed(PLAYER_SPEED))
-        .insert(Player);
+        .insert(DashCooldown::default())
+        .insert(CameraTarget)
+        .insert(Health::new(PLAYER_HEALTH))
+        .insert(InvulnerabilityOnHit::default())
+        .insert(Team::Player)
+        .insert(Inventory::new(PLAYER_INVENTORY_SIZE))
+        .insert(EquippedWeapon(PLAYER_WEAPON.into()))
+        .insert(EquippedProjectile(PLAYER_PROJECTILE.into()));
 }
 
+#[allow(clippy::type_complexity)]
 pub fn player_movement(
     keys: Res<Input<KeyCode>>,
-    mut players: Query<(&Speed, &mut Velocity, &Transform), With<Player>>,
-    mut cameras: Query<&mut Transform, (With<Camera2d>, Without<Player>)>,
+    settings: Res<Settings>,
+    mut players: Query<
+        (&Speed, &mut Velocity),
+        (
+            With<Player>,
+            Without<Staggered>,
+            Without<Dead>,
+            Without<Dashing>,
+        ),
+    >,
 ) {
-    let mut camera_transform = cameras.single_mut();
-    for (speed, mut velocity, transform) in players.iter_mut() {
+    let bindings = &settings.key_bindings;
+    for (speed, mut velocity) in players.iter_mut() {
         // Start Moving
-        if keys.pressed(KEYMAP::FORWARD) {
+        if keys.pressed(bindings.forward) {
             velocity.linvel.y = speed.0;
         }
-        if keys.pressed(KEYMAP::BACKWARD) {
-            velocity.linvel.y = -1.0 * speed.0;
+        if keys.pressed(bindings.backward) {
+            velocity.linvel.y = -speed.0;
         }
-        if keys.pressed(KEYMAP::LEFT) {
-            velocity.linvel.x = -1.0 * speed.0;
+        if keys.pressed(bindings.left) {
+            velocity.linvel.x = -speed.0;
         }
-        if keys.pressed(KEYMAP::RIGHT) {
+        if keys.pressed(bindings.right) {
             velocity.linvel.x = speed.0;
         }
 
         // Stop moving
-        if keys.just_released(KEYMAP::FORWARD) || keys.just_released(KEYMAP::BACKWARD) {
+        if keys.just_released(bindings.forward) || keys.just_released(bindings.backward) {
             velocity.linvel.y = 0.0;
         }
 
-        if keys.just_released(KEYMAP::LEFT) || keys.just_released(KEYMAP::RIGHT) {
+        if keys.just_released(bindings.left) || keys.just_released(bindings.right) {
             velocity.linvel.x = 0.0;
         }
+    }
+}
+
+/// Sends the player dashing the way they're moving, or the way they face
+/// when standing still
+#[allow(clippy::type_complexity)]
+pub fn start_dash(
+    mut commands: Commands,
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    mut players: Query<
+        (
+            Entity,
+            &mut Velocity,
+            &mut SpriteAnimator,
+            &mut DashCooldown,
+        ),
+        (
+            With<Player>,
+            Without<Staggered>,
+            Without<Dead>,
+            Without<Dashing>,
+        ),
+    >,
+) {
+    for (entity, mut velocity, mut animator, mut cooldown) in &mut players {
+        cooldown.0.tick(time.delta());
+        if !cooldown.0.finished() || !keys.just_pressed(settings.key_bindings.dash) {
+            continue;
+        }
+
+        let direction = velocity
+            .linvel
+            .try_normalize()
+            .unwrap_or_else(|| animator.facing.as_vec2());
+        velocity.linvel = direction * PLAYER_DASH_SPEED;
+        animator.play_action(AnimationState::Dash);
+        cooldown.0.reset();
+        commands.entity(entity).insert(Dashing {
+            timer: Timer::from_seconds(PLAYER_DASH_TIME, TimerMode::Once),
+        });
+    }
+}
+
+/// Stops the player once their dash is over, handing movement back to the
+/// keys
+pub fn end_dash(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut players: Query<(Entity, &mut Dashing, &mut Velocity, Option<&Staggered>)>,
+) {
+    for (entity, mut dashing, mut velocity, staggered) in &mut players {
+        if !dashing.timer.tick(time.delta()).finished() {
+            continue;
+        }
+
+        // Knockback takes over the velocity from the dash
+        if staggered.is_none() {
+            velocity.linvel = Vec2::ZERO;
+        }
+        commands.entity(entity).remove::<Dashing>();
+    }
+}
+
+/// Moves the player to the spawn point of the map that was just loaded
+pub fn place_player_at_spawn(
+    map_state: Res<MapState>,
+    mut players: Query<&mut Transform, With<Player>>,
+    mut cameras: Query<&mut CameraController, With<GameCamera>>,
+) {
+    let Some(spawn_point) = map_state.spawn_point else {
+        warn!("The map has no player spawn point");
+        return;
+    };
+
+    for mut transform in &mut players {
+        transform.translation.x = spawn_point.x;
+        transform.translation.y = spawn_point.y;
+    }
 
-        // Move the camera to match the player's position
-        camera_transform.translation.y = transform.translation.y;
-        camera_transform.translation.x = transform.translation.x;
+    for mut controller in &mut cameras {
+        controller.snap_to(spawn_point);
     }
 }
@@ -0,0 +1,83 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The stats of a kind of projectile
+///
+/// Durations are in seconds and distances in pixels.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProjectileDefinition {
+    /// How fast the projectile flies, in pixels per second
+    pub speed: f32,
+    /// How long the projectile flies for before vanishing
+    pub lifetime: f32,
+    pub damage: f32,
+    /// How hard a hit pushes the target away, in pixels per second
+    pub knockback: f32,
+    /// How many targets the projectile passes through before stopping
+    #[serde(default)]
+    pub pierce: u32,
+    /// The radius of the projectile's collider
+    pub radius: f32,
+    /// The size of the projectile's sprite
+    pub size: (f32, f32),
+    /// The color of the projectile's sprite
+    pub color: (f32, f32, f32),
+    /// The item used up when throwing or firing this projectile
+    #[serde(default)]
+    pub ammo: Option<String>,
+}
+
+/// Asset holding every projectile, keyed by the projectile's id
+#[derive(TypeUuid, Debug, Default, Deserialize)]
+#[uuid = "8a2f3c1d-6b4e-4e0a-9a57-1f0c2d9e7b64"]
+pub struct ProjectileDatabase {
+    pub projectiles: HashMap<String, ProjectileDefinition>,
+}
+
+impl ProjectileDatabase {
+    pub fn get(&self, id: &str) -> Option<&ProjectileDefinition> {
+        self.projectiles.get(id)
+    }
+
+    /// The id of the projectile that uses up an item as its ammo, if any
+    pub fn fired_from(&self, item: &str) -> Option<&str> {
+        self.projectiles
+            .iter()
+            .find(|(_, definition)| definition.ammo.as_deref() == Some(item))
+            .map(|(id, _)| id.as_str())
+    }
+}
+
+#[derive(Default)]
+pub struct ProjectileDatabaseLoader;
+
+impl AssetLoader for ProjectileDatabaseLoader {
+    /// Load the projectile database
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext<'_>,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let database: ProjectileDatabase = ron::de::from_bytes(bytes)?;
+            debug!(
+                "Loaded {} projectile definitions",
+                database.projectiles.len()
+            );
+            load_context.set_default_asset(LoadedAsset::new(database));
+
+            Ok(())
+        })
+    }
+
+    /// Returns a list of supported extensions
+    fn extensions(&self) -> &[&str] {
+        &["projectiles.ron"]
+    }
+}
@@ -0,0 +1,76 @@
+use bevy::{prelude::*, utils::HashSet};
+
+/// The projectile an entity throws, by its id in the projectile database
+#[derive(Debug, Clone, Component)]
+pub struct EquippedProjectile(pub String);
+
+/// A projectile in flight
+///
+/// Pooled projectiles that aren't in use don't have this component.
+#[derive(Debug, Component)]
+pub struct Projectile {
+    /// The entity that fired the projectile, which it never hits
+    pub owner: Option<Entity>,
+    pub damage: f32,
+    pub knockback: f32,
+    /// How many more targets the projectile can pass through
+    pub pierce_remaining: u32,
+    /// Counts down until the projectile vanishes
+    pub lifetime: Timer,
+    /// Everything the projectile already hit
+    pub hit: HashSet<Entity>,
+}
+
+impl Projectile {
+    /// Counts a hit on a target, returning what the projectile does next
+    pub fn strike(&mut self, target: Entity) -> Strike {
+        if !self.hit.insert(target) {
+            Strike::Repeat
+        } else if self.pierce_remaining == 0 {
+            Strike::Stop
+        } else {
+            self.pierce_remaining -= 1;
+            Strike::Pierce
+        }
+    }
+}
+
+/// What a projectile does after hitting a target
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Strike {
+    /// The target was already hit, so it isn't hurt again
+    Repeat,
+    /// The target is hurt and the projectile flies on through it
+    Pierce,
+    /// The target is hurt and the projectile is used up
+    Stop,
+}
+
+/// Marks an entity as belonging to the projectile pool
+#[derive(Debug, Component)]
+pub struct PooledProjectile;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pierce_runs_out() {
+        let mut projectile = Projectile {
+            owner: None,
+            damage: 1.0,
+            knockback: 0.0,
+            pierce_remaining: 1,
+            lifetime: Timer::from_seconds(1.0, TimerMode::Once),
+            hit: HashSet::new(),
+        };
+        let first = Entity::from_raw(1);
+        let second = Entity::from_raw(2);
+
+        assert_eq!(projectile.strike(first), Strike::Pierce);
+        // Hitting the same target again doesn't use up any pierce
+        assert_eq!(projectile.strike(first), Strike::Repeat);
+        assert_eq!(projectile.pierce_remaining, 0);
+        assert_eq!(projectile.strike(second), Strike::Stop);
+    }
+}
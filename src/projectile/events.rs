@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+/// Requests that a projectile is fired
+#[derive(Debug, Clone)]
+pub struct FireProjectileEvent {
+    /// The id of the projectile's definition
+    pub kind: String,
+    /// The entity firing the projectile, which it never hits
+    pub owner: Option<Entity>,
+    /// Where the projectile starts, in world coordinates
+    pub position: Vec2,
+    /// The direction the projectile flies in
+    pub direction: Vec2,
+}
+
+/// Sent whenever a projectile hits something
+#[derive(Debug, Clone)]
+pub struct ProjectileImpactEvent {
+    /// The entity that fired the projectile
+    pub owner: Option<Entity>,
+    /// Where the projectile was when it hit
+    pub position: Vec2,
+}
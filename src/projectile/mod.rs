@@ -0,0 +1,18 @@
+//! Module for projectiles
+//!
+//! This module includes:
+//! - An asset holding the stats of every projectile, loaded from a RON file
+//! - Projectiles with a velocity, a lifetime and piercing rules
+//! - Continuous collision detection so fast projectiles can't skip through
+//!   thin walls
+//! - Throwing the projectile the player readied from their inventory
+//! - A pool of projectile entities that are reused instead of being spawned
+//!   and despawned every time
+//! - A plugin to setup the required systems, loaders and events
+
+pub mod asset;
+pub mod components;
+pub mod events;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
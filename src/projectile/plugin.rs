@@ -0,0 +1,49 @@
+use super::{
+    asset::{ProjectileDatabase, ProjectileDatabaseLoader},
+    events::{FireProjectileEvent, ProjectileImpactEvent},
+    resources::{ProjectileDatabaseHandle, ProjectilePool},
+    systems::{
+        equip_player_projectile, fire_projectiles, load_projectile_database,
+        release_all_projectiles, throw_player_projectile, update_projectiles, warm_projectile_pool,
+    },
+};
+use crate::{
+    dialogue::systems::dialogue_open, health::systems::apply_damage,
+    inventory::systems::toggle_inventory_panel, menu::state::GameMenuState,
+};
+use bevy::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<ProjectileDatabase>();
+        app.init_asset_loader::<ProjectileDatabaseLoader>();
+
+        app.add_event::<FireProjectileEvent>();
+        app.add_event::<ProjectileImpactEvent>();
+
+        app.init_resource::<ProjectileDatabaseHandle>();
+        app.init_resource::<ProjectilePool>();
+
+        app.add_startup_system(load_projectile_database);
+        app.add_startup_system(warm_projectile_pool);
+
+        app.add_system(
+            equip_player_projectile
+                .after(toggle_inventory_panel)
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_system(
+            throw_player_projectile
+                .after(toggle_inventory_panel)
+                .run_if(not(dialogue_open))
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_system(fire_projectiles.after(throw_player_projectile));
+        app.add_system(update_projectiles.before(apply_damage));
+        app.add_system(release_all_projectiles.in_schedule(OnExit(GameMenuState::InGame)));
+        debug!("ProjectilePlugin loaded");
+    }
+}
@@ -0,0 +1,73 @@
+use super::asset::ProjectileDatabase;
+use bevy::{prelude::*, utils::HashSet};
+
+/// Path to the file defining every projectile
+pub const PROJECTILE_DATABASE_PATH: &str = "data/core.projectiles.ron";
+
+/// How many projectiles are created up front when the game starts
+pub const PROJECTILE_POOL_WARMUP: usize = 64;
+
+/// Handle to the projectile database used by the game
+#[derive(Debug, Default, Resource)]
+pub struct ProjectileDatabaseHandle(pub Handle<ProjectileDatabase>);
+
+/// Projectile entities that are ready to be reused
+///
+/// Projectiles go back in here instead of being despawned. When the pool
+/// runs dry a new entity is spawned, so the pool grows to match the busiest
+/// fight.
+#[derive(Debug, Default, Resource)]
+pub struct ProjectilePool {
+    /// The free projectiles, the last one released at the end
+    free: Vec<Entity>,
+    /// The same projectiles as `free`, to quickly tell whether one is free
+    pooled: HashSet<Entity>,
+}
+
+impl ProjectilePool {
+    /// Takes a free projectile out of the pool, if there is one
+    pub fn take(&mut self) -> Option<Entity> {
+        let entity = self.free.pop()?;
+        self.pooled.remove(&entity);
+        Some(entity)
+    }
+
+    /// Puts a projectile back into the pool, returning false when it was
+    /// already in there
+    pub fn release(&mut self, entity: Entity) -> bool {
+        if !self.pooled.insert(entity) {
+            return false;
+        }
+        self.free.push(entity);
+        true
+    }
+
+    /// Whether a projectile is in the pool rather than in flight
+    pub fn is_free(&self, entity: Entity) -> bool {
+        self.pooled.contains(&entity)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn projectiles_are_reused() {
+        let mut pool = ProjectilePool::default();
+        let first = Entity::from_raw(1);
+        let second = Entity::from_raw(2);
+        assert!(pool.release(first));
+        assert!(pool.release(second));
+
+        // The last projectile back is the first one out again
+        assert_eq!(pool.take(), Some(second));
+        assert!(!pool.is_free(second));
+        assert!(pool.is_free(first));
+
+        // A projectile can't go back in twice, or it would be fired twice
+        assert!(!pool.release(first));
+        assert_eq!(pool.take(), Some(first));
+        assert_eq!(pool.take(), None);
+    }
+}
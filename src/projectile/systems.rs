@@ -0,0 +1,325 @@
+//! Defines all of the systems used by the projectile plugin
+
+use super::{
+    asset::ProjectileDatabase,
+    components::{EquippedProjectile, PooledProjectile, Projectile, Strike},
+    events::{FireProjectileEvent, ProjectileImpactEvent},
+    resources::{
+        ProjectileDatabaseHandle, ProjectilePool, PROJECTILE_DATABASE_PATH, PROJECTILE_POOL_WARMUP,
+    },
+};
+use crate::{
+    animation::components::SpriteAnimator,
+    constants::{COLLISION_GROUP, DRAW_LAYER},
+    health::{
+        components::{Dead, Health, Staggered},
+        events::DamageEvent,
+    },
+    inventory::{components::Inventory, resources::InventoryPanel},
+    player::Player,
+    settings::resources::Settings,
+};
+use bevy::{prelude::*, utils::HashSet};
+use bevy_rapier2d::prelude::*;
+
+/// How far in front of the thrower projectiles appear, in pixels
+const THROW_OFFSET: f32 = 20.0;
+
+/// Starts loading the projectile database
+pub fn load_projectile_database(
+    mut projectile_database: ResMut<ProjectileDatabaseHandle>,
+    asset_server: Res<AssetServer>,
+) {
+    projectile_database.0 = asset_server.load(PROJECTILE_DATABASE_PATH);
+}
+
+/// Spawns a projectile entity that sits in the pool, disabled and hidden
+fn spawn_pooled_projectile(commands: &mut Commands) -> Entity {
+    commands
+        .spawn(SpriteBundle {
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(PooledProjectile)
+        .insert(RigidBody::Dynamic)
+        .insert(RigidBodyDisabled)
+        .insert(Collider::ball(1.0))
+        .insert(ColliderDisabled)
+        .insert(Ccd::enabled())
+        .insert(Velocity::zero())
+        .insert(GravityScale(0.0))
+        .insert(Damping::default())
+        .insert(LockedAxes::ROTATION_LOCKED)
+        .insert(Sleeping::disabled())
+        .insert(ActiveEvents::COLLISION_EVENTS)
+        // Only bounce off the world, but still report touching characters
+        .insert(SolverGroups::new(
+            COLLISION_GROUP::PROJECTILES,
+            COLLISION_GROUP::WORLD,
+        ))
+        .id()
+}
+
+/// Puts a projectile back into the pool, unless it's already there
+fn release_projectile(commands: &mut Commands, pool: &mut ProjectilePool, entity: Entity) {
+    if !pool.release(entity) {
+        return;
+    }
+    commands
+        .entity(entity)
+        .remove::<Projectile>()
+        .insert(RigidBodyDisabled)
+        .insert(ColliderDisabled)
+        .insert(Velocity::zero())
+        .insert(Visibility::Hidden);
+}
+
+/// Puts every projectile still in flight back into the pool when leaving the
+/// game
+pub fn release_all_projectiles(
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    projectile_query: Query<Entity, With<Projectile>>,
+) {
+    for entity in &projectile_query {
+        release_projectile(&mut commands, &mut pool, entity);
+    }
+}
+
+/// Fills the pool up front so the first fights don't spawn anything
+pub fn warm_projectile_pool(mut commands: Commands, mut pool: ResMut<ProjectilePool>) {
+    for _ in 0..PROJECTILE_POOL_WARMUP {
+        let entity = spawn_pooled_projectile(&mut commands);
+        pool.release(entity);
+    }
+}
+
+/// Takes projectiles out of the pool and sends them flying
+pub fn fire_projectiles(
+    mut commands: Commands,
+    mut fire_events: EventReader<FireProjectileEvent>,
+    mut pool: ResMut<ProjectilePool>,
+    projectile_database: Res<ProjectileDatabaseHandle>,
+    projectile_databases: Res<Assets<ProjectileDatabase>>,
+) {
+    let Some(projectiles) = projectile_databases.get(&projectile_database.0) else {
+        return;
+    };
+
+    for event in fire_events.iter() {
+        let Some(definition) = projectiles.get(&event.kind) else {
+            warn!("Tried to fire unknown projectile {:?}", event.kind);
+            continue;
+        };
+
+        let direction = event.direction.normalize_or_zero();
+        if direction == Vec2::ZERO {
+            continue;
+        }
+
+        let entity = match pool.take() {
+            Some(entity) => entity,
+            None => {
+                debug!("Projectile pool is empty, growing it");
+                spawn_pooled_projectile(&mut commands)
+            }
+        };
+
+        let (r, g, b) = definition.color;
+        commands
+            .entity(entity)
+            .remove::<RigidBodyDisabled>()
+            .remove::<ColliderDisabled>()
+            .insert(Projectile {
+                owner: event.owner,
+                damage: definition.damage,
+                knockback: definition.knockback,
+                pierce_remaining: definition.pierce,
+                lifetime: Timer::from_seconds(definition.lifetime, TimerMode::Once),
+                hit: HashSet::new(),
+            })
+            .insert(Sprite {
+                color: Color::rgb(r, g, b),
+                custom_size: Some(Vec2::new(definition.size.0, definition.size.1)),
+                ..default()
+            })
+            .insert(
+                Transform::from_translation(event.position.extend(DRAW_LAYER::ENTITIES))
+                    .with_rotation(Quat::from_rotation_z(Vec2::X.angle_between(direction))),
+            )
+            .insert(Collider::ball(definition.radius))
+            .insert(Velocity::linear(direction * definition.speed))
+            .insert(Visibility::Inherited);
+    }
+}
+
+/// Resolves projectile hits and lifetimes, putting finished projectiles back
+/// into the pool
+#[allow(clippy::too_many_arguments)]
+pub fn update_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut pool: ResMut<ProjectilePool>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut impact_events: EventWriter<ProjectileImpactEvent>,
+    mut projectile_query: Query<(Entity, &mut Projectile, &GlobalTransform, &Velocity)>,
+    target_query: Query<Option<&Dead>, With<Health>>,
+    sensor_query: Query<(), With<Sensor>>,
+) {
+    for event in collision_events.iter() {
+        let CollisionEvent::Started(a, b, _) = *event else {
+            continue;
+        };
+
+        for (projectile_entity, other) in [(a, b), (b, a)] {
+            // Removing the projectile only happens once the commands run, so
+            // one that went back into the pool this frame is still around
+            if pool.is_free(projectile_entity) {
+                continue;
+            }
+            let Ok((_, mut projectile, transform, velocity)) =
+                projectile_query.get_mut(projectile_entity)
+            else {
+                continue;
+            };
+
+            // Projectiles fly through their owner, pickups and triggers
+            if projectile.owner == Some(other) || sensor_query.contains(other) {
+                continue;
+            }
+
+            let position = transform.translation().truncate();
+            let target = match target_query.get(other) {
+                // Corpses don't stop projectiles
+                Ok(Some(_)) => continue,
+                Ok(None) => Some(other),
+                Err(_) => None,
+            };
+
+            impact_events.send(ProjectileImpactEvent {
+                owner: projectile.owner,
+                position,
+            });
+
+            let Some(target) = target else {
+                // Hit the world
+                release_projectile(&mut commands, &mut pool, projectile_entity);
+                continue;
+            };
+
+            let strike = projectile.strike(target);
+            if strike == Strike::Repeat {
+                continue;
+            }
+
+            damage_events.send(DamageEvent {
+                target,
+                source: projectile.owner,
+                amount: projectile.damage,
+                knockback: velocity.linvel.normalize_or_zero() * projectile.knockback,
+            });
+
+            if strike == Strike::Stop {
+                release_projectile(&mut commands, &mut pool, projectile_entity);
+            }
+        }
+    }
+
+    for (entity, mut projectile, _, _) in &mut projectile_query {
+        if pool.is_free(entity) {
+            continue;
+        }
+
+        if projectile.lifetime.tick(time.delta()).finished() {
+            release_projectile(&mut commands, &mut pool, entity);
+        }
+    }
+}
+
+/// Readies the item under the inventory panel's cursor to be thrown, when
+/// the throw key is pressed while the panel is open
+pub fn equip_player_projectile(
+    keys: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    panel: Res<InventoryPanel>,
+    projectile_database: Res<ProjectileDatabaseHandle>,
+    projectile_databases: Res<Assets<ProjectileDatabase>>,
+    mut player_query: Query<(&Inventory, &mut EquippedProjectile), With<Player>>,
+) {
+    if !panel.open || !keys.just_pressed(settings.key_bindings.throw) {
+        return;
+    }
+    let Some(projectiles) = projectile_databases.get(&projectile_database.0) else {
+        return;
+    };
+
+    for (inventory, mut equipped) in &mut player_query {
+        let Some(kind) = inventory
+            .slots()
+            .get(panel.selected)
+            .and_then(Option::as_ref)
+            .and_then(|stack| projectiles.fired_from(&stack.item))
+        else {
+            continue;
+        };
+
+        if equipped.0 != kind {
+            debug!("Equipped the {:?} projectile", kind);
+            equipped.0 = kind.into();
+        }
+    }
+}
+
+/// Makes the player throw their equipped projectile, using up its ammo from
+/// their inventory
+#[allow(clippy::type_complexity)]
+pub fn throw_player_projectile(
+    keys: Res<Input<KeyCode>>,
+    settings: Res<Settings>,
+    panel: Res<InventoryPanel>,
+    projectile_database: Res<ProjectileDatabaseHandle>,
+    projectile_databases: Res<Assets<ProjectileDatabase>>,
+    mut fire_events: EventWriter<FireProjectileEvent>,
+    mut player_query: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut Inventory,
+            &EquippedProjectile,
+            Option<&SpriteAnimator>,
+        ),
+        (With<Player>, Without<Staggered>, Without<Dead>),
+    >,
+) {
+    // The throw key equips projectiles while the inventory is open
+    if panel.open || !keys.just_pressed(settings.key_bindings.throw) {
+        return;
+    }
+    let Some(projectiles) = projectile_databases.get(&projectile_database.0) else {
+        return;
+    };
+
+    for (entity, transform, mut inventory, equipped, animator) in &mut player_query {
+        let Some(definition) = projectiles.get(&equipped.0) else {
+            warn!(
+                "The player has unknown projectile {:?} equipped",
+                equipped.0
+            );
+            continue;
+        };
+        if let Some(ammo) = &definition.ammo {
+            if inventory.remove(ammo, 1).is_err() {
+                continue;
+            }
+        }
+
+        let direction = animator.map_or(Vec2::NEG_Y, |animator| animator.facing.as_vec2());
+        fire_events.send(FireProjectileEvent {
+            kind: equipped.0.clone(),
+            owner: Some(entity),
+            position: transform.translation().truncate() + direction * THROW_OFFSET,
+            direction,
+        });
+    }
+}
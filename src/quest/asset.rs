@@ -0,0 +1,174 @@
+use crate::{dialogue::asset::Dialogue, locale::asset::LocalizedContent};
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// What the player has to do to complete an objective
+#[derive(Debug, Clone, Deserialize)]
+pub enum ObjectiveKind {
+    /// Walk into the `area` object with the given name
+    ReachArea(String),
+    /// Kill a number of enemies of a kind
+    Kill { enemy: String, count: u32 },
+    /// Pick up a number of an item
+    Collect { item: String, count: u32 },
+    /// Finish a conversation with the NPC with the given name
+    TalkTo(String),
+}
+
+impl ObjectiveKind {
+    /// How much progress completes the objective
+    pub fn target(&self) -> u32 {
+        match self {
+            Self::Kill { count, .. } | Self::Collect { count, .. } => (*count).max(1),
+            Self::ReachArea(_) | Self::TalkTo(_) => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Objective {
+    /// The message the objective tracker shows
+    pub description: String,
+    pub kind: ObjectiveKind,
+}
+
+/// How the objectives of a quest unlock
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ObjectiveOrder {
+    /// One objective at a time, in the order they're listed
+    #[default]
+    Ordered,
+    /// Every objective at once
+    Parallel,
+}
+
+/// What the player gets for completing a quest
+#[derive(Debug, Clone, Deserialize)]
+pub enum QuestReward {
+    /// Sets a game flag to one, which can open portals or unlock dialogue
+    SetFlag(String),
+    GiveItem(String, u32),
+    StartQuest(String),
+    /// Starts the conversation at the given path
+    StartDialogue(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuestDefinition {
+    /// The message with the quest's name
+    pub name: String,
+    /// The message saying what the quest is about, shown under its name in
+    /// the tracker
+    pub description: String,
+    /// Whether the quest starts with the game, instead of being started by
+    /// something like a conversation
+    #[serde(default)]
+    pub auto_start: bool,
+    #[serde(default)]
+    pub order: ObjectiveOrder,
+    pub objectives: Vec<Objective>,
+    #[serde(default)]
+    pub rewards: Vec<QuestReward>,
+}
+
+/// Asset holding every quest, keyed by the quest's id
+#[derive(TypeUuid, Debug, Default, Deserialize)]
+#[uuid = "9b4c1f27-63d8-4e0a-8a75-2d6e1c3b5f90"]
+pub struct QuestDatabase {
+    pub quests: HashMap<String, QuestDefinition>,
+    /// The conversations started by rewards, keyed by their path, so they're
+    /// loaded by the time they're needed
+    #[serde(skip)]
+    pub dialogues: HashMap<String, Handle<Dialogue>>,
+}
+
+impl QuestDatabase {
+    pub fn get(&self, id: &str) -> Option<&QuestDefinition> {
+        self.quests.get(id)
+    }
+
+    /// Checks that every quest has objectives and only starts quests that
+    /// exist
+    pub fn validate(&self) -> Result<(), String> {
+        for (id, quest) in &self.quests {
+            if quest.objectives.is_empty() {
+                return Err(format!("quest {id:?} has no objectives"));
+            }
+            for reward in &quest.rewards {
+                if let QuestReward::StartQuest(next) = reward {
+                    if !self.quests.contains_key(next) {
+                        return Err(format!("quest {id:?} starts unknown quest {next:?}"));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl LocalizedContent for QuestDatabase {
+    fn message_ids(&self) -> Vec<&str> {
+        self.quests
+            .values()
+            .flat_map(|quest| {
+                let objectives = quest.objectives.iter().map(|o| o.description.as_str());
+                [quest.name.as_str(), quest.description.as_str()]
+                    .into_iter()
+                    .chain(objectives)
+            })
+            .collect()
+    }
+}
+
+#[derive(Default)]
+pub struct QuestDatabaseLoader;
+
+impl AssetLoader for QuestDatabaseLoader {
+    /// Load the quests along with the conversations their rewards start
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext<'_>,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let mut database: QuestDatabase = ron::de::from_bytes(bytes)?;
+            database.validate().map_err(bevy::asset::Error::msg)?;
+
+            let paths: Vec<String> = database
+                .quests
+                .values()
+                .flat_map(|quest| &quest.rewards)
+                .filter_map(|reward| match reward {
+                    QuestReward::StartDialogue(path) => Some(path.clone()),
+                    _ => None,
+                })
+                .collect();
+
+            let mut dependencies = vec![];
+            for path in paths {
+                let asset_path = AssetPath::from(path.as_str()).to_owned();
+                let handle = load_context.get_handle(asset_path.clone());
+                database.dialogues.insert(path, handle);
+                dependencies.push(asset_path);
+            }
+
+            debug!("Loaded {} quests", database.quests.len());
+            load_context
+                .set_default_asset(LoadedAsset::new(database).with_dependencies(dependencies));
+
+            Ok(())
+        })
+    }
+
+    /// Returns a list of supported extensions
+    fn extensions(&self) -> &[&str] {
+        &["quests.ron"]
+    }
+}
@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+/// Marks the root node of the objective tracker
+#[derive(Debug, Component)]
+pub struct QuestTrackerNode;
@@ -0,0 +1,11 @@
+/// Starts a quest, unless it was started before
+#[derive(Debug, Clone)]
+pub struct StartQuestEvent {
+    pub quest: String,
+}
+
+/// Sent once when every objective of a quest is done
+#[derive(Debug, Clone)]
+pub struct QuestCompletedEvent {
+    pub quest: String,
+}
@@ -0,0 +1,17 @@
+//! Module for quests and their objectives
+//!
+//! This module includes:
+//! - An asset defining every quest, loaded from a RON file
+//! - A quest log tracking the progress of every started quest, which is
+//!   saved with the game
+//! - Systems moving quests along as gameplay events come in and handing out
+//!   rewards, which can open portals or start conversations
+//! - An objective tracker on the HUD
+//! - A plugin to setup the required systems, loaders and events
+
+pub mod asset;
+pub mod components;
+pub mod events;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
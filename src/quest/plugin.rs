@@ -0,0 +1,55 @@
+use super::{
+    asset::{QuestDatabase, QuestDatabaseLoader},
+    events::{QuestCompletedEvent, StartQuestEvent},
+    resources::{QuestDatabaseHandle, QuestLog},
+    systems::{
+        close_quest_tracker, load_quest_database, reset_quest_log, reward_quests,
+        start_auto_quests, start_quests, track_quest_progress, update_quest_tracker,
+    },
+};
+use crate::{
+    dialogue::systems::advance_dialogue, health::systems::apply_damage,
+    inventory::systems::pick_up_items, locale::systems::report_missing_content,
+    menu::state::GameMenuState,
+};
+use bevy::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct QuestPlugin;
+
+impl Plugin for QuestPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<QuestDatabase>();
+        app.init_asset_loader::<QuestDatabaseLoader>();
+        app.add_system(report_missing_content::<QuestDatabase>);
+
+        app.init_resource::<QuestDatabaseHandle>();
+        app.init_resource::<QuestLog>();
+
+        app.add_event::<StartQuestEvent>();
+        app.add_event::<QuestCompletedEvent>();
+
+        app.add_startup_system(load_quest_database);
+
+        app.add_systems(
+            (
+                start_auto_quests,
+                start_quests,
+                track_quest_progress,
+                reward_quests,
+            )
+                .chain()
+                .after(apply_damage)
+                .after(pick_up_items)
+                .after(advance_dialogue),
+        );
+        app.add_system(
+            update_quest_tracker
+                .after(reward_quests)
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_system(close_quest_tracker.in_schedule(OnExit(GameMenuState::InGame)));
+        app.add_system(reset_quest_log.in_schedule(OnExit(GameMenuState::InGame)));
+        debug!("QuestPlugin loaded");
+    }
+}
@@ -0,0 +1,245 @@
+use super::asset::{ObjectiveKind, ObjectiveOrder, QuestDatabase, QuestDefinition};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Path to the file defining every quest
+pub const QUEST_DATABASE_PATH: &str = "data/core.quests.ron";
+
+/// Handle to the quests used by the game
+#[derive(Debug, Default, Resource)]
+pub struct QuestDatabaseHandle(pub Handle<QuestDatabase>);
+
+/// Something that happened in the game which can move objectives along
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuestSignal<'a> {
+    EnteredArea(&'a str),
+    /// An enemy of the given kind died
+    Killed(&'a str),
+    /// The player picked up a number of an item
+    Collected(&'a str, u32),
+    /// The player finished talking to the NPC with the given name
+    TalkedTo(&'a str),
+}
+
+impl ObjectiveKind {
+    /// How much progress a signal makes towards the objective
+    pub fn progress_from(&self, signal: QuestSignal) -> u32 {
+        match (self, signal) {
+            (Self::ReachArea(area), QuestSignal::EnteredArea(entered)) if area == entered => 1,
+            (Self::Kill { enemy, .. }, QuestSignal::Killed(killed)) if enemy == killed => 1,
+            (Self::Collect { item, .. }, QuestSignal::Collected(collected, count))
+                if item == collected =>
+            {
+                count
+            }
+            (Self::TalkTo(npc), QuestSignal::TalkedTo(talked_to)) if npc == talked_to => 1,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuestStatus {
+    Active,
+    Completed,
+}
+
+/// How far along a started quest is
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestProgress {
+    pub quest: String,
+    pub status: QuestStatus,
+    /// The progress made on each objective, in the order they're defined
+    pub objectives: Vec<u32>,
+}
+
+impl QuestProgress {
+    /// The indices of the objectives that can currently make progress
+    pub fn current_objectives(&self, definition: &QuestDefinition) -> Vec<usize> {
+        if self.status == QuestStatus::Completed {
+            return vec![];
+        }
+
+        let mut unfinished = definition
+            .objectives
+            .iter()
+            .zip(&self.objectives)
+            .enumerate()
+            .filter(|(_, (objective, progress))| **progress < objective.kind.target())
+            .map(|(index, _)| index);
+
+        match definition.order {
+            ObjectiveOrder::Ordered => unfinished.next().into_iter().collect(),
+            ObjectiveOrder::Parallel => unfinished.collect(),
+        }
+    }
+}
+
+/// Every quest the player started, in the order they were started
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, Resource)]
+pub struct QuestLog {
+    quests: Vec<QuestProgress>,
+    /// Whether the quests that start with the game were started
+    pub auto_started: bool,
+}
+
+impl QuestLog {
+    /// Creates a quest log holding the given quests, like ones from a save
+    pub fn from_progress(quests: Vec<QuestProgress>, auto_started: bool) -> Self {
+        Self {
+            quests,
+            auto_started,
+        }
+    }
+
+    pub fn get(&self, quest: &str) -> Option<&QuestProgress> {
+        self.quests.iter().find(|progress| progress.quest == quest)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &QuestProgress> {
+        self.quests.iter()
+    }
+
+    /// Starts a quest, returning whether it wasn't started before
+    pub fn start(&mut self, quest: &str, definition: &QuestDefinition) -> bool {
+        if self.get(quest).is_some() {
+            return false;
+        }
+
+        self.quests.push(QuestProgress {
+            quest: quest.into(),
+            status: QuestStatus::Active,
+            objectives: vec![0; definition.objectives.len()],
+        });
+        true
+    }
+
+    /// Moves the current objectives of every active quest along, returning
+    /// the ids of the quests it completed
+    pub fn record(&mut self, database: &QuestDatabase, signal: QuestSignal) -> Vec<String> {
+        let mut completed = vec![];
+
+        for progress in &mut self.quests {
+            let Some(definition) = database.get(&progress.quest) else {
+                continue;
+            };
+
+            let mut changed = false;
+            for index in progress.current_objectives(definition) {
+                let kind = &definition.objectives[index].kind;
+                let amount = kind.progress_from(signal);
+                if amount > 0 {
+                    let objective = &mut progress.objectives[index];
+                    *objective = (*objective + amount).min(kind.target());
+                    changed = true;
+                }
+            }
+
+            if changed && progress.current_objectives(definition).is_empty() {
+                progress.status = QuestStatus::Completed;
+                completed.push(progress.quest.clone());
+            }
+        }
+
+        completed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn is_completed(log: &QuestLog, quest: &str) -> bool {
+        log.get(quest)
+            .is_some_and(|progress| progress.status == QuestStatus::Completed)
+    }
+
+    fn database() -> QuestDatabase {
+        ron::from_str(
+            r#"(
+                quests: {
+                    "ordered": (
+                        name: "Ordered",
+                        description: "",
+                        objectives: [
+                            (description: "Talk", kind: TalkTo("Borin")),
+                            (description: "Kill", kind: Kill(enemy: "cave_rat", count: 2)),
+                        ],
+                    ),
+                    "parallel": (
+                        name: "Parallel",
+                        description: "",
+                        order: Parallel,
+                        objectives: [
+                            (description: "Gems", kind: Collect(item: "cave_gem", count: 3)),
+                            (description: "Exit", kind: ReachArea("exit")),
+                        ],
+                    ),
+                },
+            )"#,
+        )
+        .unwrap()
+    }
+
+    fn start(log: &mut QuestLog, database: &QuestDatabase, quest: &str) {
+        assert!(log.start(quest, database.get(quest).unwrap()));
+    }
+
+    #[test]
+    fn ordered_objectives_unlock_one_at_a_time() {
+        let database = database();
+        let mut log = QuestLog::default();
+        start(&mut log, &database, "ordered");
+
+        // The kills don't count until the talking is done
+        assert!(log
+            .record(&database, QuestSignal::Killed("cave_rat"))
+            .is_empty());
+        assert_eq!(log.get("ordered").unwrap().objectives, vec![0, 0]);
+
+        log.record(&database, QuestSignal::TalkedTo("Borin"));
+        log.record(&database, QuestSignal::Killed("cave_rat"));
+        let completed = log.record(&database, QuestSignal::Killed("cave_rat"));
+        assert_eq!(completed, vec!["ordered".to_string()]);
+        assert!(is_completed(&log, "ordered"));
+
+        // Completed quests stop tracking
+        assert!(log
+            .record(&database, QuestSignal::Killed("cave_rat"))
+            .is_empty());
+    }
+
+    #[test]
+    fn parallel_objectives_progress_together() {
+        let database = database();
+        let mut log = QuestLog::default();
+        start(&mut log, &database, "parallel");
+
+        log.record(&database, QuestSignal::EnteredArea("exit"));
+        log.record(&database, QuestSignal::Collected("cave_gem", 5));
+        assert_eq!(log.get("parallel").unwrap().objectives, vec![3, 1]);
+        assert!(is_completed(&log, "parallel"));
+    }
+
+    #[test]
+    fn quests_only_start_once() {
+        let database = database();
+        let mut log = QuestLog::default();
+        start(&mut log, &database, "parallel");
+        log.record(&database, QuestSignal::EnteredArea("exit"));
+
+        assert!(!log.start("parallel", database.get("parallel").unwrap()));
+        assert_eq!(log.get("parallel").unwrap().objectives, vec![0, 1]);
+    }
+
+    #[test]
+    fn survives_a_save() {
+        let database = database();
+        let mut log = QuestLog::default();
+        start(&mut log, &database, "ordered");
+        log.record(&database, QuestSignal::TalkedTo("Borin"));
+
+        let saved = ron::to_string(&log).unwrap();
+        assert_eq!(ron::from_str::<QuestLog>(&saved).unwrap(), log);
+    }
+}
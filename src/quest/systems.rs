@@ -0,0 +1,304 @@
+//! Defines all of the systems used by the quest plugin
+
+use super::{
+    asset::{QuestDatabase, QuestReward},
+    components::QuestTrackerNode,
+    events::{QuestCompletedEvent, StartQuestEvent},
+    resources::{QuestDatabaseHandle, QuestLog, QuestSignal, QuestStatus, QUEST_DATABASE_PATH},
+};
+use crate::{
+    dialogue::{
+        components::Npc,
+        events::{DialogueEndedEvent, StartDialogueEvent},
+    },
+    enemy::components::Enemy,
+    general::resources::GameFlags,
+    health::events::DeathEvent,
+    inventory::{
+        asset::ItemDatabase,
+        components::{Inventory, ItemStack},
+        events::ItemPickedUpEvent,
+        resources::ItemDatabaseHandle,
+        util::spawn_item_pickup,
+    },
+    locale::{components::LocalizedText, resources::Localization},
+    map::events::AreaEnteredEvent,
+    player::Player,
+    theme::resources::CurrentTheme,
+};
+use bevy::prelude::*;
+
+/// Starts loading the quests
+pub fn load_quest_database(
+    mut quest_database: ResMut<QuestDatabaseHandle>,
+    asset_server: Res<AssetServer>,
+) {
+    quest_database.0 = asset_server.load(QUEST_DATABASE_PATH);
+}
+
+/// Starts the quests that start with the game, once the quests have loaded
+pub fn start_auto_quests(
+    mut quest_log: ResMut<QuestLog>,
+    mut start_events: EventWriter<StartQuestEvent>,
+    quest_database: Res<QuestDatabaseHandle>,
+    quest_databases: Res<Assets<QuestDatabase>>,
+) {
+    if quest_log.auto_started {
+        return;
+    }
+    let Some(quests) = quest_databases.get(&quest_database.0) else {
+        return;
+    };
+
+    let mut auto_start: Vec<&String> = quests
+        .quests
+        .iter()
+        .filter(|(_, quest)| quest.auto_start)
+        .map(|(id, _)| id)
+        .collect();
+    auto_start.sort();
+
+    for quest in auto_start {
+        start_events.send(StartQuestEvent {
+            quest: quest.clone(),
+        });
+    }
+    quest_log.auto_started = true;
+}
+
+/// Adds the quests of every [StartQuestEvent] to the quest log
+pub fn start_quests(
+    mut start_events: EventReader<StartQuestEvent>,
+    mut quest_log: ResMut<QuestLog>,
+    quest_database: Res<QuestDatabaseHandle>,
+    quest_databases: Res<Assets<QuestDatabase>>,
+) {
+    for event in start_events.iter() {
+        let Some(definition) = quest_databases
+            .get(&quest_database.0)
+            .and_then(|quests| quests.get(&event.quest))
+        else {
+            warn!("Tried to start unknown quest {:?}", event.quest);
+            continue;
+        };
+
+        if quest_log.start(&event.quest, definition) {
+            info!("Started quest {:?}", definition.name);
+        }
+    }
+}
+
+/// Moves quests along as gameplay events come in
+#[allow(clippy::too_many_arguments)]
+pub fn track_quest_progress(
+    mut area_events: EventReader<AreaEnteredEvent>,
+    mut death_events: EventReader<DeathEvent>,
+    mut picked_up_events: EventReader<ItemPickedUpEvent>,
+    mut dialogue_events: EventReader<DialogueEndedEvent>,
+    mut completed_events: EventWriter<QuestCompletedEvent>,
+    mut quest_log: ResMut<QuestLog>,
+    quest_database: Res<QuestDatabaseHandle>,
+    quest_databases: Res<Assets<QuestDatabase>>,
+    enemy_query: Query<&Enemy>,
+    npc_query: Query<&Npc>,
+    player_query: Query<(), With<Player>>,
+) {
+    let Some(quests) = quest_databases.get(&quest_database.0) else {
+        return;
+    };
+
+    let mut signals = vec![];
+    signals.extend(
+        area_events
+            .iter()
+            .map(|event| QuestSignal::EnteredArea(&event.name)),
+    );
+    signals.extend(
+        death_events
+            .iter()
+            .filter_map(|event| enemy_query.get(event.entity).ok())
+            .map(|enemy| QuestSignal::Killed(&enemy.kind)),
+    );
+    signals.extend(
+        picked_up_events
+            .iter()
+            .filter(|event| player_query.contains(event.entity))
+            .map(|event| QuestSignal::Collected(&event.item, event.count)),
+    );
+    signals.extend(
+        dialogue_events
+            .iter()
+            .filter_map(|event| npc_query.get(event.npc?).ok())
+            .map(|npc| QuestSignal::TalkedTo(&npc.name)),
+    );
+
+    // Only touch the log when something could change, so the tracker
+    // doesn't rebuild every frame
+    if signals.is_empty() {
+        return;
+    }
+
+    for signal in signals {
+        for quest in quest_log.record(quests, signal) {
+            info!("Completed quest {:?}", quest);
+            completed_events.send(QuestCompletedEvent { quest });
+        }
+    }
+}
+
+/// Hands out the rewards of completed quests
+#[allow(clippy::too_many_arguments)]
+pub fn reward_quests(
+    mut commands: Commands,
+    mut completed_events: EventReader<QuestCompletedEvent>,
+    mut start_events: EventWriter<StartQuestEvent>,
+    mut dialogue_events: EventWriter<StartDialogueEvent>,
+    mut flags: ResMut<GameFlags>,
+    quest_database: Res<QuestDatabaseHandle>,
+    quest_databases: Res<Assets<QuestDatabase>>,
+    item_database: Res<ItemDatabaseHandle>,
+    item_databases: Res<Assets<ItemDatabase>>,
+    mut player_query: Query<(&mut Inventory, &Transform), With<Player>>,
+) {
+    let Some(quests) = quest_databases.get(&quest_database.0) else {
+        return;
+    };
+    let items = item_databases.get(&item_database.0);
+
+    for event in completed_events.iter() {
+        let Some(definition) = quests.get(&event.quest) else {
+            continue;
+        };
+
+        for reward in &definition.rewards {
+            match reward {
+                QuestReward::SetFlag(flag) => flags.set(flag.clone(), 1),
+                QuestReward::GiveItem(item, count) => {
+                    let (Some(items), Ok((mut inventory, transform))) =
+                        (items, player_query.get_single_mut())
+                    else {
+                        continue;
+                    };
+                    let Some(definition) = items.get(item) else {
+                        warn!("Quest tried to give unknown item {:?}", item);
+                        continue;
+                    };
+
+                    let left_over = inventory.add(definition, *count);
+                    if left_over > 0 {
+                        spawn_item_pickup(
+                            &mut commands,
+                            items,
+                            ItemStack::new(item.clone(), left_over),
+                            transform.translation.truncate(),
+                        );
+                    }
+                }
+                QuestReward::StartQuest(quest) => start_events.send(StartQuestEvent {
+                    quest: quest.clone(),
+                }),
+                QuestReward::StartDialogue(path) => {
+                    if let Some(dialogue) = quests.dialogues.get(path) {
+                        dialogue_events.send(StartDialogueEvent {
+                            dialogue: dialogue.clone(),
+                            npc: None,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Lists the current objectives of every active quest, rebuilding the list
+/// whenever the quest log or the language changes
+pub fn update_quest_tracker(
+    mut commands: Commands,
+    quest_log: Res<QuestLog>,
+    theme: Res<CurrentTheme>,
+    localization: Res<Localization>,
+    quest_database: Res<QuestDatabaseHandle>,
+    quest_databases: Res<Assets<QuestDatabase>>,
+    tracker_query: Query<Entity, With<QuestTrackerNode>>,
+) {
+    let changed = quest_log.is_changed() || theme.is_changed() || localization.is_changed();
+    if !changed && !tracker_query.is_empty() {
+        return;
+    }
+    let Some(quests) = quest_databases.get(&quest_database.0) else {
+        return;
+    };
+
+    tracker_query.for_each(|node| commands.entity(node).despawn_recursive());
+
+    let title_style = theme.text_style("heading", "tracker_title", "accent");
+    let text_style = theme.text_style("body", "tracker_text", "text");
+    let description_style = theme.text_style("body", "tracker_text", "muted");
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(10.0),
+                    right: Val::Px(10.0),
+                    ..default()
+                },
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexEnd,
+                gap: Size::all(Val::Px(theme.spacing("tracker"))),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(QuestTrackerNode)
+        .with_children(|parent| {
+            let active = quest_log
+                .iter()
+                .filter(|progress| progress.status == QuestStatus::Active);
+
+            for progress in active {
+                let Some(definition) = quests.get(&progress.quest) else {
+                    continue;
+                };
+
+                parent.spawn(TextBundle::from_section(
+                    localization.text(&definition.name),
+                    title_style.clone(),
+                ));
+                parent.spawn(TextBundle::from_section(
+                    localization.text(&definition.description),
+                    description_style.clone(),
+                ));
+                for index in progress.current_objectives(definition) {
+                    let objective = &definition.objectives[index];
+                    let target = objective.kind.target();
+                    let description = localization.text(&objective.description);
+                    let line = if target > 1 {
+                        localization.format(
+                            &LocalizedText::new("objective-progress")
+                                .with_arg("objective", description)
+                                .with_arg("count", progress.objectives[index])
+                                .with_arg("target", target),
+                        )
+                    } else {
+                        description
+                    };
+                    parent.spawn(TextBundle::from_section(line, text_style.clone()));
+                }
+            }
+        });
+}
+
+/// Removes the objective tracker when leaving the game
+pub fn close_quest_tracker(
+    mut commands: Commands,
+    tracker_query: Query<Entity, With<QuestTrackerNode>>,
+) {
+    tracker_query.for_each(|node| commands.entity(node).despawn_recursive());
+}
+
+/// Forgets every quest when leaving the game, so the next game starts over
+pub fn reset_quest_log(mut quest_log: ResMut<QuestLog>) {
+    *quest_log = QuestLog::default();
+}
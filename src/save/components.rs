@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+
+/// Marks the root node of the save slot menu
+#[derive(Debug, Component)]
+pub struct SlotMenuNode;
+
+/// The buttons of the save slot menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum SlotButton {
+    Slot(usize),
+    Back,
+}
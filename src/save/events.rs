@@ -0,0 +1,12 @@
+/// Saves the game in progress to a slot
+#[derive(Debug, Clone, Copy)]
+pub struct SaveGameEvent {
+    pub slot: usize,
+}
+
+/// Loads the game saved in a slot, leaving the game in progress first if
+/// there is one
+#[derive(Debug, Clone, Copy)]
+pub struct LoadGameEvent {
+    pub slot: usize,
+}
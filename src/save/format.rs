@@ -0,0 +1,325 @@
+//! # Save format
+//!
+//! Saves are RON files holding a [SaveGame]. Every save has a `version`, and
+//! saves from older versions of the game are upgraded by running them
+//! through the [MIGRATIONS] before they are read.
+//!
+//! Migrations work on the save as a plain [ron::Value], which doesn't keep
+//! the names of enum variants. Because of that the save format sticks to
+//! structs, lists, maps, options and plain values, which is why quests are
+//! saved as [QuestSave]s rather than straight out of the quest log.
+
+use super::resources::WorldChanges;
+use crate::{
+    general::resources::GameFlags,
+    health::resources::PLAYER_LIVES,
+    inventory::components::ItemStack,
+    menu::ending::RunStats,
+    player::PLAYER_PROJECTILE,
+    quest::resources::{QuestProgress, QuestStatus},
+};
+use bevy::prelude::*;
+use ron::{Map, Value};
+use serde::{Deserialize, Serialize};
+
+/// Upgrades a save from one version to the next
+pub type Migration = fn(&mut Map) -> Result<(), String>;
+
+/// The migrations upgrading old saves, where the first one upgrades version
+/// 1 saves to version 2 and so on
+///
+/// Add a migration here whenever the save format changes. The current
+/// version follows from how many there are.
+pub const MIGRATIONS: &[Migration] = &[add_lives_and_stats];
+/// The version of the saves written by this version of the game
+pub const SAVE_VERSION: u32 = 1 + MIGRATIONS.len() as u32;
+
+/// Everything needed to pick a game back up where it was left
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    /// When the game was saved, in seconds since the Unix epoch
+    pub saved_at: u64,
+    /// The asset path of the map the player was on
+    pub map: String,
+    pub player: PlayerSave,
+    pub quests: Vec<QuestSave>,
+    /// Whether the quests that start with the game were started
+    pub auto_started_quests: bool,
+    pub flags: GameFlags,
+    pub world: WorldChanges,
+    /// How many lives the player has left
+    pub lives: u32,
+    pub stats: RunStats,
+}
+
+/// Where the player was and what they had
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSave {
+    pub position: Vec2,
+    pub health: f32,
+    pub max_health: f32,
+    /// The id of the equipped weapon
+    pub weapon: String,
+    /// The id of the readied projectile
+    pub projectile: String,
+    pub inventory: Vec<Option<ItemStack>>,
+}
+
+/// How far along a started quest is
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestSave {
+    pub quest: String,
+    pub completed: bool,
+    pub objectives: Vec<u32>,
+}
+
+impl From<&QuestProgress> for QuestSave {
+    fn from(progress: &QuestProgress) -> Self {
+        Self {
+            quest: progress.quest.clone(),
+            completed: progress.status == QuestStatus::Completed,
+            objectives: progress.objectives.clone(),
+        }
+    }
+}
+
+impl From<QuestSave> for QuestProgress {
+    fn from(quest: QuestSave) -> Self {
+        Self {
+            quest: quest.quest,
+            status: if quest.completed {
+                QuestStatus::Completed
+            } else {
+                QuestStatus::Active
+            },
+            objectives: quest.objectives,
+        }
+    }
+}
+
+fn field(name: &str) -> Value {
+    Value::String(name.into())
+}
+
+/// Version 2 saves the lives left, the stats of the run and the readied
+/// projectile
+///
+/// Older saves didn't count any stats, so the run counts on from nothing.
+fn add_lives_and_stats(save: &mut Map) -> Result<(), String> {
+    let Some(Value::Map(mut player)) = save.remove(&field("player")) else {
+        return Err("The save has no player".into());
+    };
+    player.insert(field("projectile"), Value::String(PLAYER_PROJECTILE.into()));
+    save.insert(field("player"), Value::Map(player));
+
+    save.insert(
+        field("lives"),
+        Value::Number(i64::from(PLAYER_LIVES).into()),
+    );
+
+    let mut stats = Map::new();
+    stats.insert(field("play_time"), Value::Number(0.0.into()));
+    for count in [
+        "enemies_defeated",
+        "deaths",
+        "items_collected",
+        "quests_completed",
+        "deepest",
+    ] {
+        stats.insert(field(count), Value::Number(0.into()));
+    }
+    save.insert(field("stats"), Value::Map(stats));
+
+    Ok(())
+}
+
+/// Runs a save through the migrations it needs to reach the newest version
+fn migrate(mut save: Map, version: u32, migrations: &[Migration]) -> Result<Map, String> {
+    let newest = 1 + migrations.len() as u32;
+    if version == 0 {
+        return Err("The save has no valid version".into());
+    }
+    if version > newest {
+        return Err(format!(
+            "The save is from a newer version of the game (version {}, expected {} at most)",
+            version, newest
+        ));
+    }
+
+    for (from, migration) in migrations
+        .iter()
+        .enumerate()
+        .skip(version as usize - 1)
+        .map(|(index, migration)| (index + 1, migration))
+    {
+        migration(&mut save).map_err(|error| {
+            format!("Couldn't upgrade the save from version {}: {}", from, error)
+        })?;
+    }
+    save.insert(field("version"), Value::Number(i64::from(newest).into()));
+
+    Ok(save)
+}
+
+/// Reads a save using the given migrations
+fn parse_save_with(source: &str, migrations: &[Migration]) -> Result<SaveGame, String> {
+    let Value::Map(save) = ron::from_str::<Value>(source).map_err(|error| error.to_string())?
+    else {
+        return Err("The save isn't a struct".into());
+    };
+
+    let version = save
+        .iter()
+        .find(|(key, _)| **key == field("version"))
+        .and_then(|(_, value)| match value {
+            Value::Number(number) => number.as_i64(),
+            _ => None,
+        })
+        .and_then(|version| u32::try_from(version).ok())
+        .unwrap_or_default();
+
+    Value::Map(migrate(save, version, migrations)?)
+        .into_rust()
+        .map_err(|error| error.to_string())
+}
+
+/// Reads a save written by this or any older version of the game
+pub fn parse_save(source: &str) -> Result<SaveGame, String> {
+    parse_save_with(source, MIGRATIONS)
+}
+
+/// Writes a save as RON
+pub fn write_save(save: &SaveGame) -> Result<String, String> {
+    ron::ser::to_string_pretty(save, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn save() -> SaveGame {
+        let mut flags = GameFlags::default();
+        flags.set("met_miner", 1);
+        flags.set("rats_killed", 7);
+        let mut world = WorldChanges::default();
+        world.map_mut("tiled/test.tmx").opened_chests.insert(10);
+        world.map_mut("tiled/deep.tmx").killed_bosses.insert(4);
+
+        SaveGame {
+            version: SAVE_VERSION,
+            saved_at: 1_792_331_000,
+            map: "tiled/test.tmx".into(),
+            player: PlayerSave {
+                position: Vec2::new(96.5, -40.0),
+                health: 62.0,
+                max_health: 100.0,
+                weapon: "pickaxe".into(),
+                projectile: "bomb".into(),
+                inventory: vec![Some(ItemStack::new("gem", 3)), None],
+            },
+            quests: vec![
+                QuestSave {
+                    quest: "rat_problem".into(),
+                    completed: false,
+                    objectives: vec![2, 0],
+                },
+                QuestSave {
+                    quest: "lost_pick".into(),
+                    completed: true,
+                    objectives: vec![1],
+                },
+            ],
+            auto_started_quests: true,
+            flags,
+            world,
+            lives: 2,
+            stats: RunStats {
+                play_time: 754.5,
+                enemies_defeated: 31,
+                deaths: 1,
+                items_collected: 12,
+                quests_completed: 1,
+                deepest: 2,
+            },
+        }
+    }
+
+    #[test]
+    fn saves_round_trip() {
+        let save = save();
+        let source = write_save(&save).unwrap();
+        assert_eq!(parse_save(&source).unwrap(), save);
+
+        // Quests lose nothing on their way through the save
+        for quest in &save.quests {
+            let progress = QuestProgress::from(quest.clone());
+            assert_eq!(&QuestSave::from(&progress), quest);
+        }
+    }
+
+    #[test]
+    fn old_saves_are_migrated() {
+        // Version 2 renamed `map` to `level`, and version 3 put it back
+        fn rename(save: &mut Map, from: &str, to: &str) -> Result<(), String> {
+            let value = save
+                .remove(&field(from))
+                .ok_or(format!("The save has no {}", from))?;
+            save.insert(field(to), value);
+            Ok(())
+        }
+        let migrations: [Migration; 2] = [
+            |save| rename(save, "map", "level"),
+            |save| rename(save, "level", "map"),
+        ];
+
+        let mut save = save();
+        save.version = 1;
+        let source = write_save(&save).unwrap();
+        let loaded = parse_save_with(&source, &migrations).unwrap();
+        assert_eq!(loaded.version, 3);
+        assert_eq!(loaded.map, save.map);
+
+        // Migrations that fail stop the save from loading
+        let source = source.replace("map:", "mop:");
+        let error = parse_save_with(&source, &migrations).unwrap_err();
+        assert!(error.contains("from version 1"), "{}", error);
+    }
+
+    #[test]
+    fn version_1_saves_get_lives_and_stats() {
+        // Write a save the way version 1 did, without the fields added since
+        let source = write_save(&save()).unwrap();
+        let Value::Map(mut old) = ron::from_str::<Value>(&source).unwrap() else {
+            panic!("The save isn't a struct");
+        };
+        old.remove(&field("lives"));
+        old.remove(&field("stats"));
+        old.insert(field("version"), Value::Number(1.into()));
+        let Some(Value::Map(mut player)) = old.remove(&field("player")) else {
+            panic!("The save has no player");
+        };
+        player.remove(&field("projectile"));
+        old.insert(field("player"), Value::Map(player));
+
+        let loaded = parse_save(&ron::to_string(&Value::Map(old)).unwrap()).unwrap();
+        assert_eq!(loaded.version, SAVE_VERSION);
+        assert_eq!(loaded.lives, PLAYER_LIVES);
+        assert_eq!(loaded.stats, RunStats::default());
+        assert_eq!(loaded.player.projectile, PLAYER_PROJECTILE);
+        assert_eq!(loaded.player.inventory, save().player.inventory);
+    }
+
+    #[test]
+    fn bad_saves_are_rejected() {
+        let mut save = save();
+        save.version = SAVE_VERSION + 1;
+        let error = parse_save(&write_save(&save).unwrap()).unwrap_err();
+        assert!(error.contains("newer version"), "{}", error);
+
+        assert!(parse_save("(version: 1, map: ").is_err());
+        assert!(parse_save("(map: \"tiled/test.tmx\")").is_err());
+        assert!(parse_save("[1, 2]").is_err());
+    }
+}
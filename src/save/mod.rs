@@ -0,0 +1,20 @@
+//! Module for saving and loading games
+//!
+//! This module includes:
+//! - A versioned [SaveGame](format::SaveGame) format with migrations for
+//!   saves from older versions of the game
+//! - A number of save slots in the platform's data directory
+//! - [WorldChanges](resources::WorldChanges) remembering the chests opened
+//!   and bosses killed on every map
+//! - A slot menu for saving from the pause menu and loading from the start
+//!   screen or the pause menu
+//! - A plugin to setup the required systems and states
+
+pub mod components;
+pub mod events;
+pub mod format;
+pub mod plugin;
+pub mod resources;
+pub mod state;
+pub mod systems;
+pub mod util;
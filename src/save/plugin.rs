@@ -0,0 +1,70 @@
+use super::{
+    components::SlotButton,
+    events::{LoadGameEvent, SaveGameEvent},
+    resources::{PendingLoad, QueuedLoad, SaveSlots, WorldChanges},
+    state::SlotMenuState,
+    systems::{
+        cleanup_slot_menu, close_slot_menu_on_escape, load_game, queue_load, record_map_changes,
+        refresh_save_slots, reset_world_changes, restore_player, save_game, setup_slot_menu,
+        slot_menu_open, update_slot_buttons,
+    },
+};
+use crate::{
+    map::state::MapReadinessState,
+    menu::{builder::MenuAppExt, state::GameMenuState, systems::setup_start_screen},
+    player::place_player_at_spawn,
+};
+use bevy::prelude::*;
+
+/// Sets up the save slots, saving and loading games and remembering what
+/// changed on every map
+#[derive(Debug, Default)]
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<SlotMenuState>();
+        app.init_resource::<SaveSlots>();
+        app.init_resource::<WorldChanges>();
+        app.init_resource::<QueuedLoad>();
+        app.init_resource::<PendingLoad>();
+        app.add_event::<SaveGameEvent>();
+        app.add_event::<LoadGameEvent>();
+
+        // The start screen needs to know whether there is a game to continue
+        app.add_system(
+            refresh_save_slots
+                .before(setup_start_screen)
+                .in_schedule(OnEnter(GameMenuState::StartScreen)),
+        );
+        app.add_system(record_map_changes.in_set(OnUpdate(GameMenuState::InGame)));
+        app.add_system(save_game);
+        // Loading waits for the map of the game left behind to unload
+        app.add_system(queue_load);
+        app.add_system(
+            load_game
+                .after(queue_load)
+                .run_if(in_state(MapReadinessState::Unloaded))
+                .in_set(OnUpdate(GameMenuState::StartScreen)),
+        );
+        app.add_system(
+            restore_player
+                .after(place_player_at_spawn)
+                .in_schedule(OnEnter(MapReadinessState::Loaded)),
+        );
+        app.add_system(reset_world_changes.in_schedule(OnExit(GameMenuState::InGame)));
+
+        // The save slots
+        app.add_menu_actions::<SlotButton>();
+        app.add_system(setup_slot_menu.in_schedule(OnEnter(SlotMenuState::Save)));
+        app.add_system(setup_slot_menu.in_schedule(OnEnter(SlotMenuState::Load)));
+        app.add_system(cleanup_slot_menu.in_schedule(OnExit(SlotMenuState::Save)));
+        app.add_system(cleanup_slot_menu.in_schedule(OnExit(SlotMenuState::Load)));
+        app.add_systems(
+            (update_slot_buttons, close_slot_menu_on_escape)
+                .chain()
+                .distributive_run_if(slot_menu_open),
+        );
+        debug!("SavePlugin loaded");
+    }
+}
@@ -0,0 +1,103 @@
+use super::format::SaveGame;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
+
+/// How many save slots there are
+pub const SAVE_SLOTS: usize = 3;
+/// The name of the folder saves go in, inside the game's data directory
+pub const SAVE_DIR_NAME: &str = "saves";
+
+/// What changed on one map, by the ids of its map objects
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapChanges {
+    pub opened_chests: BTreeSet<u32>,
+    /// Enemy objects with the `boss` property whose enemies were killed
+    pub killed_bosses: BTreeSet<u32>,
+}
+
+/// What changed on every map the player visited, keyed by the asset path of
+/// the map
+///
+/// Maps are loaded fresh from their files every time, so anything that
+/// should stay the way the player left it is kept here and applied as the
+/// map's objects spawn.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, Resource)]
+pub struct WorldChanges(BTreeMap<String, MapChanges>);
+
+impl WorldChanges {
+    pub fn map(&self, map: &str) -> Option<&MapChanges> {
+        self.0.get(map)
+    }
+
+    pub fn map_mut(&mut self, map: &str) -> &mut MapChanges {
+        self.0.entry(map.into()).or_default()
+    }
+
+    pub fn is_chest_opened(&self, map: &str, object: u32) -> bool {
+        self.map(map)
+            .is_some_and(|changes| changes.opened_chests.contains(&object))
+    }
+
+    pub fn is_boss_killed(&self, map: &str, object: u32) -> bool {
+        self.map(map)
+            .is_some_and(|changes| changes.killed_bosses.contains(&object))
+    }
+}
+
+/// A short description of what's in a save slot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotSummary {
+    /// The asset path of the map the player was on
+    pub map: String,
+    /// When the game was saved, in seconds since the Unix epoch
+    pub saved_at: u64,
+}
+
+/// The save slots and what's in them
+#[derive(Debug, Resource)]
+pub struct SaveSlots {
+    /// The folder saves are kept in, or `None` when the platform has no data
+    /// directory and games can't be saved
+    pub dir: Option<PathBuf>,
+    /// What's in each slot, with `None` for the empty ones
+    pub slots: [Option<SlotSummary>; SAVE_SLOTS],
+}
+
+impl Default for SaveSlots {
+    fn default() -> Self {
+        Self {
+            dir: dirs::data_dir().map(|dir| {
+                dir.join(crate::settings::resources::CONFIG_DIR_NAME)
+                    .join(SAVE_DIR_NAME)
+            }),
+            slots: default(),
+        }
+    }
+}
+
+impl SaveSlots {
+    /// The slot saved to most recently, if any
+    pub fn latest(&self) -> Option<usize> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, summary)| Some((slot, summary.as_ref()?.saved_at)))
+            .max_by_key(|(_, saved_at)| *saved_at)
+            .map(|(slot, _)| slot)
+    }
+}
+
+/// The slot to load once the start screen is showing and no map is loaded,
+/// which takes a moment when loading from the pause menu
+#[derive(Debug, Default, Resource)]
+pub struct QueuedLoad(pub Option<usize>);
+
+/// A loaded save waiting for its map to finish loading before the player is
+/// put back where they were
+#[derive(Debug, Default, Resource)]
+pub struct PendingLoad(pub Option<SaveGame>);
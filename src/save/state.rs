@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+
+/// Whether the save slots are open, and what picking one does
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, States)]
+pub enum SlotMenuState {
+    #[default]
+    Closed,
+    /// Saving from the pause menu
+    Save,
+    /// Loading from the start screen or the pause menu
+    Load,
+}
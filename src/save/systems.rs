@@ -0,0 +1,372 @@
+//! Defines all of the systems used by the save plugin
+
+use super::{
+    components::{SlotButton, SlotMenuNode},
+    events::{LoadGameEvent, SaveGameEvent},
+    format::{PlayerSave, QuestSave, SaveGame, SAVE_VERSION},
+    resources::{PendingLoad, QueuedLoad, SaveSlots, SlotSummary, WorldChanges},
+    state::SlotMenuState,
+    util::{now, read_slot, read_slot_summaries, slot_label, write_slot},
+};
+use crate::{
+    camera::components::{CameraController, GameCamera},
+    combat::components::EquippedWeapon,
+    enemy::components::{Enemy, SpawnedEnemy},
+    general::resources::GameFlags,
+    health::{
+        components::{Dead, Health},
+        resources::Lives,
+    },
+    interaction::components::Chest,
+    inventory::components::Inventory,
+    locale::resources::Localization,
+    map::{
+        objects::MapObject,
+        state::{MapReadinessState, MapState},
+        util::map_id,
+    },
+    menu::{
+        builder::{MenuActionEvent, MenuBuilder},
+        ending::RunStats,
+        state::{GameMenuState, PauseState},
+    },
+    player::{spawn_player, Player},
+    projectile::components::EquippedProjectile,
+    quest::resources::QuestLog,
+    theme::resources::CurrentTheme,
+    trigger::events::ShowTextEvent,
+};
+use bevy::prelude::*;
+
+const SLOT_BUTTON_SIZE: Vec2 = Vec2::new(640.0, 65.0);
+
+/// Reads what's in the save slots, so the start screen knows whether there
+/// is a game to continue
+pub fn refresh_save_slots(mut slots: ResMut<SaveSlots>) {
+    if let Some(dir) = slots.dir.clone() {
+        slots.slots = read_slot_summaries(&dir);
+    }
+}
+
+/// Remembers the chests opened and the bosses killed on the current map
+pub fn record_map_changes(
+    mut world: ResMut<WorldChanges>,
+    map_state: Res<MapState>,
+    asset_server: Res<AssetServer>,
+    chest_query: Query<(&Chest, &MapObject), Changed<Chest>>,
+    boss_query: Query<(&MapObject, &SpawnedEnemy)>,
+    alive_query: Query<(), (With<Enemy>, Without<Dead>)>,
+) {
+    let Some(map) = map_id(&asset_server, &map_state.handle) else {
+        return;
+    };
+
+    for (chest, object) in &chest_query {
+        if chest.opened && !world.is_chest_opened(&map, object.id) {
+            world.map_mut(&map).opened_chests.insert(object.id);
+        }
+    }
+
+    for (object, spawned) in &boss_query {
+        if object.bool_property("boss") != Some(true)
+            || spawned.0.is_empty()
+            || world.is_boss_killed(&map, object.id)
+        {
+            continue;
+        }
+
+        // Enemies that are gone entirely count as dead too
+        if spawned
+            .0
+            .iter()
+            .all(|enemy| alive_query.get(*enemy).is_err())
+        {
+            debug!("The boss from map object {} was killed", object.id);
+            world.map_mut(&map).killed_bosses.insert(object.id);
+        }
+    }
+}
+
+/// Saves the game in progress to the slots asked for
+#[allow(clippy::too_many_arguments)]
+pub fn save_game(
+    mut save_events: EventReader<SaveGameEvent>,
+    mut slots: ResMut<SaveSlots>,
+    mut text_events: EventWriter<ShowTextEvent>,
+    map_state: Res<MapState>,
+    asset_server: Res<AssetServer>,
+    flags: Res<GameFlags>,
+    quest_log: Res<QuestLog>,
+    world: Res<WorldChanges>,
+    lives: Res<Lives>,
+    stats: Res<RunStats>,
+    player_query: Query<
+        (
+            &Transform,
+            &Health,
+            &Inventory,
+            &EquippedWeapon,
+            &EquippedProjectile,
+        ),
+        With<Player>,
+    >,
+) {
+    for event in save_events.iter() {
+        let Some(dir) = slots.dir.clone() else {
+            warn!("There is no data directory to save games in");
+            continue;
+        };
+        let Some(map) = map_id(&asset_server, &map_state.handle) else {
+            warn!("Can't save without a map loaded");
+            continue;
+        };
+        let Ok((transform, health, inventory, weapon, projectile)) = player_query.get_single()
+        else {
+            warn!("Can't save without a player");
+            continue;
+        };
+
+        let save = SaveGame {
+            version: SAVE_VERSION,
+            saved_at: now(),
+            map,
+            player: PlayerSave {
+                position: transform.translation.truncate(),
+                health: health.current,
+                max_health: health.max,
+                weapon: weapon.0.clone(),
+                projectile: projectile.0.clone(),
+                inventory: inventory.slots().to_vec(),
+            },
+            quests: quest_log.iter().map(QuestSave::from).collect(),
+            auto_started_quests: quest_log.auto_started,
+            flags: flags.clone(),
+            world: world.clone(),
+            lives: lives.0,
+            stats: stats.clone(),
+        };
+
+        match write_slot(&dir, event.slot, &save) {
+            Ok(()) => {
+                info!("Saved the game to slot {}", event.slot + 1);
+                slots.slots[event.slot] = Some(SlotSummary {
+                    map: save.map,
+                    saved_at: save.saved_at,
+                });
+                text_events.send(ShowTextEvent {
+                    text: "game-saved".into(),
+                });
+            }
+            Err(error) => {
+                error!(
+                    "Couldn't save the game to slot {}: {}",
+                    event.slot + 1,
+                    error
+                );
+                text_events.send(ShowTextEvent {
+                    text: "game-save-failed".into(),
+                });
+            }
+        }
+    }
+}
+
+/// Queues the slot asked for to be loaded, heading back to the start screen
+/// first when a game is in progress
+///
+/// The game in progress is torn down on the way out, and the saved game is
+/// loaded by [load_game] once its map is gone.
+pub fn queue_load(
+    mut load_events: EventReader<LoadGameEvent>,
+    mut queued: ResMut<QueuedLoad>,
+    mut next_state: ResMut<NextState<GameMenuState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+    game_state: Res<State<GameMenuState>>,
+) {
+    // Only one game can be loaded at a time
+    let Some(event) = load_events.iter().last() else {
+        return;
+    };
+    queued.0 = Some(event.slot);
+
+    if game_state.0 == GameMenuState::InGame {
+        next_pause_state.set(PauseState::Running);
+        next_state.set(GameMenuState::StartScreen);
+    }
+}
+
+/// Starts the game saved in the queued slot
+///
+/// Everything but the player is restored right away. The player is put back
+/// by [restore_player] once the saved map has loaded.
+#[allow(clippy::too_many_arguments)]
+pub fn load_game(
+    mut commands: Commands,
+    mut queued: ResMut<QueuedLoad>,
+    mut map_state: ResMut<MapState>,
+    mut flags: ResMut<GameFlags>,
+    mut quest_log: ResMut<QuestLog>,
+    mut world: ResMut<WorldChanges>,
+    mut lives: ResMut<Lives>,
+    mut pending: ResMut<PendingLoad>,
+    mut next_map_readiness: ResMut<NextState<MapReadinessState>>,
+    mut next_state: ResMut<NextState<GameMenuState>>,
+    mut next_slot_state: ResMut<NextState<SlotMenuState>>,
+    slots: Res<SaveSlots>,
+    asset_server: Res<AssetServer>,
+) {
+    let Some(slot) = queued.0.take() else {
+        return;
+    };
+    let Some(dir) = &slots.dir else {
+        return;
+    };
+
+    let save = match read_slot(dir, slot) {
+        Ok(save) => save,
+        Err(error) => {
+            error!("Couldn't load the game in slot {}: {}", slot + 1, error);
+            return;
+        }
+    };
+    info!("Loading the game in slot {}", slot + 1);
+
+    *flags = save.flags.clone();
+    *quest_log = QuestLog::from_progress(
+        save.quests.iter().cloned().map(Into::into).collect(),
+        save.auto_started_quests,
+    );
+    *world = save.world.clone();
+    *lives = Lives(save.lives);
+
+    map_state.handle = asset_server.load(save.map.as_str());
+    spawn_player(&mut commands, &asset_server);
+    pending.0 = Some(save);
+
+    // Loaded games skip the intro
+    next_map_readiness.set(MapReadinessState::Loading);
+    next_state.set(GameMenuState::InGame);
+    next_slot_state.set(SlotMenuState::Closed);
+}
+
+/// Puts the player back where they were in the game being loaded, once its
+/// map has loaded
+///
+/// The stats of the run are picked back up here too, since they start over
+/// as the game starts.
+pub fn restore_player(
+    mut pending: ResMut<PendingLoad>,
+    mut stats: ResMut<RunStats>,
+    mut player_query: Query<
+        (
+            &mut Transform,
+            &mut Health,
+            &mut Inventory,
+            &mut EquippedWeapon,
+            &mut EquippedProjectile,
+        ),
+        With<Player>,
+    >,
+    mut cameras: Query<&mut CameraController, With<GameCamera>>,
+) {
+    let Some(save) = pending.0.take() else {
+        return;
+    };
+    *stats = save.stats;
+    let player = save.player;
+
+    for (mut transform, mut health, mut inventory, mut weapon, mut projectile) in &mut player_query
+    {
+        transform.translation.x = player.position.x;
+        transform.translation.y = player.position.y;
+        health.max = player.max_health;
+        health.current = player.health.min(player.max_health);
+        *inventory = Inventory::from_slots(player.inventory.clone());
+        weapon.0 = player.weapon.clone();
+        projectile.0 = player.projectile.clone();
+    }
+
+    for mut controller in &mut cameras {
+        controller.snap_to(player.position);
+    }
+}
+
+/// Forgets what changed in the world once the game ends
+pub fn reset_world_changes(mut world: ResMut<WorldChanges>, mut pending: ResMut<PendingLoad>) {
+    *world = WorldChanges::default();
+    pending.0 = None;
+}
+
+/// Whether the save slots are open
+pub fn slot_menu_open(slot_state: Res<State<SlotMenuState>>) -> bool {
+    slot_state.0 != SlotMenuState::Closed
+}
+
+/// Spawns the save slots, saving or loading depending on how they were
+/// opened
+pub fn setup_slot_menu(
+    mut commands: Commands,
+    theme: Res<CurrentTheme>,
+    localization: Res<Localization>,
+    slots: Res<SaveSlots>,
+    slot_state: Res<State<SlotMenuState>>,
+) {
+    let title = match slot_state.0 {
+        SlotMenuState::Save => "slots-save-title",
+        _ => "slots-load-title",
+    };
+
+    // Above the start screen and the pause menu
+    slots
+        .slots
+        .iter()
+        .enumerate()
+        .fold(
+            MenuBuilder::new(SLOT_BUTTON_SIZE).title(title).overlay(20),
+            |menu, (slot, summary)| {
+                menu.item(slot_label(slot, summary.as_ref()), SlotButton::Slot(slot))
+            },
+        )
+        .item("menu-back", SlotButton::Back)
+        .spawn(&mut commands, &theme, &localization, SlotMenuNode);
+}
+
+/// Despawns the save slots
+pub fn cleanup_slot_menu(mut commands: Commands, menu_query: Query<Entity, With<SlotMenuNode>>) {
+    menu_query.for_each(|node| commands.entity(node).despawn_recursive());
+}
+
+/// Saves to or loads from the picked slot
+pub fn update_slot_buttons(
+    mut action_events: EventReader<MenuActionEvent<SlotButton>>,
+    mut save_events: EventWriter<SaveGameEvent>,
+    mut load_events: EventWriter<LoadGameEvent>,
+    mut next_slot_state: ResMut<NextState<SlotMenuState>>,
+    slot_state: Res<State<SlotMenuState>>,
+    slots: Res<SaveSlots>,
+) {
+    for event in action_events.iter() {
+        match (event.action, slot_state.0) {
+            (SlotButton::Slot(slot), SlotMenuState::Save) => {
+                save_events.send(SaveGameEvent { slot });
+                next_slot_state.set(SlotMenuState::Closed);
+            }
+            // Empty slots have nothing to load
+            (SlotButton::Slot(slot), SlotMenuState::Load) if slots.slots[slot].is_some() => {
+                load_events.send(LoadGameEvent { slot });
+            }
+            (SlotButton::Back, _) => next_slot_state.set(SlotMenuState::Closed),
+            _ => {}
+        }
+    }
+}
+
+/// Closes the save slots when Escape is pressed
+pub fn close_slot_menu_on_escape(
+    keys: Res<Input<KeyCode>>,
+    mut next_slot_state: ResMut<NextState<SlotMenuState>>,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        next_slot_state.set(SlotMenuState::Closed);
+    }
+}
@@ -0,0 +1,181 @@
+use super::{
+    format::{parse_save, write_save, SaveGame},
+    resources::{SlotSummary, SAVE_SLOTS},
+};
+use crate::locale::components::LocalizedText;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The path of the file a slot is saved to
+pub fn slot_path(dir: &Path, slot: usize) -> PathBuf {
+    dir.join(format!("slot_{}.ron", slot + 1))
+}
+
+/// Reads the save in a slot
+pub fn read_slot(dir: &Path, slot: usize) -> Result<SaveGame, String> {
+    let source = fs::read_to_string(slot_path(dir, slot)).map_err(|error| error.to_string())?;
+    parse_save(&source)
+}
+
+/// Writes a save to a slot
+///
+/// The save is written next to the slot first and then moved over it, so a
+/// crash halfway through never leaves a broken save behind.
+pub fn write_slot(dir: &Path, slot: usize, save: &SaveGame) -> Result<(), String> {
+    let source = write_save(save)?;
+    let path = slot_path(dir, slot);
+    let temporary = path.with_extension("ron.tmp");
+
+    fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+    fs::write(&temporary, source).map_err(|error| error.to_string())?;
+    fs::rename(&temporary, &path).map_err(|error| error.to_string())
+}
+
+/// Describes what's in every slot, leaving out the ones that are empty or
+/// can't be read
+pub fn read_slot_summaries(dir: &Path) -> [Option<SlotSummary>; SAVE_SLOTS] {
+    std::array::from_fn(|slot| {
+        read_slot(dir, slot).ok().map(|save| SlotSummary {
+            map: save.map,
+            saved_at: save.saved_at,
+        })
+    })
+}
+
+/// The current time in seconds since the Unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Formats a time in seconds since the Unix epoch as a UTC date and time,
+/// like `2026-10-18 14:03`
+pub fn format_timestamp(seconds: u64) -> String {
+    let days = (seconds / 86_400) as i64;
+    let minutes = seconds % 86_400 / 60;
+
+    // Howard Hinnant's days to civil date algorithm
+    let shifted = days + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+/// A readable name for a map from its asset path, like `test` for
+/// `tiled/test.tmx`
+pub fn map_name(map: &str) -> &str {
+    Path::new(map)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(map)
+}
+
+/// What a slot's button says, like `Slot 1: test, 2026-10-18 14:03`
+pub fn slot_label(slot: usize, summary: Option<&SlotSummary>) -> LocalizedText {
+    match summary {
+        Some(summary) => LocalizedText::new("slot-saved")
+            .with_arg("slot", slot + 1)
+            .with_arg("map", map_name(&summary.map))
+            .with_arg("time", format_timestamp(summary.saved_at)),
+        None => LocalizedText::new("slot-empty").with_arg("slot", slot + 1),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        general::resources::GameFlags,
+        locale::resources::test::english,
+        menu::ending::RunStats,
+        save::{
+            format::{PlayerSave, SAVE_VERSION},
+            resources::WorldChanges,
+        },
+    };
+    use bevy::prelude::Vec2;
+
+    #[test]
+    fn slots_round_trip_through_files() {
+        let dir = std::env::temp_dir().join(format!("the_caverns_saves_{}", std::process::id()));
+        let save = SaveGame {
+            version: SAVE_VERSION,
+            saved_at: 1_792_331_000,
+            map: "tiled/test.tmx".into(),
+            player: PlayerSave {
+                position: Vec2::new(10.0, 20.0),
+                health: 100.0,
+                max_health: 100.0,
+                weapon: "pickaxe".into(),
+                projectile: "dagger".into(),
+                inventory: vec![None; 4],
+            },
+            quests: vec![],
+            auto_started_quests: false,
+            flags: GameFlags::default(),
+            world: WorldChanges::default(),
+            lives: 3,
+            stats: RunStats::default(),
+        };
+
+        write_slot(&dir, 1, &save).unwrap();
+        assert_eq!(read_slot(&dir, 1).unwrap(), save);
+        assert!(read_slot(&dir, 0).is_err());
+
+        let summaries = read_slot_summaries(&dir);
+        assert_eq!(summaries[0], None);
+        assert_eq!(
+            summaries[1],
+            Some(SlotSummary {
+                map: "tiled/test.tmx".into(),
+                saved_at: 1_792_331_000,
+            })
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn timestamps_are_readable() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00");
+        assert_eq!(format_timestamp(1_792_331_000), "2026-10-18 13:43");
+        assert_eq!(map_name("tiled/test.tmx"), "test");
+        let localization = english();
+        assert_eq!(localization.format(&slot_label(2, None)), "Slot 3: Empty");
+        assert_eq!(
+            localization.format(&slot_label(
+                0,
+                Some(&SlotSummary {
+                    map: "tiled/test.tmx".into(),
+                    saved_at: 1_792_331_000,
+                })
+            )),
+            "Slot 1: test, 2026-10-18 13:43"
+        );
+    }
+}
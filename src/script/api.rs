@@ -0,0 +1,212 @@
+//! # API
+//!
+//! The functions scripts can call. Scripts only ever see a snapshot of the
+//! game taken before they run, and whatever they do is queued up as
+//! [ScriptCommand]s that are carried out once they are done.
+//!
+//! | Function | Does |
+//! | --- | --- |
+//! | `player_position()` | The player's position as `#{x, y}`, or `()` without a player |
+//! | `player_health()` | The player's health |
+//! | `source_position()` | The position of whatever ran the script, like a trigger, or `()` |
+//! | `flag(name)` | The value of a game flag |
+//! | `is_flag_set(name)` | Whether a game flag is set |
+//! | `set_flag(name)`, `set_flag(name, value)` | Sets a game flag, to one unless a value is given |
+//! | `clear_flag(name)` | Clears a game flag |
+//! | `add_to_flag(name, amount)` | Adds to a game flag, returning its new value |
+//! | `show_text(id)` | Shows the message with an id at the bottom of the screen |
+//! | `play_sound(path)` | Plays a sound |
+//! | `start_dialogue(path)` | Starts a conversation |
+//! | `load_map(path)` | Takes the player to another map |
+//! | `open_door(id)`, `close_door(id)` | Opens or closes the door with a map object id |
+//! | `spawn_enemy(kind, x, y)` | Spawns an enemy |
+//! | `spawn_enemies(kind, count, x, y)` | Spawns a group of enemies |
+//! | `spawn_item(item, count, x, y)` | Drops items on the ground |
+
+use crate::{
+    general::resources::GameFlags, inventory::components::ItemStack,
+    trigger::actions::TriggerAction,
+};
+use bevy::prelude::*;
+use rhai::{Dynamic, Engine, Map, FLOAT, INT};
+use std::sync::{Arc, Mutex};
+
+/// Something a script asked for, carried out once it's done running
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptCommand {
+    /// Runs a trigger action
+    Run(TriggerAction),
+    SpawnEnemies {
+        kind: String,
+        count: u32,
+        position: Vec2,
+    },
+    SpawnItem {
+        stack: ItemStack,
+        position: Vec2,
+    },
+}
+
+/// What a script can see of the game, along with what it asked for
+#[derive(Debug, Default, Clone)]
+pub struct ScriptContext {
+    pub player: Option<Vec2>,
+    pub player_health: f32,
+    /// The position of whatever ran the script
+    pub source: Option<Vec2>,
+    /// The game flags, which scripts change directly
+    pub flags: GameFlags,
+    pub commands: Vec<ScriptCommand>,
+}
+
+/// The context shared between the engine's functions and the script runner
+pub type SharedContext = Arc<Mutex<ScriptContext>>;
+
+fn position_to_map(position: Option<Vec2>) -> Dynamic {
+    let Some(position) = position else {
+        return Dynamic::UNIT;
+    };
+
+    let mut map = Map::new();
+    map.insert("x".into(), Dynamic::from_float(position.x as FLOAT));
+    map.insert("y".into(), Dynamic::from_float(position.y as FLOAT));
+    map.into()
+}
+
+/// Reads a number that might be written as an integer or a float
+fn number(value: &Dynamic) -> f32 {
+    value
+        .as_float()
+        .map(|value| value as f32)
+        .or_else(|_| value.as_int().map(|value| value as f32))
+        .unwrap_or_default()
+}
+
+fn position(x: &Dynamic, y: &Dynamic) -> Vec2 {
+    Vec2::new(number(x), number(y))
+}
+
+/// Registers every function of the API on an engine
+pub fn register_api(engine: &mut Engine, context: &SharedContext) {
+    // Reads from the context
+    let read = |engine: &mut Engine, name: &str, getter: fn(&ScriptContext) -> Dynamic| {
+        let context = context.clone();
+        engine.register_fn(name, move || getter(&context.lock().unwrap()));
+    };
+    read(engine, "player_position", |context| {
+        position_to_map(context.player)
+    });
+    read(engine, "player_health", |context| {
+        Dynamic::from_float(context.player_health as FLOAT)
+    });
+    read(engine, "source_position", |context| {
+        position_to_map(context.source)
+    });
+
+    // Queues up a command
+    let queue = |engine: &mut Engine, name: &str, command: fn(String) -> ScriptCommand| {
+        let context = context.clone();
+        engine.register_fn(name, move |value: &str| {
+            context.lock().unwrap().commands.push(command(value.into()));
+        });
+    };
+    queue(engine, "show_text", |text| {
+        ScriptCommand::Run(TriggerAction::ShowText(text))
+    });
+    queue(engine, "play_sound", |path| {
+        ScriptCommand::Run(TriggerAction::PlaySound(path))
+    });
+    queue(engine, "start_dialogue", |path| {
+        ScriptCommand::Run(TriggerAction::StartDialogue(path))
+    });
+    queue(engine, "load_map", |path| {
+        ScriptCommand::Run(TriggerAction::ChangeMap(path))
+    });
+
+    let shared = context.clone();
+    engine.register_fn("open_door", move |id: INT| {
+        let action = TriggerAction::OpenDoor(id as u32);
+        shared
+            .lock()
+            .unwrap()
+            .commands
+            .push(ScriptCommand::Run(action));
+    });
+    let shared = context.clone();
+    engine.register_fn("close_door", move |id: INT| {
+        let action = TriggerAction::CloseDoor(id as u32);
+        shared
+            .lock()
+            .unwrap()
+            .commands
+            .push(ScriptCommand::Run(action));
+    });
+
+    let shared = context.clone();
+    engine.register_fn(
+        "spawn_enemies",
+        move |kind: &str, count: INT, x: Dynamic, y: Dynamic| {
+            shared
+                .lock()
+                .unwrap()
+                .commands
+                .push(ScriptCommand::SpawnEnemies {
+                    kind: kind.into(),
+                    count: count.max(0) as u32,
+                    position: position(&x, &y),
+                });
+        },
+    );
+    let shared = context.clone();
+    engine.register_fn("spawn_enemy", move |kind: &str, x: Dynamic, y: Dynamic| {
+        shared
+            .lock()
+            .unwrap()
+            .commands
+            .push(ScriptCommand::SpawnEnemies {
+                kind: kind.into(),
+                count: 1,
+                position: position(&x, &y),
+            });
+    });
+    let shared = context.clone();
+    engine.register_fn(
+        "spawn_item",
+        move |item: &str, count: INT, x: Dynamic, y: Dynamic| {
+            shared
+                .lock()
+                .unwrap()
+                .commands
+                .push(ScriptCommand::SpawnItem {
+                    stack: ItemStack::new(item, count.max(0) as u32),
+                    position: position(&x, &y),
+                });
+        },
+    );
+
+    // Game flags
+    let shared = context.clone();
+    engine.register_fn("flag", move |name: &str| {
+        shared.lock().unwrap().flags.get(name) as INT
+    });
+    let shared = context.clone();
+    engine.register_fn("is_flag_set", move |name: &str| {
+        shared.lock().unwrap().flags.is_set(name)
+    });
+    let shared = context.clone();
+    engine.register_fn("set_flag", move |name: &str| {
+        shared.lock().unwrap().flags.set(name, 1);
+    });
+    let shared = context.clone();
+    engine.register_fn("set_flag", move |name: &str, value: INT| {
+        shared.lock().unwrap().flags.set(name, value as i32);
+    });
+    let shared = context.clone();
+    engine.register_fn("clear_flag", move |name: &str| {
+        shared.lock().unwrap().flags.clear(name);
+    });
+    let shared = context.clone();
+    engine.register_fn("add_to_flag", move |name: &str, amount: INT| {
+        shared.lock().unwrap().flags.add(name, amount as i32) as INT
+    });
+}
@@ -0,0 +1,44 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+
+/// The source of a script, compiled the first time it runs
+#[derive(TypeUuid, Debug, Clone)]
+#[uuid = "3c8e51d2-7f4a-4e0b-9d16-a2b54f8c07e9"]
+pub struct Script {
+    /// Path to the file the script was loaded from, used in error messages
+    pub path: String,
+    pub source: String,
+}
+
+#[derive(Default)]
+pub struct ScriptLoader;
+
+impl AssetLoader for ScriptLoader {
+    /// Load a script
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext<'_>,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let script = Script {
+                path: load_context.path().display().to_string(),
+                source: String::from_utf8(bytes.to_vec())?,
+            };
+
+            debug!("Loaded script {:?}", script.path);
+            load_context.set_default_asset(LoadedAsset::new(script));
+
+            Ok(())
+        })
+    }
+
+    /// Returns a list of supported extensions
+    fn extensions(&self) -> &[&str] {
+        &["rhai"]
+    }
+}
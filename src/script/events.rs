@@ -0,0 +1,12 @@
+use super::asset::Script;
+use bevy::prelude::*;
+
+/// Runs a script, once it's done loading
+#[derive(Debug, Clone)]
+pub struct RunScriptEvent {
+    pub script: Handle<Script>,
+    /// The function to call, or `None` to run the whole script
+    pub function: Option<String>,
+    /// What ran the script, like a trigger, if anything
+    pub source: Option<Entity>,
+}
@@ -0,0 +1,18 @@
+//! Module for scripting level logic with [Rhai](https://rhai.rs)
+//!
+//! This module includes:
+//! - An asset holding a script, loaded from a `.rhai` file and reloaded
+//!   whenever the file changes
+//! - A sandboxed engine exposing an API for spawning things, querying the
+//!   player, game flags, dialogue, sounds and loading maps
+//! - Scripts attached to maps through their `script` property and to
+//!   triggers through their `script` object property
+//! - Script errors logged with their file and line instead of crashing
+//! - A plugin to setup the required systems, loaders and events
+
+pub mod api;
+pub mod asset;
+pub mod events;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
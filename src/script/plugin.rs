@@ -0,0 +1,38 @@
+use super::{
+    asset::{Script, ScriptLoader},
+    events::RunScriptEvent,
+    resources::ScriptEngine,
+    systems::{forget_pending_scripts, reload_changed_scripts, run_map_script, run_scripts},
+};
+use crate::{
+    map::state::MapReadinessState,
+    menu::state::GameMenuState,
+    trigger::systems::{fire_triggers, run_trigger_actions},
+};
+use bevy::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct ScriptPlugin;
+
+impl Plugin for ScriptPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Script>();
+        app.init_asset_loader::<ScriptLoader>();
+
+        app.init_resource::<ScriptEngine>();
+
+        app.add_event::<RunScriptEvent>();
+
+        app.add_system(run_map_script.in_schedule(OnEnter(MapReadinessState::Loaded)));
+        // Scripts hooked to triggers run the same frame the trigger fires, and
+        // their actions run right after
+        app.add_systems(
+            (reload_changed_scripts, run_scripts)
+                .chain()
+                .after(fire_triggers)
+                .before(run_trigger_actions),
+        );
+        app.add_system(forget_pending_scripts.in_schedule(OnExit(GameMenuState::InGame)));
+        debug!("ScriptPlugin loaded");
+    }
+}
@@ -0,0 +1,253 @@
+use super::{
+    api::{register_api, ScriptContext, SharedContext},
+    asset::Script,
+    events::RunScriptEvent,
+};
+use bevy::{asset::HandleId, prelude::*};
+use rhai::{
+    module_resolvers::DummyModuleResolver, CallFnOptions, Dynamic, Engine, EvalAltResult, Scope,
+    AST,
+};
+use std::collections::HashMap;
+
+/// The name of the Tiled property attaching a script to a map or trigger
+pub const SCRIPT_PROPERTY: &str = "script";
+/// How many operations a script can do in one run before it's stopped, which
+/// stops scripts that loop forever from freezing the game
+pub const MAX_OPERATIONS: u64 = 100_000;
+/// How deep scripts can call functions
+pub const MAX_CALL_LEVELS: usize = 32;
+/// How long strings, arrays and maps made by scripts can get
+pub const MAX_SIZE: usize = 10_000;
+
+/// Runs scripts in a sandbox only exposing the [API](super::api)
+///
+/// Scripts can't touch the file system, import modules or `eval` code.
+#[derive(Resource)]
+pub struct ScriptEngine {
+    engine: Engine,
+    context: SharedContext,
+    /// Compiled scripts, or `None` for the ones that failed to compile
+    compiled: HashMap<HandleId, Option<AST>>,
+    /// Scripts waiting for their asset to load
+    pub pending: Vec<RunScriptEvent>,
+}
+
+impl Default for ScriptEngine {
+    fn default() -> Self {
+        let context = SharedContext::default();
+        let mut engine = Engine::new();
+
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(MAX_CALL_LEVELS)
+            .set_max_expr_depths(64, 32)
+            .set_max_string_size(MAX_SIZE)
+            .set_max_array_size(MAX_SIZE)
+            .set_max_map_size(MAX_SIZE)
+            .set_module_resolver(DummyModuleResolver::new())
+            .disable_symbol("eval");
+        engine.on_print(|text| info!("Script: {}", text));
+        engine.on_debug(|text, source, position| {
+            debug!(
+                "Script {}:{}: {}",
+                source.unwrap_or("?"),
+                position.line().unwrap_or_default(),
+                text
+            )
+        });
+        register_api(&mut engine, &context);
+
+        Self {
+            engine,
+            context,
+            compiled: HashMap::new(),
+            pending: vec![],
+        }
+    }
+}
+
+impl ScriptEngine {
+    /// Compiles a script, describing what went wrong with its file and line
+    /// when it fails
+    pub fn compile(&self, script: &Script) -> Result<AST, String> {
+        self.engine
+            .compile(&script.source)
+            .map(|mut ast| {
+                ast.set_source(script.path.as_str());
+                ast
+            })
+            .map_err(|error| {
+                format!(
+                    "{}:{}: {}",
+                    script.path,
+                    error.1.line().unwrap_or_default(),
+                    error.0
+                )
+            })
+    }
+
+    /// Returns the compiled script, compiling it the first time
+    ///
+    /// Scripts that fail to compile log their error once and are skipped
+    /// until they change.
+    pub fn compiled(&mut self, id: HandleId, script: &Script) -> Option<AST> {
+        if !self.compiled.contains_key(&id) {
+            let ast = self
+                .compile(script)
+                .map_err(|error| error!("Script error at {}", error))
+                .ok();
+            self.compiled.insert(id, ast);
+        }
+
+        self.compiled.get(&id).cloned().flatten()
+    }
+
+    /// Forgets a compiled script so it's compiled again the next time it runs
+    pub fn forget(&mut self, id: HandleId) {
+        self.compiled.remove(&id);
+    }
+
+    /// Runs a script, or only one of its functions, returning the context
+    /// with whatever the script asked for
+    ///
+    /// Scripts without the function are fine and do nothing. Errors are
+    /// described with the script's file and line.
+    pub fn run(
+        &self,
+        ast: &AST,
+        function: Option<&str>,
+        context: ScriptContext,
+    ) -> Result<ScriptContext, String> {
+        *self.context.lock().unwrap() = context;
+
+        let mut scope = Scope::new();
+        let result = match function {
+            None => self.engine.run_ast_with_scope(&mut scope, ast),
+            Some(function) if ast.iter_functions().any(|meta| meta.name == function) => self
+                .engine
+                .call_fn_with_options::<Dynamic>(
+                    CallFnOptions::new().eval_ast(false),
+                    &mut scope,
+                    ast,
+                    function,
+                    (),
+                )
+                .map(|_| ()),
+            Some(_) => Ok(()),
+        };
+        let context = std::mem::take(&mut *self.context.lock().unwrap());
+
+        result
+            .map(|_| context)
+            .map_err(|error| describe_error(ast, *error))
+    }
+}
+
+fn describe_error(ast: &AST, mut error: EvalAltResult) -> String {
+    let line = error.position().line().unwrap_or_default();
+    error.clear_position();
+    format!("{}:{}: {}", ast.source().unwrap_or("?"), line, error)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        general::resources::GameFlags, script::api::ScriptCommand, trigger::actions::TriggerAction,
+    };
+
+    fn script(source: &str) -> Script {
+        Script {
+            path: "scripts/test.rhai".into(),
+            source: source.into(),
+        }
+    }
+
+    fn run(
+        source: &str,
+        function: Option<&str>,
+        context: ScriptContext,
+    ) -> Result<ScriptContext, String> {
+        let engine = ScriptEngine::default();
+        let ast = engine.compile(&script(source))?;
+        engine.run(&ast, function, context)
+    }
+
+    #[test]
+    fn api_queues_commands_and_changes_flags() {
+        let mut flags = GameFlags::default();
+        flags.set("rats", 2);
+        let context = ScriptContext {
+            player: Some(Vec2::new(10.0, 20.0)),
+            flags,
+            ..default()
+        };
+
+        let context = run(
+            r#"
+                let player = player_position();
+                if flag("rats") >= 2 && !is_flag_set("ambushed") {
+                    set_flag("ambushed");
+                    add_to_flag("rats", 3);
+                    spawn_enemies("cave_rat", 3, player.x + 32, player.y);
+                    show_text("Ambush!");
+                    open_door(12);
+                }
+            "#,
+            None,
+            context,
+        )
+        .unwrap();
+
+        assert!(context.flags.is_set("ambushed"));
+        assert_eq!(context.flags.get("rats"), 5);
+        assert_eq!(
+            context.commands,
+            vec![
+                ScriptCommand::SpawnEnemies {
+                    kind: "cave_rat".into(),
+                    count: 3,
+                    position: Vec2::new(42.0, 20.0),
+                },
+                ScriptCommand::Run(TriggerAction::ShowText("Ambush!".into())),
+                ScriptCommand::Run(TriggerAction::OpenDoor(12)),
+            ]
+        );
+    }
+
+    #[test]
+    fn functions_run_on_their_own() {
+        let source = r#"
+            show_text("top");
+            fn on_enter() { show_text("enter"); }
+        "#;
+
+        let context = run(source, Some("on_enter"), default()).unwrap();
+        assert_eq!(
+            context.commands,
+            vec![ScriptCommand::Run(TriggerAction::ShowText("enter".into()))]
+        );
+        // Missing functions do nothing
+        assert!(run(source, Some("on_exit"), default())
+            .unwrap()
+            .commands
+            .is_empty());
+    }
+
+    #[test]
+    fn errors_have_file_and_line() {
+        let error = run("let a = 1;\nlet b = ;", None, default()).unwrap_err();
+        assert!(error.starts_with("scripts/test.rhai:2:"), "{}", error);
+
+        let error = run("let a = 1;\n\nshow_txt(\"typo\");", None, default()).unwrap_err();
+        assert!(error.starts_with("scripts/test.rhai:3:"), "{}", error);
+    }
+
+    #[test]
+    fn scripts_are_sandboxed() {
+        assert!(run("loop {}", None, default()).is_err());
+        assert!(run(r#"eval("show_text(1)")"#, None, default()).is_err());
+        assert!(run(r#"import "secrets" as s;"#, None, default()).is_err());
+    }
+}
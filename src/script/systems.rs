@@ -0,0 +1,149 @@
+//! Defines all of the systems used by the script plugin
+
+use super::{
+    api::{ScriptCommand, ScriptContext},
+    asset::Script,
+    events::RunScriptEvent,
+    resources::{ScriptEngine, SCRIPT_PROPERTY},
+};
+use crate::{
+    enemy::events::SpawnEnemiesEvent,
+    general::resources::GameFlags,
+    health::components::Health,
+    inventory::{asset::ItemDatabase, resources::ItemDatabaseHandle, util::spawn_item_pickup},
+    map::{asset::MapAsset, state::MapState},
+    player::Player,
+    trigger::events::RunActionsEvent,
+};
+use bevy::{asset::LoadState, prelude::*};
+
+/// Runs the script in the map's `script` property once the map is loaded
+pub fn run_map_script(
+    mut script_events: EventWriter<RunScriptEvent>,
+    map_state: Res<MapState>,
+    maps: Res<Assets<MapAsset>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Some(map) = maps.get(&map_state.handle) else {
+        return;
+    };
+    let Some(tiled::PropertyValue::StringValue(path)) = map.map.properties.get(SCRIPT_PROPERTY)
+    else {
+        return;
+    };
+
+    debug!("Running map script {:?}", path);
+    script_events.send(RunScriptEvent {
+        script: asset_server.load(path.as_str()),
+        function: None,
+        source: None,
+    });
+}
+
+/// Drops the scripts still waiting to run when leaving the game
+pub fn forget_pending_scripts(mut engine: ResMut<ScriptEngine>) {
+    engine.pending.clear();
+}
+
+/// Makes scripts that changed on disk compile again the next time they run
+pub fn reload_changed_scripts(
+    mut engine: ResMut<ScriptEngine>,
+    mut asset_events: EventReader<AssetEvent<Script>>,
+) {
+    for event in asset_events.iter() {
+        if let AssetEvent::Modified { handle } | AssetEvent::Removed { handle } = event {
+            engine.forget(handle.id());
+        }
+    }
+}
+
+/// Runs the scripts asked for by [RunScriptEvent]s once they are loaded,
+/// then carries out whatever they asked for
+///
+/// Scripts that fail are logged with their file and line and otherwise
+/// ignored.
+#[allow(clippy::too_many_arguments)]
+pub fn run_scripts(
+    mut commands: Commands,
+    mut engine: ResMut<ScriptEngine>,
+    mut script_events: EventReader<RunScriptEvent>,
+    mut action_events: EventWriter<RunActionsEvent>,
+    mut enemy_events: EventWriter<SpawnEnemiesEvent>,
+    mut flags: ResMut<GameFlags>,
+    asset_server: Res<AssetServer>,
+    scripts: Res<Assets<Script>>,
+    item_database: Res<ItemDatabaseHandle>,
+    item_databases: Res<Assets<ItemDatabase>>,
+    player_query: Query<(&GlobalTransform, &Health), With<Player>>,
+    transform_query: Query<&GlobalTransform>,
+) {
+    engine.pending.extend(script_events.iter().cloned());
+    if engine.pending.is_empty() {
+        return;
+    }
+
+    let player = player_query.get_single().ok();
+    for event in std::mem::take(&mut engine.pending) {
+        let Some(script) = scripts.get(&event.script) else {
+            // Try again once the script is done loading
+            if asset_server.get_load_state(&event.script) != LoadState::Failed {
+                engine.pending.push(event);
+            }
+            continue;
+        };
+        let Some(ast) = engine.compiled(event.script.id(), script) else {
+            continue;
+        };
+
+        let context = ScriptContext {
+            player: player.map(|(transform, _)| transform.translation().truncate()),
+            player_health: player.map_or(0.0, |(_, health)| health.current),
+            source: event
+                .source
+                .and_then(|source| transform_query.get(source).ok())
+                .map(|transform| transform.translation().truncate()),
+            flags: flags.clone(),
+            commands: vec![],
+        };
+        let context = match engine.run(&ast, event.function.as_deref(), context) {
+            Ok(context) => context,
+            Err(error) => {
+                error!("Script error at {}", error);
+                continue;
+            }
+        };
+
+        if *flags != context.flags {
+            *flags = context.flags;
+        }
+
+        let mut actions = vec![];
+        for command in context.commands {
+            match command {
+                ScriptCommand::Run(action) => actions.push(action),
+                ScriptCommand::SpawnEnemies {
+                    kind,
+                    count,
+                    position,
+                } => enemy_events.send(SpawnEnemiesEvent {
+                    kind,
+                    count,
+                    position,
+                }),
+                ScriptCommand::SpawnItem { stack, position } => {
+                    let Some(items) = item_databases.get(&item_database.0) else {
+                        warn!("Script tried to spawn items before they loaded");
+                        continue;
+                    };
+                    spawn_item_pickup(&mut commands, items, stack, position);
+                }
+            }
+        }
+        if !actions.is_empty() {
+            action_events.send(RunActionsEvent {
+                source: event.source,
+                actions,
+            });
+        }
+    }
+}
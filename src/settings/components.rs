@@ -0,0 +1,20 @@
+use super::resources::{InputAction, SettingKind};
+use bevy::prelude::*;
+
+/// Marks the root node of the settings screen
+#[derive(Debug, Component)]
+pub struct SettingsMenuNode;
+
+/// The buttons of the settings screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum SettingsButton {
+    /// Changes a setting by a number of steps
+    Change(SettingKind, i32),
+    /// Waits for the next key pressed to bind it to an action
+    Rebind(InputAction),
+    Back,
+}
+
+/// Marks the text showing the value of a setting
+#[derive(Debug, Component)]
+pub struct SettingValueText(pub SettingKind);
@@ -0,0 +1,17 @@
+//! Module for the player's settings
+//!
+//! This module includes:
+//! - A [Settings](resources::Settings) resource covering volumes, the
+//!   window, key bindings, the FPS overlay and screen shake
+//! - Loading the settings from the platform's config directory before the
+//!   window is made, with fallbacks for files that are corrupt, and saving
+//!   them whenever they change
+//! - A settings screen reachable from the start screen and the pause menu
+//! - A plugin to setup the required systems and states
+
+pub mod components;
+pub mod plugin;
+pub mod resources;
+pub mod state;
+pub mod systems;
+pub mod util;
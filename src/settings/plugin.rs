@@ -0,0 +1,56 @@
+use super::{
+    components::SettingsButton,
+    resources::{Rebinding, Settings, SettingsFile},
+    state::SettingsMenuState,
+    systems::{
+        adjust_focused_setting, apply_window_settings, capture_rebind, cleanup_settings_menu,
+        close_settings_on_escape, report_settings_problems, save_changed_settings,
+        setup_settings_menu, update_settings_buttons, update_settings_text,
+    },
+};
+use crate::menu::builder::{MenuAppExt, MenuSet};
+use bevy::prelude::*;
+
+/// Sets up the settings screen and keeps the settings applied and saved
+///
+/// The [Settings] are loaded before the app is built, as they pick how the
+/// window is made, and inserted along with their [SettingsFile]. Either is
+/// defaulted when missing.
+#[derive(Debug, Default)]
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<SettingsMenuState>();
+        app.init_resource::<Settings>();
+        app.init_resource::<SettingsFile>();
+        app.init_resource::<Rebinding>();
+
+        app.add_startup_system(report_settings_problems);
+        app.add_system(apply_window_settings);
+        app.add_system(save_changed_settings);
+
+        // The settings screen
+        app.add_menu_actions::<SettingsButton>();
+        app.add_system(setup_settings_menu.in_schedule(OnEnter(SettingsMenuState::Open)));
+        app.add_system(cleanup_settings_menu.in_schedule(OnExit(SettingsMenuState::Open)));
+        // Rebinding takes the key before the menus can act on it
+        app.add_system(
+            capture_rebind
+                .before(MenuSet)
+                .in_set(OnUpdate(SettingsMenuState::Open)),
+        );
+        app.add_systems(
+            (
+                close_settings_on_escape,
+                update_settings_buttons,
+                adjust_focused_setting,
+                update_settings_text,
+            )
+                .chain()
+                .after(capture_rebind)
+                .in_set(OnUpdate(SettingsMenuState::Open)),
+        );
+        debug!("SettingsPlugin loaded");
+    }
+}
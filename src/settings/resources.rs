@@ -0,0 +1,475 @@
+use crate::{
+    constants::KEYMAP,
+    locale::{
+        components::LocalizedText,
+        resources::{FALLBACK_LANGUAGE, LANGUAGES},
+    },
+};
+use bevy::{
+    prelude::*,
+    window::{PresentMode, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The name of the game's folder in the platform's config directory
+pub const CONFIG_DIR_NAME: &str = "the_caverns";
+/// The name of the file settings are saved to
+pub const SETTINGS_FILE_NAME: &str = "settings.ron";
+
+/// The resolutions the settings screen offers
+pub const RESOLUTIONS: [[u32; 2]; 6] = [
+    [1280, 720],
+    [1366, 768],
+    [1600, 900],
+    [1920, 1080],
+    [2560, 1440],
+    [3840, 2160],
+];
+/// The window modes the settings screen offers
+pub const WINDOW_MODES: [WindowMode; 3] = [
+    WindowMode::Windowed,
+    WindowMode::BorderlessFullscreen,
+    WindowMode::Fullscreen,
+];
+/// How much the volumes and screen shake change with every click
+pub const SETTING_STEP: f32 = 0.1;
+
+/// Everything the player can rebind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputAction {
+    Forward,
+    Backward,
+    Left,
+    Right,
+    Interact,
+    Attack,
+    Throw,
+    Dash,
+    Inventory,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 9] = [
+        Self::Forward,
+        Self::Backward,
+        Self::Left,
+        Self::Right,
+        Self::Interact,
+        Self::Attack,
+        Self::Throw,
+        Self::Dash,
+        Self::Inventory,
+    ];
+
+    /// The key of the action's name in the locale files
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Forward => "action-up",
+            Self::Backward => "action-down",
+            Self::Left => "action-left",
+            Self::Right => "action-right",
+            Self::Interact => "action-interact",
+            Self::Attack => "action-attack",
+            Self::Throw => "action-throw",
+            Self::Dash => "action-dash",
+            Self::Inventory => "action-inventory",
+        }
+    }
+}
+
+/// The key for every [InputAction], defaulting to [KEYMAP]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub forward: KeyCode,
+    pub backward: KeyCode,
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub interact: KeyCode,
+    pub attack: KeyCode,
+    pub throw: KeyCode,
+    pub dash: KeyCode,
+    pub inventory: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            forward: KEYMAP::FORWARD,
+            backward: KEYMAP::BACKWARD,
+            left: KEYMAP::LEFT,
+            right: KEYMAP::RIGHT,
+            interact: KEYMAP::INTERACT,
+            attack: KEYMAP::ATTACK,
+            throw: KEYMAP::THROW,
+            dash: KEYMAP::DASH,
+            inventory: KEYMAP::INVENTORY,
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn key(&self, action: InputAction) -> KeyCode {
+        match action {
+            InputAction::Forward => self.forward,
+            InputAction::Backward => self.backward,
+            InputAction::Left => self.left,
+            InputAction::Right => self.right,
+            InputAction::Interact => self.interact,
+            InputAction::Attack => self.attack,
+            InputAction::Throw => self.throw,
+            InputAction::Dash => self.dash,
+            InputAction::Inventory => self.inventory,
+        }
+    }
+
+    fn key_mut(&mut self, action: InputAction) -> &mut KeyCode {
+        match action {
+            InputAction::Forward => &mut self.forward,
+            InputAction::Backward => &mut self.backward,
+            InputAction::Left => &mut self.left,
+            InputAction::Right => &mut self.right,
+            InputAction::Interact => &mut self.interact,
+            InputAction::Attack => &mut self.attack,
+            InputAction::Throw => &mut self.throw,
+            InputAction::Dash => &mut self.dash,
+            InputAction::Inventory => &mut self.inventory,
+        }
+    }
+
+    /// Binds an action to a key, swapping keys with whatever action had it
+    /// before so no two actions share a key
+    pub fn bind(&mut self, action: InputAction, key: KeyCode) {
+        let old_key = self.key(action);
+        if let Some(other) = InputAction::ALL
+            .into_iter()
+            .find(|other| *other != action && self.key(*other) == key)
+        {
+            *self.key_mut(other) = old_key;
+        }
+        *self.key_mut(action) = key;
+    }
+
+    /// Whether two actions share a key
+    fn has_conflicts(&self) -> bool {
+        InputAction::ALL.iter().enumerate().any(|(index, action)| {
+            InputAction::ALL[index + 1..]
+                .iter()
+                .any(|other| self.key(*action) == self.key(*other))
+        })
+    }
+}
+
+/// A setting shown on the settings screen, apart from the key bindings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SettingKind {
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    WindowMode,
+    Resolution,
+    Vsync,
+    ShowFps,
+    ScreenShake,
+    Language,
+}
+
+impl SettingKind {
+    pub const ALL: [SettingKind; 9] = [
+        Self::MasterVolume,
+        Self::MusicVolume,
+        Self::SfxVolume,
+        Self::WindowMode,
+        Self::Resolution,
+        Self::Vsync,
+        Self::ShowFps,
+        Self::ScreenShake,
+        Self::Language,
+    ];
+
+    /// The key of the setting's name in the locale files
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::MasterVolume => "setting-volume",
+            Self::MusicVolume => "setting-music",
+            Self::SfxVolume => "setting-sounds",
+            Self::WindowMode => "setting-window",
+            Self::Resolution => "setting-resolution",
+            Self::Vsync => "setting-vsync",
+            Self::ShowFps => "setting-show-fps",
+            Self::ScreenShake => "setting-screen-shake",
+            Self::Language => "setting-language",
+        }
+    }
+}
+
+/// The player's settings, saved to the platform's config directory
+///
+/// Anything missing from the file falls back to its default, and values that
+/// make no sense are fixed up by [validated](Settings::validated).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Resource)]
+#[serde(default)]
+pub struct Settings {
+    /// From zero to one, like the other volumes
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub window_mode: WindowMode,
+    /// The width and height of the window when it's windowed
+    pub resolution: [u32; 2],
+    pub vsync: bool,
+    pub key_bindings: KeyBindings,
+    pub show_fps: bool,
+    /// How much the camera shakes, from zero to one
+    pub screen_shake: f32,
+    /// The code of the language the game is played in, one of [LANGUAGES]
+    pub language: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 0.7,
+            sfx_volume: 0.8,
+            window_mode: WindowMode::Windowed,
+            resolution: RESOLUTIONS[0],
+            vsync: true,
+            key_bindings: KeyBindings::default(),
+            show_fps: false,
+            screen_shake: 1.0,
+            language: FALLBACK_LANGUAGE.into(),
+        }
+    }
+}
+
+/// Keeps a fraction between zero and one, falling back when it isn't a number
+fn unit(value: f32, fallback: f32) -> f32 {
+    if value.is_finite() {
+        value.clamp(0.0, 1.0)
+    } else {
+        fallback
+    }
+}
+
+/// Steps a fraction, snapping it to the step so rounding errors don't pile up
+fn step_unit(value: f32, step: i32) -> f32 {
+    let steps = (value / SETTING_STEP).round() as i32 + step;
+    (steps as f32 * SETTING_STEP).clamp(0.0, 1.0)
+}
+
+/// Picks the option after or before the current one, wrapping around
+fn cycle<T: PartialEq + Copy>(options: &[T], current: T, step: i32) -> T {
+    let index = options
+        .iter()
+        .position(|option| *option == current)
+        .unwrap_or_default() as i32;
+    options[(index + step).rem_euclid(options.len() as i32) as usize]
+}
+
+impl Settings {
+    /// Fixes up anything that makes no sense, like volumes out of range or
+    /// tiny windows, returning what had to be fixed
+    pub fn validated(mut self) -> (Self, Vec<String>) {
+        let default = Self::default();
+        let mut problems = vec![];
+
+        for (name, value, fallback) in [
+            (
+                "master_volume",
+                &mut self.master_volume,
+                default.master_volume,
+            ),
+            ("music_volume", &mut self.music_volume, default.music_volume),
+            ("sfx_volume", &mut self.sfx_volume, default.sfx_volume),
+            ("screen_shake", &mut self.screen_shake, default.screen_shake),
+        ] {
+            let fixed = unit(*value, fallback);
+            if fixed != *value {
+                problems.push(format!("{} was {}", name, value));
+                *value = fixed;
+            }
+        }
+
+        let [width, height] = self.resolution;
+        if !(640..=7680).contains(&width) || !(360..=4320).contains(&height) {
+            problems.push(format!("resolution was {}x{}", width, height));
+            self.resolution = default.resolution;
+        }
+
+        if !LANGUAGES.contains(&self.language.as_str()) {
+            problems.push(format!("language was {:?}", self.language));
+            self.language = default.language;
+        }
+
+        if self.key_bindings.has_conflicts() {
+            problems.push("two actions shared a key".into());
+            self.key_bindings = default.key_bindings;
+        }
+
+        (self, problems)
+    }
+
+    /// Changes a setting by a number of steps, cycling through the options
+    /// of the ones that have them
+    pub fn change(&mut self, setting: SettingKind, step: i32) {
+        match setting {
+            SettingKind::MasterVolume => {
+                self.master_volume = step_unit(self.master_volume, step);
+            }
+            SettingKind::MusicVolume => self.music_volume = step_unit(self.music_volume, step),
+            SettingKind::SfxVolume => self.sfx_volume = step_unit(self.sfx_volume, step),
+            SettingKind::ScreenShake => self.screen_shake = step_unit(self.screen_shake, step),
+            SettingKind::WindowMode => {
+                self.window_mode = cycle(&WINDOW_MODES, self.window_mode, step);
+            }
+            SettingKind::Resolution => self.resolution = cycle(&RESOLUTIONS, self.resolution, step),
+            SettingKind::Vsync => self.vsync = !self.vsync,
+            SettingKind::ShowFps => self.show_fps = !self.show_fps,
+            SettingKind::Language => {
+                self.language = cycle(&LANGUAGES, self.language.as_str(), step).into();
+            }
+        }
+    }
+
+    /// Describes the value of a setting for the settings screen
+    ///
+    /// Languages are described by the `language-name` message, which every
+    /// locale has in its own language.
+    pub fn describe(&self, setting: SettingKind) -> LocalizedText {
+        let percent = |value: f32| {
+            LocalizedText::new("setting-percent").with_arg("value", (value * 100.0).round())
+        };
+        let toggle =
+            |value: bool| LocalizedText::new(if value { "setting-on" } else { "setting-off" });
+
+        match setting {
+            SettingKind::MasterVolume => percent(self.master_volume),
+            SettingKind::MusicVolume => percent(self.music_volume),
+            SettingKind::SfxVolume => percent(self.sfx_volume),
+            SettingKind::ScreenShake => percent(self.screen_shake),
+            SettingKind::WindowMode => LocalizedText::new(match self.window_mode {
+                WindowMode::Windowed => "window-windowed",
+                WindowMode::BorderlessFullscreen => "window-borderless",
+                WindowMode::SizedFullscreen | WindowMode::Fullscreen => "window-fullscreen",
+            }),
+            SettingKind::Resolution => LocalizedText::new("setting-resolution-value")
+                .with_arg("width", self.resolution[0])
+                .with_arg("height", self.resolution[1]),
+            SettingKind::Vsync => toggle(self.vsync),
+            SettingKind::ShowFps => toggle(self.show_fps),
+            SettingKind::Language => LocalizedText::new("language-name"),
+        }
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+
+    /// Sets up a window to match the settings
+    pub fn apply_to_window(&self, window: &mut Window) {
+        if window.mode != self.window_mode {
+            window.mode = self.window_mode;
+        }
+        let [width, height] = self.resolution;
+        if window.resolution.width() != width as f32 || window.resolution.height() != height as f32
+        {
+            window.resolution.set(width as f32, height as f32);
+        }
+        if window.present_mode != self.present_mode() {
+            window.present_mode = self.present_mode();
+        }
+    }
+}
+
+/// Where the settings were loaded from and saved to
+#[derive(Debug, Default, Resource)]
+pub struct SettingsFile {
+    /// The path of the file, or `None` when the platform has no config
+    /// directory and settings aren't saved
+    pub path: Option<PathBuf>,
+    /// What was wrong with the file when it was loaded, reported once the
+    /// game's logging is up
+    pub problems: Vec<String>,
+}
+
+/// The action waiting for the player to press its new key, if any
+#[derive(Debug, Default, Resource)]
+pub struct Rebinding(pub Option<InputAction>);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bad_values_fall_back() {
+        let settings = Settings {
+            master_volume: 3.0,
+            music_volume: f32::NAN,
+            resolution: [12, 0],
+            ..default()
+        };
+
+        let (settings, problems) = settings.validated();
+        assert_eq!(settings.master_volume, 1.0);
+        assert_eq!(settings.music_volume, Settings::default().music_volume);
+        assert_eq!(settings.resolution, RESOLUTIONS[0]);
+        assert_eq!(problems.len(), 3);
+
+        let (_, problems) = Settings::default().validated();
+        assert!(problems.is_empty());
+    }
+
+    #[test]
+    fn binding_a_used_key_swaps_keys() {
+        let mut bindings = KeyBindings::default();
+
+        bindings.bind(InputAction::Attack, KEYMAP::INTERACT);
+        assert_eq!(bindings.attack, KEYMAP::INTERACT);
+        assert_eq!(bindings.interact, KEYMAP::ATTACK);
+        assert!(!bindings.has_conflicts());
+
+        bindings.forward = bindings.left;
+        let (settings, _) = Settings {
+            key_bindings: bindings,
+            ..default()
+        }
+        .validated();
+        assert_eq!(settings.key_bindings, KeyBindings::default());
+    }
+
+    #[test]
+    fn changing_settings_steps_and_cycles() {
+        let mut settings = Settings::default();
+
+        settings.change(SettingKind::MasterVolume, 1);
+        assert_eq!(
+            settings.describe(SettingKind::MasterVolume),
+            LocalizedText::new("setting-percent").with_arg("value", 100.0)
+        );
+        for _ in 0..3 {
+            settings.change(SettingKind::MasterVolume, -1);
+        }
+        assert_eq!(
+            settings.describe(SettingKind::MasterVolume),
+            LocalizedText::new("setting-percent").with_arg("value", 70.0)
+        );
+
+        settings.change(SettingKind::Resolution, -1);
+        assert_eq!(settings.resolution, RESOLUTIONS[RESOLUTIONS.len() - 1]);
+        settings.change(SettingKind::WindowMode, 1);
+        assert_eq!(settings.window_mode, WindowMode::BorderlessFullscreen);
+        settings.change(SettingKind::Vsync, 1);
+        assert_eq!(
+            settings.describe(SettingKind::Vsync),
+            LocalizedText::new("setting-off")
+        );
+        settings.change(SettingKind::Language, -1);
+        assert_eq!(settings.language, LANGUAGES[LANGUAGES.len() - 1]);
+    }
+}
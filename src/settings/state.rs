@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+
+/// Whether the settings screen is open, over the start screen or the pause
+/// menu
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, States)]
+pub enum SettingsMenuState {
+    #[default]
+    Closed,
+    Open,
+}
@@ -0,0 +1,367 @@
+//! Defines all of the systems used by the settings plugin
+
+use super::{
+    components::{SettingValueText, SettingsButton, SettingsMenuNode},
+    resources::{InputAction, Rebinding, SettingKind, Settings, SettingsFile},
+    state::SettingsMenuState,
+    util::save_settings,
+};
+use crate::{
+    locale::{components::LocalizedText, resources::Localization},
+    menu::{
+        builder::{side_step, spawn_menu_item, Menu, MenuActionEvent},
+        util::localized_text,
+    },
+    theme::{
+        components::{ThemedBackground, ThemedText},
+        resources::CurrentTheme,
+    },
+};
+use bevy::{prelude::*, window::PrimaryWindow};
+
+const TITLE_TEXT: ThemedText = ThemedText::new("heading", "heading", "text");
+const SETTING_TEXT: ThemedText = ThemedText::new("heading", "settings", "text");
+const LABEL_WIDTH: f32 = 200.0;
+const VALUE_WIDTH: f32 = 180.0;
+const ROW_HEIGHT: f32 = 36.0;
+const STEP_BUTTON_SIZE: Vec2 = Vec2::new(36.0, ROW_HEIGHT);
+const BINDING_BUTTON_SIZE: Vec2 = Vec2::new(VALUE_WIDTH, ROW_HEIGHT);
+const BACK_BUTTON_SIZE: Vec2 = Vec2::new(260.0, 65.0);
+const MENU_LAYER: i32 = 20;
+
+/// Logs whatever was wrong with the settings file when it was loaded
+pub fn report_settings_problems(settings_file: Res<SettingsFile>) {
+    for problem in &settings_file.problems {
+        warn!("{}", problem);
+    }
+}
+
+/// Updates the window whenever the window settings change
+pub fn apply_window_settings(
+    settings: Res<Settings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for mut window in &mut windows {
+        settings.apply_to_window(&mut window);
+    }
+}
+
+/// Saves the settings whenever they change
+pub fn save_changed_settings(settings: Res<Settings>, settings_file: Res<SettingsFile>) {
+    if !settings.is_changed() || settings.is_added() {
+        return;
+    }
+    let Some(path) = &settings_file.path else {
+        return;
+    };
+
+    match save_settings(&settings, path) {
+        Ok(()) => debug!("Saved settings to {:?}", path),
+        Err(error) => error!("Couldn't save settings to {:?}: {}", path, error),
+    }
+}
+
+fn binding_text(key: Option<KeyCode>) -> LocalizedText {
+    match key {
+        Some(key) => LocalizedText::new("settings-key").with_arg("key", format!("{:?}", key)),
+        None => LocalizedText::new("settings-press-key"),
+    }
+}
+
+/// Spawns a row with a label on the left
+fn spawn_row(
+    parent: &mut ChildBuilder,
+    theme: &CurrentTheme,
+    localization: &Localization,
+    label: &'static str,
+    content: impl FnOnce(&mut ChildBuilder),
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                align_items: AlignItems::Center,
+                gap: Size::width(Val::Px(theme.spacing("settings_rows"))),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            let (text, themed, localized) =
+                localized_text(theme, localization, label, SETTING_TEXT);
+            parent.spawn((
+                text.with_style(Style {
+                    size: Size::width(Val::Px(LABEL_WIDTH)),
+                    ..default()
+                }),
+                themed,
+                localized,
+            ));
+            content(parent);
+        });
+}
+
+/// Spawns a column of rows
+fn spawn_column(
+    parent: &mut ChildBuilder,
+    theme: &CurrentTheme,
+    rows: impl FnOnce(&mut ChildBuilder),
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                gap: Size::height(Val::Px(theme.spacing("settings_rows"))),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(rows);
+}
+
+/// Spawns the settings screen, with the settings on the left and the key
+/// bindings on the right
+///
+/// The screen is laid out by hand, so its buttons are added to its [Menu]
+/// once they're all spawned.
+pub fn setup_settings_menu(
+    mut commands: Commands,
+    theme: Res<CurrentTheme>,
+    localization: Res<Localization>,
+    settings: Res<Settings>,
+) {
+    debug!("Opening the settings screen");
+    let mut items = Vec::new();
+
+    let mut menu = commands.spawn(NodeBundle {
+        style: Style {
+            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            position_type: PositionType::Absolute,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            gap: Size::height(Val::Px(theme.spacing("settings_sections"))),
+            ..default()
+        },
+        background_color: theme.color("backdrop").into(),
+        // Above the start screen and the pause menu
+        z_index: ZIndex::Global(MENU_LAYER),
+        ..default()
+    });
+    let menu_id = menu.id();
+
+    menu.insert((SettingsMenuNode, ThemedBackground("backdrop")))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                &theme,
+                &localization,
+                "settings-title",
+                TITLE_TEXT,
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        gap: Size::width(Val::Px(theme.spacing("settings_columns"))),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_column(parent, &theme, |parent| {
+                        for setting in SettingKind::ALL {
+                            let label = setting.label();
+                            spawn_row(parent, &theme, &localization, label, |parent| {
+                                items.push(spawn_menu_item(
+                                    parent,
+                                    menu_id,
+                                    &theme,
+                                    &localization,
+                                    "settings-decrease",
+                                    STEP_BUTTON_SIZE,
+                                    SettingsButton::Change(setting, -1),
+                                ));
+                                let (text, themed, localized) = localized_text(
+                                    &theme,
+                                    &localization,
+                                    settings.describe(setting),
+                                    SETTING_TEXT,
+                                );
+                                parent.spawn((
+                                    text.with_text_alignment(TextAlignment::Center).with_style(
+                                        Style {
+                                            size: Size::width(Val::Px(VALUE_WIDTH)),
+                                            justify_content: JustifyContent::Center,
+                                            ..default()
+                                        },
+                                    ),
+                                    themed,
+                                    localized,
+                                    SettingValueText(setting),
+                                ));
+                                items.push(spawn_menu_item(
+                                    parent,
+                                    menu_id,
+                                    &theme,
+                                    &localization,
+                                    "settings-increase",
+                                    STEP_BUTTON_SIZE,
+                                    SettingsButton::Change(setting, 1),
+                                ));
+                            });
+                        }
+                    });
+
+                    spawn_column(parent, &theme, |parent| {
+                        for action in InputAction::ALL {
+                            let label = action.label();
+                            spawn_row(parent, &theme, &localization, label, |parent| {
+                                items.push(spawn_menu_item(
+                                    parent,
+                                    menu_id,
+                                    &theme,
+                                    &localization,
+                                    binding_text(Some(settings.key_bindings.key(action))),
+                                    BINDING_BUTTON_SIZE,
+                                    SettingsButton::Rebind(action),
+                                ));
+                            });
+                        }
+                    });
+                });
+
+            items.push(spawn_menu_item(
+                parent,
+                menu_id,
+                &theme,
+                &localization,
+                "menu-back",
+                BACK_BUTTON_SIZE,
+                SettingsButton::Back,
+            ));
+        });
+
+    commands
+        .entity(menu_id)
+        .insert(Menu::new(MENU_LAYER, items));
+}
+
+/// Despawns the settings screen
+pub fn cleanup_settings_menu(
+    mut commands: Commands,
+    mut rebinding: ResMut<Rebinding>,
+    menu_query: Query<Entity, With<SettingsMenuNode>>,
+) {
+    rebinding.0 = None;
+    menu_query.for_each(|node| commands.entity(node).despawn_recursive());
+}
+
+/// Does whatever the picked settings button does
+pub fn update_settings_buttons(
+    mut action_events: EventReader<MenuActionEvent<SettingsButton>>,
+    mut settings: ResMut<Settings>,
+    mut rebinding: ResMut<Rebinding>,
+    mut next_settings_state: ResMut<NextState<SettingsMenuState>>,
+) {
+    for event in action_events.iter() {
+        match event.action {
+            SettingsButton::Change(setting, step) => settings.change(setting, step),
+            SettingsButton::Rebind(action) => rebinding.0 = Some(action),
+            SettingsButton::Back => next_settings_state.set(SettingsMenuState::Closed),
+        }
+    }
+}
+
+/// Changes the setting of the focused row with Left and Right, the same as
+/// picking its decrease and increase buttons
+pub fn adjust_focused_setting(
+    keys: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut settings: ResMut<Settings>,
+    menu_query: Query<&Menu, With<SettingsMenuNode>>,
+    button_query: Query<&SettingsButton>,
+) {
+    let step = side_step(&keys, &gamepad_buttons);
+    if step == 0 {
+        return;
+    }
+
+    let focused = menu_query
+        .get_single()
+        .ok()
+        .and_then(|menu| menu.focused())
+        .and_then(|item| button_query.get(item).ok());
+    if let Some(SettingsButton::Change(setting, _)) = focused {
+        settings.change(*setting, step);
+    }
+}
+
+/// Binds the next key pressed to the action waiting for one, with Escape
+/// keeping the old key
+pub fn capture_rebind(
+    mut keys: ResMut<Input<KeyCode>>,
+    mut settings: ResMut<Settings>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+    let Some(key) = keys.get_just_pressed().next().copied() else {
+        return;
+    };
+
+    // Keep the key from doing anything else, like closing the settings
+    keys.clear_just_pressed(key);
+    rebinding.0 = None;
+    if key != KeyCode::Escape {
+        info!("Binding {:?} to {:?}", action, key);
+        settings.key_bindings.bind(action, key);
+    }
+}
+
+/// Closes the settings screen when Escape is pressed
+pub fn close_settings_on_escape(
+    keys: Res<Input<KeyCode>>,
+    rebinding: Res<Rebinding>,
+    mut next_settings_state: ResMut<NextState<SettingsMenuState>>,
+) {
+    if rebinding.0.is_none() && keys.just_pressed(KeyCode::Escape) {
+        next_settings_state.set(SettingsMenuState::Closed);
+    }
+}
+
+/// Shows the current values on the settings screen
+pub fn update_settings_text(
+    settings: Res<Settings>,
+    rebinding: Res<Rebinding>,
+    localization: Res<Localization>,
+    mut value_query: Query<(&SettingValueText, &mut LocalizedText, &mut Text)>,
+    button_query: Query<(&SettingsButton, &Children)>,
+    mut text_query: Query<(&mut LocalizedText, &mut Text), Without<SettingValueText>>,
+) {
+    if !settings.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+
+    let show = |localized: &mut LocalizedText, text: &mut Text, value: LocalizedText| {
+        text.sections[0].value = localization.format(&value);
+        *localized = value;
+    };
+    for (value, mut localized, mut text) in &mut value_query {
+        show(&mut localized, &mut text, settings.describe(value.0));
+    }
+    for (button, children) in &button_query {
+        let SettingsButton::Rebind(action) = *button else {
+            continue;
+        };
+
+        let key = (rebinding.0 != Some(action)).then(|| settings.key_bindings.key(action));
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some((mut localized, mut text)) = texts.fetch_next() {
+            show(&mut localized, &mut text, binding_text(key));
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use super::resources::{Settings, SettingsFile, CONFIG_DIR_NAME, SETTINGS_FILE_NAME};
+use std::{fs, io::ErrorKind, path::Path};
+
+/// Reads settings from RON, fixing up anything that makes no sense
+pub fn parse_settings(source: &str) -> Result<(Settings, Vec<String>), String> {
+    ron::from_str::<Settings>(source)
+        .map(Settings::validated)
+        .map_err(|error| error.to_string())
+}
+
+/// Loads the settings from the platform's config directory
+///
+/// This runs before the app is built, as the settings pick the window's size
+/// and mode, so nothing is logged here. What went wrong is kept in the
+/// [SettingsFile] to be reported later. A missing file gives the defaults
+/// and a corrupt one falls back to them.
+pub fn load_settings() -> (Settings, SettingsFile) {
+    let path = dirs::config_dir().map(|dir| dir.join(CONFIG_DIR_NAME).join(SETTINGS_FILE_NAME));
+    let Some(file_path) = &path else {
+        return (
+            Settings::default(),
+            SettingsFile {
+                path,
+                problems: vec!["There is no config directory to keep settings in".into()],
+            },
+        );
+    };
+
+    let (settings, problems) = match fs::read_to_string(file_path) {
+        Ok(source) => match parse_settings(&source) {
+            Ok((settings, problems)) => (
+                settings,
+                problems
+                    .into_iter()
+                    .map(|problem| format!("Fixed settings, {}", problem))
+                    .collect(),
+            ),
+            Err(error) => (
+                Settings::default(),
+                vec![format!(
+                    "Settings in {:?} are corrupt, using the defaults: {}",
+                    file_path, error
+                )],
+            ),
+        },
+        Err(error) if error.kind() == ErrorKind::NotFound => (Settings::default(), vec![]),
+        Err(error) => (
+            Settings::default(),
+            vec![format!(
+                "Couldn't read settings from {:?}: {}",
+                file_path, error
+            )],
+        ),
+    };
+
+    (settings, SettingsFile { path, problems })
+}
+
+/// Saves the settings as RON, making the config directory if needed
+pub fn save_settings(settings: &Settings, path: &Path) -> Result<(), String> {
+    let source = ron::ser::to_string_pretty(settings, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+    }
+    fs::write(path, source).map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip_and_fill_in_defaults() {
+        let mut settings = Settings {
+            show_fps: true,
+            ..Settings::default()
+        };
+        settings.key_bindings.attack = bevy::prelude::KeyCode::J;
+        let source =
+            ron::ser::to_string_pretty(&settings, ron::ser::PrettyConfig::default()).unwrap();
+        assert_eq!(parse_settings(&source).unwrap(), (settings, vec![]));
+
+        // Anything missing is left at its default
+        let (settings, problems) = parse_settings("(sfx_volume: 5.0)").unwrap();
+        assert_eq!(settings.sfx_volume, 1.0);
+        assert_eq!(settings.resolution, Settings::default().resolution);
+        assert_eq!(problems.len(), 1);
+
+        assert!(parse_settings("(master_volume: \"loud\"").is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The colors every theme has to define
+pub const THEME_COLORS: [&str; 16] = [
+    "accent",
+    "button",
+    "button_focus",
+    "button_text",
+    "text",
+    "muted",
+    "danger",
+    "fps",
+    "background",
+    "overlay",
+    "backdrop",
+    "dialogue_panel",
+    "inventory_panel",
+    "inventory_slot",
+    "inventory_slot_focus",
+    "inventory_slot_held",
+];
+/// The fonts every theme has to define
+pub const THEME_FONTS: [&str; 2] = ["heading", "body"];
+/// The font sizes every theme has to define
+pub const THEME_SIZES: [&str; 15] = [
+    "title",
+    "heading",
+    "button",
+    "menu_line",
+    "settings",
+    "fps",
+    "dialogue_name",
+    "dialogue_text",
+    "tracker_title",
+    "tracker_text",
+    "story",
+    "trigger_text",
+    "prompt",
+    "inventory",
+    "debug",
+];
+/// The gaps and padding every theme has to define
+pub const THEME_SPACING: [&str; 9] = [
+    "menu",
+    "menu_lines",
+    "settings_sections",
+    "settings_columns",
+    "settings_rows",
+    "dialogue_padding",
+    "dialogue_choices",
+    "tracker",
+    "inventory_padding",
+];
+
+/// A theme as it's written in its file, with colors as hex strings and fonts
+/// as paths
+#[derive(Debug, Default, Deserialize)]
+pub struct ThemeFile {
+    #[serde(default)]
+    pub colors: HashMap<String, String>,
+    #[serde(default)]
+    pub fonts: HashMap<String, String>,
+    #[serde(default)]
+    pub sizes: HashMap<String, f32>,
+    #[serde(default)]
+    pub spacing: HashMap<String, f32>,
+}
+
+impl ThemeFile {
+    /// Checks that every name the UI uses is defined and that every color
+    /// can be read
+    pub fn validate(&self) -> Result<(), String> {
+        fn undefined<'a, T>(
+            kind: &'a str,
+            names: &'a [&'a str],
+            defined: &'a HashMap<String, T>,
+        ) -> impl Iterator<Item = (&'a str, &'a str)> {
+            names
+                .iter()
+                .filter(|name| !defined.contains_key(**name))
+                .map(move |name| (kind, *name))
+        }
+
+        let missing = undefined("color", &THEME_COLORS, &self.colors)
+            .chain(undefined("font", &THEME_FONTS, &self.fonts))
+            .chain(undefined("size", &THEME_SIZES, &self.sizes))
+            .chain(undefined("spacing", &THEME_SPACING, &self.spacing))
+            .next();
+        if let Some((kind, name)) = missing {
+            return Err(format!("the theme doesn't define the {kind} {name:?}"));
+        }
+
+        for (name, hex) in &self.colors {
+            if Color::hex(hex).is_err() {
+                return Err(format!("the color {name:?} isn't a hex color: {hex:?}"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The look of the UI, with named colors, fonts, font sizes and spacing
+///
+/// Names that aren't defined fall back to white text, the default font, a
+/// font size of 16 and no spacing.
+#[derive(TypeUuid, Debug, Default, Clone)]
+#[uuid = "6b1f3c2e-8d47-4f0a-9e15-2c7a94d0b3f8"]
+pub struct UiTheme {
+    pub colors: HashMap<String, Color>,
+    pub fonts: HashMap<String, Handle<Font>>,
+    pub sizes: HashMap<String, f32>,
+    pub spacing: HashMap<String, f32>,
+}
+
+impl UiTheme {
+    pub fn color(&self, name: &str) -> Color {
+        self.colors.get(name).copied().unwrap_or(Color::WHITE)
+    }
+
+    pub fn font(&self, name: &str) -> Handle<Font> {
+        self.fonts.get(name).cloned().unwrap_or_default()
+    }
+
+    pub fn size(&self, name: &str) -> f32 {
+        self.sizes.get(name).copied().unwrap_or(16.0)
+    }
+
+    pub fn spacing(&self, name: &str) -> f32 {
+        self.spacing.get(name).copied().unwrap_or_default()
+    }
+
+    /// Builds a text style out of named parts of the theme
+    pub fn text_style(&self, font: &str, size: &str, color: &str) -> TextStyle {
+        TextStyle {
+            font: self.font(font),
+            font_size: self.size(size),
+            color: self.color(color),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct UiThemeLoader;
+
+impl AssetLoader for UiThemeLoader {
+    /// Load a theme along with its fonts
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext<'_>,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let file: ThemeFile = ron::de::from_bytes(bytes)?;
+            file.validate().map_err(bevy::asset::Error::msg)?;
+
+            let mut font_paths = vec![];
+            let mut fonts = HashMap::new();
+            for (name, path) in file.fonts {
+                let path = AssetPath::from(path.as_str()).to_owned();
+                fonts.insert(name, load_context.get_handle(path.clone()));
+                font_paths.push(path);
+            }
+
+            let theme = UiTheme {
+                colors: file
+                    .colors
+                    .into_iter()
+                    .filter_map(|(name, hex)| Some((name, Color::hex(hex).ok()?)))
+                    .collect(),
+                fonts,
+                sizes: file.sizes,
+                spacing: file.spacing,
+            };
+
+            debug!("Loaded UI theme {:?}", load_context.path());
+            load_context.set_default_asset(LoadedAsset::new(theme).with_dependencies(font_paths));
+
+            Ok(())
+        })
+    }
+
+    /// Returns a list of supported extensions
+    fn extensions(&self) -> &[&str] {
+        &["theme.ron"]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn the_default_theme_is_complete() {
+        let file: ThemeFile =
+            ron::from_str(include_str!("../../assets/ui/default.theme.ron")).unwrap();
+        assert_eq!(file.validate(), Ok(()));
+    }
+
+    #[test]
+    fn broken_themes_are_caught() {
+        let mut file: ThemeFile =
+            ron::from_str(include_str!("../../assets/ui/default.theme.ron")).unwrap();
+        file.colors.insert("accent".into(), "yellow".into());
+        assert!(file.validate().unwrap_err().contains("accent"));
+
+        file.colors.remove("accent");
+        assert_eq!(
+            file.validate(),
+            Err("the theme doesn't define the color \"accent\"".into())
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+/// Text whose font, size and color come from the theme, restyled whenever
+/// the theme changes
+#[derive(Debug, Clone, Copy, Component)]
+pub struct ThemedText {
+    pub font: &'static str,
+    pub size: &'static str,
+    pub color: &'static str,
+    /// The largest the font can get, for text that has to fit in small
+    /// buttons
+    pub max_size: f32,
+}
+
+impl ThemedText {
+    pub const fn new(font: &'static str, size: &'static str, color: &'static str) -> Self {
+        Self {
+            font,
+            size,
+            color,
+            max_size: f32::INFINITY,
+        }
+    }
+
+    pub const fn with_max_size(mut self, max_size: f32) -> Self {
+        self.max_size = max_size;
+        self
+    }
+}
+
+/// A UI node whose background color comes from the theme, recolored
+/// whenever the theme changes
+#[derive(Debug, Clone, Copy, Component)]
+pub struct ThemedBackground(pub &'static str);
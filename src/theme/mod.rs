@@ -0,0 +1,14 @@
+//! Module for the look of the UI
+//!
+//! This module includes:
+//! - An asset with named colors, fonts, font sizes and spacing, loaded from
+//!   a RON file
+//! - A resource holding the theme in use, which every UI system reads
+//! - Components restyling UI already on screen when the theme file changes
+//! - A plugin to setup the required systems and loaders
+
+pub mod asset;
+pub mod components;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
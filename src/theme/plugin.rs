@@ -0,0 +1,30 @@
+use super::{
+    asset::{UiTheme, UiThemeLoader},
+    resources::{CurrentTheme, UiThemeHandle},
+    systems::{apply_loaded_ui_theme, apply_ui_theme, load_ui_theme, reload_ui_theme},
+};
+use crate::menu::state::GameMenuState;
+use bevy::prelude::*;
+
+/// Loads the look of the UI and keeps it up to date with the theme file
+///
+/// The game waits in [Loading](GameMenuState::Loading) until the theme is
+/// loaded, so the start screen never shows unstyled.
+#[derive(Debug, Default)]
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<UiTheme>();
+        app.init_asset_loader::<UiThemeLoader>();
+
+        app.init_resource::<UiThemeHandle>();
+        app.init_resource::<CurrentTheme>();
+
+        app.add_startup_system(load_ui_theme);
+        app.add_system(apply_loaded_ui_theme.in_schedule(OnExit(GameMenuState::Loading)));
+        app.add_system(reload_ui_theme);
+        app.add_system(apply_ui_theme.after(reload_ui_theme));
+        debug!("ThemePlugin loaded");
+    }
+}
@@ -0,0 +1,33 @@
+use super::{asset::UiTheme, components::ThemedText};
+use bevy::prelude::*;
+
+/// Path to the file with the look of the UI
+pub const UI_THEME_PATH: &str = "ui/default.theme.ron";
+
+/// Handle to the theme file, kept around so it reloads when it changes
+#[derive(Debug, Default, Resource)]
+pub struct UiThemeHandle(pub Handle<UiTheme>);
+
+/// The theme the UI is drawn with
+///
+/// This is a copy of the loaded [UiTheme], so UI can be spawned without
+/// looking the asset up.
+#[derive(Debug, Default, Resource, Deref)]
+pub struct CurrentTheme(pub UiTheme);
+
+impl CurrentTheme {
+    /// Builds the text style of themed text
+    pub fn themed_text_style(&self, text: ThemedText) -> TextStyle {
+        let mut style = self.text_style(text.font, text.size, text.color);
+        style.font_size = style.font_size.min(text.max_size);
+        style
+    }
+
+    /// A bundle of themed text, which keeps up with changes to the theme
+    pub fn text(&self, value: impl Into<String>, text: ThemedText) -> (TextBundle, ThemedText) {
+        (
+            TextBundle::from_section(value, self.themed_text_style(text)),
+            text,
+        )
+    }
+}
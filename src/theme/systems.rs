@@ -0,0 +1,76 @@
+//! Defines all of the systems used by the theme plugin
+
+use super::{
+    asset::UiTheme,
+    components::{ThemedBackground, ThemedText},
+    resources::{CurrentTheme, UiThemeHandle, UI_THEME_PATH},
+};
+use crate::menu::resources::LoadingAssets;
+use bevy::prelude::*;
+
+/// Starts loading the theme file, which the start screen waits on
+pub fn load_ui_theme(
+    mut theme: ResMut<UiThemeHandle>,
+    mut loading: ResMut<LoadingAssets>,
+    asset_server: Res<AssetServer>,
+) {
+    theme.0 = asset_server.load(UI_THEME_PATH);
+    loading.0.push(theme.0.clone_untyped());
+}
+
+/// Switches to the loaded theme before the start screen shows
+///
+/// A theme that fails to load leaves the UI with the fallback look rather
+/// than keeping the game from starting.
+pub fn apply_loaded_ui_theme(
+    handle: Res<UiThemeHandle>,
+    themes: Res<Assets<UiTheme>>,
+    mut current: ResMut<CurrentTheme>,
+) {
+    match themes.get(&handle.0) {
+        Some(theme) => current.0 = theme.clone(),
+        None => warn!("Drawing the UI without a theme"),
+    }
+}
+
+/// Switches to the theme file's new look whenever it changes
+pub fn reload_ui_theme(
+    mut theme_events: EventReader<AssetEvent<UiTheme>>,
+    handle: Res<UiThemeHandle>,
+    themes: Res<Assets<UiTheme>>,
+    mut current: ResMut<CurrentTheme>,
+) {
+    for event in theme_events.iter() {
+        let AssetEvent::Modified { handle: modified } = event else {
+            continue;
+        };
+        if *modified != handle.0 {
+            continue;
+        }
+        if let Some(theme) = themes.get(modified) {
+            info!("Reloaded the UI theme");
+            current.0 = theme.clone();
+        }
+    }
+}
+
+/// Restyles the themed UI on screen whenever the theme changes
+pub fn apply_ui_theme(
+    theme: Res<CurrentTheme>,
+    mut text_query: Query<(&ThemedText, &mut Text)>,
+    mut background_query: Query<(&ThemedBackground, &mut BackgroundColor)>,
+) {
+    if !theme.is_changed() || theme.is_added() {
+        return;
+    }
+
+    for (themed, mut text) in &mut text_query {
+        let style = theme.themed_text_style(*themed);
+        for section in &mut text.sections {
+            section.style = style.clone();
+        }
+    }
+    for (themed, mut background) in &mut background_query {
+        *background = theme.color(themed.0).into();
+    }
+}
@@ -0,0 +1,87 @@
+//! # Actions
+//!
+//! What a trigger does when it fires. The `on_enter`, `on_exit` and
+//! `on_stay` properties of a trigger object hold a RON list of actions, for
+//! example:
+//!
+//! ```ron
+//! [ShowText("text-ground-shakes"), SpawnEnemies(kind: "cave_rat", count: 3)]
+//! ```
+
+use serde::Deserialize;
+
+/// Something a trigger does when it fires
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum TriggerAction {
+    /// Shows the message with the given id at the bottom of the screen for a
+    /// while
+    ShowText(String),
+    /// Plays the sound at the given path
+    PlaySound(String),
+    /// Spawns enemies of a kind at the map object with the id in `at`, or
+    /// in the middle of the trigger when there is none
+    SpawnEnemies {
+        kind: String,
+        #[serde(default = "default_count")]
+        count: u32,
+        #[serde(default)]
+        at: Option<u32>,
+    },
+    /// Opens the door with the given map object id
+    OpenDoor(u32),
+    /// Closes the door with the given map object id
+    CloseDoor(u32),
+    /// Starts the conversation at the given path
+    StartDialogue(String),
+    /// Takes the player to the map at the given path
+    ChangeMap(String),
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+/// Reads a list of actions from a trigger property
+pub fn parse_actions(source: &str) -> Result<Vec<TriggerAction>, ron::error::SpannedError> {
+    ron::from_str(source)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn actions_are_parsed() {
+        let actions = parse_actions(
+            r#"[
+                ShowText("text-ground-shakes"),
+                SpawnEnemies(kind: "cave_rat", count: 3),
+                SpawnEnemies(kind: "cave_troll", at: Some(12)),
+                OpenDoor(4),
+                PlaySound("audio/hit.ogg"),
+                ChangeMap("tiled/deep_halls.tmx"),
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(actions.len(), 6);
+        assert_eq!(
+            actions[1],
+            TriggerAction::SpawnEnemies {
+                kind: "cave_rat".into(),
+                count: 3,
+                at: None,
+            }
+        );
+        assert_eq!(
+            actions[2],
+            TriggerAction::SpawnEnemies {
+                kind: "cave_troll".into(),
+                count: 1,
+                at: Some(12),
+            }
+        );
+        assert_eq!(actions[4], TriggerAction::PlaySound("audio/hit.ogg".into()));
+        assert!(parse_actions("[Explode]").is_err());
+    }
+}
@@ -0,0 +1,211 @@
+use super::{actions::TriggerAction, resources::STAY_INTERVAL};
+use crate::{dialogue::asset::Dialogue, script::asset::Script};
+use bevy::prelude::*;
+
+/// When a trigger fires
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerHook {
+    Enter,
+    Exit,
+    Stay,
+}
+
+impl TriggerHook {
+    /// The name of the function called on the trigger's script
+    pub fn function(&self) -> &'static str {
+        match self {
+            Self::Enter => "on_enter",
+            Self::Exit => "on_exit",
+            Self::Stay => "on_stay",
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// A sensor that runs actions as the player enters, leaves and stays inside
+/// it
+#[derive(Debug, Clone, Component)]
+pub struct Trigger {
+    pub on_enter: Vec<TriggerAction>,
+    pub on_exit: Vec<TriggerAction>,
+    pub on_stay: Vec<TriggerAction>,
+    /// The script whose `on_enter`, `on_exit` and `on_stay` functions are
+    /// called along with the actions
+    pub script: Option<Handle<Script>>,
+    /// Whether each hook fires once only, rather than on every entry
+    pub once: bool,
+    /// How often the `on_stay` hook fires while the player stays inside, in
+    /// seconds
+    pub stay_interval: f32,
+    /// Conversations the actions start, loaded ahead of time so they are
+    /// ready when the trigger fires
+    pub dialogues: Vec<Handle<Dialogue>>,
+    inside: bool,
+    stayed: f32,
+    /// Which of the hooks already fired
+    fired: [bool; 3],
+}
+
+impl Default for Trigger {
+    fn default() -> Self {
+        Self {
+            on_enter: vec![],
+            on_exit: vec![],
+            on_stay: vec![],
+            script: None,
+            once: false,
+            stay_interval: STAY_INTERVAL,
+            dialogues: vec![],
+            inside: false,
+            stayed: 0.0,
+            fired: [false; 3],
+        }
+    }
+}
+
+impl Trigger {
+    pub fn is_inside(&self) -> bool {
+        self.inside
+    }
+
+    /// The actions run when a hook fires
+    pub fn actions(&self, hook: TriggerHook) -> &[TriggerAction] {
+        match hook {
+            TriggerHook::Enter => &self.on_enter,
+            TriggerHook::Exit => &self.on_exit,
+            TriggerHook::Stay => &self.on_stay,
+        }
+    }
+
+    /// Whether anything happens when a hook fires
+    fn has_hook(&self, hook: TriggerHook) -> bool {
+        self.script.is_some() || !self.actions(hook).is_empty()
+    }
+
+    /// Lets the player walk in, returning the hook that fired, if any
+    pub fn enter(&mut self) -> Option<TriggerHook> {
+        if self.inside {
+            return None;
+        }
+        self.inside = true;
+        self.stayed = 0.0;
+        self.fire(TriggerHook::Enter)
+    }
+
+    /// Lets the player walk out, returning the hook that fired, if any
+    pub fn exit(&mut self) -> Option<TriggerHook> {
+        if !self.inside {
+            return None;
+        }
+        self.inside = false;
+        self.fire(TriggerHook::Exit)
+    }
+
+    /// Keeps the player inside for another `delta` seconds, returning the
+    /// hook that fired, if any
+    pub fn stay(&mut self, delta: f32) -> Option<TriggerHook> {
+        if !self.inside || !self.has_hook(TriggerHook::Stay) {
+            return None;
+        }
+
+        self.stayed += delta;
+        if self.stayed < self.stay_interval {
+            return None;
+        }
+        self.stayed -= self.stay_interval;
+        self.fire(TriggerHook::Stay)
+    }
+
+    fn fire(&mut self, hook: TriggerHook) -> Option<TriggerHook> {
+        if (self.once && self.fired[hook.index()]) || !self.has_hook(hook) {
+            return None;
+        }
+
+        self.fired[hook.index()] = true;
+        Some(hook)
+    }
+}
+
+/// Marks the text shown by a trigger, along with how long it has left
+#[derive(Debug, Component)]
+pub struct TriggerTextNode {
+    pub remaining: f32,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn trigger(once: bool) -> Trigger {
+        Trigger {
+            on_enter: vec![TriggerAction::ShowText("Hello".into())],
+            on_exit: vec![TriggerAction::OpenDoor(3)],
+            on_stay: vec![TriggerAction::PlaySound("drip".into())],
+            once,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn fires_on_every_entry() {
+        let mut trigger = trigger(false);
+
+        for _ in 0..2 {
+            assert_eq!(trigger.enter(), Some(TriggerHook::Enter));
+            // Entering again while inside does nothing
+            assert_eq!(trigger.enter(), None);
+            assert_eq!(trigger.exit(), Some(TriggerHook::Exit));
+            assert_eq!(trigger.exit(), None);
+        }
+        assert_eq!(
+            trigger.actions(TriggerHook::Exit),
+            &[TriggerAction::OpenDoor(3)]
+        );
+    }
+
+    #[test]
+    fn fires_once_only() {
+        let mut trigger = trigger(true);
+
+        assert_eq!(trigger.enter(), Some(TriggerHook::Enter));
+        assert_eq!(trigger.exit(), Some(TriggerHook::Exit));
+        assert_eq!(trigger.enter(), None);
+        assert_eq!(trigger.exit(), None);
+    }
+
+    #[test]
+    fn stays_fire_every_interval() {
+        let mut trigger = trigger(false);
+        assert_eq!(trigger.stay(5.0), None);
+
+        trigger.enter();
+        assert_eq!(trigger.stay(0.6), None);
+        assert_eq!(trigger.stay(0.6), Some(TriggerHook::Stay));
+        assert_eq!(trigger.stay(0.6), None);
+        assert_eq!(trigger.stay(0.6), Some(TriggerHook::Stay));
+
+        // The time spent inside starts over on every entry
+        trigger.exit();
+        trigger.enter();
+        assert_eq!(trigger.stay(0.9), None);
+    }
+
+    #[test]
+    fn hooks_without_anything_to_do_dont_fire() {
+        let mut trigger = Trigger {
+            on_exit: vec![TriggerAction::OpenDoor(3)],
+            once: true,
+            ..default()
+        };
+
+        assert_eq!(trigger.enter(), None);
+        assert_eq!(trigger.exit(), Some(TriggerHook::Exit));
+
+        // Scripts are called on every hook
+        trigger.script = Some(Handle::default());
+        assert_eq!(trigger.enter(), Some(TriggerHook::Enter));
+    }
+}
@@ -0,0 +1,30 @@
+use super::actions::TriggerAction;
+use crate::locale::components::LocalizedText;
+use bevy::prelude::*;
+
+/// Runs trigger actions, like when a trigger fires
+#[derive(Debug, Clone)]
+pub struct RunActionsEvent {
+    /// What the actions came from, like the trigger that fired, if anything
+    pub source: Option<Entity>,
+    pub actions: Vec<TriggerAction>,
+}
+
+/// Shows a line of text at the bottom of the screen for a while
+#[derive(Debug, Clone)]
+pub struct ShowTextEvent {
+    pub text: LocalizedText,
+}
+
+/// Asks for a sound effect to be played
+///
+/// The audio plugin plays these, along with the sounds of footsteps, hits
+/// and pickups.
+#[derive(Debug, Clone)]
+pub struct PlaySoundEvent {
+    /// Path to the sound
+    pub path: String,
+    /// Where the sound comes from, which makes it quieter the further it is
+    /// from the player. Sounds without a position play at full volume.
+    pub position: Option<Vec2>,
+}
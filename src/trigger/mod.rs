@@ -0,0 +1,16 @@
+//! Module for trigger volumes placed on the map
+//!
+//! This module includes:
+//! - Sensors spawned from Tiled objects of class `trigger`, which notice the
+//!   player entering, leaving and staying inside them
+//! - Actions read from the object's properties, like showing text, spawning
+//!   enemies, opening doors, starting conversations or changing map
+//! - Triggers that fire once only or on every entry
+//! - A plugin to setup the required systems and events
+
+pub mod actions;
+pub mod components;
+pub mod events;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
@@ -0,0 +1,34 @@
+use super::{
+    events::{PlaySoundEvent, RunActionsEvent, ShowTextEvent},
+    systems::{
+        close_trigger_text, fade_trigger_text, fire_triggers, run_trigger_actions,
+        show_trigger_text, spawn_map_triggers,
+    },
+};
+use crate::menu::state::GameMenuState;
+use bevy::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct TriggerPlugin;
+
+impl Plugin for TriggerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RunActionsEvent>();
+        app.add_event::<ShowTextEvent>();
+        app.add_event::<PlaySoundEvent>();
+
+        app.add_system(spawn_map_triggers);
+        app.add_systems(
+            (
+                fire_triggers,
+                run_trigger_actions,
+                show_trigger_text,
+                fade_trigger_text,
+            )
+                .chain()
+                .in_set(OnUpdate(GameMenuState::InGame)),
+        );
+        app.add_system(close_trigger_text.in_schedule(OnExit(GameMenuState::InGame)));
+        debug!("TriggerPlugin loaded");
+    }
+}
@@ -0,0 +1,9 @@
+/// The class of the Tiled objects that spawn a [Trigger](super::components::Trigger)
+pub const TRIGGER_OBJECT_CLASS: &str = "trigger";
+/// How often the `on_stay` actions fire while the player stays inside a
+/// trigger, unless its `stay_interval` property says otherwise, in seconds
+pub const STAY_INTERVAL: f32 = 1.0;
+/// How long text shown by a trigger stays on screen, in seconds
+pub const TEXT_TIME: f32 = 4.0;
+/// How long text shown by a trigger takes to fade out, in seconds
+pub const TEXT_FADE_TIME: f32 = 1.0;
@@ -0,0 +1,323 @@
+//! Defines all of the systems used by the trigger plugin
+
+use super::{
+    actions::{parse_actions, TriggerAction},
+    components::{Trigger, TriggerHook, TriggerTextNode},
+    events::{PlaySoundEvent, RunActionsEvent, ShowTextEvent},
+    resources::{TEXT_FADE_TIME, TEXT_TIME, TRIGGER_OBJECT_CLASS},
+};
+use crate::{
+    dialogue::events::StartDialogueEvent,
+    enemy::events::SpawnEnemiesEvent,
+    interaction::components::Door,
+    locale::{components::LocalizedText, resources::Localization},
+    map::{
+        areas::object_collider,
+        objects::MapObject,
+        state::{MapReadinessState, MapState},
+    },
+    menu::util::localized_text,
+    player::Player,
+    script::{events::RunScriptEvent, resources::SCRIPT_PROPERTY},
+    theme::{components::ThemedText, resources::CurrentTheme},
+};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+const TRIGGER_TEXT: ThemedText = ThemedText::new("body", "trigger_text", "text");
+
+/// Turns `trigger` objects into sensors
+///
+/// The `on_enter`, `on_exit` and `on_stay` properties hold the
+/// [actions](super::actions) to run, and the functions of the same name are
+/// called on the script in the `script` property. With the `once` property
+/// set, each of them fires only the first time. The `stay_interval` property sets how
+/// often the `on_stay` actions fire while the player stays inside.
+pub fn spawn_map_triggers(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    object_query: Query<(Entity, &MapObject), Added<MapObject>>,
+) {
+    for (entity, object) in &object_query {
+        if object.class != TRIGGER_OBJECT_CLASS {
+            continue;
+        }
+
+        let Some(collider) = object_collider(&object.shape) else {
+            warn!("Trigger object {} has no area", object.id);
+            continue;
+        };
+
+        let mut trigger = Trigger::default();
+        trigger.once = object.bool_property("once").unwrap_or(false);
+        if let Some(interval) = object.float_property("stay_interval") {
+            trigger.stay_interval = interval;
+        }
+        for (property, actions) in [
+            ("on_enter", &mut trigger.on_enter),
+            ("on_exit", &mut trigger.on_exit),
+            ("on_stay", &mut trigger.on_stay),
+        ] {
+            let Some(source) = object.string_property(property) else {
+                continue;
+            };
+            match parse_actions(source) {
+                Ok(parsed) => *actions = parsed,
+                Err(error) => warn!(
+                    "Trigger object {} has invalid {}: {}",
+                    object.id, property, error
+                ),
+            }
+        }
+
+        trigger.script = object
+            .string_property(SCRIPT_PROPERTY)
+            .map(|path| asset_server.load(path));
+        trigger.dialogues = trigger
+            .on_enter
+            .iter()
+            .chain(&trigger.on_exit)
+            .chain(&trigger.on_stay)
+            .filter_map(|action| match action {
+                TriggerAction::StartDialogue(path) => Some(asset_server.load(path.as_str())),
+                _ => None,
+            })
+            .collect();
+
+        debug!("Spawning trigger from map object {}", object.id);
+        commands
+            .entity(entity)
+            .insert(trigger)
+            .insert(collider)
+            .insert(Sensor)
+            .insert(ActiveEvents::COLLISION_EVENTS);
+    }
+}
+
+/// Fires triggers as the player enters, leaves and stays inside them,
+/// running their actions and calling their script
+pub fn fire_triggers(
+    time: Res<Time>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut action_events: EventWriter<RunActionsEvent>,
+    mut script_events: EventWriter<RunScriptEvent>,
+    mut trigger_query: Query<(Entity, &mut Trigger)>,
+    player_query: Query<(), With<Player>>,
+) {
+    let mut fire = |entity: Entity, trigger: &Trigger, hook: Option<TriggerHook>| {
+        let Some(hook) = hook else {
+            return;
+        };
+
+        let actions = trigger.actions(hook);
+        if !actions.is_empty() {
+            action_events.send(RunActionsEvent {
+                source: Some(entity),
+                actions: actions.to_vec(),
+            });
+        }
+        if let Some(script) = &trigger.script {
+            script_events.send(RunScriptEvent {
+                script: script.clone(),
+                function: Some(hook.function().into()),
+                source: Some(entity),
+            });
+        }
+    };
+
+    for event in collision_events.iter() {
+        let (a, b, entered) = match *event {
+            CollisionEvent::Started(a, b, _) => (a, b, true),
+            CollisionEvent::Stopped(a, b, _) => (a, b, false),
+        };
+
+        for (trigger_entity, player) in [(a, b), (b, a)] {
+            let (Ok((_, mut trigger)), true) = (
+                trigger_query.get_mut(trigger_entity),
+                player_query.contains(player),
+            ) else {
+                continue;
+            };
+
+            let hook = if entered {
+                trigger.enter()
+            } else {
+                trigger.exit()
+            };
+            fire(trigger_entity, &trigger, hook);
+        }
+    }
+
+    for (entity, mut trigger) in &mut trigger_query {
+        if trigger.is_inside() {
+            let hook = trigger.stay(time.delta_seconds());
+            fire(entity, &trigger, hook);
+        }
+    }
+}
+
+/// Runs the actions of every [RunActionsEvent]
+#[allow(clippy::too_many_arguments)]
+pub fn run_trigger_actions(
+    mut action_events: EventReader<RunActionsEvent>,
+    mut text_events: EventWriter<ShowTextEvent>,
+    mut sound_events: EventWriter<PlaySoundEvent>,
+    mut enemy_events: EventWriter<SpawnEnemiesEvent>,
+    mut dialogue_events: EventWriter<StartDialogueEvent>,
+    mut map_state: ResMut<MapState>,
+    mut next_map_readiness: ResMut<NextState<MapReadinessState>>,
+    asset_server: Res<AssetServer>,
+    transform_query: Query<&GlobalTransform>,
+    object_query: Query<(Entity, &MapObject)>,
+    mut door_query: Query<&mut Door>,
+) {
+    // Finds the entity of the map object with the given id
+    let find_object = |id: u32| {
+        object_query
+            .iter()
+            .find(|(_, object)| object.id == id)
+            .map(|(entity, _)| entity)
+    };
+
+    for event in action_events.iter() {
+        for action in &event.actions {
+            debug!("Running trigger action {:?}", action);
+
+            match action {
+                TriggerAction::ShowText(text) => {
+                    text_events.send(ShowTextEvent {
+                        text: LocalizedText::new(text.clone()),
+                    });
+                }
+                TriggerAction::PlaySound(path) => {
+                    let position = event
+                        .source
+                        .and_then(|source| transform_query.get(source).ok())
+                        .map(|transform| transform.translation().truncate());
+                    sound_events.send(PlaySoundEvent {
+                        path: path.clone(),
+                        position,
+                    });
+                }
+                TriggerAction::SpawnEnemies { kind, count, at } => {
+                    let at = match at {
+                        Some(id) => find_object(*id),
+                        None => event.source,
+                    };
+                    let Some(transform) = at.and_then(|entity| transform_query.get(entity).ok())
+                    else {
+                        warn!("Trigger tried to spawn enemies at missing object {:?}", at);
+                        continue;
+                    };
+
+                    enemy_events.send(SpawnEnemiesEvent {
+                        kind: kind.clone(),
+                        count: *count,
+                        position: transform.translation().truncate(),
+                    });
+                }
+                TriggerAction::OpenDoor(id) | TriggerAction::CloseDoor(id) => {
+                    let Some(mut door) =
+                        find_object(*id).and_then(|entity| door_query.get_mut(entity).ok())
+                    else {
+                        warn!("Trigger tried to use missing door {}", id);
+                        continue;
+                    };
+
+                    let open = matches!(action, TriggerAction::OpenDoor(_));
+                    if door.open != open {
+                        door.open = open;
+                    }
+                }
+                TriggerAction::StartDialogue(path) => {
+                    dialogue_events.send(StartDialogueEvent {
+                        dialogue: asset_server.load(path.as_str()),
+                        npc: None,
+                    });
+                }
+                TriggerAction::ChangeMap(path) => {
+                    if map_state.next_map.is_some() {
+                        continue;
+                    }
+
+                    info!("Trigger changing map to {:?}", path);
+                    map_state.next_map = Some(asset_server.load(path.as_str()));
+                    next_map_readiness.set(MapReadinessState::Unloading);
+                }
+            }
+        }
+    }
+}
+
+/// Shows the text asked for by [ShowTextEvent]s, replacing whatever text was
+/// shown before
+pub fn show_trigger_text(
+    mut commands: Commands,
+    mut text_events: EventReader<ShowTextEvent>,
+    theme: Res<CurrentTheme>,
+    localization: Res<Localization>,
+    text_query: Query<Entity, With<TriggerTextNode>>,
+) {
+    let Some(event) = text_events.iter().last() else {
+        return;
+    };
+
+    text_query.for_each(|node| commands.entity(node).despawn_recursive());
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: Val::Px(40.0),
+                    left: Val::Px(0.0),
+                    right: Val::Px(0.0),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(TriggerTextNode {
+            remaining: TEXT_TIME,
+        })
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                &theme,
+                &localization,
+                event.text.clone(),
+                TRIGGER_TEXT,
+            ));
+        });
+}
+
+/// Fades out text shown by triggers once it has been on screen long enough
+pub fn fade_trigger_text(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut node_query: Query<(Entity, &mut TriggerTextNode, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (entity, mut node, children) in &mut node_query {
+        node.remaining -= time.delta_seconds();
+        if node.remaining <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let alpha = (node.remaining / TEXT_FADE_TIME).min(1.0);
+        for child in children {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].style.color.set_a(alpha);
+            }
+        }
+    }
+}
+
+/// Removes the text shown by triggers
+pub fn close_trigger_text(
+    mut commands: Commands,
+    text_query: Query<Entity, With<TriggerTextNode>>,
+) {
+    text_query.for_each(|node| commands.entity(node).despawn_recursive());
+}